@@ -0,0 +1,52 @@
+//! Compares `BitSet` with `HashSet<usize>` on dense indices.
+//!
+//! Run with `cargo bench -p aoc-common --bench bitset`. Each iteration
+//! marks half a million indices in `0..1_000_000` as visited, then looks up
+//! as many more, like a flood fill over a grid by flat index, and counts
+//! the lookups that hit.
+
+use aoc_common::values::Values;
+use aoc_common::BitSet;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::collections::HashSet;
+use std::hint::black_box;
+
+const RANGE: usize = 1_000_000;
+
+/// `RANGE / 2` indices in `0..RANGE`, so some repeat.
+fn indices(seed: u64) -> Vec<usize> {
+    let mut values = Values(seed);
+    (0..RANGE / 2)
+        .map(|_| values.below(RANGE as u64) as usize)
+        .collect()
+}
+
+fn bitset(c: &mut Criterion) {
+    let visits = indices(0x2024_1206);
+    let lookups = indices(0x1206_2024);
+
+    let mut group = c.benchmark_group("visit then look up");
+    group.throughput(Throughput::Elements((visits.len() + lookups.len()) as u64));
+    group.bench_function("HashSet", |b| {
+        b.iter(|| {
+            let mut visited = HashSet::with_capacity(RANGE / 2);
+            for &i in black_box(&visits) {
+                visited.insert(i);
+            }
+            lookups.iter().filter(|&i| visited.contains(i)).count()
+        })
+    });
+    group.bench_function("BitSet", |b| {
+        b.iter(|| {
+            let mut visited = BitSet::with_capacity(RANGE);
+            for &i in black_box(&visits) {
+                visited.insert(i);
+            }
+            lookups.iter().filter(|&&i| visited.contains(i)).count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bitset);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+//! Compares `cartesian_power` with collecting every assignment first, on
+//! day 7's operator search.
+//!
+//! Run with `cargo bench -p aoc-common --bench combinatorics`. Each of 20
+//! equations has 12 operands, and its target is the value of one
+//! assignment of `+`, `*`, and `||`, so a search stops about halfway
+//! through the 177,147 assignments on average. Both searches stop at the
+//! first assignment that hits the target.
+
+use aoc_common::cartesian_power;
+use aoc_common::values::Values;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const EQUATIONS: usize = 20;
+
+/// Operands per equation, so 11 operator slots
+const OPERANDS: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Mul,
+    Concat,
+}
+
+const OPS: [Op; 3] = [Op::Add, Op::Mul, Op::Concat];
+
+/// The value of `operands` joined left to right by `ops`, wrapping rather
+/// than overflowing, since only equality with the target matters.
+fn evaluate(operands: &[u64], ops: &[Op]) -> u64 {
+    ops.iter()
+        .zip(&operands[1..])
+        .fold(operands[0], |acc, (op, &n)| match op {
+            Op::Add => acc.wrapping_add(n),
+            Op::Mul => acc.wrapping_mul(n),
+            Op::Concat => acc.wrapping_mul(10u64.pow(n.ilog10() + 1)).wrapping_add(n),
+        })
+}
+
+/// Equations of operands 1 to 99, each with the target one assignment of
+/// operators gives.
+fn equations() -> Vec<(u64, Vec<u64>)> {
+    let mut values = Values(0x2024_1207);
+    (0..EQUATIONS)
+        .map(|_| {
+            let operands: Vec<u64> = (0..OPERANDS).map(|_| 1 + values.below(99)).collect();
+            let ops: Vec<Op> = (1..OPERANDS)
+                .map(|_| OPS[values.below(3) as usize])
+                .collect();
+            (evaluate(&operands, &ops), operands)
+        })
+        .collect()
+}
+
+/// Assignments tried before each hit, summed over every equation.
+fn lazy(equations: &[(u64, Vec<u64>)]) -> usize {
+    let mut tried = 0;
+    for (target, operands) in equations {
+        let mut assignments = cartesian_power(&OPS, OPERANDS - 1);
+        while let Some(ops) = assignments.next() {
+            tried += 1;
+            if evaluate(operands, ops) == *target {
+                break;
+            }
+        }
+    }
+    tried
+}
+
+/// The same count, from every assignment collected up front.
+fn collected(equations: &[(u64, Vec<u64>)]) -> usize {
+    let mut tried = 0;
+    for (target, operands) in equations {
+        let mut all = Vec::new();
+        let mut assignments = cartesian_power(&OPS, OPERANDS - 1);
+        while let Some(ops) = assignments.next() {
+            all.push(ops.to_vec());
+        }
+        let hit = all
+            .iter()
+            .position(|ops| evaluate(operands, ops) == *target);
+        tried += hit.map_or(all.len(), |i| i + 1);
+    }
+    tried
+}
+
+fn combinatorics(c: &mut Criterion) {
+    let equations = equations();
+    assert_eq!(lazy(&equations), collected(&equations));
+
+    let mut group = c.benchmark_group("operator search");
+    group.sample_size(10);
+    group.bench_function("lazy", |b| b.iter(|| lazy(&equations)));
+    group.bench_function("collected", |b| b.iter(|| collected(&equations)));
+    group.finish();
+}
+
+criterion_group!(benches, combinatorics);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+//! Compares `Counter` with the raw `HashMap<i64, u64>` it wraps.
+//!
+//! Run with `cargo bench -p aoc-common --bench counter`. Both count one
+//! list and then look up every value of a second, day 1's similarity
+//! score, so the wrapper's counting and its zero-for-missing lookups are
+//! both measured. About half the values are distinct, like the puzzle's.
+
+use aoc_common::values::Values;
+use aoc_common::Counter;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::collections::HashMap;
+use std::hint::black_box;
+
+/// Values per list
+const COUNT: usize = 100_000;
+
+fn values(seed: u64) -> Vec<i64> {
+    let mut values = Values(seed);
+    (0..COUNT)
+        .map(|_| values.below(2 * COUNT as u64) as i64)
+        .collect()
+}
+
+fn counter(c: &mut Criterion) {
+    let left = values(0x2024_1201);
+    let right = values(0x1201_2024);
+
+    let mut group = c.benchmark_group("similarity");
+    group.throughput(Throughput::Elements(COUNT as u64));
+    group.bench_function("HashMap", |b| {
+        b.iter(|| {
+            let mut counts: HashMap<i64, u64> = HashMap::new();
+            for &v in black_box(&right) {
+                *counts.entry(v).or_default() += 1;
+            }
+            left.iter()
+                .map(|v| v * counts.get(v).copied().unwrap_or(0) as i64)
+                .sum::<i64>()
+        })
+    });
+    group.bench_function("Counter", |b| {
+        b.iter(|| {
+            let counts: Counter<i64> = black_box(&right).iter().copied().collect();
+            left.iter().map(|v| v * counts.count(v) as i64).sum::<i64>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, counter);
+criterion_main!(benches);
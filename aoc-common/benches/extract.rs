@@ -0,0 +1,83 @@
+//! Compares `extract_ints` with the regex a day would otherwise reach for.
+//!
+//! Run with `cargo bench -p aoc-common --bench extract`. The baseline finds
+//! every `-?\d+` with `regex` and parses each match with `str::parse`.
+//! Lines are shaped like days 3, 13, and 14, and both extractors must find
+//! the same integers in every one before they are timed.
+
+use aoc_common::extract_ints;
+use aoc_common::values::Values;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use regex::Regex;
+use std::hint::black_box;
+
+/// Lines per shape
+const LINES: usize = 10_000;
+
+/// `LINES` lines of one puzzle's shape from `line`.
+fn lines(seed: u64, line: impl Fn(&mut Values) -> String) -> Vec<String> {
+    let mut values = Values(seed);
+    (0..LINES).map(|_| line(&mut values)).collect()
+}
+
+fn sum<I: AsRef<[i64]>>(lines: &[String], extract: impl Fn(&str) -> I) -> i64 {
+    lines.iter().fold(0i64, |sum, line| {
+        extract(black_box(line))
+            .as_ref()
+            .iter()
+            .fold(sum, |sum, &v| sum.wrapping_add(v))
+    })
+}
+
+fn extract(c: &mut Criterion) {
+    let integer = Regex::new(r"-?\d+").unwrap();
+    let with_regex = |line: &str| -> Vec<i64> {
+        integer
+            .find_iter(line)
+            .map(|m| m.as_str().parse().unwrap())
+            .collect()
+    };
+
+    let shapes = [
+        (
+            "day 14, p=.. v=..",
+            lines(0x2024_1214, |v| {
+                format!(
+                    "p={},{} v={},{}",
+                    v.below(101),
+                    v.below(103),
+                    v.signed(100),
+                    v.signed(100)
+                )
+            }),
+        ),
+        (
+            "day 13, buttons",
+            lines(0x2024_1213, |v| {
+                format!("Button A: X+{}, Y+{}", v.below(100), v.below(100))
+            }),
+        ),
+        // Several instructions to a line, among the junk around them
+        (
+            "day 3, mul(..)",
+            lines(0x2024_1203, |v| {
+                (0..4)
+                    .map(|_| format!("%&mul({},{})]!@^don't()", v.below(1000), v.below(1000)))
+                    .collect()
+            }),
+        ),
+    ];
+    for (name, lines) in &shapes {
+        for line in lines {
+            assert_eq!(extract_ints(line).as_slice(), with_regex(line), "{}", line);
+        }
+        let mut group = c.benchmark_group(*name);
+        group.throughput(Throughput::Elements(LINES as u64));
+        group.bench_function("regex", |b| b.iter(|| sum(lines, with_regex)));
+        group.bench_function("extract_ints", |b| b.iter(|| sum(lines, extract_ints)));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, extract);
+criterion_main!(benches);
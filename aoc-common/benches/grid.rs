@@ -0,0 +1,74 @@
+//! Compares random access into a `Grid` with a `Vec<Vec<T>>` of rows.
+//!
+//! Run with `cargo bench -p aoc-common --bench grid`. Both layouts hold the
+//! same 1000x1000 cells and are read at the same coordinates, about one in
+//! eight just outside the grid so the bounds checks are exercised too.
+//! Unchecked indexing is compared on a 140x140 corner, the size of an AoC
+//! map, which fits in cache either way.
+
+use aoc_common::values::Values;
+use aoc_common::Grid;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::hint::black_box;
+
+const SIDE: usize = 1000;
+
+/// Side of the in-cache corner
+const SMALL: usize = 140;
+
+/// Lookups per iteration
+const LOOKUPS: usize = 100_000;
+
+/// `LOOKUPS` coordinates up to an eighth past a `SIDE` by `SIDE` square.
+fn coordinates() -> Vec<(usize, usize)> {
+    let mut values = Values(0x2024_1204);
+    let reach = (SIDE + SIDE / 8 + 1) as u64;
+    (0..LOOKUPS)
+        .map(|_| (values.below(reach) as usize, values.below(reach) as usize))
+        .collect()
+}
+
+fn sum(coordinates: &[(usize, usize)], get: impl Fn(usize, usize) -> Option<u32>) -> u32 {
+    coordinates.iter().fold(0u32, |sum, &(x, y)| {
+        let (x, y) = black_box((x, y));
+        sum.wrapping_add(get(x, y).unwrap_or(0))
+    })
+}
+
+fn grid(c: &mut Criterion) {
+    let cells: Vec<u32> = (0..SIDE * SIDE).map(|i| i as u32).collect();
+    let grid = Grid::from_vec(SIDE, cells.clone()).expect("a square of cells");
+    let rows: Vec<Vec<u32>> = cells.chunks(SIDE).map(<[u32]>::to_vec).collect();
+    let coordinates = coordinates();
+
+    let mut group = c.benchmark_group("checked get");
+    group.throughput(Throughput::Elements(LOOKUPS as u64));
+    group.bench_function("Vec<Vec<u32>>", |b| {
+        b.iter(|| {
+            sum(&coordinates, |x, y| {
+                rows.get(y).and_then(|row| row.get(x)).copied()
+            })
+        })
+    });
+    group.bench_function("Grid", |b| {
+        b.iter(|| sum(&coordinates, |x, y| grid.get(x, y).copied()))
+    });
+    group.finish();
+
+    let in_bounds: Vec<_> = coordinates
+        .iter()
+        .map(|&(x, y)| (x % SMALL, y % SMALL))
+        .collect();
+    let mut group = c.benchmark_group("in-cache 140x140 corner");
+    group.throughput(Throughput::Elements(LOOKUPS as u64));
+    group.bench_function("Vec<Vec<u32>>", |b| {
+        b.iter(|| sum(&in_bounds, |x, y| Some(rows[y][x])))
+    });
+    group.bench_function("Grid", |b| {
+        b.iter(|| sum(&in_bounds, |x, y| Some(grid[(x, y)])))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, grid);
+criterion_main!(benches);
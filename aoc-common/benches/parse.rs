@@ -0,0 +1,111 @@
+//! Compares `parse_int` with the hand-rolled parsers it replaced.
+//!
+//! Run with `cargo bench -p aoc-common --bench parse`. Each parser sums the
+//! same tokens, every eighth one negative: five-digit IDs like day 1's,
+//! two-digit levels like day 2's, and 18-digit values that take the
+//! overflow-checked path.
+
+use aoc_common::parse_int;
+use aoc_common::values::Values;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::hint::black_box;
+
+/// Tokens summed per iteration
+const TOKENS: usize = 100_000;
+
+/// Day 1's former `parse_number` core: an all-digit check, then checked
+/// accumulation branching on the sign for every digit.
+fn day_01_baseline(token: &[u8]) -> Option<i64> {
+    let (negative, digits) = match token.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        _ => (false, token),
+    };
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let mut value: i64 = 0;
+    for &b in digits {
+        let digit = (b - b'0') as i64;
+        value = value.checked_mul(10).and_then(|v| {
+            if negative {
+                v.checked_sub(digit)
+            } else {
+                v.checked_add(digit)
+            }
+        })?;
+    }
+    Some(value)
+}
+
+/// Day 2's former `parse_level` core: digits checked and accumulated in
+/// one checked pass.
+fn day_02_baseline(token: &[u8]) -> Option<i32> {
+    let (negative, digits) = match token {
+        [b'-', digits @ ..] => (true, digits),
+        _ => (false, token),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let mut value: i32 = 0;
+    for &b in digits {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        let digit = (b - b'0') as i32;
+        value = if negative {
+            value.checked_mul(10)?.checked_sub(digit)?
+        } else {
+            value.checked_mul(10)?.checked_add(digit)?
+        };
+    }
+    Some(value)
+}
+
+/// `TOKENS` tokens of `digits` digits each.
+fn tokens(digits: u32) -> Vec<Vec<u8>> {
+    let mut values = Values(0x2024_1202);
+    (0..TOKENS)
+        .map(|i| {
+            let value = values.below(10u64.pow(digits));
+            let sign = if i % 8 == 0 { "-" } else { "" };
+            format!("{}{:0width$}", sign, value, width = digits as usize).into_bytes()
+        })
+        .collect()
+}
+
+fn sum<T: Into<i64>>(tokens: &[Vec<u8>], parse: fn(&[u8]) -> Option<T>) -> i64 {
+    tokens.iter().fold(0i64, |sum, token| {
+        sum.wrapping_add(parse(black_box(token)).map_or(0, Into::into))
+    })
+}
+
+fn compare<T: Into<i64>>(
+    c: &mut Criterion,
+    name: &str,
+    digits: u32,
+    baseline: fn(&[u8]) -> Option<T>,
+    shared: fn(&[u8]) -> Option<T>,
+) {
+    let tokens = tokens(digits);
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Elements(TOKENS as u64));
+    group.bench_function("hand-rolled", |b| b.iter(|| sum(&tokens, baseline)));
+    group.bench_function("parse_int", |b| b.iter(|| sum(&tokens, shared)));
+    group.finish();
+}
+
+fn parse(c: &mut Criterion) {
+    compare(c, "day 1, 5-digit i64", 5, day_01_baseline, |t| {
+        parse_int::<i64>(t).ok()
+    });
+    compare(c, "day 2, 2-digit i32", 2, day_02_baseline, |t| {
+        parse_int::<i32>(t).ok()
+    });
+    compare(c, "day 1, 18-digit i64", 18, day_01_baseline, |t| {
+        parse_int::<i64>(t).ok()
+    });
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+//! Times `search` on a grid maze: `bfs`, `dijkstra`, and `astar` from one
+//! corner to the other, and `dijkstra_all` over the whole grid.
+//!
+//! Run with `cargo bench -p aoc-common --bench search`. The maze is 500
+//! cells a side; cells cost 1 to 9 to enter and about one in eight is a
+//! wall.
+
+use aoc_common::search::{astar, bfs, dijkstra, dijkstra_all};
+use aoc_common::values::Values;
+use aoc_common::{manhattan, neighbors4, Grid, Point2};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const SIDE: usize = 500;
+
+/// Builds the maze, with 0 for a wall, and clears both corners and the
+/// cells beside them so neither is walled in.
+fn maze() -> Grid<u8> {
+    let mut values = Values(0x2024_1216);
+    let cells = (0..SIDE * SIDE)
+        .map(|_| match values.below(8) {
+            0 => 0,
+            _ => 1 + values.below(9) as u8,
+        })
+        .collect();
+    let mut grid = Grid::from_vec(SIDE, cells).expect("a square of cells");
+    for (x, y) in [(0, 0), (1, 0), (0, 1)] {
+        grid[(x, y)] = 1;
+        grid[(SIDE - 1 - x, SIDE - 1 - y)] = 1;
+    }
+    grid
+}
+
+fn search(c: &mut Criterion) {
+    let grid = maze();
+    let end = Point2::new(SIDE as i64 - 1, SIDE as i64 - 1);
+    let open = |p: &Point2| {
+        neighbors4(*p)
+            .into_iter()
+            .filter(|&n| grid.get_point(n).is_some_and(|&c| c != 0))
+    };
+    let weighted = |p: &Point2| open(p).map(|n| (n, u32::from(grid[n])));
+    let heuristic = |p: &Point2| manhattan(*p, end) as u32;
+
+    let mut group = c.benchmark_group("500x500 maze");
+    group.sample_size(20);
+    group.bench_function("bfs", |b| b.iter(|| bfs(Point2::ZERO, open, |p| *p == end)));
+    group.bench_function("dijkstra", |b| {
+        b.iter(|| dijkstra(Point2::ZERO, weighted, |p| *p == end))
+    });
+    group.bench_function("astar", |b| {
+        b.iter(|| astar(Point2::ZERO, weighted, heuristic, |p| *p == end))
+    });
+    group.bench_function("dijkstra_all", |b| {
+        b.iter(|| dijkstra_all(Point2::ZERO, weighted))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
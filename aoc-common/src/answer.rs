@@ -0,0 +1,206 @@
+//! Puzzle answers, whatever shape the puzzle asks for.
+//!
+//! Most answers are counts or sums, but some are text, like a program's
+//! output or a pattern read off a grid, and day 25 has no Part 2 at all.
+//! Every solver returns an `Answer` and every reporter prints one, so the
+//! answer is typed once, at the solver, and compared anywhere after by the
+//! string the puzzle site would accept:
+//!
+//! ```
+//! use aoc_common::Answer;
+//!
+//! assert_eq!(Answer::from(31u64), "31");
+//! assert_eq!(Answer::from("4,6,3,5,6,3,5,2,1,0").to_string(), "4,6,3,5,6,3,5,2,1,0");
+//! assert_eq!(Answer::from(-11i64), Answer::from("-11"));
+//! ```
+
+use std::fmt;
+
+/// A puzzle answer, printed as the puzzle expects it.
+///
+/// Two answers are equal when they print the same, so an integer equals
+/// the same integer in either variant and matches its string as stored in
+/// an answers file. The exception is `None`, which equals only itself.
+#[derive(Debug, Clone, Eq)]
+pub enum Answer {
+    /// A count, sum, or any other answer that can't be negative
+    U64(u64),
+    /// An integer answer that may be negative
+    I64(i64),
+    /// An answer that isn't a number, or one too wide for the others
+    String(String),
+    /// A part with nothing to answer, like day 25's Part 2; prints as
+    /// nothing
+    None,
+}
+
+impl Answer {
+    /// Whether there is an answer, as opposed to `None`.
+    pub fn is_some(&self) -> bool {
+        !matches!(self, Answer::None)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::U64(value) => write!(f, "{}", value),
+            Answer::I64(value) => write!(f, "{}", value),
+            Answer::String(text) => write!(f, "{}", text),
+            Answer::None => Ok(()),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Answer) -> bool {
+        match (self, other) {
+            (Answer::U64(a), Answer::U64(b)) => a == b,
+            (Answer::I64(a), Answer::I64(b)) => a == b,
+            (Answer::U64(a), Answer::I64(b)) | (Answer::I64(b), Answer::U64(a)) => {
+                i128::from(*a) == i128::from(*b)
+            }
+            (Answer::String(a), Answer::String(b)) => a == b,
+            (Answer::None, Answer::None) => true,
+            (Answer::None, _) | (_, Answer::None) => false,
+            // A number against text compares the number's digits
+            (Answer::String(text), number) | (number, Answer::String(text)) => *number == **text,
+        }
+    }
+}
+
+/// Compares against an answer's string form, such as one read back from an
+/// answers file. `None` matches nothing, not even an empty string.
+impl PartialEq<str> for Answer {
+    fn eq(&self, stored: &str) -> bool {
+        match self {
+            Answer::U64(value) => value.to_string() == stored,
+            Answer::I64(value) => value.to_string() == stored,
+            Answer::String(text) => text == stored,
+            Answer::None => false,
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, stored: &&str) -> bool {
+        *self == **stored
+    }
+}
+
+impl PartialEq<String> for Answer {
+    fn eq(&self, stored: &String) -> bool {
+        *self == **stored
+    }
+}
+
+macro_rules! impl_answer_from {
+    ($variant:ident: $($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(value: $int) -> Self {
+                    Answer::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from!(U64: u8, u16, u32, u64);
+impl_answer_from!(I64: i8, i16, i32, i64);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        // usize is at most 64 bits on every supported target
+        Answer::U64(value as u64)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Answer::I64(value as i64)
+    }
+}
+
+/// Fits in `U64` or `I64` where it can, and is kept as its digits where
+/// it can't, so a `--wide` result prints the same either way.
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        u64::try_from(value).map_or_else(|_| Answer::String(value.to_string()), Answer::U64)
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        if let Ok(value) = u64::try_from(value) {
+            Answer::U64(value)
+        } else if let Ok(value) = i64::try_from(value) {
+            Answer::I64(value)
+        } else {
+            Answer::String(value.to_string())
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::String(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::String(text.to_string())
+    }
+}
+
+/// `None` for a part with nothing to answer.
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Answer::None, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prints_each_variant_as_the_puzzle_expects() {
+        assert_eq!(Answer::U64(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Answer::I64(i64::MIN).to_string(), "-9223372036854775808");
+        assert_eq!(Answer::from("4,6,3,5").to_string(), "4,6,3,5");
+        assert_eq!(Answer::None.to_string(), "");
+        assert_eq!(format!("[{:>4}]", Answer::U64(7)), "[7]");
+
+        // Wide integers narrow where they fit and keep their digits otherwise
+        assert!(matches!(Answer::from(31u128), Answer::U64(31)));
+        assert!(matches!(Answer::from(-11i128), Answer::I64(-11)));
+        assert!(matches!(Answer::from(5i128), Answer::U64(5)));
+        let wide = Answer::from(-(1i128 << 100));
+        assert_eq!(wide.to_string(), "-1267650600228229401496703205376");
+        assert!(matches!(wide, Answer::String(_)));
+        assert!(matches!(Answer::from(None::<u64>), Answer::None));
+        assert!(Answer::from(Some(3usize)).is_some());
+    }
+
+    #[test]
+    fn equals_its_stored_string_form() {
+        assert_eq!(Answer::U64(31), "31");
+        assert_eq!(Answer::U64(31), "31".to_string());
+        assert_eq!(Answer::I64(-11), "-11");
+        assert_ne!(Answer::U64(31), "031");
+        assert_ne!(Answer::U64(31), " 31");
+        assert_eq!(Answer::from("NUMERIC"), "NUMERIC");
+        assert_ne!(Answer::None, "");
+        assert_eq!(Answer::U64(u64::MAX), "18446744073709551615");
+
+        // Against each other, numbers compare by value and text by digits
+        assert_eq!(Answer::U64(31), Answer::I64(31));
+        assert_ne!(Answer::U64(u64::MAX), Answer::I64(-1));
+        assert_eq!(Answer::I64(-11), Answer::from("-11"));
+        assert_eq!(Answer::from("31"), Answer::U64(31));
+        assert_ne!(Answer::None, Answer::from(""));
+        assert_eq!(Answer::None, Answer::None);
+    }
+}
@@ -0,0 +1,458 @@
+//! The answers each day is known to give, from `answers.toml`.
+//!
+//! Once a part is solved, its accepted answer goes in `answers.toml` at the
+//! workspace root, so later changes can be checked against it. Each day is
+//! a table named `day-NN`, with the answers for the puzzle input and,
+//! optionally, for the puzzle's worked example:
+//!
+//! ```toml
+//! [day-01]
+//! part1 = 1830467
+//! part2 = 26674158
+//!
+//! [day-01.example]
+//! part1 = 11
+//! part2 = 31
+//!
+//! [day-25]
+//! part1 = "3"
+//! ```
+//!
+//! An answer is an integer or a string; a string is compared as the answer
+//! prints, so integers too wide for TOML go in quotes. Days and parts that
+//! aren't solved yet are left out.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use toml::{Table, Value};
+
+use super::input::workspace_root;
+use super::{Answer, AocError};
+
+/// File name of the answers at the workspace root
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Known answers by day, for the puzzle input and the worked example.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Answers {
+    days: BTreeMap<u8, DayAnswers>,
+}
+
+/// One day's known answers, Part 1 first.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct DayAnswers {
+    input: [Option<Answer>; 2],
+    example: [Option<Answer>; 2],
+}
+
+impl Answers {
+    /// No known answers.
+    pub fn new() -> Self {
+        Answers::default()
+    }
+
+    /// The answers in `answers.toml` at the workspace root, or none if
+    /// there is no such file.
+    ///
+    /// The workspace is looked for from the working directory and then
+    /// from `crate_dir`, as `Config::load` does.
+    ///
+    /// # Errors
+    /// Returns `AocError::Config` if the file can't be read or isn't a
+    /// valid answers file.
+    pub fn load(crate_dir: &Path) -> Result<Answers, AocError> {
+        let start_dir = env::current_dir().unwrap_or_else(|_| crate_dir.to_path_buf());
+        match answers_path(&start_dir, crate_dir) {
+            Some(path) => Answers::read(&path),
+            None => Ok(Answers::new()),
+        }
+    }
+
+    /// Reads and parses one answers file.
+    ///
+    /// # Errors
+    /// Returns `AocError::Config` naming the file if it can't be read or
+    /// isn't a valid answers file.
+    pub fn read(path: &Path) -> Result<Answers, AocError> {
+        let error = |message: String| AocError::Config {
+            origin: path.display().to_string(),
+            message,
+        };
+        let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        parse(&text).map_err(error)
+    }
+
+    /// The answer to `part` of `day` for the puzzle input, if known.
+    pub fn expected(&self, day: u8, part: u8) -> Option<Answer> {
+        let day = self.days.get(&day)?;
+        day.input.get(usize::from(part).checked_sub(1)?)?.clone()
+    }
+
+    /// The answer to `part` of `day` for the worked example, if known.
+    pub fn expected_example(&self, day: u8, part: u8) -> Option<Answer> {
+        let day = self.days.get(&day)?;
+        day.example.get(usize::from(part).checked_sub(1)?)?.clone()
+    }
+
+    /// Records the answer to `part` of `day` for the puzzle input;
+    /// `Answer::None` forgets it.
+    ///
+    /// # Panics
+    /// If `day` isn't 1-25 or `part` isn't 1 or 2.
+    pub fn insert(&mut self, day: u8, part: u8, answer: Answer) {
+        let slot = slot(day, part);
+        self.days.entry(day).or_default().input[slot] = answer.is_some().then_some(answer);
+    }
+
+    /// Records the answer to `part` of `day` for the worked example, as
+    /// `insert` does.
+    ///
+    /// # Panics
+    /// If `day` isn't 1-25 or `part` isn't 1 or 2.
+    pub fn insert_example(&mut self, day: u8, part: u8, answer: Answer) {
+        let slot = slot(day, part);
+        self.days.entry(day).or_default().example[slot] = answer.is_some().then_some(answer);
+    }
+
+    /// Days with at least one known answer, in order.
+    pub fn days(&self) -> impl Iterator<Item = u8> + '_ {
+        self.days
+            .iter()
+            .filter(|(_, answers)| **answers != DayAnswers::default())
+            .map(|(&day, _)| day)
+    }
+
+    /// The answers as an `answers.toml`, which parses back to the same
+    /// answers.
+    pub fn to_toml(&self) -> String {
+        let mut file = Table::new();
+        for (&day, answers) in &self.days {
+            let mut table = parts_table(&answers.input);
+            let example = parts_table(&answers.example);
+            if !example.is_empty() {
+                table.insert("example".to_string(), Value::Table(example));
+            }
+            if !table.is_empty() {
+                file.insert(day_key(day), Value::Table(table));
+            }
+        }
+        toml::to_string(&file).expect("answers serialize to TOML")
+    }
+}
+
+impl FromStr for Answers {
+    type Err = AocError;
+
+    /// Parses the text of an answers file, named `answers.toml` in errors.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        parse(text).map_err(|message| AocError::Config {
+            origin: ANSWERS_FILE.to_string(),
+            message,
+        })
+    }
+}
+
+/// Parses and validates an answers file, with errors naming the key at
+/// fault.
+fn parse(text: &str) -> Result<Answers, String> {
+    let file: Table = toml::from_str(text).map_err(|e| e.message().to_string())?;
+    let mut answers = Answers::new();
+    let mut keys = BTreeMap::new();
+    for (key, value) in &file {
+        let day = parse_day_key(key)
+            .ok_or_else(|| format!("`{}`: expected a day, from `day-01` to `day-25`", key))?;
+        if let Some(first) = keys.insert(day, key) {
+            return Err(format!(
+                "day {} appears twice, as `{}` and `{}`",
+                day, first, key
+            ));
+        }
+        let table = value
+            .as_table()
+            .ok_or_else(|| format!("`{}`: expected a table of answers", key))?;
+        let mut day_answers = DayAnswers {
+            input: parse_parts(key, table, true)?,
+            example: [None, None],
+        };
+        if let Some(example) = table.get("example") {
+            let path = format!("{}.example", key);
+            let example = example
+                .as_table()
+                .ok_or_else(|| format!("`{}`: expected a table of answers", path))?;
+            day_answers.example = parse_parts(&path, example, false)?;
+        }
+        answers.days.insert(day, day_answers);
+    }
+    Ok(answers)
+}
+
+/// The `part1` and `part2` answers in `table`, at `path` in the file,
+/// rejecting any other key except an `example` table where one is allowed.
+fn parse_parts(
+    path: &str,
+    table: &Table,
+    allow_example: bool,
+) -> Result<[Option<Answer>; 2], String> {
+    let mut parts = [None, None];
+    for (key, value) in table {
+        let part = match key.as_str() {
+            "part1" => 0,
+            "part2" => 1,
+            "example" if allow_example => continue,
+            _ => {
+                let expected = if allow_example {
+                    "`part1`, `part2`, or `example`"
+                } else {
+                    "`part1` or `part2`"
+                };
+                return Err(format!(
+                    "`{}.{}`: unknown key, expected {}",
+                    path, key, expected
+                ));
+            }
+        };
+        parts[part] = Some(parse_answer(value).map_err(|e| format!("`{}.{}`: {}", path, key, e))?);
+    }
+    Ok(parts)
+}
+
+/// An answer stored as a TOML integer or non-empty string.
+fn parse_answer(value: &Value) -> Result<Answer, String> {
+    match value {
+        Value::Integer(value) => Ok(u64::try_from(*value).map_or(Answer::I64(*value), Answer::U64)),
+        Value::String(text) if text.is_empty() => Err("expected a non-empty answer".to_string()),
+        Value::String(text) => Ok(Answer::String(text.clone())),
+        other => Err(format!(
+            "expected an integer or a string, found {}",
+            other.type_str()
+        )),
+    }
+}
+
+/// The `part1` and `part2` entries of `parts` that are known.
+fn parts_table(parts: &[Option<Answer>; 2]) -> Table {
+    let mut table = Table::new();
+    for (key, answer) in ["part1", "part2"].into_iter().zip(parts) {
+        let value = match answer {
+            Some(Answer::U64(value)) => {
+                // TOML integers are signed 64-bit, so the widest are quoted
+                i64::try_from(*value)
+                    .map_or_else(|_| Value::String(value.to_string()), Value::Integer)
+            }
+            Some(Answer::I64(value)) => Value::Integer(*value),
+            Some(Answer::String(text)) => Value::String(text.clone()),
+            Some(Answer::None) | None => continue,
+        };
+        table.insert(key.to_string(), value);
+    }
+    table
+}
+
+/// The index into a day's answers for `part`, checking `day` too.
+fn slot(day: u8, part: u8) -> usize {
+    assert!((1..=25).contains(&day), "day {} is not 1-25", day);
+    assert!(part == 1 || part == 2, "part {} is not 1 or 2", part);
+    usize::from(part - 1)
+}
+
+/// The table name for `day`, like `day-01`.
+fn day_key(day: u8) -> String {
+    format!("day-{:02}", day)
+}
+
+/// The day a table name like `day-01` or `day-1` names, if it's 1-25.
+fn parse_day_key(key: &str) -> Option<u8> {
+    let digits = key.strip_prefix("day-")?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().filter(|day| (1..=25).contains(day))
+}
+
+/// The answers file to read: `answers.toml` in the workspace enclosing
+/// `start_dir` or `crate_dir`.
+fn answers_path(start_dir: &Path, crate_dir: &Path) -> Option<PathBuf> {
+    [start_dir, crate_dir]
+        .into_iter()
+        .filter_map(workspace_root)
+        .map(|root| root.join(ANSWERS_FILE))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(text: &str) -> Answers {
+        text.parse().unwrap()
+    }
+
+    fn error(text: &str) -> String {
+        text.parse::<Answers>().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn looks_up_answers_and_tolerates_missing_ones() {
+        let parsed = answers(
+            "[day-01]\npart1 = 1830467\npart2 = -5\n\
+             [day-01.example]\npart1 = 11\n\
+             [day-3]\npart2 = \"18446744073709551616\"\n\
+             [day-25]\npart1 = \"3\"\n",
+        );
+        assert_eq!(parsed.expected(1, 1), Some(Answer::U64(1_830_467)));
+        assert_eq!(parsed.expected(1, 2), Some(Answer::I64(-5)));
+        assert_eq!(parsed.expected_example(1, 1), Some(Answer::U64(11)));
+        assert_eq!(parsed.expected_example(1, 2), None);
+        assert_eq!(parsed.expected(3, 1), None);
+        assert_eq!(parsed.expected(3, 2).unwrap(), "18446744073709551616");
+        assert_eq!(parsed.expected(25, 1), Some(Answer::U64(3)));
+        assert_eq!(parsed.expected(2, 1), None);
+        assert_eq!((parsed.expected(1, 0), parsed.expected(1, 3)), (None, None));
+        assert_eq!(parsed.days().collect::<Vec<_>>(), [1, 3, 25]);
+
+        assert_eq!(answers(""), Answers::new());
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut original = Answers::new();
+        original.insert(1, 1, Answer::U64(1_830_467));
+        original.insert(1, 2, Answer::I64(-26_674_158));
+        original.insert_example(1, 1, Answer::U64(11));
+        original.insert_example(1, 2, Answer::U64(31));
+        original.insert(2, 2, Answer::U64(u64::MAX));
+        original.insert(17, 1, Answer::from("4,6,3,5,6,3,5,2,1,0"));
+        original.insert_example(24, 2, Answer::from("z00,z01,z02,z05"));
+        // Forgetting an answer leaves nothing behind for that day
+        original.insert(9, 1, Answer::U64(1));
+        original.insert(9, 1, Answer::None);
+
+        let text = original.to_toml();
+        assert_eq!(
+            text,
+            "[day-01]\n\
+             part1 = 1830467\n\
+             part2 = -26674158\n\
+             \n\
+             [day-01.example]\n\
+             part1 = 11\n\
+             part2 = 31\n\
+             \n\
+             [day-02]\n\
+             part2 = \"18446744073709551615\"\n\
+             \n\
+             [day-17]\n\
+             part1 = \"4,6,3,5,6,3,5,2,1,0\"\n\
+             \n\
+             [day-24.example]\n\
+             part2 = \"z00,z01,z02,z05\"\n"
+        );
+        let parsed = answers(&text);
+        assert_eq!(parsed.to_toml(), text);
+        assert_eq!(parsed.days().collect::<Vec<_>>(), [1, 2, 17, 24]);
+        for day in 1..=25 {
+            for part in 1..=2 {
+                assert_eq!(parsed.expected(day, part), original.expected(day, part));
+                assert_eq!(
+                    parsed.expected_example(day, part),
+                    original.expected_example(day, part)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_duplicate_days() {
+        assert_eq!(
+            error("[day-01]\npart1 = 1\n[day-1]\npart1 = 2\n"),
+            "answers.toml: day 1 appears twice, as `day-01` and `day-1`"
+        );
+        // The same name twice is already a TOML error
+        let message = error("[day-01]\npart1 = 1\n[day-01]\npart2 = 2\n");
+        assert!(message.contains("duplicate"), "{}", message);
+    }
+
+    #[test]
+    fn rejects_answers_that_are_neither_integers_nor_strings() {
+        assert_eq!(
+            error("[day-04]\npart1 = 2.5\n"),
+            "answers.toml: `day-04.part1`: expected an integer or a string, found float"
+        );
+        assert_eq!(
+            error("[day-04.example]\npart2 = [1, 2]\n"),
+            "answers.toml: `day-04.example.part2`: expected an integer or a string, found array"
+        );
+        assert_eq!(
+            error("[day-04]\npart1 = true\n"),
+            "answers.toml: `day-04.part1`: expected an integer or a string, found boolean"
+        );
+        assert_eq!(
+            error("[day-04]\npart1 = \"\"\n"),
+            "answers.toml: `day-04.part1`: expected a non-empty answer"
+        );
+    }
+
+    #[test]
+    fn rejects_anything_else_out_of_place() {
+        assert_eq!(
+            error("[day-26]\npart1 = 1\n"),
+            "answers.toml: `day-26`: expected a day, from `day-01` to `day-25`"
+        );
+        assert_eq!(
+            error("year = 2024\n"),
+            "answers.toml: `year`: expected a day, from `day-01` to `day-25`"
+        );
+        assert_eq!(
+            error("day-02 = 5\n"),
+            "answers.toml: `day-02`: expected a table of answers"
+        );
+        assert_eq!(
+            error("[day-02]\npart3 = 5\n"),
+            "answers.toml: `day-02.part3`: unknown key, expected `part1`, `part2`, or `example`"
+        );
+        assert_eq!(
+            error("[day-02]\nexample = 5\n"),
+            "answers.toml: `day-02.example`: expected a table of answers"
+        );
+        assert_eq!(
+            error("[day-02.example.example]\npart1 = 5\n"),
+            "answers.toml: `day-02.example.example`: unknown key, expected `part1` or `part2`"
+        );
+    }
+
+    #[test]
+    fn reads_the_workspaces_own_file() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let answers = Answers::read(&crate_dir.join("..").join(ANSWERS_FILE)).unwrap();
+        assert_eq!(answers.expected_example(1, 2), Some(Answer::U64(31)));
+        assert_eq!(answers.expected_example(2, 1), Some(Answer::U64(2)));
+    }
+
+    #[test]
+    fn loads_the_workspace_file_or_nothing() {
+        let workspace = tempfile::tempdir().unwrap();
+        let root = workspace.path();
+        fs::write(root.join("Cargo.toml"), "[workspace]\n").unwrap();
+        fs::create_dir_all(root.join("day-01/rs")).unwrap();
+        let crate_dir = root.join("day-01/rs");
+        assert_eq!(answers_path(&crate_dir, &crate_dir), None);
+
+        fs::write(root.join(ANSWERS_FILE), "[day-01]\npart2 = 31\n").unwrap();
+        let elsewhere = tempfile::tempdir().unwrap();
+        let path = answers_path(elsewhere.path(), &crate_dir).unwrap();
+        assert_eq!(path, root.join(ANSWERS_FILE));
+        assert_eq!(Answers::read(&path).unwrap().expected(1, 2).unwrap(), "31");
+
+        fs::write(&path, "[day-01]\npart2 = 3.1\n").unwrap();
+        let message = Answers::read(&path).unwrap_err().to_string();
+        assert!(
+            message.starts_with(&format!("{}: ", path.display())),
+            "{}",
+            message
+        );
+    }
+}
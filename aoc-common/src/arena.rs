@@ -0,0 +1,353 @@
+//! A bump arena for temporary slices that all die at the same time.
+//!
+//! Work done once per input line, like the dampener's differences for a
+//! report, needs a few scratch slices that are dropped together when the
+//! line is done. Allocating each from the heap costs an allocation per
+//! line; an `Arena` hands them out from blocks it keeps, and `reset` makes
+//! the whole space free again without returning it, so after the first
+//! few lines no allocation happens at all.
+//!
+//! `with_scratch` lends each thread its own arena, reset after every use,
+//! so solver code running on the rayon pool needs no arena passed in:
+//!
+//! ```
+//! use aoc_common::with_scratch;
+//!
+//! let levels = [7, 6, 4, 2, 1];
+//! let widest = with_scratch(|arena| {
+//!     let diffs = arena.alloc_slice(levels.len() - 1, 0i32);
+//!     for (diff, pair) in diffs.iter_mut().zip(levels.windows(2)) {
+//!         *diff = pair[1] - pair[0];
+//!     }
+//!     diffs.iter().map(|diff| diff.abs()).max()
+//! });
+//! assert_eq!(widest, Some(2));
+//! ```
+
+use std::cell::RefCell;
+use std::mem::{align_of, size_of};
+use std::ptr::NonNull;
+
+/// The unit blocks are allocated in, which sets the largest alignment an
+/// arena can serve
+type Word = u128;
+
+/// Bytes per `Word`
+const WORD: usize = size_of::<Word>();
+
+/// Words in the first block, enough for a few short reports' scratch
+const FIRST_BLOCK: usize = 64;
+
+/// Scratch space for `Copy` slices that are freed together by `reset`.
+///
+/// # Implementation Details
+/// - Slices are carved from the end of the newest block; when it runs out,
+///   a block at least twice its size is added. Blocks never move, so every
+///   slice handed out stays valid until `reset`
+/// - `reset` takes `&mut self`, so no slice can outlive it, and replaces
+///   several blocks with one as large as all of them together. A workload
+///   whose lines need at most `n` bytes therefore settles on one block and
+///   stops allocating, holding at most a small multiple of `n`
+/// - Only `Copy` types are allowed, so nothing in the arena needs dropping
+#[derive(Debug)]
+pub struct Arena {
+    state: RefCell<Blocks>,
+}
+
+#[derive(Debug)]
+struct Blocks {
+    /// Each block's start and length in words; only the last has room
+    blocks: Vec<(NonNull<Word>, usize)>,
+    /// Words handed out from the last block
+    last_used: usize,
+    /// Bytes handed out since the last `reset`
+    live: usize,
+    /// Most bytes handed out between two resets
+    peak: usize,
+    /// Blocks allocated over the arena's life
+    allocations: usize,
+}
+
+// The arena owns its blocks outright, and a slice borrowed from it keeps
+// the arena itself borrowed, so moving it to another thread moves no
+// outstanding access along with it
+unsafe impl Send for Arena {}
+
+impl Arena {
+    /// An arena that allocates nothing until the first slice.
+    pub fn new() -> Self {
+        Arena {
+            state: RefCell::new(Blocks {
+                blocks: Vec::new(),
+                last_used: 0,
+                live: 0,
+                peak: 0,
+                allocations: 0,
+            }),
+        }
+    }
+
+    /// A slice of `len` copies of `value`, valid until the next `reset`.
+    ///
+    /// # Panics
+    /// If `T` is aligned to more than 16 bytes, or the slice is larger
+    /// than memory can hold.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice<T: Copy>(&self, len: usize, value: T) -> &mut [T] {
+        assert!(
+            align_of::<T>() <= align_of::<Word>(),
+            "arena slices can be aligned to at most {} bytes",
+            align_of::<Word>()
+        );
+        let bytes = size_of::<T>()
+            .checked_mul(len)
+            .expect("arena slice too large");
+        if bytes == 0 {
+            // Nothing to store, and a dangling pointer is a valid empty or
+            // zero-sized slice
+            return unsafe { std::slice::from_raw_parts_mut(NonNull::dangling().as_ptr(), len) };
+        }
+        let words = bytes.div_ceil(WORD);
+
+        let mut state = self.state.borrow_mut();
+        let room = state
+            .blocks
+            .last()
+            .map_or(0, |&(_, size)| size - state.last_used);
+        if room < words {
+            let last = state
+                .blocks
+                .last()
+                .map_or(FIRST_BLOCK / 2, |&(_, size)| size);
+            state.push_block(words.max(last * 2));
+        }
+        let (start, _) = state.blocks[state.blocks.len() - 1];
+        // The last block has `words` free words from `last_used` on, and no
+        // slice handed out before overlaps them
+        let slice = unsafe {
+            let at = start.as_ptr().add(state.last_used).cast::<T>();
+            for i in 0..len {
+                at.add(i).write(value);
+            }
+            std::slice::from_raw_parts_mut(at, len)
+        };
+        state.last_used += words;
+        state.live += words * WORD;
+        state.peak = state.peak.max(state.live);
+        slice
+    }
+
+    /// Frees every slice at once, keeping the space for the next ones.
+    ///
+    /// Several blocks are replaced by one of their combined size, so the
+    /// next round that needs as much fits without allocating.
+    pub fn reset(&mut self) {
+        let state = self.state.get_mut();
+        if state.blocks.len() > 1 {
+            let total = state.capacity() / WORD;
+            state.free_blocks();
+            state.push_block(total);
+        }
+        state.last_used = 0;
+        state.live = 0;
+    }
+
+    /// Bytes handed out since the last `reset`, rounded up to whole words.
+    pub fn used(&self) -> usize {
+        self.state.borrow().live
+    }
+
+    /// Most bytes handed out between two resets.
+    pub fn peak(&self) -> usize {
+        self.state.borrow().peak
+    }
+
+    /// Bytes of blocks held, used or not.
+    pub fn capacity(&self) -> usize {
+        self.state.borrow().capacity()
+    }
+
+    /// Heap allocations the arena has made for its blocks.
+    pub fn allocations(&self) -> usize {
+        self.state.borrow().allocations
+    }
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Arena::new()
+    }
+}
+
+impl Blocks {
+    /// Adds an empty block of `words` words and makes it the last.
+    fn push_block(&mut self, words: usize) {
+        let block: Box<[Word]> = vec![0; words].into_boxed_slice();
+        // Kept as a raw pointer, so later slices carved from the block
+        // don't reborrow the earlier ones
+        let start = NonNull::new(Box::into_raw(block).cast::<Word>()).expect("box is non-null");
+        self.blocks.push((start, words));
+        self.last_used = 0;
+        self.allocations += 1;
+    }
+
+    /// Returns every block to the heap.
+    fn free_blocks(&mut self) {
+        for (start, words) in self.blocks.drain(..) {
+            // Each block came from `Box::into_raw` with this length
+            drop(unsafe {
+                Box::from_raw(std::ptr::slice_from_raw_parts_mut(start.as_ptr(), words))
+            });
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.blocks.iter().map(|&(_, words)| words * WORD).sum()
+    }
+}
+
+impl Drop for Blocks {
+    fn drop(&mut self) {
+        self.free_blocks();
+    }
+}
+
+thread_local! {
+    static SCRATCH: RefCell<Arena> = RefCell::new(Arena::new());
+}
+
+/// Runs `f` with this thread's scratch arena, reset once `f` returns.
+///
+/// Every thread, rayon's workers included, has an arena of its own, so
+/// parallel callers never contend and each settles on a block sized for
+/// the largest line it has seen.
+///
+/// # Returns
+/// What `f` returns, which can't borrow from the arena.
+///
+/// # Panics
+/// If called again from inside `f`, which would share the arena.
+pub fn with_scratch<R>(f: impl FnOnce(&mut Arena) -> R) -> R {
+    SCRATCH.with(|scratch| {
+        let mut arena = scratch
+            .try_borrow_mut()
+            .expect("with_scratch called inside with_scratch");
+        let result = f(&mut arena);
+        arena.reset();
+        result
+    })
+}
+
+/// Most bytes this thread's `with_scratch` arena has handed out at once.
+///
+/// # Panics
+/// If called from inside `with_scratch`.
+pub fn scratch_peak() -> usize {
+    SCRATCH.with(|scratch| scratch.borrow().peak())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hands_out_disjoint_slices_of_any_copy_type() {
+        let arena = Arena::new();
+        assert_eq!((arena.capacity(), arena.allocations()), (0, 0));
+        let bytes = arena.alloc_slice(3, 7u8);
+        let wide = arena.alloc_slice(2, [1u64, 2]);
+        let costs = arena.alloc_slice(4, [usize::MAX; 3]);
+        bytes[1] = 9;
+        wide[0][1] = 5;
+        costs[3][0] = 0;
+        assert_eq!(bytes, [7, 9, 7]);
+        assert_eq!(wide, [[1, 5], [1, 2]]);
+        assert_eq!(costs[..2], [[usize::MAX; 3]; 2]);
+        assert_eq!(costs[3], [0, usize::MAX, usize::MAX]);
+        // The bytes round up to a word; the rest fill theirs exactly
+        assert_eq!(arena.used(), 16 + 32 + 96);
+        assert_eq!(arena.allocations(), 1);
+
+        // Empty and zero-sized slices take no space
+        assert!(arena.alloc_slice(0, 1u32).is_empty());
+        assert_eq!(arena.alloc_slice(5, ()).len(), 5);
+        assert_eq!(arena.used(), 144);
+    }
+
+    #[test]
+    fn grows_past_its_first_block_and_coalesces_on_reset() {
+        let mut arena = Arena::new();
+        let small = arena.alloc_slice(10, 1u32);
+        let large = arena.alloc_slice(10_000, 2u32);
+        // The earlier slice survives the new block
+        assert!(small.iter().all(|&x| x == 1) && large.iter().all(|&x| x == 2));
+        assert_eq!(arena.allocations(), 2);
+        let held = arena.capacity();
+
+        arena.reset();
+        assert_eq!((arena.used(), arena.capacity()), (0, held));
+        assert_eq!(arena.allocations(), 3);
+        // The same round again fits in the one block
+        arena.alloc_slice(10, 1u32);
+        arena.alloc_slice(10_000, 2u32);
+        arena.reset();
+        assert_eq!(arena.allocations(), 3);
+        assert_eq!(arena.peak(), 48 + 40_000);
+    }
+
+    #[test]
+    fn stays_bounded_over_a_million_lines() {
+        // Lines of varying length, each needing the dampener's two scratch
+        // slices, with one long line far in
+        let mut arena = Arena::new();
+        let mut longest = 0;
+        for line in 0..1_000_000usize {
+            let levels = if line == 500_000 {
+                5_000
+            } else {
+                2 + line % 30
+            };
+            let diffs = arena.alloc_slice(levels - 1, 0i32);
+            let costs = arena.alloc_slice(levels, [usize::MAX; 3]);
+            diffs[levels - 2] = 1;
+            costs[0][0] = line;
+            longest = longest.max(arena.used());
+            arena.reset();
+        }
+        assert_eq!(arena.peak(), longest);
+        // Each line's space is reused, so the arena holds a few times the
+        // longest line rather than the sum of all of them
+        assert!(
+            arena.capacity() <= 4 * longest,
+            "{} bytes held for lines of at most {}",
+            arena.capacity(),
+            longest
+        );
+        assert!(arena.allocations() <= 4, "{}", arena.allocations());
+    }
+
+    #[test]
+    fn lends_each_thread_its_own_scratch() {
+        let sum = with_scratch(|arena| {
+            let values = arena.alloc_slice(100, 3u64);
+            values.iter().sum::<u64>()
+        });
+        assert_eq!(sum, 300);
+        // Reset afterwards, but the peak is remembered
+        assert_eq!(with_scratch(|arena| arena.used()), 0);
+        assert_eq!(scratch_peak(), 800);
+
+        let other = std::thread::spawn(|| {
+            with_scratch(|arena| arena.alloc_slice(1, 0u8).len());
+            scratch_peak()
+        });
+        assert_eq!(other.join().unwrap(), 16);
+        assert_eq!(scratch_peak(), 800);
+    }
+
+    #[test]
+    #[should_panic(expected = "with_scratch called inside with_scratch")]
+    fn refuses_nested_scratch() {
+        with_scratch(|_| with_scratch(|_| ()));
+    }
+}
@@ -0,0 +1,347 @@
+//! Command-line options every day binary accepts.
+//!
+//! Each day's `main` declares its own clap `Parser` with `DayArgs`
+//! flattened in, next to whatever options only that day has:
+//!
+//! ```
+//! use aoc_common::{parse_args_from, DayArgs, Part};
+//! use clap::Parser;
+//!
+//! #[derive(Parser)]
+//! struct Cli {
+//!     #[command(flatten)]
+//!     common: DayArgs,
+//!     /// A day-specific option
+//!     #[arg(long)]
+//!     wide: bool,
+//! }
+//!
+//! let cli: Cli = parse_args_from(["day-99", "input.txt", "--part", "2", "--wide"]).unwrap();
+//! assert_eq!(cli.common.inputs, ["input.txt"]);
+//! assert_eq!(cli.common.part, Part::Two);
+//! assert!(cli.wide);
+//! ```
+//!
+//! so the shared flags are spelled, documented, and validated the same way
+//! on every day, and usage errors come back as `AocError::BadArgument`.
+
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use clap::error::ErrorKind;
+use clap::{Parser, ValueEnum};
+
+use super::{AocError, Config, OutputStyle, QuietReporter, Reporter};
+
+/// Which answers a run computes and reports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Part {
+    /// Part 1 only
+    #[value(name = "1")]
+    One,
+    /// Part 2 only; whatever Part 1 work it builds on still runs
+    #[value(name = "2")]
+    Two,
+    /// Both parts
+    #[default]
+    Both,
+}
+
+impl Part {
+    /// Whether the Part 1 answer is reported.
+    pub fn reports_part1(self) -> bool {
+        self != Part::Two
+    }
+
+    /// Whether Part 2 runs.
+    pub fn runs_part2(self) -> bool {
+        self != Part::One
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Part::One => "1",
+            Part::Two => "2",
+            Part::Both => "both",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Destination of `--metrics-json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetricsOutput {
+    /// Standard output, for `--metrics-json` without a path or with `-`
+    Stdout,
+    /// A file, created or truncated once for the whole run
+    File(String),
+}
+
+impl MetricsOutput {
+    /// Opens the destination for writing.
+    ///
+    /// # Errors
+    /// Returns `AocError::Io`, naming the path, if the file can't be
+    /// created.
+    pub fn open(&self) -> Result<Box<dyn Write>, AocError> {
+        Ok(match self {
+            MetricsOutput::Stdout => Box::new(io::stdout()),
+            MetricsOutput::File(path) => Box::new(File::create(path).map_err(|e| {
+                io::Error::new(e.kind(), format!("failed to create {}: {}", path, e))
+            })?),
+        })
+    }
+}
+
+/// The reporter for `style`, except that a quiet run writing
+/// `--metrics-json` to standard output prints only the JSON, which
+/// already holds the answers, so it stays parseable.
+pub fn day_reporter(style: OutputStyle, metrics_json: Option<&MetricsOutput>) -> Box<dyn Reporter> {
+    match (style, metrics_json) {
+        (OutputStyle::Quiet, Some(MetricsOutput::Stdout)) => {
+            Box::new(QuietReporter::new(io::sink()))
+        }
+        (style, _) => style.reporter(),
+    }
+}
+
+impl FromStr for MetricsOutput {
+    type Err = std::convert::Infallible;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Ok(match path {
+            "-" => MetricsOutput::Stdout,
+            path => MetricsOutput::File(path.to_string()),
+        })
+    }
+}
+
+/// The options shared by every day, flattened into each day's parser.
+#[derive(Debug, Clone, Default, PartialEq, clap::Args)]
+pub struct DayArgs {
+    /// Puzzle input paths, `-` for stdin; without one, the day's own input
+    #[arg(value_name = "INPUT")]
+    pub inputs: Vec<String>,
+
+    /// Answers to compute
+    #[arg(long, value_enum, default_value_t = Part::Both, hide_possible_values = true)]
+    pub part: Part,
+
+    /// Print only the answers (or set $AOC_OUTPUT=quiet)
+    #[arg(long)]
+    pub quiet: bool,
+
+    /// Write the metrics as JSON to PATH, or to stdout without one; a path
+    /// must come before any input path
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = "-")]
+    pub metrics_json: Option<MetricsOutput>,
+
+    /// Worker threads, overriding `threads` in aoc.toml and $AOC_THREADS
+    #[arg(long, value_name = "N")]
+    pub threads: Option<NonZeroUsize>,
+}
+
+impl DayArgs {
+    /// The single input path, `None` for the day's default.
+    ///
+    /// # Errors
+    /// Returns `AocError::BadArgument` if several inputs were given to a
+    /// day that reads only one.
+    pub fn single_input(&self) -> Result<Option<&str>, AocError> {
+        match self.inputs.as_slice() {
+            [] => Ok(None),
+            [input] => Ok(Some(input)),
+            [_, extra, ..] => Err(AocError::BadArgument(format!(
+                "unexpected argument `{}`",
+                extra
+            ))),
+        }
+    }
+
+    /// `Quiet` for `--quiet`, else the style `$AOC_OUTPUT` names.
+    ///
+    /// # Errors
+    /// Returns `AocError::BadArgument` if `$AOC_OUTPUT` names no style.
+    pub fn output_style(&self) -> Result<OutputStyle, AocError> {
+        if self.quiet {
+            Ok(OutputStyle::Quiet)
+        } else {
+            OutputStyle::from_env()
+        }
+    }
+
+    /// The settings given as flags, for `Config::load` to layer over the
+    /// file and environment.
+    pub fn config_flags(&self) -> Config {
+        Config {
+            threads: self.threads,
+            ..Config::default()
+        }
+    }
+}
+
+/// Parses the process arguments into a day's parser.
+///
+/// `--help` is printed and the process exits successfully, as clap does
+/// on its own.
+///
+/// # Errors
+/// Returns `AocError::BadArgument` with clap's message and usage for an
+/// unknown, missing, or malformed option.
+pub fn parse_args<P: Parser>() -> Result<P, AocError> {
+    parse_args_from(std::env::args_os())
+}
+
+/// Parses `args`, the program name first, as `parse_args` does.
+pub fn parse_args_from<P, I, T>(args: I) -> Result<P, AocError>
+where
+    P: Parser,
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    P::try_parse_from(args).map_err(|error| match error.kind() {
+        ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => error.exit(),
+        _ => {
+            let rendered = error.render().to_string();
+            // Binaries print their own `Error: ` before the message
+            let message = rendered.strip_prefix("error: ").unwrap_or(&rendered);
+            AocError::BadArgument(message.trim_end().to_string())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    /// A day with a dampener
+    #[derive(Debug, Parser)]
+    #[command(name = "day-99")]
+    struct Cli {
+        #[command(flatten)]
+        common: DayArgs,
+        /// Levels the dampener may remove
+        #[arg(long, default_value_t = 1)]
+        dampener: usize,
+    }
+
+    fn parse(args: &[&str]) -> Result<Cli, AocError> {
+        parse_args_from(std::iter::once("day-99").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn parses_the_shared_options() {
+        let cli = parse(&[]).unwrap();
+        assert_eq!(cli.common, DayArgs::default());
+        assert_eq!(cli.dampener, 1);
+        assert_eq!(cli.common.single_input().unwrap(), None);
+
+        let cli = parse(&[
+            "--part",
+            "1",
+            "--quiet",
+            "--threads",
+            "4",
+            "a.txt",
+            "--dampener",
+            "2",
+            "b.txt",
+        ])
+        .unwrap();
+        assert_eq!(cli.common.inputs, ["a.txt", "b.txt"]);
+        assert_eq!(cli.common.part, Part::One);
+        assert_eq!(cli.common.output_style().unwrap(), OutputStyle::Quiet);
+        assert_eq!(cli.common.config_flags().threads, NonZeroUsize::new(4));
+        assert_eq!(cli.dampener, 2);
+        assert_eq!(
+            cli.common.single_input().unwrap_err().to_string(),
+            "unexpected argument `b.txt`"
+        );
+    }
+
+    #[test]
+    fn takes_an_optional_metrics_path() {
+        let cli = parse(&["--metrics-json"]).unwrap();
+        assert_eq!(cli.common.metrics_json, Some(MetricsOutput::Stdout));
+        let cli = parse(&["--metrics-json", "--quiet"]).unwrap();
+        assert_eq!(cli.common.metrics_json, Some(MetricsOutput::Stdout));
+        // A path right after the flag is the destination, not an input
+        let cli = parse(&["--metrics-json", "out.json", "in.txt"]).unwrap();
+        assert_eq!(
+            cli.common.metrics_json,
+            Some(MetricsOutput::File("out.json".to_string()))
+        );
+        assert_eq!(cli.common.inputs, ["in.txt"]);
+    }
+
+    #[test]
+    fn rejects_bad_usage_as_a_bad_argument() {
+        let error = parse(&["--bogus"]).unwrap_err();
+        assert!(matches!(error, AocError::BadArgument(_)));
+        assert_eq!(
+            error.to_string(),
+            "unexpected argument '--bogus' found\n\n  \
+             tip: to pass '--bogus' as a value, use '-- --bogus'\n\n\
+             Usage: day-99 [OPTIONS] [INPUT]...\n\n\
+             For more information, try '--help'."
+        );
+
+        let error = parse(&["--part", "3"]).unwrap_err().to_string();
+        assert!(
+            error.starts_with("invalid value '3' for '--part <PART>'"),
+            "{}",
+            error
+        );
+        let error = parse(&["--threads", "0"]).unwrap_err().to_string();
+        assert!(
+            error.starts_with("invalid value '0' for '--threads <N>'"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn documents_every_shared_option() {
+        let help = Cli::command().render_help().to_string();
+        assert_eq!(
+            help,
+            "\
+A day with a dampener
+
+Usage: day-99 [OPTIONS] [INPUT]...
+
+Arguments:
+  [INPUT]...  Puzzle input paths, `-` for stdin; without one, the day's own input
+
+Options:
+      --part <PART>            Answers to compute [default: both]
+      --quiet                  Print only the answers (or set $AOC_OUTPUT=quiet)
+      --metrics-json [<PATH>]  Write the metrics as JSON to PATH, or to stdout without one; a path must come before any input path
+      --threads <N>            Worker threads, overriding `threads` in aoc.toml and $AOC_THREADS
+      --dampener <DAMPENER>    Levels the dampener may remove [default: 1]
+  -h, --help                   Print help
+"
+        );
+    }
+
+    #[test]
+    fn selects_the_requested_part() {
+        for (arg, part, answers) in [
+            ("1", Part::One, (true, false)),
+            ("2", Part::Two, (false, true)),
+            ("both", Part::Both, (true, true)),
+        ] {
+            let cli = parse(&["--part", arg]).unwrap();
+            assert_eq!(cli.common.part, part);
+            assert_eq!(part.to_string(), arg);
+            assert_eq!((part.reports_part1(), part.runs_part2()), answers);
+        }
+    }
+}
@@ -0,0 +1,536 @@
+//! Sets of small integers, one bit per possible member.
+//!
+//! Visited cells by flat index, states like `(cell, direction)` packed
+//! into one number, or the positions a dampener removed are all dense
+//! ranges of small integers, and a `HashSet<usize>` spends a hash and a
+//! probe on each. A bit set answers `contains` with a shift and a mask,
+//! and unions and intersections a word of 64 members at a time.
+//!
+//! `BitSet` grows to fit whatever is inserted. `FixedBitSet` holds a
+//! capacity fixed at compile time, needs no allocation, and can be built
+//! in a `const`:
+//!
+//! ```
+//! use aoc_common::FixedBitSet;
+//!
+//! const VOWELS: FixedBitSet<1> = FixedBitSet::new()
+//!     .with(0)
+//!     .with(4)
+//!     .with(8)
+//!     .with(14)
+//!     .with(20);
+//! let is_vowel = |c: char| VOWELS.contains(c as usize - 'a' as usize);
+//! assert_eq!("bitset".chars().filter(|&c| is_vowel(c)).count(), 2);
+//! ```
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign};
+
+/// Bits per word
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A set of `usize`s stored as a bit per value below the largest.
+///
+/// # Implementation Details
+/// - Bit `i % 64` of word `i / 64` is set when `i` is in the set; words
+///   are added as larger values are inserted and never removed, so the
+///   set takes memory for its largest member, not its size
+/// - The member count is kept up to date, so `len` needn't scan
+#[derive(Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// An empty set.
+    pub fn new() -> Self {
+        BitSet::default()
+    }
+
+    /// An empty set with room for the values `0..bits` without growing.
+    pub fn with_capacity(bits: usize) -> Self {
+        BitSet {
+            words: vec![0; bits.div_ceil(WORD_BITS)],
+            len: 0,
+        }
+    }
+
+    /// Adds `value`, growing the set to fit it.
+    ///
+    /// # Returns
+    /// Whether `value` was newly added, as `HashSet::insert` does.
+    #[inline]
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / WORD_BITS, 1 << (value % WORD_BITS));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        self.len += usize::from(added);
+        added
+    }
+
+    /// Whether `value` is in the set.
+    #[inline]
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / WORD_BITS)
+            .is_some_and(|word| word & (1 << (value % WORD_BITS)) != 0)
+    }
+
+    /// Takes `value` out of the set.
+    ///
+    /// # Returns
+    /// Whether `value` was in the set.
+    #[inline]
+    pub fn remove(&mut self, value: usize) -> bool {
+        let Some(word) = self.words.get_mut(value / WORD_BITS) else {
+            return false;
+        };
+        let bit = 1 << (value % WORD_BITS);
+        let removed = *word & bit != 0;
+        *word &= !bit;
+        self.len -= usize::from(removed);
+        removed
+    }
+
+    /// Members in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the set has no members.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Takes every member out, keeping the space for the next ones.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+        self.len = 0;
+    }
+
+    /// Adds every member of `other`.
+    pub fn union_with(&mut self, other: &BitSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, &theirs) in self.words.iter_mut().zip(&other.words) {
+            *word |= theirs;
+        }
+        self.len = count_ones(&self.words);
+    }
+
+    /// Keeps only the members also in `other`.
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        for (i, word) in self.words.iter_mut().enumerate() {
+            *word &= other.words.get(i).copied().unwrap_or(0);
+        }
+        self.len = count_ones(&self.words);
+    }
+
+    /// The members, smallest first.
+    pub fn iter(&self) -> Bits<'_> {
+        Bits::new(&self.words)
+    }
+}
+
+impl PartialEq for BitSet {
+    /// Equal when the members are, however much space either has grown.
+    fn eq(&self, other: &BitSet) -> bool {
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        long[..short.len()] == short[..] && long[short.len()..].iter().all(|&word| word == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+/// Lists the members in order, like a `BTreeSet` does.
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(values: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(values);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, values: I) {
+        for value in values {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Bits<'a>;
+
+    fn into_iter(self) -> Bits<'a> {
+        self.iter()
+    }
+}
+
+impl BitOrAssign<&BitSet> for BitSet {
+    fn bitor_assign(&mut self, other: &BitSet) {
+        self.union_with(other);
+    }
+}
+
+impl BitAndAssign<&BitSet> for BitSet {
+    fn bitand_assign(&mut self, other: &BitSet) {
+        self.intersect_with(other);
+    }
+}
+
+/// The union of two sets.
+impl BitOr for &BitSet {
+    type Output = BitSet;
+
+    fn bitor(self, other: &BitSet) -> BitSet {
+        let mut union = self.clone();
+        union.union_with(other);
+        union
+    }
+}
+
+/// The intersection of two sets.
+impl BitAnd for &BitSet {
+    type Output = BitSet;
+
+    fn bitand(self, other: &BitSet) -> BitSet {
+        let mut intersection = self.clone();
+        intersection.intersect_with(other);
+        intersection
+    }
+}
+
+/// A set of the values `0..64 * WORDS`, in an array that needs no
+/// allocation and can be built and queried in a `const`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedBitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> FixedBitSet<WORDS> {
+    /// Values the set can hold: `0..CAPACITY`
+    pub const CAPACITY: usize = WORDS * WORD_BITS;
+
+    /// An empty set.
+    pub const fn new() -> Self {
+        FixedBitSet { words: [0; WORDS] }
+    }
+
+    /// This set with `value` added, for building one in a `const`.
+    ///
+    /// # Panics
+    /// If `value` is `CAPACITY` or more.
+    pub const fn with(mut self, value: usize) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Adds `value`.
+    ///
+    /// # Returns
+    /// Whether `value` was newly added.
+    ///
+    /// # Panics
+    /// If `value` is `CAPACITY` or more.
+    #[inline]
+    pub const fn insert(&mut self, value: usize) -> bool {
+        assert!(value < Self::CAPACITY, "value past the set's capacity");
+        let bit = 1 << (value % WORD_BITS);
+        let added = self.words[value / WORD_BITS] & bit == 0;
+        self.words[value / WORD_BITS] |= bit;
+        added
+    }
+
+    /// Whether `value` is in the set; nothing past `CAPACITY` ever is.
+    #[inline]
+    pub const fn contains(&self, value: usize) -> bool {
+        value < Self::CAPACITY && self.words[value / WORD_BITS] & (1 << (value % WORD_BITS)) != 0
+    }
+
+    /// Takes `value` out of the set.
+    ///
+    /// # Returns
+    /// Whether `value` was in the set.
+    #[inline]
+    pub const fn remove(&mut self, value: usize) -> bool {
+        if !self.contains(value) {
+            return false;
+        }
+        self.words[value / WORD_BITS] &= !(1 << (value % WORD_BITS));
+        true
+    }
+
+    /// Members in the set, counted from its words.
+    pub const fn len(&self) -> usize {
+        let mut len = 0;
+        let mut i = 0;
+        while i < WORDS {
+            len += self.words[i].count_ones() as usize;
+            i += 1;
+        }
+        len
+    }
+
+    /// Whether the set has no members.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The members of either set.
+    pub const fn union(mut self, other: &Self) -> Self {
+        let mut i = 0;
+        while i < WORDS {
+            self.words[i] |= other.words[i];
+            i += 1;
+        }
+        self
+    }
+
+    /// The members of both sets.
+    pub const fn intersection(mut self, other: &Self) -> Self {
+        let mut i = 0;
+        while i < WORDS {
+            self.words[i] &= other.words[i];
+            i += 1;
+        }
+        self
+    }
+
+    /// The members, smallest first.
+    pub fn iter(&self) -> Bits<'_> {
+        Bits::new(&self.words)
+    }
+}
+
+impl<const WORDS: usize> Default for FixedBitSet<WORDS> {
+    fn default() -> Self {
+        FixedBitSet::new()
+    }
+}
+
+/// Lists the members in order, like a `BTreeSet` does.
+impl<const WORDS: usize> fmt::Debug for FixedBitSet<WORDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, const WORDS: usize> IntoIterator for &'a FixedBitSet<WORDS> {
+    type Item = usize;
+    type IntoIter = Bits<'a>;
+
+    fn into_iter(self) -> Bits<'a> {
+        self.iter()
+    }
+}
+
+/// The members of a `BitSet` or `FixedBitSet`, smallest first.
+#[derive(Debug, Clone)]
+pub struct Bits<'a> {
+    words: &'a [u64],
+    /// Index of the word `current` came from
+    index: usize,
+    /// The bits of that word not yet yielded
+    current: u64,
+}
+
+impl<'a> Bits<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        Bits {
+            words,
+            index: 0,
+            current: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl Iterator for Bits<'_> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        // Whole empty words are skipped at once
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // Clears the lowest set bit
+        self.current &= self.current - 1;
+        Some(self.index * WORD_BITS + bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = self.words.get(self.index + 1..).unwrap_or_default();
+        let left = self.current.count_ones() as usize + count_ones(rest);
+        (left, Some(left))
+    }
+}
+
+impl ExactSizeIterator for Bits<'_> {}
+
+impl FusedIterator for Bits<'_> {}
+
+/// Set bits across `words`.
+fn count_ones(words: &[u64]) -> usize {
+    words.iter().map(|word| word.count_ones() as usize).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::Values;
+    use std::collections::HashSet;
+
+    /// Sorted members of a `HashSet`, to compare against a bit set's.
+    fn sorted(set: &HashSet<usize>) -> Vec<usize> {
+        let mut members: Vec<_> = set.iter().copied().collect();
+        members.sort_unstable();
+        members
+    }
+
+    #[test]
+    fn inserts_removes_and_lists_members() {
+        let mut set = BitSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(set.insert(130));
+        assert!(!set.insert(3));
+        assert!(set.insert(64));
+        assert!(set.insert(0));
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(130) && !set.contains(129) && !set.contains(100_000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0, 3, 64, 130]);
+        assert_eq!(set.iter().len(), 4);
+        assert_eq!(format!("{:?}", set), "{0, 3, 64, 130}");
+
+        assert!(set.remove(64));
+        assert!(!set.remove(64));
+        assert!(!set.remove(100_000));
+        assert_eq!(set.len(), 3);
+        set.clear();
+        assert!(set.is_empty() && set.iter().next().is_none());
+        // Growing without members changes nothing about equality
+        assert_eq!(set, BitSet::new());
+        assert_eq!(BitSet::with_capacity(1000), BitSet::new());
+    }
+
+    #[test]
+    fn combines_sets() {
+        let evens: BitSet = (0..200).step_by(2).collect();
+        let threes: BitSet = (0..100).step_by(3).collect();
+        let union = &evens | &threes;
+        let both = &evens & &threes;
+        assert_eq!(union.len(), 100 + 17);
+        assert_eq!(
+            both.iter().collect::<Vec<_>>(),
+            (0..100).step_by(6).collect::<Vec<_>>()
+        );
+        assert_eq!(both.len(), 17);
+        // Both orders agree, whichever set is longer
+        assert_eq!(&threes | &evens, union);
+        assert_eq!(&threes & &evens, both);
+
+        let mut set = threes.clone();
+        set &= &BitSet::new();
+        assert!(set.is_empty());
+        set |= &evens;
+        assert_eq!(set, evens);
+    }
+
+    #[test]
+    fn matches_a_hash_set_over_random_operations() {
+        let mut values = Values(0x9e37_79b9_7f4a_7c15);
+        for round in 0..50 {
+            // Ranges from a word or two up to a few thousand values
+            let range = 1 + values.below(3000) as usize;
+            let (mut bits, mut hashed) = (BitSet::new(), HashSet::new());
+            let (mut other_bits, mut other_hashed) = (BitSet::new(), HashSet::new());
+            for _ in 0..500 {
+                let value = values.below(range as u64) as usize;
+                match values.below(6) {
+                    0 | 1 => assert_eq!(bits.insert(value), hashed.insert(value)),
+                    2 => assert_eq!(bits.remove(value), hashed.remove(&value)),
+                    3 => assert_eq!(bits.contains(value), hashed.contains(&value)),
+                    _ => assert_eq!(other_bits.insert(value), other_hashed.insert(value)),
+                }
+                assert_eq!(bits.len(), hashed.len(), "round {}", round);
+            }
+            assert_eq!(
+                bits.iter().collect::<Vec<_>>(),
+                sorted(&hashed),
+                "round {}",
+                round
+            );
+
+            let union: HashSet<usize> = hashed.union(&other_hashed).copied().collect();
+            let both: HashSet<usize> = hashed.intersection(&other_hashed).copied().collect();
+            assert_eq!(
+                (&bits | &other_bits).iter().collect::<Vec<_>>(),
+                sorted(&union)
+            );
+            assert_eq!(
+                (&bits & &other_bits).iter().collect::<Vec<_>>(),
+                sorted(&both)
+            );
+            assert_eq!((&bits & &other_bits).len(), both.len());
+
+            // The fixed set agrees too, over the values it can hold
+            let mut fixed = FixedBitSet::<48>::new();
+            let mut fixed_hashed = HashSet::new();
+            for _ in 0..200 {
+                let value = values.below(FixedBitSet::<48>::CAPACITY as u64) as usize;
+                if values.value().is_multiple_of(3) {
+                    assert_eq!(fixed.remove(value), fixed_hashed.remove(&value));
+                } else {
+                    assert_eq!(fixed.insert(value), fixed_hashed.insert(value));
+                }
+            }
+            assert_eq!(fixed.len(), fixed_hashed.len());
+            assert_eq!(fixed.iter().collect::<Vec<_>>(), sorted(&fixed_hashed));
+        }
+    }
+
+    #[test]
+    fn works_in_a_const() {
+        const SMALL: FixedBitSet<1> = FixedBitSet::new().with(1).with(63);
+        const BOTH: FixedBitSet<1> = SMALL.intersection(&FixedBitSet::new().with(63).with(5));
+        const LEN: usize = SMALL.union(&BOTH.with(7)).len();
+        assert_eq!(LEN, 3);
+        assert_eq!(format!("{:?}", BOTH), "{63}");
+        assert!(SMALL.contains(1) && !SMALL.contains(64) && !SMALL.contains(usize::MAX));
+        assert_eq!(FixedBitSet::<2>::CAPACITY, 128);
+
+        let mut set = FixedBitSet::<2>::default();
+        assert!(set.is_empty());
+        assert!(set.insert(127) && !set.insert(127));
+        assert_eq!((&set).into_iter().collect::<Vec<_>>(), [127]);
+        assert!(set.remove(127) && !set.remove(127) && !set.remove(500));
+    }
+
+    #[test]
+    #[should_panic(expected = "value past the set's capacity")]
+    fn refuses_values_past_a_fixed_capacity() {
+        FixedBitSet::<1>::new().insert(64);
+    }
+}
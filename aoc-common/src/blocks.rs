@@ -0,0 +1,130 @@
+//! Splitting inputs made of sections separated by blank lines.
+//!
+//! `input.split("\n\n")` is the obvious way to split such an input, and it
+//! breaks on CRLF endings, on two blank lines in a row, and on a trailing
+//! blank line, each of which puzzle inputs and hand-edited tests have. A
+//! blank line here is any line holding nothing but whitespace.
+
+/// Whether `line` holds nothing but whitespace, including its ending.
+#[inline]
+fn is_blank(line: &str) -> bool {
+    line.bytes().all(|b| b.is_ascii_whitespace())
+}
+
+/// The first block of `text` and everything after it, or `None` if `text`
+/// is blank.
+fn next_block(text: &str) -> Option<(&str, &str)> {
+    let mut offset = 0;
+    let mut start = None;
+    let mut end = 0;
+    for line in text.split_inclusive('\n') {
+        if is_blank(line) {
+            if let Some(start) = start {
+                return Some((&text[start..end], &text[offset..]));
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.trim_end().len();
+        }
+        offset += line.len();
+    }
+    start.map(|start| (&text[start..end], ""))
+}
+
+/// Each block of lines in `input`, separated by one or more blank lines.
+///
+/// Blocks are trimmed of the blank lines around them and of trailing
+/// whitespace, including the last line's `\n` or `\r\n`. Lines inside a
+/// block keep their endings, which `str::lines` strips either way, and the
+/// first line keeps its indentation, since a drawing may start with it.
+///
+/// # Time Complexity
+/// O(n) in the input length over the whole iteration
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        let (block, after) = next_block(rest)?;
+        rest = after;
+        Some(block)
+    })
+}
+
+/// Splits `input` at its first blank line into the first block and the
+/// rest, for the common two-section input.
+///
+/// Both parts are trimmed like `blocks`; the rest may itself hold several
+/// blocks.
+///
+/// # Returns
+/// `None` if `input` has fewer than two blocks.
+pub fn split_once_blank(input: &str) -> Option<(&str, &str)> {
+    let (first, rest) = next_block(input)?;
+    let blank: usize = rest
+        .split_inclusive('\n')
+        .take_while(|line| is_blank(line))
+        .map(str::len)
+        .sum();
+    let second = rest[blank..].trim_end();
+    (!second.is_empty()).then_some((first, second))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(input: &str) -> Vec<&str> {
+        blocks(input).collect()
+    }
+
+    #[test]
+    fn splits_on_blank_lines() {
+        assert_eq!(collect("a\nb\n\nc\n"), ["a\nb", "c"]);
+        assert_eq!(collect("a\n\n\n\nb\n"), ["a", "b"]);
+        assert_eq!(collect("a\n  \t\nb"), ["a", "b"]);
+        assert_eq!(collect("\n\na\n"), ["a"]);
+        assert_eq!(collect("a\nb"), ["a\nb"]);
+        assert!(collect("").is_empty());
+        assert!(collect("\n \n\r\n").is_empty());
+    }
+
+    #[test]
+    fn handles_windows_line_endings() {
+        let input = "47|53\r\n97|13\r\n\r\n75,47,61\r\n97,61\r\n";
+        assert_eq!(collect(input), ["47|53\r\n97|13", "75,47,61\r\n97,61"]);
+        let lines: Vec<_> = collect(input)[0].lines().collect();
+        assert_eq!(lines, ["47|53", "97|13"]);
+    }
+
+    #[test]
+    fn handles_a_missing_or_blank_final_line() {
+        assert_eq!(collect("a\n\nb"), ["a", "b"]);
+        assert_eq!(collect("a\n\nb\n\n"), ["a", "b"]);
+        assert_eq!(collect("a\r\n\r\nb\r\n\r\n\r\n"), ["a", "b"]);
+        assert_eq!(collect("a  \n\nb \t"), ["a", "b"]);
+    }
+
+    #[test]
+    fn keeps_the_first_line_indented() {
+        assert_eq!(
+            collect("\n    [D]\n[N] [C]\n\nmove 1"),
+            ["    [D]\n[N] [C]", "move 1"]
+        );
+    }
+
+    #[test]
+    fn splits_two_sections() {
+        let input = "47|53\n97|13\n\n75,47,61\n97,61\n";
+        assert_eq!(
+            split_once_blank(input),
+            Some(("47|53\n97|13", "75,47,61\n97,61"))
+        );
+        assert_eq!(
+            split_once_blank("a\r\n\r\n\r\nb\r\n\r\nc\r\n"),
+            Some(("a", "b\r\n\r\nc"))
+        );
+        assert_eq!(split_once_blank("a\n\nb"), Some(("a", "b")));
+        assert_eq!(split_once_blank("a\nb\n"), None);
+        assert_eq!(split_once_blank("a\n\n\n"), None);
+        assert_eq!(split_once_blank(""), None);
+    }
+}
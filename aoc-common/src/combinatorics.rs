@@ -0,0 +1,340 @@
+//! Brute-force enumeration: every assignment, pair, or combination.
+//!
+//! Part 1s that try everything, like day 7's operator insertion, want
+//! each candidate in turn rather than all of them up front, since the
+//! search usually stops at the first that works. These are lazy, and none
+//! allocates per candidate: `pairs` yields references, and `cartesian_power`
+//! and `combinations` rewrite one buffer in place, lending it out from
+//! `next` until the following call:
+//!
+//! ```
+//! use aoc_common::cartesian_power;
+//!
+//! // 3267: 81 40 27, from day 7's example
+//! let (target, operands) = (3267, [81, 40, 27]);
+//! let mut assignments = cartesian_power(&['+', '*'], operands.len() - 1);
+//! let mut found = None;
+//! while let Some(operators) = assignments.next() {
+//!     let value = operators.iter().zip(&operands[1..]).fold(operands[0], |acc, (op, &n)| {
+//!         if *op == '+' { acc + n } else { acc * n }
+//!     });
+//!     if value == target {
+//!         found = Some(operators.to_vec());
+//!         break;
+//!     }
+//! }
+//! assert_eq!(found, Some(vec!['+', '*']));
+//! ```
+//!
+//! Every order is lexicographic in the positions of the chosen items, so
+//! runs are reproducible.
+
+/// Every way to fill `n` slots from `choices`, repeats allowed, from
+/// `cartesian_power`.
+#[derive(Debug, Clone)]
+pub struct CartesianPower<'a, T> {
+    choices: &'a [T],
+    /// The index into `choices` for each slot of the current assignment
+    indices: Vec<usize>,
+    current: Vec<T>,
+    started: bool,
+    done: bool,
+}
+
+/// Every assignment of one of `choices` to each of `n` slots, with the last
+/// slot changing fastest: `choices.len()` to the power `n` in all.
+///
+/// With `n` of 0 there is one assignment, the empty one; with no choices
+/// and at least one slot there are none.
+pub fn cartesian_power<T: Clone>(choices: &[T], n: usize) -> CartesianPower<'_, T> {
+    CartesianPower {
+        choices,
+        indices: vec![0; n],
+        current: Vec::with_capacity(n),
+        started: false,
+        done: choices.is_empty() && n > 0,
+    }
+}
+
+impl<T: Clone> CartesianPower<'_, T> {
+    /// The next assignment, valid until the following call.
+    ///
+    /// # Implementation Details
+    /// - Counts like an odometer in base `choices.len()`: the last slot
+    ///   advances, and each slot that wraps back to the first choice
+    ///   carries into the one before it
+    /// - Only the slots that changed are cloned again, which is one slot
+    ///   most of the time
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[T]> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            self.current
+                .extend(self.indices.iter().map(|&i| self.choices[i].clone()));
+            return Some(&self.current);
+        }
+        for slot in (0..self.indices.len()).rev() {
+            let index = &mut self.indices[slot];
+            *index += 1;
+            if *index < self.choices.len() {
+                self.current[slot] = self.choices[*index].clone();
+                return Some(&self.current);
+            }
+            *index = 0;
+            self.current[slot] = self.choices[0].clone();
+        }
+        // Every slot wrapped, so every assignment has been seen
+        self.done = true;
+        None
+    }
+
+    /// The index into `choices` for each slot in the assignment `next` last
+    /// returned.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+/// Every unordered pair of distinct positions in a slice, from `pairs`.
+#[derive(Debug, Clone)]
+pub struct Pairs<'a, T> {
+    items: &'a [T],
+    i: usize,
+    j: usize,
+}
+
+/// Every pair of items at two different positions, `(items[i], items[j])`
+/// for `i < j`, with `j` changing fastest: `n * (n - 1) / 2` in all.
+pub fn pairs<T>(items: &[T]) -> Pairs<'_, T> {
+    Pairs { items, i: 0, j: 1 }
+}
+
+impl<'a, T> Iterator for Pairs<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.j >= self.items.len() {
+            self.i += 1;
+            self.j = self.i + 1;
+            if self.j >= self.items.len() {
+                return None;
+            }
+        }
+        let pair = (&self.items[self.i], &self.items[self.j]);
+        self.j += 1;
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.items.len();
+        // The rest of row `i`, then every later row in full
+        let left = match n.checked_sub(self.i + 1) {
+            Some(later) => n.saturating_sub(self.j) + later * (later.saturating_sub(1)) / 2,
+            None => 0,
+        };
+        (left, Some(left))
+    }
+}
+
+impl<T> ExactSizeIterator for Pairs<'_, T> {}
+
+/// Every choice of `k` items at distinct positions in a slice, from
+/// `combinations`.
+#[derive(Debug, Clone)]
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    /// The increasing positions of the current combination's items
+    indices: Vec<usize>,
+    current: Vec<T>,
+    started: bool,
+    done: bool,
+}
+
+/// Every choice of `k` items from `items`, each in slice order, with the
+/// last position changing fastest: `n` choose `k` in all.
+///
+/// With `k` of 0 there is one combination, the empty one; with `k` past
+/// `items.len()` there are none.
+pub fn combinations<T: Clone>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indices: (0..k).collect(),
+        current: Vec::with_capacity(k),
+        started: false,
+        done: k > items.len(),
+    }
+}
+
+impl<T: Clone> Combinations<'_, T> {
+    /// The next combination, valid until the following call.
+    ///
+    /// # Implementation Details
+    /// - The last position that can still move right does, and every
+    ///   position after it follows on directly behind, so the positions
+    ///   stay increasing
+    /// - Only the moved positions are cloned again
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[T]> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            self.current
+                .extend(self.indices.iter().map(|&i| self.items[i].clone()));
+            return Some(&self.current);
+        }
+        let (n, k) = (self.items.len(), self.indices.len());
+        // Position `slot` can hold at most `n - k + slot`, leaving room for
+        // the positions after it
+        let Some(slot) = (0..k).rev().find(|&slot| self.indices[slot] < n - k + slot) else {
+            self.done = true;
+            return None;
+        };
+        self.indices[slot] += 1;
+        for next in slot..k {
+            self.indices[next] = self.indices[slot] + (next - slot);
+            self.current[next] = self.items[self.indices[next]].clone();
+        }
+        Some(&self.current)
+    }
+
+    /// The positions in `items` of the combination `next` last returned.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `n` choose `k`, exactly, for the small values tested here.
+    fn binomial(n: u64, k: u64) -> u64 {
+        if k > n {
+            return 0;
+        }
+        (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+    }
+
+    /// Every assignment or combination, copied out of the lent buffer.
+    fn collect_power(choices: &[u8], n: usize) -> Vec<Vec<u8>> {
+        let mut all = Vec::new();
+        let mut power = cartesian_power(choices, n);
+        while let Some(next) = power.next() {
+            all.push(next.to_vec());
+        }
+        all
+    }
+
+    fn collect_combinations(items: &[u8], k: usize) -> Vec<Vec<u8>> {
+        let mut all = Vec::new();
+        let mut combinations = combinations(items, k);
+        while let Some(next) = combinations.next() {
+            all.push(next.to_vec());
+        }
+        all
+    }
+
+    #[test]
+    fn enumerates_assignments_in_odometer_order() {
+        assert_eq!(
+            collect_power(&[0, 1, 2], 2),
+            [
+                [0, 0],
+                [0, 1],
+                [0, 2],
+                [1, 0],
+                [1, 1],
+                [1, 2],
+                [2, 0],
+                [2, 1],
+                [2, 2]
+            ]
+        );
+        for choices in 0..=4u8 {
+            let choices: Vec<u8> = (0..choices).collect();
+            for n in 0..=6 {
+                let all = collect_power(&choices, n);
+                assert_eq!(
+                    all.len(),
+                    choices.len().pow(n as u32),
+                    "{:?}^{}",
+                    choices,
+                    n
+                );
+                // Strictly increasing, so every assignment is new and the
+                // order is the same every run
+                assert!(all.windows(2).all(|w| w[0] < w[1]));
+            }
+        }
+
+        let mut power = cartesian_power(&["+", "*", "||"], 3);
+        power.next();
+        power.next();
+        assert_eq!(power.next(), Some(&["+", "+", "||"][..]));
+        assert_eq!(power.indices(), [0, 0, 2]);
+    }
+
+    #[test]
+    fn enumerates_pairs_in_order() {
+        let pairs: Vec<_> = pairs(&['a', 'b', 'c', 'd']).collect();
+        assert_eq!(
+            pairs,
+            [
+                (&'a', &'b'),
+                (&'a', &'c'),
+                (&'a', &'d'),
+                (&'b', &'c'),
+                (&'b', &'d'),
+                (&'c', &'d')
+            ]
+        );
+        for n in 0..=30u8 {
+            let items: Vec<u8> = (0..n).collect();
+            let mut iter = super::pairs(&items);
+            let expected = binomial(n.into(), 2) as usize;
+            assert_eq!(iter.len(), expected);
+            iter.next();
+            assert_eq!(iter.len(), expected.saturating_sub(1));
+            assert_eq!(iter.count(), expected.saturating_sub(1));
+        }
+    }
+
+    #[test]
+    fn enumerates_combinations_in_lexicographic_order() {
+        assert_eq!(
+            collect_combinations(&[1, 2, 3, 4], 2),
+            [[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]]
+        );
+        assert_eq!(collect_combinations(&[1, 2, 3], 0), [Vec::<u8>::new()]);
+        assert!(collect_combinations(&[1, 2], 3).is_empty());
+        for n in 0..=10u8 {
+            let items: Vec<u8> = (0..n).collect();
+            for k in 0..=n + 1 {
+                let all = collect_combinations(&items, k.into());
+                assert_eq!(
+                    all.len() as u64,
+                    binomial(n.into(), k.into()),
+                    "{} choose {}",
+                    n,
+                    k
+                );
+                assert!(all.windows(2).all(|w| w[0] < w[1]));
+                // Pairs are the same as combinations of two
+                if k == 2 {
+                    let pairs: Vec<_> = pairs(&items).map(|(&a, &b)| vec![a, b]).collect();
+                    assert_eq!(pairs, all);
+                }
+            }
+        }
+
+        let mut combinations = combinations(&['x', 'y', 'z', 'w'], 3);
+        combinations.next();
+        assert_eq!(combinations.next(), Some(&['x', 'y', 'w'][..]));
+        assert_eq!(combinations.indices(), [0, 1, 3]);
+    }
+}
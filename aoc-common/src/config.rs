@@ -0,0 +1,265 @@
+//! Per-machine settings from `aoc.toml`, the environment, and flags.
+//!
+//! A setting can come from four places. From lowest to highest
+//! precedence, they are:
+//! 1. the config file, which is `aoc.toml` at the workspace root or,
+//!    failing that, `$XDG_CONFIG_HOME/aoc/config.toml`; without
+//!    `$XDG_CONFIG_HOME` that is `~/.config/aoc/config.toml`
+//! 2. environment variables: `$AOC_INPUT_DIR`, `$AOC_YEAR`,
+//!    `$AOC_SESSION_COOKIE` and `$AOC_THREADS`
+//! 3. command-line flags, which a binary passes in as a `Config` of its own
+//! 4. the default each consumer falls back to when a setting is `None`
+//!
+//! A file example:
+//!
+//! ```toml
+//! input_dir = "/home/me/aoc/inputs"
+//! year = 2024
+//! session_cookie = "/home/me/.config/aoc/session"
+//! threads = 8
+//! ```
+//!
+//! Keys the file doesn't define are warned about and otherwise ignored,
+//! so a file shared between versions of the workspace keeps working.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+use super::input::{workspace_root, INPUT_DIR_VAR};
+use super::AocError;
+
+/// File name of the config at the workspace root
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Environment variable naming the puzzle year
+pub const YEAR_VAR: &str = "AOC_YEAR";
+
+/// Environment variable naming the file holding the session cookie
+pub const SESSION_COOKIE_VAR: &str = "AOC_SESSION_COOKIE";
+
+/// Environment variable setting the worker thread count
+pub const THREADS_VAR: &str = "AOC_THREADS";
+
+/// Settings shared by every binary; `None` leaves a setting to the next
+/// source down, and finally to whoever reads it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Directory holding inputs named `day-NN.txt`
+    pub input_dir: Option<PathBuf>,
+    /// Advent of Code year the inputs are from
+    pub year: Option<u16>,
+    /// File holding the adventofcode.com session cookie
+    pub session_cookie: Option<PathBuf>,
+    /// Worker threads for the days that parallelize
+    pub threads: Option<NonZeroUsize>,
+    /// Keys the file had that aren't settings, kept to be warned about
+    #[serde(flatten, skip_serializing)]
+    pub unknown: BTreeMap<String, IgnoredAny>,
+}
+
+impl Config {
+    /// The settings a binary runs with: the config file, overridden by
+    /// the environment, overridden by `flags`.
+    ///
+    /// The workspace is looked for from the working directory and then
+    /// from `crate_dir`, as `input::resolve` does. Unknown keys in the file
+    /// are warned about on stderr.
+    ///
+    /// # Errors
+    /// Returns `AocError::Config` if the config file can't be read or
+    /// parsed, or an environment variable doesn't parse.
+    pub fn load(crate_dir: &Path, flags: Config) -> Result<Config, AocError> {
+        let start_dir = env::current_dir().unwrap_or_else(|_| crate_dir.to_path_buf());
+        let file = match config_path(&start_dir, crate_dir, config_home().as_deref()) {
+            Some(path) => {
+                let config = Config::read(&path)?;
+                for key in config.unknown.keys() {
+                    eprintln!("Warning: {}: unknown key `{}`", path.display(), key);
+                }
+                config
+            }
+            None => Config::default(),
+        };
+        Ok(file
+            .merged(Config::from_vars(|name| env::var(name).ok())?)
+            .merged(flags))
+    }
+
+    /// Reads and parses one config file.
+    ///
+    /// # Errors
+    /// Returns `AocError::Config` naming the file if it can't be read or
+    /// isn't a valid config.
+    pub fn read(path: &Path) -> Result<Config, AocError> {
+        let error = |message: String| AocError::Config {
+            origin: path.display().to_string(),
+            message,
+        };
+        let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        toml::from_str(&text).map_err(|e| error(e.message().to_string()))
+    }
+
+    /// The settings named by environment variables, looked up by `var`.
+    ///
+    /// # Errors
+    /// Returns `AocError::Config` naming the variable if a number doesn't
+    /// parse.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Config, AocError> {
+        Ok(Config {
+            input_dir: var(INPUT_DIR_VAR).map(PathBuf::from),
+            year: number(&var, YEAR_VAR)?,
+            session_cookie: var(SESSION_COOKIE_VAR).map(PathBuf::from),
+            threads: number(&var, THREADS_VAR)?,
+            unknown: BTreeMap::new(),
+        })
+    }
+
+    /// These settings with every one that `over` sets replaced by its.
+    pub fn merged(self, over: Config) -> Config {
+        Config {
+            input_dir: over.input_dir.or(self.input_dir),
+            year: over.year.or(self.year),
+            session_cookie: over.session_cookie.or(self.session_cookie),
+            threads: over.threads.or(self.threads),
+            unknown: self.unknown,
+        }
+    }
+}
+
+/// The number in the environment variable `name`, if it's set; a thread
+/// count of zero doesn't parse.
+fn number<T: FromStr>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+) -> Result<Option<T>, AocError> {
+    var(name)
+        .map(|value| {
+            value.trim().parse().map_err(|_| AocError::Config {
+                origin: format!("${}", name),
+                message: format!("could not parse `{}`", value),
+            })
+        })
+        .transpose()
+}
+
+/// The user's config directory: `$XDG_CONFIG_HOME`, or `~/.config`.
+fn config_home() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+}
+
+/// The config file to read: `aoc.toml` in the workspace enclosing
+/// `start_dir` or `crate_dir`, else `aoc/config.toml` in `config_home`.
+fn config_path(start_dir: &Path, crate_dir: &Path, config_home: Option<&Path>) -> Option<PathBuf> {
+    let workspace = [start_dir, crate_dir]
+        .into_iter()
+        .filter_map(workspace_root)
+        .map(|root| root.join(CONFIG_FILE));
+    let user = config_home.map(|dir| dir.join("aoc").join("config.toml"));
+    workspace.chain(user).find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(text: &str) -> Config {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let original = Config {
+            input_dir: Some(PathBuf::from("/srv/aoc/inputs")),
+            year: Some(2024),
+            session_cookie: Some(PathBuf::from("/home/me/.aoc-session")),
+            threads: NonZeroUsize::new(8),
+            ..Config::default()
+        };
+        let text = toml::to_string(&original).unwrap();
+        assert_eq!(config(&text), original);
+        assert_eq!(config(""), Config::default());
+    }
+
+    #[test]
+    fn keeps_unknown_keys_to_warn_about() {
+        let parsed = config("threads = 2\ncolour = true\n[download]\nretries = 3\n");
+        assert_eq!(parsed.threads, NonZeroUsize::new(2));
+        let unknown: Vec<_> = parsed.unknown.keys().collect();
+        assert_eq!(unknown, ["colour", "download"]);
+    }
+
+    #[test]
+    fn flags_override_the_environment_which_overrides_the_file() {
+        let file = config("input_dir = \"file\"\nyear = 2023\nthreads = 4\n");
+        let env = Config::from_vars(|name| match name {
+            "AOC_INPUT_DIR" => Some("env".to_string()),
+            "AOC_THREADS" => Some("6".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        let flags = Config {
+            threads: NonZeroUsize::new(1),
+            ..Config::default()
+        };
+
+        let merged = file.merged(env).merged(flags);
+        assert_eq!(merged.input_dir, Some(PathBuf::from("env")));
+        assert_eq!(merged.year, Some(2023));
+        assert_eq!(merged.threads, NonZeroUsize::new(1));
+        assert_eq!(merged.session_cookie, None);
+    }
+
+    #[test]
+    fn rejects_malformed_settings() {
+        let error = Config::from_vars(|name| (name == THREADS_VAR).then(|| "many".to_string()));
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "$AOC_THREADS: could not parse `many`"
+        );
+        let error = Config::from_vars(|name| (name == THREADS_VAR).then(|| "0".to_string()));
+        assert!(error.is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(&path, "threads = \"eight\"\n").unwrap();
+        let message = Config::read(&path).unwrap_err().to_string();
+        assert!(
+            message.starts_with(&format!("{}: ", path.display())),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn prefers_the_workspace_file_over_the_user_one() {
+        let workspace = tempfile::tempdir().unwrap();
+        let root = workspace.path();
+        fs::write(root.join("Cargo.toml"), "[workspace]\n").unwrap();
+        fs::create_dir_all(root.join("day-01/rs")).unwrap();
+        let home = tempfile::tempdir().unwrap();
+        fs::create_dir_all(home.path().join("aoc")).unwrap();
+        fs::write(home.path().join("aoc/config.toml"), "year = 2023\n").unwrap();
+
+        let crate_dir = root.join("day-01/rs");
+        let user = home.path().join("aoc/config.toml");
+        assert_eq!(config_path(root, &crate_dir, Some(home.path())), Some(user));
+
+        fs::write(root.join(CONFIG_FILE), "year = 2024\n").unwrap();
+        let elsewhere = tempfile::tempdir().unwrap();
+        assert_eq!(
+            config_path(elsewhere.path(), &crate_dir, Some(home.path())),
+            Some(root.join(CONFIG_FILE))
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! What a run hands a solver besides its input.
+//!
+//! Flags keep multiplying, from the thread count to day 2's step bounds
+//! and lenient parsing, and threading each through free functions meant
+//! every new one touched every signature on the way down. An `AocContext`
+//! is built once where the run starts, from the merged `Config` and the
+//! command line, and every `Solver` step takes it: the `Reporter` answers
+//! and notes go to, the `Metrics` a day records its own phases and
+//! counters in, the parts to answer, the threads its parallel work may
+//! use, and a bag of day-specific options by name.
+//!
+//! Options are strings, `min-step=2` or just `lenient` on the command line,
+//! and each day parses the ones it reads and names the ones it knows, so a
+//! misspelled option is an error rather than silently ignored. A test
+//! builds a context with `new` and `with_option`, and solves an input
+//! without any command line or file at all.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{parallelism, AocError, Config, Metrics, Part, Reporter};
+
+/// The settings, output, and scratch metrics shared by every step of one
+/// run of a solver.
+pub struct AocContext<'a> {
+    /// The merged settings the run started with
+    pub config: Config,
+    /// Where answers, timings, and notes go
+    pub out: &'a mut dyn Reporter,
+    /// Phases and counters the solver records beyond its three steps
+    pub metrics: Metrics,
+    /// Parts `solve` answers
+    part: Part,
+    /// Threads the solver's parallel work may use, or `None` for what
+    /// `parallelism` reports once it's asked
+    threads: Option<usize>,
+    /// Day-specific options by name, `""` for one given without a value
+    options: BTreeMap<String, String>,
+}
+
+impl<'a> AocContext<'a> {
+    /// A context reporting to `out`, answering both parts with no options,
+    /// on as many threads as `parallelism` reports.
+    pub fn new(config: Config, out: &'a mut dyn Reporter) -> Self {
+        AocContext {
+            config,
+            out,
+            metrics: Metrics::new(),
+            part: Part::Both,
+            threads: None,
+            options: BTreeMap::new(),
+        }
+    }
+
+    /// This context answering only `part`, or both.
+    pub fn with_part(mut self, part: Part) -> Self {
+        self.part = part;
+        self
+    }
+
+    /// This context with `threads` threads for parallel work, at least 1.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads.max(1));
+        self
+    }
+
+    /// This context with option `key` set to `value`.
+    pub fn with_option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets options from the command line, each `key=value`, or a bare
+    /// `key` for a flag; a later value for a key replaces an earlier one.
+    ///
+    /// # Errors
+    /// Returns `AocError::BadArgument` for an empty key.
+    pub fn set_options<S: AsRef<str>>(
+        &mut self,
+        pairs: impl IntoIterator<Item = S>,
+    ) -> Result<(), AocError> {
+        for pair in pairs {
+            let pair = pair.as_ref();
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            if key.is_empty() {
+                return Err(AocError::BadArgument(format!(
+                    "expected an option as KEY=VALUE (got `{}`)",
+                    pair
+                )));
+            }
+            self.options.insert(key.to_string(), value.to_string());
+        }
+        Ok(())
+    }
+
+    /// Parts `solve` answers.
+    #[inline]
+    pub fn part(&self) -> Part {
+        self.part
+    }
+
+    /// Threads the solver's parallel work may use, 1 to run serially.
+    ///
+    /// Unless `with_threads` set a count, this is `parallelism`, which
+    /// starts the rayon pool if nothing has yet.
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.threads.unwrap_or_else(parallelism)
+    }
+
+    /// Option `key` parsed as a `T`, or `None` if it wasn't given.
+    ///
+    /// # Errors
+    /// Returns `AocError::BadArgument` naming the option if its value
+    /// doesn't parse.
+    pub fn option<T>(&self, key: &str) -> Result<Option<T>, AocError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.options
+            .get(key)
+            .map(|value| {
+                value.parse().map_err(|e| {
+                    AocError::BadArgument(format!("option `{}`: `{}`: {}", key, value, e))
+                })
+            })
+            .transpose()
+    }
+
+    /// Whether flag `key` is on: given bare or as `true`, and off if it
+    /// is missing or `false`.
+    ///
+    /// # Errors
+    /// Returns `AocError::BadArgument` naming the flag for any other value.
+    pub fn flag(&self, key: &str) -> Result<bool, AocError> {
+        match self.options.get(key).map(String::as_str) {
+            None | Some("false") => Ok(false),
+            Some("" | "true") => Ok(true),
+            Some(other) => Err(AocError::BadArgument(format!(
+                "option `{}` is a flag, so it takes `true` or `false` (got `{}`)",
+                key, other
+            ))),
+        }
+    }
+
+    /// Checks that every option given is one of `known`, the options day
+    /// `day` reads.
+    ///
+    /// # Errors
+    /// Returns `AocError::BadArgument` naming the first unknown option and
+    /// listing the known ones.
+    pub fn expect_options(&self, day: u8, known: &[&str]) -> Result<(), AocError> {
+        match self
+            .options
+            .keys()
+            .find(|key| !known.contains(&key.as_str()))
+        {
+            None => Ok(()),
+            Some(key) if known.is_empty() => Err(AocError::BadArgument(format!(
+                "unknown option `{}`; day {} takes no options",
+                key, day
+            ))),
+            Some(key) => Err(AocError::BadArgument(format!(
+                "unknown option `{}` for day {}; expected one of: {}",
+                key,
+                day,
+                known.join(", ")
+            ))),
+        }
+    }
+}
+
+impl fmt::Debug for AocContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AocContext")
+            .field("config", &self.config)
+            .field("part", &self.part)
+            .field("threads", &self.threads)
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RecordingReporter;
+
+    #[test]
+    fn parses_options_by_name() {
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out)
+            .with_option("max-step", "5")
+            .with_threads(0);
+        ctx.set_options(["min-step=2", "lenient", "max-step=4"])
+            .unwrap();
+        assert_eq!(ctx.threads(), 1);
+        assert_eq!(ctx.option::<i32>("min-step").unwrap(), Some(2));
+        assert_eq!(ctx.option::<i32>("max-step").unwrap(), Some(4));
+        assert_eq!(ctx.option::<i32>("dampener").unwrap(), None);
+        assert!(ctx.flag("lenient").unwrap());
+        assert!(!ctx.flag("allow-plateaus").unwrap());
+
+        let ctx = ctx.with_option("lenient", "false");
+        assert!(!ctx.flag("lenient").unwrap());
+    }
+
+    #[test]
+    fn names_the_option_at_fault() {
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out)
+            .with_option("min-step", "x")
+            .with_option("lenient", "yes");
+        assert_eq!(
+            ctx.option::<i32>("min-step").unwrap_err().to_string(),
+            "option `min-step`: `x`: invalid digit found in string"
+        );
+        assert_eq!(
+            ctx.flag("lenient").unwrap_err().to_string(),
+            "option `lenient` is a flag, so it takes `true` or `false` (got `yes`)"
+        );
+        assert_eq!(
+            ctx.set_options(["=3"]).unwrap_err().to_string(),
+            "expected an option as KEY=VALUE (got `=3`)"
+        );
+
+        assert!(ctx.expect_options(2, &["lenient", "min-step"]).is_ok());
+        assert_eq!(
+            ctx.expect_options(2, &["min-step"])
+                .unwrap_err()
+                .to_string(),
+            "unknown option `lenient` for day 2; expected one of: min-step"
+        );
+        assert_eq!(
+            ctx.expect_options(1, &[]).unwrap_err().to_string(),
+            "unknown option `lenient`; day 1 takes no options"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Counting how often each value occurs.
+//!
+//! A `HashMap<T, u64>` does the job, with `*map.entry(v).or_default() += 1`
+//! to count and `map.get(&v).copied().unwrap_or(0)` to read, and its
+//! arbitrary order changes from run to run when printed. `Counter` is that
+//! map wrapped in the operations puzzles ask for: counts that read as 0
+//! when missing, the most common values, and counters added together, with
+//! displayed output sorted so two runs print the same thing.
+
+use std::collections::hash_map::{self, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+/// How many times each value has been seen.
+///
+/// Counts are never zero: a value whose count drops to 0 is removed, so
+/// `len` is the number of distinct values present.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Hash + Eq> {
+    counts: HashMap<T, u64>,
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    /// An empty counter.
+    pub fn new() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Counts `value` once more.
+    #[inline]
+    pub fn insert(&mut self, value: T) {
+        self.insert_n(value, 1);
+    }
+
+    /// Counts `value` `n` more times.
+    #[inline]
+    pub fn insert_n(&mut self, value: T, n: u64) {
+        if n > 0 {
+            *self.counts.entry(value).or_default() += n;
+        }
+    }
+
+    /// Times `value` has been counted, 0 if never.
+    #[inline]
+    pub fn count(&self, value: &T) -> u64 {
+        self.counts.get(value).copied().unwrap_or(0)
+    }
+
+    /// Distinct values counted.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Whether nothing has been counted.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Sum of every count, the number of values counted.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Each value with its count, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, u64)> {
+        self.counts.iter().map(|(value, &count)| (value, count))
+    }
+
+    /// Each value with its count, sorted by value.
+    pub fn sorted(&self) -> Vec<(&T, u64)>
+    where
+        T: Ord,
+    {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries
+    }
+
+    /// The `n` values counted most, highest count first and the smaller
+    /// value first on ties, so the result doesn't depend on hash order.
+    pub fn most_common(&self, n: usize) -> Vec<(&T, u64)>
+    where
+        T: Ord,
+    {
+        let mut entries: Vec<_> = self.iter().collect();
+        let by_count = |a: &(&T, u64), b: &(&T, u64)| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0));
+        if n < entries.len() {
+            entries.select_nth_unstable_by(n, by_count);
+            entries.truncate(n);
+        }
+        entries.sort_unstable_by(by_count);
+        entries
+    }
+}
+
+impl<T: Hash + Eq> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(values);
+        counter
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: Hash + Eq> IntoIterator for Counter<T> {
+    type Item = (T, u64);
+    type IntoIter = hash_map::IntoIter<T, u64>;
+
+    /// Each value with its count, in arbitrary order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+/// Adds the other counter's counts to these.
+impl<T: Hash + Eq + Clone> AddAssign<&Counter<T>> for Counter<T> {
+    fn add_assign(&mut self, other: &Counter<T>) {
+        for (value, count) in other.iter() {
+            self.insert_n(value.clone(), count);
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> Add<&Counter<T>> for Counter<T> {
+    type Output = Counter<T>;
+
+    fn add(mut self, other: &Counter<T>) -> Counter<T> {
+        self += other;
+        self
+    }
+}
+
+/// Takes the other counter's counts from these, stopping at 0: a value
+/// counted more times there than here is dropped, not made negative.
+impl<T: Hash + Eq> SubAssign<&Counter<T>> for Counter<T> {
+    fn sub_assign(&mut self, other: &Counter<T>) {
+        self.counts.retain(|value, count| {
+            *count = count.saturating_sub(other.count(value));
+            *count > 0
+        });
+    }
+}
+
+impl<T: Hash + Eq> Sub<&Counter<T>> for Counter<T> {
+    type Output = Counter<T>;
+
+    fn sub(mut self, other: &Counter<T>) -> Counter<T> {
+        self -= other;
+        self
+    }
+}
+
+/// Shows `{value: count, ...}` sorted by value.
+impl<T: Hash + Eq + Ord + fmt::Display> fmt::Display for Counter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (value, count)) in self.sorted().into_iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", value, count)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_values_and_reads_missing_ones_as_zero() {
+        let counter: Counter<i64> = [3, 4, 2, 1, 3, 3].into_iter().collect();
+        assert_eq!(counter.count(&3), 3);
+        assert_eq!(counter.count(&1), 1);
+        assert_eq!(counter.count(&5), 0);
+        assert_eq!((counter.len(), counter.total()), (4, 6));
+
+        let mut counter = Counter::new();
+        assert!(counter.is_empty());
+        counter.insert_n("a", 0);
+        assert!(counter.is_empty());
+        counter.insert_n("a", 2);
+        counter.insert("a");
+        assert_eq!(counter.count(&"a"), 3);
+    }
+
+    #[test]
+    fn adds_and_subtracts_counters() {
+        let a: Counter<char> = "aabbbc".chars().collect();
+        let b: Counter<char> = "abd".chars().collect();
+
+        let sum = a.clone() + &b;
+        assert_eq!(sum.to_string(), "{a: 3, b: 4, c: 1, d: 1}");
+        assert_eq!(sum.total(), a.total() + b.total());
+
+        // Counts stop at zero, and values reaching it are gone
+        let difference = a.clone() - &b;
+        assert_eq!(difference.to_string(), "{a: 1, b: 2, c: 1}");
+        let gone = b.clone() - &a;
+        assert_eq!(gone.to_string(), "{d: 1}");
+        assert_eq!(gone.len(), 1);
+
+        let mut round_trip = a.clone();
+        round_trip += &b;
+        round_trip -= &b;
+        assert_eq!(round_trip, a);
+        assert!((a.clone() - &a).is_empty());
+    }
+
+    #[test]
+    fn orders_output_deterministically() {
+        let counter: Counter<i64> = [5, -1, 3, 5, 3, 7, 5, 3, 9, 9].into_iter().collect();
+        assert_eq!(
+            counter.sorted(),
+            [(&-1, 1), (&3, 3), (&5, 3), (&7, 1), (&9, 2)]
+        );
+        assert_eq!(counter.to_string(), "{-1: 1, 3: 3, 5: 3, 7: 1, 9: 2}");
+
+        // Ties go to the smaller value
+        assert_eq!(counter.most_common(3), [(&3, 3), (&5, 3), (&9, 2)]);
+        assert_eq!(
+            counter.most_common(4),
+            [(&3, 3), (&5, 3), (&9, 2), (&-1, 1)]
+        );
+        assert_eq!(counter.most_common(10).len(), 5);
+        assert!(counter.most_common(0).is_empty());
+        assert_eq!(Counter::<i64>::new().to_string(), "{}");
+    }
+}
@@ -0,0 +1,256 @@
+//! Parse errors shown under the line of input they point at.
+//!
+//! `AocError::Parse` names a line and a column range, which is enough to
+//! find the fault but not to see it. `render` lays the error out the way
+//! rustc does, with the line quoted under its number and `^` under the
+//! columns at fault:
+//!
+//! ```text
+//! line 3, columns 5-6: could not parse `5x` as an integer
+//!   |
+//! 3 | 1 2 5x 4
+//!   |     ^^
+//! ```
+//!
+//! Binaries call `AocError::in_input` or `AocError::on_line` where the
+//! input is still at hand, so the rendered error is what they print.
+
+use std::ops::Range;
+
+use super::AocError;
+
+/// Display columns a tab expands to
+const TAB_WIDTH: usize = 4;
+
+/// Longest stretch of a line quoted; longer lines are cut around the span
+const MAX_WIDTH: usize = 80;
+
+/// Display columns kept before the span when a long line is cut
+const CONTEXT: usize = 16;
+
+/// Renders `msg` above line `line` of `input`, underlining `columns`.
+///
+/// # Arguments
+/// * `line` - 1-based line number, counting `\n`s
+/// * `columns` - 1-based, inclusive-exclusive byte range into the line,
+///   as `AocError::Parse` holds
+///
+/// # Implementation Details
+/// - A line past the end of `input` is quoted as empty, with the caret at
+///   its start
+/// - See `render_line` for how the line itself is laid out
+pub fn render(input: &str, line: usize, columns: Range<usize>, msg: &str) -> String {
+    let text = input.split('\n').nth(line.saturating_sub(1)).unwrap_or("");
+    render_line(text, line, columns, msg)
+}
+
+/// Renders `msg` above `text`, which is line `line` of the input,
+/// underlining `columns`.
+///
+/// # Implementation Details
+/// - A trailing `\n` or `\r\n` is dropped, and tabs expand to `TAB_WIDTH`
+///   spaces with the carets expanded to match
+/// - Byte columns are widened to whole characters, so a span starting or
+///   ending inside a multi-byte character never splits it, and each
+///   character takes one display column
+/// - An empty span, or one past the end of the line, gets a single caret
+/// - A line wider than `MAX_WIDTH` is cut to `MAX_WIDTH` columns starting
+///   up to `CONTEXT` columns before the span, with `...` marking each cut
+pub fn render_line(text: &str, line: usize, columns: Range<usize>, msg: &str) -> String {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    let text = text.strip_suffix('\r').unwrap_or(text);
+
+    // Byte span, clamped to the line and widened to character boundaries
+    let mut start = columns.start.saturating_sub(1).min(text.len());
+    let mut end = columns.end.saturating_sub(1).clamp(start, text.len());
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+
+    // The line in display columns, with the span's position in them
+    let mut shown = Vec::with_capacity(text.len());
+    let (mut caret_start, mut caret_end) = (0, 0);
+    for (i, c) in text.char_indices() {
+        if i == start {
+            caret_start = shown.len();
+        }
+        if i == end {
+            caret_end = shown.len();
+        }
+        match c {
+            '\t' => shown.extend([' '; TAB_WIDTH]),
+            c => shown.push(c),
+        }
+    }
+    if start == text.len() {
+        caret_start = shown.len();
+    }
+    if end == text.len() {
+        caret_end = shown.len();
+    }
+    let caret_end = caret_end.max(caret_start + 1);
+
+    // Cut long lines to a window around the span
+    let (mut from, mut to) = (0, shown.len());
+    if shown.len() > MAX_WIDTH {
+        from = caret_start.saturating_sub(CONTEXT);
+        to = (from + MAX_WIDTH).min(shown.len());
+        from = to - MAX_WIDTH;
+    }
+    let before = if from > 0 { "..." } else { "" };
+    let after = if to < shown.len() { "..." } else { "" };
+    let quoted: String = shown[from..to].iter().collect();
+    let indent = before.len() + caret_start - from;
+    let carets = caret_end.min(to.max(caret_start + 1)) - caret_start;
+
+    let number = line.to_string();
+    let gutter = " ".repeat(number.len());
+    format!(
+        "{}\n{} |\n{} | {}{}{}\n{} | {}{}",
+        msg,
+        gutter,
+        number,
+        before,
+        quoted,
+        after,
+        gutter,
+        " ".repeat(indent),
+        "^".repeat(carets)
+    )
+}
+
+impl AocError {
+    /// This error rendered under the line of `input` it points at, if it
+    /// is a `Parse` error; any other error is returned unchanged.
+    pub fn in_input(self, input: &str) -> AocError {
+        match &self {
+            AocError::Parse { line, columns, .. } => {
+                let rendered = render(input, *line, columns.clone(), &self.to_string());
+                self.annotated(rendered)
+            }
+            _ => self,
+        }
+    }
+
+    /// This error rendered under `text`, the line it points at, if it is a
+    /// `Parse` error; any other error is returned unchanged.
+    pub fn on_line(self, text: &str) -> AocError {
+        match &self {
+            AocError::Parse { line, columns, .. } => {
+                let rendered = render_line(text, *line, columns.clone(), &self.to_string());
+                self.annotated(rendered)
+            }
+            _ => self,
+        }
+    }
+
+    /// Wraps this error with its rendering.
+    fn annotated(self, rendered: String) -> AocError {
+        AocError::Annotated {
+            rendered,
+            error: Box::new(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn underlines_a_bad_token() {
+        let input = "1 2\n3 4\n1 2 5x 4\n";
+        assert_eq!(
+            render(input, 3, 5..7, "could not parse `5x` as an integer"),
+            "could not parse `5x` as an integer\n\
+             \x20 |\n\
+             3 | 1 2 5x 4\n\
+             \x20 |     ^^"
+        );
+    }
+
+    #[test]
+    fn widens_the_gutter_for_long_line_numbers() {
+        let input = "7\n".repeat(99) + "8 9 x\r\n";
+        assert_eq!(
+            render(&input, 100, 5..6, "bad"),
+            "bad\n    |\n100 | 8 9 x\n    |     ^"
+        );
+    }
+
+    #[test]
+    fn expands_tabs_before_the_span() {
+        assert_eq!(
+            render_line("1\t2\tzz", 1, 5..7, "bad"),
+            "bad\n  |\n1 | 1    2    zz\n  |           ^^"
+        );
+    }
+
+    #[test]
+    fn keeps_multi_byte_characters_whole() {
+        // `é` is two bytes; a span from its second byte still covers all of it
+        assert_eq!(
+            render_line("1 é3 4", 1, 4..6, "bad"),
+            "bad\n  |\n1 | 1 é3 4\n  |   ^^"
+        );
+        assert_eq!(
+            render_line("日本 x", 1, 8..9, "bad"),
+            "bad\n  |\n1 | 日本 x\n  |    ^"
+        );
+    }
+
+    #[test]
+    fn points_past_the_end_of_a_short_line() {
+        assert_eq!(
+            render_line("3 4", 2, 4..5, "expected 3 numbers, found 2"),
+            "expected 3 numbers, found 2\n  |\n2 | 3 4\n  |    ^"
+        );
+        assert_eq!(render("1\n", 5, 1..2, "gone"), "gone\n  |\n5 | \n  | ^");
+    }
+
+    #[test]
+    fn cuts_long_lines_around_the_span() {
+        let line = format!("{}oops{}", "1 ".repeat(60), " 2".repeat(60));
+        let rendered = render_line(&line, 1, 121..125, "bad");
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(
+            lines[2],
+            format!("1 | ...{}oops{}...", "1 ".repeat(8), " 2".repeat(30))
+        );
+        assert_eq!(lines[3], format!("  | {}^^^^", " ".repeat(3 + 16)));
+
+        // Near either end, the window stops at it
+        let rendered = render_line(&line, 1, 1..2, "bad");
+        assert!(rendered.contains(&format!("1 | {}...", &line[..MAX_WIDTH])));
+        let rendered = render_line(&line, 1, 244..245, "bad");
+        let tail = &line[line.len() - MAX_WIDTH..];
+        assert!(
+            rendered.contains(&format!("1 | ...{}\n", tail)),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn annotates_only_parse_errors() {
+        let error = AocError::parse(2, 3..4, "could not parse `x` as an integer");
+        let annotated = error.in_input("1 2\n3 x\n");
+        assert_eq!(
+            annotated.to_string(),
+            "line 2, columns 3-3: could not parse `x` as an integer\n  |\n2 | 3 x\n  |   ^"
+        );
+        assert_eq!(
+            AocError::parse(2, 3..4, "bad").on_line("3 x").to_string(),
+            "line 2, columns 3-3: bad\n  |\n2 | 3 x\n  |   ^"
+        );
+        let streamed = AocError::parse(2, 3..4, "bad").on_line("3 x\r\n");
+        assert!(streamed.to_string().ends_with("\n2 | 3 x\n  |   ^"));
+        assert_eq!(streamed.exit_code(), std::process::ExitCode::FAILURE);
+
+        let other = AocError::Unsolvable("odd".into()).in_input("1 2\n");
+        assert!(matches!(other, AocError::Unsolvable(_)));
+    }
+}
@@ -0,0 +1,380 @@
+//! Union-find, for grouping things that connect pairwise into regions.
+//!
+//! `Dsu` groups the elements `0..n`, which suits anything already indexed,
+//! like grid cells by `y * width + x`. `KeyedDsu` groups arbitrary values
+//! by numbering each the first time it's seen and grouping the numbers.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Disjoint sets over `0..len`, each element starting in a set of its own.
+///
+/// # Implementation Details
+/// - `find` compresses paths, pointing each element it passes at the root
+/// - `union` hangs the smaller set under the larger root, so together the
+///   two keep every operation near constant amortized time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dsu {
+    /// Each element's parent; a root is its own parent
+    parent: Vec<usize>,
+    /// Elements under each root; meaningless for the rest
+    size: Vec<usize>,
+    /// Distinct sets, so `group_count` needn't scan
+    groups: usize,
+}
+
+impl Dsu {
+    /// `len` elements, each in its own set.
+    pub fn new(len: usize) -> Self {
+        Dsu {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            groups: len,
+        }
+    }
+
+    /// Elements in all sets together.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Whether there are no elements at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Distinct sets.
+    #[inline]
+    pub fn group_count(&self) -> usize {
+        self.groups
+    }
+
+    /// Adds an element in a set of its own and returns it.
+    pub fn push(&mut self) -> usize {
+        let element = self.parent.len();
+        self.parent.push(element);
+        self.size.push(1);
+        self.groups += 1;
+        element
+    }
+
+    /// The root of `x`'s set, the same for every element in it.
+    ///
+    /// # Panics
+    /// If `x` is out of range.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Point the whole path at the root, so the next find is one step
+        let mut at = x;
+        while self.parent[at] != root {
+            at = std::mem::replace(&mut self.parent[at], root);
+        }
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`.
+    ///
+    /// # Returns
+    /// Whether they were in different sets, so a merge happened.
+    ///
+    /// # Panics
+    /// If either element is out of range.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.groups -= 1;
+        true
+    }
+
+    /// Whether `a` and `b` are in the same set.
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Elements in `x`'s set, `x` included.
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Every set's elements in ascending order, with the sets ordered by
+    /// their smallest element, so the grouping reads the same however the
+    /// unions were made.
+    ///
+    /// # Time Complexity
+    /// O(n α(n))
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        // Sets get numbered in order of their smallest element
+        let mut slot = vec![usize::MAX; self.len()];
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.groups);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            if slot[root] == usize::MAX {
+                slot[root] = groups.len();
+                groups.push(Vec::with_capacity(self.size[root]));
+            }
+            groups[slot[root]].push(x);
+        }
+        groups
+    }
+}
+
+/// Disjoint sets over arbitrary values, added the first time they're seen.
+///
+/// Each value is numbered in the order it first appears and grouped by its
+/// number in a `Dsu`, so every operation costs a hash lookup on top.
+#[derive(Debug, Clone)]
+pub struct KeyedDsu<T: Hash + Eq> {
+    ids: HashMap<T, usize>,
+    values: Vec<T>,
+    sets: Dsu,
+}
+
+impl<T: Hash + Eq + Clone> KeyedDsu<T> {
+    /// A structure with no values yet.
+    pub fn new() -> Self {
+        KeyedDsu {
+            ids: HashMap::new(),
+            values: Vec::new(),
+            sets: Dsu::new(0),
+        }
+    }
+
+    /// Distinct values seen.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether no value has been seen.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Distinct sets.
+    #[inline]
+    pub fn group_count(&self) -> usize {
+        self.sets.group_count()
+    }
+
+    /// The number of `value`, adding it in a set of its own if it's new.
+    pub fn insert(&mut self, value: T) -> usize {
+        if let Some(&id) = self.ids.get(&value) {
+            return id;
+        }
+        let id = self.sets.push();
+        self.values.push(value.clone());
+        self.ids.insert(value, id);
+        id
+    }
+
+    /// Merges the sets holding `a` and `b`, adding either if it's new.
+    ///
+    /// # Returns
+    /// Whether they were in different sets, so a merge happened.
+    pub fn union(&mut self, a: T, b: T) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.sets.union(a, b)
+    }
+
+    /// Whether `a` and `b` are in the same set; a value never seen is in
+    /// none, but is the same as itself.
+    pub fn same(&mut self, a: &T, b: &T) -> bool {
+        match (self.ids.get(a), self.ids.get(b)) {
+            (Some(&a), Some(&b)) => self.sets.same(a, b),
+            _ => a == b,
+        }
+    }
+
+    /// Values in `value`'s set, or 0 for a value never seen.
+    pub fn size_of(&mut self, value: &T) -> usize {
+        match self.ids.get(value) {
+            Some(&id) => self.sets.size_of(id),
+            None => 0,
+        }
+    }
+
+    /// Every set's values, ordered as `Dsu::groups` orders them by the
+    /// order the values were first seen.
+    pub fn groups(&mut self) -> Vec<Vec<&T>> {
+        let values = &self.values;
+        self.sets
+            .groups()
+            .into_iter()
+            .map(|group| group.into_iter().map(|id| &values[id]).collect())
+            .collect()
+    }
+}
+
+impl<T: Hash + Eq + Clone> Default for KeyedDsu<T> {
+    fn default() -> Self {
+        KeyedDsu::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::Values;
+
+    /// Random pairs over `0..len`
+    fn pairs(values: &mut Values, len: usize, count: usize) -> Vec<(usize, usize)> {
+        (0..count)
+            .map(|_| {
+                let a = values.below(len as u64) as usize;
+                (a, values.below(len as u64) as usize)
+            })
+            .collect()
+    }
+
+    /// The grouping after applying `pairs`, by relabelling every element
+    /// of one set whenever it merges, which is slow and plainly right.
+    fn naive_groups(len: usize, pairs: &[(usize, usize)]) -> Vec<Vec<usize>> {
+        let mut label: Vec<usize> = (0..len).collect();
+        for &(a, b) in pairs {
+            let (from, to) = (label[b], label[a]);
+            label
+                .iter_mut()
+                .filter(|l| **l == from)
+                .for_each(|l| *l = to);
+        }
+        // Elements in ascending order put each set's smallest first
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (x, &l) in label.iter().enumerate() {
+            match groups.iter_mut().find(|group| label[group[0]] == l) {
+                Some(group) => group.push(x),
+                None => groups.push(vec![x]),
+            }
+        }
+        groups
+    }
+
+    #[test]
+    fn groups_connected_elements() {
+        let mut dsu = Dsu::new(6);
+        assert_eq!(dsu.group_count(), 6);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(4, 3));
+        assert!(dsu.union(1, 4));
+        assert!(!dsu.union(3, 0));
+        assert!(dsu.same(0, 3) && !dsu.same(0, 2));
+        assert_eq!((dsu.size_of(4), dsu.size_of(2)), (4, 1));
+        assert_eq!(dsu.group_count(), 3);
+        assert_eq!(dsu.groups(), [vec![0, 1, 3, 4], vec![2], vec![5]]);
+
+        assert_eq!(dsu.push(), 6);
+        assert!(dsu.union(6, 5));
+        assert_eq!(dsu.groups(), [vec![0, 1, 3, 4], vec![2], vec![5, 6]]);
+        assert!(Dsu::new(0).groups().is_empty());
+    }
+
+    #[test]
+    fn grouping_ignores_union_order_and_direction() {
+        let mut values = Values(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..200 {
+            let len = 1 + values.below(40) as usize;
+            let count = values.below(40) as usize;
+            let mut unions = pairs(&mut values, len, count);
+            let expected = naive_groups(len, &unions);
+
+            let mut forward = Dsu::new(len);
+            let merges = unions.iter().filter(|&&(a, b)| forward.union(a, b)).count();
+            assert_eq!(forward.groups(), expected);
+            assert_eq!(forward.group_count(), expected.len());
+            // Every merge joined two sets
+            assert_eq!(merges, len - expected.len());
+
+            // Swapped pairs, applied in reverse, end the same way
+            unions.reverse();
+            let mut backward = Dsu::new(len);
+            for &(a, b) in &unions {
+                backward.union(b, a);
+            }
+            assert_eq!(backward.groups(), expected);
+
+            for group in &expected {
+                assert!(group.iter().all(|&x| forward.size_of(x) == group.len()));
+            }
+        }
+    }
+
+    #[test]
+    fn regrouping_in_either_bracketing_agrees() {
+        // (a ∪ b) ∪ c and a ∪ (b ∪ c), with each union a batch of pairs
+        let mut values = Values(0x2545_f491_4f6c_dd1d);
+        for _ in 0..200 {
+            let len = 30;
+            let (a, b, c) = (
+                pairs(&mut values, len, 8),
+                pairs(&mut values, len, 8),
+                pairs(&mut values, len, 8),
+            );
+            let mut left = Dsu::new(len);
+            for &(x, y) in a.iter().chain(&b).chain(&c) {
+                left.union(x, y);
+            }
+            let mut right = Dsu::new(len);
+            for &(x, y) in b.iter().chain(&c).chain(&a) {
+                right.union(x, y);
+            }
+            assert_eq!(left.groups(), right.groups());
+        }
+    }
+
+    #[test]
+    fn keeps_a_million_elements_near_linear() {
+        const LEN: usize = 1_000_000;
+        let mut dsu = Dsu::new(LEN);
+        // A chain built from the far end, which without balancing or
+        // compression makes one path a million long
+        for x in (1..LEN).rev() {
+            dsu.union(x, x - 1);
+        }
+        for x in 0..LEN {
+            assert_eq!(dsu.find(x), dsu.find(0));
+        }
+        assert_eq!(dsu.size_of(LEN - 1), LEN);
+
+        // Evens and odds, interleaved across the whole range
+        let mut dsu = Dsu::new(LEN);
+        for x in 2..LEN {
+            dsu.union(x, x % 2);
+        }
+        assert_eq!(dsu.group_count(), 2);
+        let groups = dsu.groups();
+        assert_eq!((groups[0].len(), groups[1].len()), (LEN / 2, LEN / 2));
+    }
+
+    #[test]
+    fn groups_values_by_key() {
+        let mut dsu = KeyedDsu::new();
+        assert!(dsu.union("kh", "tc"));
+        assert!(dsu.union("qp", "kh"));
+        assert!(dsu.union("de", "cg"));
+        assert!(!dsu.union("tc", "qp"));
+        assert_eq!(dsu.insert("ka"), 5);
+        assert_eq!(dsu.insert("kh"), 0);
+
+        assert!(dsu.same(&"qp", &"tc") && !dsu.same(&"qp", &"de"));
+        assert!(dsu.same(&"zz", &"zz") && !dsu.same(&"zz", &"kh"));
+        assert_eq!((dsu.size_of(&"tc"), dsu.size_of(&"zz")), (3, 0));
+        assert_eq!((dsu.len(), dsu.group_count()), (6, 3));
+        assert_eq!(
+            dsu.groups(),
+            [vec![&"kh", &"tc", &"qp"], vec![&"de", &"cg"], vec![&"ka"]]
+        );
+    }
+}
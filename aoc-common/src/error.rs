@@ -0,0 +1,120 @@
+//! The error every day reports when it can't produce an answer.
+//!
+//! Days keep their own structured errors where they need to inspect them,
+//! such as to skip a malformed line, and convert into `AocError` at the
+//! boundary, so a binary has one type to print and one exit code rule.
+
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use thiserror::Error;
+
+/// Anything that stops a day from producing its answers.
+///
+/// The `Display` output is what a binary prints after `Error: `, so it is
+/// kept stable enough for tests to assert on.
+#[derive(Debug, Error)]
+pub enum AocError {
+    /// Reading the input or writing output failed
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// A line of input that doesn't parse
+    ///
+    /// Lines and columns are 1-based; `columns` is an inclusive-exclusive
+    /// byte range into the line, rendered as an inclusive range for humans.
+    #[error("line {line}, columns {}-{}: {message}", .columns.start, .columns.end - 1)]
+    Parse {
+        line: usize,
+        columns: Range<usize>,
+        message: String,
+    },
+    /// No input was found in any of the places a day looks
+    #[error("no puzzle input found for day {day}; tried:{}", list_paths(.tried))]
+    MissingInput { day: u8, tried: Vec<PathBuf> },
+    /// A config file or environment variable that doesn't parse, named by
+    /// its path or as `$NAME`
+    #[error("{origin}: {message}")]
+    Config { origin: String, message: String },
+    /// An unknown or malformed command-line option
+    #[error("{0}")]
+    BadArgument(String),
+    /// An input that parses but can't be answered, such as lists that don't
+    /// pair up
+    #[error("{0}")]
+    Unsolvable(String),
+    /// An error rendered under the input it points at, as
+    /// `diagnostics::render` lays it out
+    #[error("{rendered}")]
+    Annotated {
+        rendered: String,
+        #[source]
+        error: Box<AocError>,
+    },
+}
+
+impl AocError {
+    /// Builds a `Parse` error from anything displayable.
+    pub fn parse(line: usize, columns: Range<usize>, message: impl ToString) -> Self {
+        AocError::Parse {
+            line,
+            columns,
+            message: message.to_string(),
+        }
+    }
+
+    /// The exit code a binary stops with: 2 for bad usage, as command-line
+    /// parsers conventionally do, and 1 for everything else.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            AocError::BadArgument(_) => ExitCode::from(2),
+            AocError::Annotated { error, .. } => error.exit_code(),
+            _ => ExitCode::FAILURE,
+        }
+    }
+}
+
+/// Renders each path on its own indented line.
+fn list_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("\n  {}", path.display()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displays_each_variant() {
+        let error = AocError::parse(3, 4..6, "could not parse `5x` as an integer");
+        assert_eq!(
+            error.to_string(),
+            "line 3, columns 4-5: could not parse `5x` as an integer"
+        );
+
+        let error = AocError::MissingInput {
+            day: 4,
+            tried: vec![PathBuf::from("a/day-04.txt"), PathBuf::from("b.txt")],
+        };
+        assert_eq!(
+            error.to_string(),
+            "no puzzle input found for day 4; tried:\n  a/day-04.txt\n  b.txt"
+        );
+
+        let error = AocError::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert_eq!(error.to_string(), "gone");
+        assert_eq!(AocError::BadArgument("no".into()).to_string(), "no");
+        assert_eq!(AocError::Unsolvable("odd".into()).to_string(), "odd");
+    }
+
+    #[test]
+    fn exits_with_2_for_bad_usage_only() {
+        let usage = AocError::BadArgument("unknown option".into());
+        assert_eq!(usage.exit_code(), ExitCode::from(2));
+        let io = AocError::from(io::Error::other("disk"));
+        assert_eq!(io.exit_code(), ExitCode::FAILURE);
+    }
+}
@@ -0,0 +1,422 @@
+//! Directed graphs of dependencies, and ordering them.
+//!
+//! Nodes are interned: each distinct value gets a dense id the first time
+//! it's added, and edges are kept as lists of ids. Hashing happens only
+//! when a value goes in or is looked up; sorting and searching walk ids,
+//! and callers with ids in hand can add edges by id without hashing at all.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+use thiserror::Error;
+
+use super::AocError;
+
+/// The nodes of a cycle, which keeps a graph from being ordered.
+///
+/// Each node has an edge to the next, and the last has one back to the
+/// first; a self-loop is a cycle of one node.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("dependency cycle: {}", render_cycle(.cycle))]
+pub struct CycleError<N: fmt::Debug + fmt::Display> {
+    pub cycle: Vec<N>,
+}
+
+/// Renders a cycle as `a -> b -> a`, back to where it started.
+fn render_cycle<N: fmt::Display>(cycle: &[N]) -> String {
+    let mut rendered = String::new();
+    for node in cycle.iter().chain(cycle.first()) {
+        if !rendered.is_empty() {
+            rendered.push_str(" -> ");
+        }
+        rendered.push_str(&node.to_string());
+    }
+    rendered
+}
+
+impl<N: fmt::Debug + fmt::Display> From<CycleError<N>> for AocError {
+    fn from(e: CycleError<N>) -> Self {
+        AocError::Unsolvable(e.to_string())
+    }
+}
+
+/// A directed graph over values of `N`, with at most one edge from any
+/// node to another.
+#[derive(Debug, Clone)]
+pub struct DiGraph<N> {
+    ids: HashMap<N, usize>,
+    nodes: Vec<N>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+    edges: usize,
+}
+
+impl<N: Hash + Eq + Clone> DiGraph<N> {
+    /// A graph with no nodes.
+    pub fn new() -> Self {
+        DiGraph {
+            ids: HashMap::new(),
+            nodes: Vec::new(),
+            successors: Vec::new(),
+            predecessors: Vec::new(),
+            edges: 0,
+        }
+    }
+
+    /// Nodes in the graph.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the graph has no nodes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Edges in the graph.
+    #[inline]
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// Every node, indexed by id, in the order they were added.
+    #[inline]
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// The id of `node`, if it's in the graph.
+    #[inline]
+    pub fn id(&self, node: &N) -> Option<usize> {
+        self.ids.get(node).copied()
+    }
+
+    /// The id of `node`, adding it without edges if it's new.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(node.clone());
+        self.ids.insert(node, id);
+        self.successors.push(Vec::new());
+        self.predecessors.push(Vec::new());
+        id
+    }
+
+    /// Adds an edge from `from` to `to`, adding either node if it's new.
+    ///
+    /// # Returns
+    /// Whether the edge is new, since each is kept once.
+    pub fn add_edge(&mut self, from: N, to: N) -> bool {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.add_edge_ids(from, to)
+    }
+
+    /// Adds an edge between two nodes by id, without hashing.
+    ///
+    /// # Returns
+    /// Whether the edge is new, since each is kept once.
+    ///
+    /// # Panics
+    /// If either id isn't in the graph.
+    pub fn add_edge_ids(&mut self, from: usize, to: usize) -> bool {
+        assert!(to < self.len(), "no node with id {}", to);
+        if self.successors[from].contains(&to) {
+            return false;
+        }
+        self.successors[from].push(to);
+        self.predecessors[to].push(from);
+        self.edges += 1;
+        true
+    }
+
+    /// Ids of the nodes `id` has edges to, in the order they were added.
+    #[inline]
+    pub fn successors(&self, id: usize) -> &[usize] {
+        &self.successors[id]
+    }
+
+    /// Ids of the nodes with edges to `id`, in the order they were added.
+    #[inline]
+    pub fn predecessors(&self, id: usize) -> &[usize] {
+        &self.predecessors[id]
+    }
+
+    /// Edges into `node`, 0 if it isn't in the graph.
+    pub fn in_degree(&self, node: &N) -> usize {
+        self.id(node).map_or(0, |id| self.predecessors[id].len())
+    }
+
+    /// Edges out of `node`, 0 if it isn't in the graph.
+    pub fn out_degree(&self, node: &N) -> usize {
+        self.id(node).map_or(0, |id| self.successors[id].len())
+    }
+
+    /// Whether a path of edges leads from `from` to `to`; a node reaches
+    /// itself by the empty path.
+    ///
+    /// # Returns
+    /// `false` if either node isn't in the graph.
+    ///
+    /// # Time Complexity
+    /// O(V + E)
+    pub fn has_path(&self, from: &N, to: &N) -> bool {
+        let (Some(from), Some(to)) = (self.id(from), self.id(to)) else {
+            return false;
+        };
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![from];
+        seen[from] = true;
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+            for &next in &self.successors[id] {
+                if !seen[next] {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Ids of every node, ordered so each comes before all its successors.
+    ///
+    /// Among nodes free to go next, the earliest added goes first, so the
+    /// order is the same on every run.
+    ///
+    /// # Errors
+    /// Returns one cycle, by id and starting from its earliest added node,
+    /// if the graph has any.
+    ///
+    /// # Implementation Details
+    /// - Kahn's algorithm, taking ready nodes from a min-heap of ids
+    /// - When nodes are left over, each still has a predecessor among
+    ///   them, so walking predecessors from any of them must revisit a
+    ///   node, and the walk from there is the cycle, backwards
+    ///
+    /// # Time Complexity
+    /// O(E + V log V)
+    pub fn topo_sort_ids(&self) -> Result<Vec<usize>, CycleError<usize>> {
+        let mut remaining: Vec<usize> = self.predecessors.iter().map(Vec::len).collect();
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.len())
+            .filter(|&id| remaining[id] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(id)) = ready.pop() {
+            order.push(id);
+            for &next in &self.successors[id] {
+                remaining[next] -= 1;
+                if remaining[next] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+        if order.len() == self.len() {
+            return Ok(order);
+        }
+
+        let start = (0..self.len()).find(|&id| remaining[id] > 0).unwrap();
+        let mut visited_at = vec![usize::MAX; self.len()];
+        let mut walk = Vec::new();
+        let mut at = start;
+        while visited_at[at] == usize::MAX {
+            visited_at[at] = walk.len();
+            walk.push(at);
+            at = *self.predecessors[at]
+                .iter()
+                .find(|&&p| remaining[p] > 0)
+                .expect("every node left over has a predecessor left over");
+        }
+        let mut cycle = walk.split_off(visited_at[at]);
+        cycle.reverse();
+        // Start from the earliest added node, so the report is stable
+        let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+        cycle.rotate_left(first);
+        Err(CycleError { cycle })
+    }
+
+    /// Every node, ordered so each comes before all its successors, with
+    /// ties broken as `topo_sort_ids` breaks them.
+    ///
+    /// # Errors
+    /// Returns one cycle if the graph has any.
+    pub fn topo_sort(&self) -> Result<Vec<N>, CycleError<N>>
+    where
+        N: fmt::Debug + fmt::Display,
+    {
+        let node = |id: usize| self.nodes[id].clone();
+        match self.topo_sort_ids() {
+            Ok(order) => Ok(order.into_iter().map(node).collect()),
+            Err(e) => Err(CycleError {
+                cycle: e.cycle.into_iter().map(node).collect(),
+            }),
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> Default for DiGraph<N> {
+    fn default() -> Self {
+        DiGraph::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_edges<N: Hash + Eq + Clone>(edges: &[(N, N)]) -> DiGraph<N> {
+        let mut graph = DiGraph::new();
+        for (from, to) in edges {
+            graph.add_edge(from.clone(), to.clone());
+        }
+        graph
+    }
+
+    /// Asserts `order` holds every node once, each before its successors.
+    fn assert_ordered<N: Hash + Eq + Clone + fmt::Debug>(graph: &DiGraph<N>, order: &[N]) {
+        assert_eq!(order.len(), graph.len());
+        let position: HashMap<&N, usize> = order.iter().enumerate().map(|(i, n)| (n, i)).collect();
+        for (id, node) in graph.nodes().iter().enumerate() {
+            for &next in graph.successors(id) {
+                let next = &graph.nodes()[next];
+                assert!(
+                    position[node] < position[next],
+                    "{:?} after {:?}",
+                    node,
+                    next
+                );
+            }
+        }
+    }
+
+    /// Asserts `cycle` is a real cycle of `graph`.
+    fn assert_cycle<N: Hash + Eq + Clone + fmt::Debug + fmt::Display>(
+        graph: &DiGraph<N>,
+        cycle: &[N],
+    ) {
+        assert!(!cycle.is_empty());
+        for (i, node) in cycle.iter().enumerate() {
+            let next = &cycle[(i + 1) % cycle.len()];
+            let (from, to) = (graph.id(node).unwrap(), graph.id(next).unwrap());
+            assert!(graph.successors(from).contains(&to), "{:?}", cycle);
+        }
+    }
+
+    #[test]
+    fn orders_a_diamond() {
+        let graph = from_edges(&[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")]);
+        let order = graph.topo_sort().unwrap();
+        assert_eq!(order, ["a", "b", "c", "d"]);
+        assert_ordered(&graph, &order);
+        assert_eq!((graph.in_degree(&"d"), graph.out_degree(&"a")), (2, 2));
+        assert_eq!((graph.in_degree(&"a"), graph.out_degree(&"zz")), (0, 0));
+        assert!(graph.has_path(&"a", &"d") && !graph.has_path(&"d", &"a"));
+        assert!(graph.has_path(&"b", &"b") && !graph.has_path(&"b", &"c"));
+    }
+
+    #[test]
+    fn orders_the_day_5_rules() {
+        let rules = [
+            (47, 53),
+            (97, 13),
+            (97, 61),
+            (97, 47),
+            (75, 29),
+            (61, 13),
+            (75, 53),
+            (29, 13),
+            (97, 29),
+            (53, 29),
+            (61, 53),
+            (97, 53),
+            (61, 29),
+            (47, 13),
+            (75, 47),
+            (97, 75),
+            (47, 61),
+            (75, 61),
+            (47, 29),
+            (75, 13),
+            (53, 13),
+        ];
+        let graph = from_edges(&rules);
+        let order = graph.topo_sort().unwrap();
+        assert_eq!(order, [97, 75, 47, 61, 53, 29, 13]);
+        assert_ordered(&graph, &order);
+    }
+
+    #[test]
+    fn orders_disconnected_components_by_insertion() {
+        let mut graph = from_edges(&[(3, 1), (10, 20), (20, 30), (1, 2)]);
+        graph.add_node(99);
+        let order = graph.topo_sort().unwrap();
+        assert_eq!(order, [3, 1, 10, 20, 30, 2, 99]);
+        assert_ordered(&graph, &order);
+        assert!(!graph.has_path(&3, &30) && !graph.has_path(&99, &98));
+        assert_eq!(graph.edge_count(), 4);
+    }
+
+    #[test]
+    fn keeps_each_edge_once() {
+        let mut graph = DiGraph::new();
+        assert!(graph.add_edge('a', 'b'));
+        assert!(!graph.add_edge('a', 'b'));
+        assert!(graph.add_edge('b', 'a'));
+        assert_eq!((graph.len(), graph.edge_count()), (2, 2));
+
+        // Ids skip the hashing once nodes are in
+        let (a, c) = (graph.id(&'a').unwrap(), graph.add_node('c'));
+        assert!(graph.add_edge_ids(c, a));
+        assert!(!graph.add_edge_ids(c, a));
+        assert_eq!(graph.successors(c), [a]);
+        assert_eq!(graph.predecessors(a), [1, c]);
+    }
+
+    #[test]
+    fn reports_a_self_loop() {
+        let graph = from_edges(&[("a", "b"), ("b", "b"), ("b", "c")]);
+        let error = graph.topo_sort().unwrap_err();
+        assert_eq!(error.cycle, ["b"]);
+        assert_eq!(error.to_string(), "dependency cycle: b -> b");
+        assert!(graph.has_path(&"a", &"c"));
+    }
+
+    #[test]
+    fn reports_a_concrete_cycle() {
+        // A cycle downstream of a sorted prefix, feeding a node after it
+        let graph = from_edges(&[
+            ("start", "x"),
+            ("x", "y"),
+            ("y", "z"),
+            ("z", "x"),
+            ("z", "end"),
+        ]);
+        let error = graph.topo_sort().unwrap_err();
+        assert_eq!(error.cycle, ["x", "y", "z"]);
+        assert_cycle(&graph, &error.cycle);
+        assert_eq!(error.to_string(), "dependency cycle: x -> y -> z -> x");
+        assert_eq!(
+            AocError::from(error).to_string(),
+            "dependency cycle: x -> y -> z -> x"
+        );
+
+        // Two cycles sharing a node: whichever is reported is real
+        let graph = from_edges(&[(1, 2), (2, 1), (2, 3), (3, 4), (4, 2)]);
+        let error = graph.topo_sort().unwrap_err();
+        assert_cycle(&graph, &error.cycle);
+        assert!(graph.has_path(&4, &1));
+    }
+
+    #[test]
+    fn sorts_an_empty_graph() {
+        assert!(DiGraph::<u8>::new().topo_sort().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,862 @@
+//! A rectangular grid of cells, for the puzzles whose input is a map.
+//!
+//! Cells are stored row by row in one flat `Vec`, so a lookup is a bounds
+//! check and one multiply-add, and walking the grid in reading order walks
+//! memory in order. Coordinates are `(x, y)` with `x` the column and `y`
+//! the row, both counted from the top-left corner.
+//!
+//! `walk` steps from a point in one direction to the edge, the way a guard
+//! patrols or a line of sight extends, and `ray_until` stops it early at
+//! the first cell that blocks it.
+//!
+//! `render` and its variants draw a grid back out one character per cell,
+//! the same way every time, so a test can compare an intermediate state
+//! against the map it expects.
+
+use std::fmt;
+use std::ops::{Index, IndexMut, Range};
+use std::str::FromStr;
+
+use super::{AocError, Direction, Point2};
+
+/// A `width` by `height` grid of cells stored in reading order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Arranges `cells`, in reading order, into rows of `width`.
+    ///
+    /// # Returns
+    /// `None` if the cells don't fill a whole number of rows, or if there
+    /// are cells but `width` is zero.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Option<Self> {
+        let height = match width {
+            0 if cells.is_empty() => 0,
+            0 => return None,
+            _ if !cells.len().is_multiple_of(width) => return None,
+            _ => cells.len() / width,
+        };
+        Some(Grid {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// Cells per row.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the grid has no cells.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whether `(x, y)` is a cell of the grid.
+    #[inline]
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// The flat index of `(x, y)`, if it's in bounds.
+    #[inline]
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        self.in_bounds(x, y).then(|| y * self.width + x)
+    }
+
+    /// The cell at `(x, y)`, or `None` outside the grid.
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    /// The cell at `(x, y)` for writing, or `None` outside the grid.
+    #[inline]
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index_of(x, y).map(|i| &mut self.cells[i])
+    }
+
+    /// Replaces the cell at `(x, y)`.
+    ///
+    /// # Returns
+    /// The previous value, or `None` with the grid unchanged if `(x, y)` is
+    /// outside it.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        self.get_mut(x, y)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// The flat index of `p`, if it's in bounds; a negative coordinate is
+    /// always outside.
+    #[inline]
+    fn point_index(&self, p: Point2) -> Option<usize> {
+        let x = usize::try_from(p.x).ok()?;
+        let y = usize::try_from(p.y).ok()?;
+        self.index_of(x, y)
+    }
+
+    /// Whether `p` is a cell of the grid.
+    #[inline]
+    pub fn contains_point(&self, p: Point2) -> bool {
+        self.point_index(p).is_some()
+    }
+
+    /// The cell at `p`, or `None` outside the grid, including off its top
+    /// or left edge.
+    #[inline]
+    pub fn get_point(&self, p: Point2) -> Option<&T> {
+        self.point_index(p).map(|i| &self.cells[i])
+    }
+
+    /// The cell at `p` for writing, or `None` outside the grid.
+    #[inline]
+    pub fn get_point_mut(&mut self, p: Point2) -> Option<&mut T> {
+        self.point_index(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Row `y`, or `None` past the last row.
+    #[inline]
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Every row, top to bottom.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        (0..self.height).map(move |y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Every cell in reading order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    /// Every cell in reading order, for writing.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Every cell with its `(x, y)`, in reading order.
+    pub fn iter_with_pos(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// The `(x, y)` of the first cell in reading order matching `predicate`.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        let i = self.cells.iter().position(predicate)?;
+        Some((i % self.width, i / self.width))
+    }
+
+    /// A grid of the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// The cells in reading order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.cells
+    }
+
+    /// Every cell from `from` out to the edge in `direction`, with its
+    /// point, not counting `from` itself.
+    ///
+    /// The walk ends at the first step off the grid, so one that starts on
+    /// the edge facing out, or outside the grid facing away from it, yields
+    /// nothing. One that starts outside facing in yields the cells it
+    /// crosses.
+    pub fn walk(&self, from: Point2, direction: Direction) -> Walk<'_, T> {
+        Walk {
+            grid: self,
+            at: from,
+            delta: direction.delta(),
+        }
+    }
+
+    /// Walks from `from` in `direction` until the next cell matches `stop`
+    /// or the walk leaves the grid, like a guard stepping until it faces
+    /// an obstacle.
+    ///
+    /// # Returns
+    /// Where the walk ended, `from` itself if it couldn't take a step, and
+    /// the cell that stopped it, if it didn't reach the edge.
+    pub fn ray_until(
+        &self,
+        from: Point2,
+        direction: Direction,
+        mut stop: impl FnMut(&T) -> bool,
+    ) -> Ray<'_, T> {
+        let mut ray = Ray {
+            end: from,
+            steps: 0,
+            blocked_by: None,
+        };
+        for (p, cell) in self.walk(from, direction) {
+            if stop(cell) {
+                ray.blocked_by = Some((p, cell));
+                break;
+            }
+            ray.end = p;
+            ray.steps += 1;
+        }
+        ray
+    }
+
+    /// Draws the grid one character per cell, each row ending in `\n`,
+    /// for printing while debugging or comparing against an expected map
+    /// in a test.
+    pub fn render(&self, cell_to_char: impl Fn(&T) -> char) -> String {
+        self.render_region(0..self.width, 0..self.height, cell_to_char)
+    }
+
+    /// Draws the grid as `render` does, with every cell in `overlay`, such
+    /// as a path or the cells a search visited, drawn as `mark` instead.
+    ///
+    /// Points of `overlay` outside the grid are ignored.
+    pub fn render_with_overlay(
+        &self,
+        cell_to_char: impl Fn(&T) -> char,
+        overlay: impl IntoIterator<Item = Point2>,
+        mark: char,
+    ) -> String {
+        let mut marked = Grid::new(self.width, self.height, false);
+        for p in overlay {
+            if let Some(cell) = marked.get_point_mut(p) {
+                *cell = true;
+            }
+        }
+        self.render_cells(0..self.width, 0..self.height, |i, cell| {
+            if marked.cells[i] {
+                mark
+            } else {
+                cell_to_char(cell)
+            }
+        })
+    }
+
+    /// Draws only columns `xs` of rows `ys`, as `render` does, for a look
+    /// at part of a grid too large to print whole.
+    ///
+    /// The ranges are cut to the grid, so any part outside it is left out.
+    pub fn render_region(
+        &self,
+        xs: Range<usize>,
+        ys: Range<usize>,
+        cell_to_char: impl Fn(&T) -> char,
+    ) -> String {
+        self.render_cells(xs, ys, |_, cell| cell_to_char(cell))
+    }
+
+    /// Draws columns `xs` of rows `ys`, cut to the grid, with `draw` given
+    /// each cell and its flat index.
+    fn render_cells(
+        &self,
+        xs: Range<usize>,
+        ys: Range<usize>,
+        draw: impl Fn(usize, &T) -> char,
+    ) -> String {
+        let xs = xs.start.min(self.width)..xs.end.min(self.width);
+        let ys = ys.start.min(self.height)..ys.end.min(self.height);
+        let mut out = String::with_capacity((xs.len() + 1) * ys.len());
+        for y in ys {
+            for x in xs.clone() {
+                let i = y * self.width + x;
+                out.push(draw(i, &self.cells[i]));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The cells out to the edge of a grid in one direction, from
+/// `Grid::walk`.
+#[derive(Debug, Clone)]
+pub struct Walk<'a, T> {
+    grid: &'a Grid<T>,
+    /// The point last yielded, or the start
+    at: Point2,
+    delta: Point2,
+}
+
+impl<'a, T> Iterator for Walk<'a, T> {
+    type Item = (Point2, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.at + self.delta;
+        let cell = self.grid.get_point(next)?;
+        self.at = next;
+        Some((next, cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = steps_left(self.at.x, self.delta.x, self.grid.width).min(steps_left(
+            self.at.y,
+            self.delta.y,
+            self.grid.height,
+        ));
+        (left, Some(left))
+    }
+}
+
+impl<T> ExactSizeIterator for Walk<'_, T> {}
+
+// Once a step leaves the grid, `at` stays put, so every later step does too
+impl<T> std::iter::FusedIterator for Walk<'_, T> {}
+
+/// Steps along one axis from `at` that stay inside `0..len`, moving by
+/// `delta` of -1, 0, or 1 each time; unbounded in practice when the axis
+/// doesn't move and `at` is inside.
+fn steps_left(at: i64, delta: i64, len: usize) -> usize {
+    let inside = |v: i64| usize::try_from(v).is_ok_and(|v| v < len);
+    match delta {
+        _ if !inside(at + delta) => 0,
+        0 => usize::MAX,
+        1 => len - (at + 1) as usize,
+        _ => at as usize,
+    }
+}
+
+/// Where `Grid::ray_until` ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ray<'a, T> {
+    /// The last point reached, the start if no step was taken
+    pub end: Point2,
+    /// Steps taken from the start to `end`
+    pub steps: usize,
+    /// The cell one step past `end` that stopped the ray, with its point,
+    /// or `None` if the next step would leave the grid
+    pub blocked_by: Option<(Point2, &'a T)>,
+}
+
+impl Grid<u8> {
+    /// Reads a block of ASCII lines as a grid of bytes, one cell per byte,
+    /// such as day 4's letters or day 6's map.
+    ///
+    /// Lines end in `\n` or `\r\n`. Trailing blank lines are ignored, so an
+    /// input that is empty or all blank gives an empty grid.
+    ///
+    /// # Errors
+    /// Returns `AocError::Parse` naming the first line whose width differs
+    /// from the first line's, including a blank line inside the block. The
+    /// columns are the extra cells of a long line, or the first missing one
+    /// of a short line.
+    ///
+    /// # Time Complexity
+    /// O(n) in the input length
+    pub fn parse_bytes(input: &str) -> Result<Self, AocError> {
+        parse_cells(input, "", Some)
+    }
+
+    /// Reads a block of lines of single digits, like day 10's map, as a
+    /// grid of their values `0..=9`.
+    ///
+    /// Lines are split as `parse_bytes` splits them.
+    ///
+    /// # Errors
+    /// Returns `AocError::Parse` for a ragged line as `parse_bytes` does, or
+    /// pointing at the first character that isn't a digit, whichever comes
+    /// first in reading order.
+    ///
+    /// # Time Complexity
+    /// O(n) in the input length
+    pub fn parse_digits(input: &str) -> Result<Self, AocError> {
+        parse_cells(input, "a digit", |byte| match byte {
+            b'0'..=b'9' => Some(byte - b'0'),
+            _ => None,
+        })
+    }
+}
+
+/// Splits `input` into lines as wide as the first and makes each byte a
+/// cell with `cell`, which returns `None` for a byte that isn't `expected`.
+fn parse_cells<T>(
+    input: &str,
+    expected: &str,
+    cell: impl Fn(u8) -> Option<T>,
+) -> Result<Grid<T>, AocError> {
+    let mut lines: Vec<&str> = input
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    let width = lines.first().map_or(0, |line| line.len());
+    let mut cells = Vec::with_capacity(width * lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        if line.len() != width {
+            let found = line.len();
+            let columns = if found > width {
+                width + 1..found + 1
+            } else {
+                found + 1..found + 2
+            };
+            let message = format!(
+                "expected {} cells like the first row, found {}",
+                width, found
+            );
+            return Err(AocError::parse(i + 1, columns, message));
+        }
+        for (column, &byte) in line.as_bytes().iter().enumerate() {
+            let Some(value) = cell(byte) else {
+                // Point at the whole character, which may be wider than a
+                // byte; its lead byte is what was rejected
+                let found = line[column..].chars().next().unwrap_or_default();
+                let columns = column + 1..column + 1 + found.len_utf8();
+                let message = format!("expected {}, found `{}`", expected, found);
+                return Err(AocError::parse(i + 1, columns, message));
+            };
+            cells.push(value);
+        }
+    }
+    Ok(Grid {
+        cells,
+        width,
+        height: lines.len(),
+    })
+}
+
+impl FromStr for Grid<u8> {
+    type Err = AocError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Grid::parse_bytes(input)
+    }
+}
+
+/// Renders a byte grid back into lines, each ending in `\n`.
+impl fmt::Display for Grid<u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+        Ok(())
+    }
+}
+
+/// Panics for an index outside a `width` by `height` grid, kept out of
+/// line so indexing inlines to just the checks.
+#[cold]
+#[inline(never)]
+fn out_of_bounds(x: usize, y: usize, width: usize, height: usize) -> ! {
+    panic!("({}, {}) is outside a {}x{} grid", x, y, width, height)
+}
+
+/// Like `out_of_bounds`, for a signed point.
+#[cold]
+#[inline(never)]
+fn point_out_of_bounds(p: Point2, width: usize, height: usize) -> ! {
+    panic!("{} is outside a {}x{} grid", p, width, height)
+}
+
+/// Indexes by `(x, y)`.
+///
+/// # Panics
+/// Panics if `(x, y)` is outside the grid; use `get` to check instead.
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        match self.index_of(x, y) {
+            Some(i) => &self.cells[i],
+            None => out_of_bounds(x, y, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    #[inline]
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        match self.index_of(x, y) {
+            Some(i) => &mut self.cells[i],
+            None => out_of_bounds(x, y, self.width, self.height),
+        }
+    }
+}
+
+/// Indexes by signed point.
+///
+/// # Panics
+/// Panics if `p` is outside the grid; use `get_point` to check instead.
+impl<T> Index<Point2> for Grid<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, p: Point2) -> &T {
+        match self.point_index(p) {
+            Some(i) => &self.cells[i],
+            None => point_out_of_bounds(p, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Point2> for Grid<T> {
+    #[inline]
+    fn index_mut(&mut self, p: Point2) -> &mut T {
+        match self.point_index(p) {
+            Some(i) => &mut self.cells[i],
+            None => point_out_of_bounds(p, self.width, self.height),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{neighbors8, Direction};
+
+    const MAP: &str = "..#.\n#...\n...S\n";
+
+    #[test]
+    fn reads_an_ascii_block() {
+        let grid: Grid<u8> = MAP.parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid.get(2, 0), Some(&b'#'));
+        assert_eq!(grid.get(0, 1), Some(&b'#'));
+        assert_eq!(grid.position(|&b| b == b'S'), Some((3, 2)));
+        assert_eq!(grid.to_string(), MAP);
+    }
+
+    #[test]
+    fn reads_crlf_and_a_missing_final_newline() {
+        let crlf = Grid::parse_bytes("..#.\r\n#...\r\n...S").unwrap();
+        assert_eq!(crlf, Grid::parse_bytes(MAP).unwrap());
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        assert_eq!(
+            Grid::parse_bytes("...\n..\n...\n").unwrap_err().to_string(),
+            "line 2, columns 3-3: expected 3 cells like the first row, found 2"
+        );
+        assert_eq!(
+            Grid::parse_bytes("...\n...\n.....\n")
+                .unwrap_err()
+                .to_string(),
+            "line 3, columns 4-5: expected 3 cells like the first row, found 5"
+        );
+        // A blank line inside the block is a row of width zero
+        assert_eq!(
+            Grid::parse_bytes("...\n\n...\n").unwrap_err().to_string(),
+            "line 2, columns 1-1: expected 3 cells like the first row, found 0"
+        );
+        assert_eq!(Grid::from_vec(3, vec![0; 7]), None);
+        assert_eq!(Grid::from_vec(0, vec![0]), None);
+    }
+
+    #[test]
+    fn reads_a_digit_map() {
+        let map = "0123\n1234\n8765\n9876\n";
+        let grid = Grid::parse_digits(map).unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 4));
+        assert_eq!(grid.row(0), Some(&[0, 1, 2, 3][..]));
+        assert_eq!(grid[(0, 3)], 9);
+        assert_eq!(grid.map(|&d| b'0' + d).to_string(), map);
+    }
+
+    #[test]
+    fn reads_single_rows_with_any_line_ending() {
+        for input in [
+            "90210",
+            "90210\n",
+            "90210\r\n",
+            "90210\n\n",
+            "90210\r\n\r\n",
+        ] {
+            let digits = Grid::parse_digits(input).unwrap();
+            assert_eq!((digits.width(), digits.height()), (5, 1), "{:?}", input);
+            assert_eq!(digits.into_vec(), [9, 0, 2, 1, 0]);
+            let bytes = Grid::parse_bytes(input).unwrap();
+            assert_eq!(bytes.row(0), Some(&b"90210"[..]), "{:?}", input);
+        }
+        let mixed = Grid::parse_digits("12\r\n34\n56").unwrap();
+        assert_eq!(mixed.into_vec(), [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn rejects_anything_but_digits_where_it_is() {
+        assert_eq!(
+            Grid::parse_digits("0123\n12.4\n").unwrap_err().to_string(),
+            "line 2, columns 3-3: expected a digit, found `.`"
+        );
+        // Widths are in bytes, and `²` takes two
+        assert_eq!(
+            Grid::parse_digits("012\n1\u{b2}\n")
+                .unwrap_err()
+                .to_string(),
+            "line 2, columns 2-3: expected a digit, found `\u{b2}`"
+        );
+        // Ragged rows are rejected as for bytes, before their cells are read
+        assert_eq!(
+            Grid::parse_digits("012\n1x\n").unwrap_err().to_string(),
+            "line 2, columns 3-3: expected 3 cells like the first row, found 2"
+        );
+        assert_eq!(
+            Grid::parse_digits("012\n\n345\n").unwrap_err().to_string(),
+            "line 2, columns 1-1: expected 3 cells like the first row, found 0"
+        );
+        // A carriage return inside a line is a cell like any other
+        assert_eq!(
+            Grid::parse_digits("0\r1\n").unwrap_err().to_string(),
+            "line 1, columns 2-2: expected a digit, found `\r`"
+        );
+    }
+
+    #[test]
+    fn handles_empty_grids() {
+        for input in ["", "\n", "\n\n", "\r\n"] {
+            assert!(Grid::parse_digits(input).unwrap().is_empty(), "{:?}", input);
+            let grid = Grid::parse_bytes(input).unwrap();
+            assert!(grid.is_empty(), "{:?}", input);
+            assert_eq!((grid.width(), grid.height()), (0, 0));
+            assert_eq!(grid.rows().count(), 0);
+            assert_eq!(grid.iter_with_pos().count(), 0);
+            assert_eq!(grid.get(0, 0), None);
+        }
+        let empty = Grid::<i32>::from_vec(0, Vec::new()).unwrap();
+        assert_eq!(empty.position(|_| true), None);
+        assert_eq!(Grid::new(0, 5, 1).rows().count(), 5);
+    }
+
+    #[test]
+    fn checks_edge_coordinates() {
+        let grid = Grid::parse_bytes(MAP).unwrap();
+        assert_eq!(grid.get(0, 0), Some(&b'.'));
+        assert_eq!(grid.get(3, 2), Some(&b'S'));
+        for (x, y) in [(4, 0), (0, 3), (4, 3), (usize::MAX, 0), (0, usize::MAX)] {
+            assert!(!grid.in_bounds(x, y), "({}, {})", x, y);
+            assert_eq!(grid.get(x, y), None);
+        }
+        assert_eq!(grid.row(2), Some(&b"...S"[..]));
+        assert_eq!(grid.row(3), None);
+    }
+
+    #[test]
+    fn iterates_in_reading_order() {
+        let grid = Grid::from_vec(3, (0..6).collect()).unwrap();
+        let rows: Vec<_> = grid.rows().collect();
+        assert_eq!(rows, [&[0, 1, 2][..], &[3, 4, 5][..]]);
+        let cells: Vec<_> = grid.iter_with_pos().collect();
+        assert_eq!(cells[0], ((0, 0), &0));
+        assert_eq!(cells[4], ((1, 1), &4));
+        assert_eq!(grid.iter().sum::<i32>(), 15);
+    }
+
+    #[test]
+    fn mutates_cells() {
+        let mut grid = Grid::new(3, 2, 0u32);
+        assert_eq!(grid.set(2, 1, 7), Some(0));
+        assert_eq!(grid.set(3, 1, 9), None);
+        *grid.get_mut(0, 0).unwrap() += 1;
+        grid[(1, 1)] = 5;
+        grid.iter_mut().for_each(|cell| *cell *= 10);
+        assert_eq!(grid.into_vec(), [10, 0, 0, 0, 50, 70]);
+
+        let grid = Grid::parse_bytes("#.\n.#\n").unwrap();
+        let walls = grid.map(|&b| b == b'#');
+        assert!(walls[(0, 0)] && walls[(1, 1)]);
+        assert!(!walls[(1, 0)]);
+    }
+
+    #[test]
+    fn renders_with_and_without_an_overlay() {
+        let heights = Grid::parse_digits("0123\n1234\n8765\n9876\n").unwrap();
+        let digit = |&h: &u8| char::from(b'0' + h);
+        assert_eq!(heights.render(digit), "0123\n1234\n8765\n9876\n");
+        assert_eq!(
+            heights.render(|&h| if h >= 5 { '#' } else { '.' }),
+            "....\n....\n####\n####\n"
+        );
+
+        // The trail from the trailhead at the top left to the 9 below
+        let trail = [(0, 0), (1, 0), (2, 0), (3, 0), (3, 1), (3, 2), (2, 2)]
+            .into_iter()
+            .chain([(1, 2), (0, 2), (0, 3)])
+            .map(Point2::from);
+        assert_eq!(
+            heights.render_with_overlay(digit, trail, '*'),
+            "****\n123*\n****\n*876\n"
+        );
+        // Points off the grid are ignored, and repeats are harmless
+        let outside = [Point2::new(-1, 0), Point2::new(4, 1), Point2::new(1, 1)];
+        assert_eq!(
+            heights.render_with_overlay(digit, outside.into_iter().chain(outside), '@'),
+            "0123\n1@34\n8765\n9876\n"
+        );
+        assert_eq!(Grid::<u8>::default().render(digit), "");
+    }
+
+    #[test]
+    fn renders_a_region_cut_to_the_grid() {
+        let grid = Grid::from_vec(5, (0..25).map(|i| i % 10).collect::<Vec<u8>>()).unwrap();
+        let digit = |&d: &u8| char::from(b'0' + d);
+        assert_eq!(grid.render_region(1..3, 2..4, digit), "12\n67\n");
+        assert_eq!(grid.render_region(3..99, 4..99, digit), "34\n");
+        assert_eq!(
+            grid.render_region(0..usize::MAX, 0..usize::MAX, digit),
+            grid.render(digit)
+        );
+        assert_eq!(grid.render_region(5..9, 0..2, digit), "\n\n");
+        assert_eq!(grid.render_region(0..2, 5..9, digit), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "(2, 0) is outside a 2x2 grid")]
+    fn panics_when_indexing_outside() {
+        let grid = Grid::new(2, 2, 0);
+        let _ = grid[(2, 0)];
+    }
+
+    #[test]
+    fn indexes_by_signed_point() {
+        let mut grid = Grid::parse_bytes(MAP).unwrap();
+        let start = Point2::from(grid.position(|&b| b == b'S').unwrap());
+        assert_eq!(grid[start], b'S');
+        assert_eq!(grid.get_point(start + Direction::North), Some(&b'.'));
+        assert_eq!(grid.get_point(start + Direction::East), None);
+        for edge in [
+            Point2::new(-1, 0),
+            Point2::new(0, -1),
+            Point2::new(i64::MIN, 2),
+        ] {
+            assert!(!grid.contains_point(edge), "{}", edge);
+            assert_eq!(grid.get_point(edge), None);
+        }
+
+        let corner = Point2::ZERO;
+        let open: Vec<_> = neighbors8(corner)
+            .into_iter()
+            .filter(|&p| grid.get_point(p) == Some(&b'.'))
+            .collect();
+        assert_eq!(open, [Point2::new(1, 0), Point2::new(1, 1)]);
+
+        grid[corner] = b'O';
+        *grid.get_point_mut(start).unwrap() = b'E';
+        assert_eq!(grid.get(0, 0), Some(&b'O'));
+        assert_eq!(grid.get(3, 2), Some(&b'E'));
+        assert_eq!(grid.get_point_mut(Point2::new(4, 2)), None);
+    }
+
+    /// The letters a walk visits, in order.
+    fn walked(grid: &Grid<u8>, from: Point2, direction: Direction) -> String {
+        grid.walk(from, direction)
+            .map(|(_, &b)| b as char)
+            .collect()
+    }
+
+    #[test]
+    fn walks_to_the_edge_in_every_direction() {
+        let grid = Grid::parse_bytes("abcd\nefgh\nijkl\nmnop\n").unwrap();
+        let from = Point2::new(1, 1);
+        let walks: Vec<String> = Direction::ALL
+            .iter()
+            .map(|&direction| walked(&grid, from, direction))
+            .collect();
+        assert_eq!(walks, ["b", "c", "gh", "kp", "jn", "i", "e", "a"]);
+        assert_eq!(
+            grid.walk(from, Direction::SouthEast)
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>(),
+            [Point2::new(2, 2), Point2::new(3, 3)]
+        );
+
+        // On the edge facing out, and outside facing away, go nowhere
+        assert_eq!(walked(&grid, Point2::new(3, 1), Direction::East), "");
+        assert_eq!(walked(&grid, Point2::ZERO, Direction::NorthWest), "");
+        assert_eq!(walked(&grid, Point2::new(-1, 2), Direction::West), "");
+        assert_eq!(
+            walked(&Grid::new(0, 0, b'.'), Point2::ZERO, Direction::East),
+            ""
+        );
+        // From a corner across, or from outside in
+        assert_eq!(walked(&grid, Point2::ZERO, Direction::SouthEast), "fkp");
+        assert_eq!(walked(&grid, Point2::new(-1, 2), Direction::East), "ijkl");
+        assert_eq!(
+            walked(&grid, Point2::new(4, -1), Direction::SouthWest),
+            "dgjm"
+        );
+        assert_eq!(walked(&grid, Point2::new(1, 4), Direction::North), "njfb");
+
+        for x in -2..=5 {
+            for y in -2..=5 {
+                for direction in Direction::ALL {
+                    let mut walk = grid.walk(Point2::new(x, y), direction);
+                    let len = walk.len();
+                    assert_eq!(walk.by_ref().count(), len, "({}, {}) {:?}", x, y, direction);
+                    assert_eq!(walk.next(), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stops_a_ray_at_the_first_blocking_cell() {
+        let grid = Grid::parse_bytes(MAP).unwrap();
+        let wall = |&b: &u8| b == b'#';
+        assert_eq!(
+            grid.ray_until(Point2::new(3, 1), Direction::West, wall),
+            Ray {
+                end: Point2::new(1, 1),
+                steps: 2,
+                blocked_by: Some((Point2::new(0, 1), &b'#')),
+            }
+        );
+        assert_eq!(
+            grid.ray_until(Point2::new(3, 2), Direction::North, wall),
+            Ray {
+                end: Point2::new(3, 0),
+                steps: 2,
+                blocked_by: None,
+            }
+        );
+        // Facing a wall, or the edge, takes no steps
+        let facing_wall = grid.ray_until(Point2::new(1, 0), Direction::East, wall);
+        assert_eq!((facing_wall.end, facing_wall.steps), (Point2::new(1, 0), 0));
+        assert_eq!(facing_wall.blocked_by, Some((Point2::new(2, 0), &b'#')));
+        let facing_edge = grid.ray_until(Point2::ZERO, Direction::North, wall);
+        assert_eq!((facing_edge.end, facing_edge.steps), (Point2::ZERO, 0));
+        assert_eq!(facing_edge.blocked_by, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "(0, -1) is outside a 2x2 grid")]
+    fn panics_when_indexing_outside_by_point() {
+        let grid = Grid::new(2, 2, 0);
+        let _ = grid[Point2::new(0, -1)];
+    }
+}
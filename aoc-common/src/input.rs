@@ -0,0 +1,247 @@
+//! Finding a day's puzzle input regardless of the working directory.
+//!
+//! Inputs live next to each day's code, but not at the same depth: some
+//! days keep `puzzle_input.txt` in `day-NN/` and others in the crate under
+//! it. `resolve` looks in every place an input may be, so a day binary
+//! finds its input from the workspace root, from inside its crate, or from
+//! anywhere else.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{AocError, Config};
+
+/// Environment variable naming a directory of inputs called `day-NN.txt`
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// File name of an input kept next to a day's code
+const PUZZLE_INPUT: &str = "puzzle_input.txt";
+
+/// The places one day's input is looked for, in the order they're tried.
+#[derive(Debug, Clone, Default)]
+pub struct Search {
+    /// A path given on the command line; when set, nothing else is tried,
+    /// since falling back would silently read a different file
+    pub explicit: Option<PathBuf>,
+    /// Directory holding `day-NN.txt`, normally `Config::input_dir`
+    pub input_dir: Option<PathBuf>,
+    /// Where the walk up to the workspace root starts, normally the
+    /// working directory
+    pub start_dir: PathBuf,
+    /// The day crate's own directory, which also locates the workspace
+    /// when `start_dir` is outside it
+    pub crate_dir: Option<PathBuf>,
+}
+
+impl Search {
+    /// The search a day binary runs: the configured input directory if
+    /// any, the working directory, and the crate directory it was built
+    /// from. The walk starts from the crate instead if the working
+    /// directory can't be read.
+    pub fn from_config(explicit: Option<&Path>, crate_dir: &Path, config: &Config) -> Self {
+        Search {
+            explicit: explicit.map(Path::to_path_buf),
+            input_dir: config.input_dir.clone(),
+            start_dir: env::current_dir().unwrap_or_else(|_| crate_dir.to_path_buf()),
+            crate_dir: Some(crate_dir.to_path_buf()),
+        }
+    }
+
+    /// Every path tried for `day`, in order, without duplicates.
+    ///
+    /// # Returns
+    /// The explicit path alone if one was given. Otherwise
+    /// `<input_dir>/day-NN.txt`, then `day-NN/puzzle_input.txt` under the
+    /// workspace enclosing `start_dir` and under the one enclosing
+    /// `crate_dir`, then `puzzle_input.txt` in `crate_dir`.
+    pub fn candidates(&self, day: u8) -> Vec<PathBuf> {
+        if let Some(path) = &self.explicit {
+            return vec![path.clone()];
+        }
+
+        let mut candidates = Vec::new();
+        if let Some(dir) = &self.input_dir {
+            candidates.push(dir.join(format!("day-{:02}.txt", day)));
+        }
+        let roots = [Some(self.start_dir.as_path()), self.crate_dir.as_deref()];
+        for root in roots.into_iter().flatten().filter_map(workspace_root) {
+            candidates.push(root.join(format!("day-{:02}", day)).join(PUZZLE_INPUT));
+        }
+        if let Some(dir) = &self.crate_dir {
+            candidates.push(dir.join(PUZZLE_INPUT));
+        }
+
+        let mut unique = Vec::with_capacity(candidates.len());
+        for path in candidates {
+            if !unique.contains(&path) {
+                unique.push(path);
+            }
+        }
+        unique
+    }
+
+    /// The first candidate for `day` that is a file.
+    ///
+    /// # Errors
+    /// Returns `AocError::MissingInput` with every path tried if none of
+    /// them is a file.
+    pub fn resolve(&self, day: u8) -> Result<PathBuf, AocError> {
+        let tried = self.candidates(day);
+        match tried.iter().find(|path| path.is_file()) {
+            Some(path) => Ok(path.clone()),
+            None => Err(AocError::MissingInput { day, tried }),
+        }
+    }
+}
+
+/// Finds the input for `day` the way a day binary does, starting from the
+/// working directory; see `Search::candidates` for the order.
+///
+/// # Arguments
+/// * `explicit` - Path given on the command line, if any
+/// * `crate_dir` - The calling crate's `CARGO_MANIFEST_DIR`
+/// * `config` - The binary's settings, for `input_dir`
+///
+/// # Errors
+/// Returns `AocError::MissingInput` with every path tried if none of them
+/// is a file.
+pub fn resolve(
+    day: u8,
+    explicit: Option<&Path>,
+    crate_dir: &Path,
+    config: &Config,
+) -> Result<PathBuf, AocError> {
+    Search::from_config(explicit, crate_dir, config).resolve(day)
+}
+
+/// The nearest directory at or above `dir` whose `Cargo.toml` declares a
+/// workspace.
+pub(crate) fn workspace_root(dir: &Path) -> Option<&Path> {
+    dir.ancestors().find(|dir| {
+        fs::read_to_string(dir.join("Cargo.toml"))
+            .is_ok_and(|manifest| manifest.lines().any(|line| line.trim() == "[workspace]"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A workspace with day 1's input at `day-01/` and day 2's inside its
+    /// crate at `day-02/rs/`, like this repository's.
+    fn workspace() -> TempDir {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path();
+        fs::write(path.join("Cargo.toml"), "[workspace]\nmembers = []\n").unwrap();
+        fs::create_dir_all(path.join("day-01/rs/src")).unwrap();
+        fs::create_dir_all(path.join("day-02/rs/src")).unwrap();
+        fs::write(path.join("day-01/rs/Cargo.toml"), "[package]\n").unwrap();
+        fs::write(path.join("day-01").join(PUZZLE_INPUT), "1 2\n").unwrap();
+        fs::write(path.join("day-02/rs").join(PUZZLE_INPUT), "1 2 3\n").unwrap();
+        root
+    }
+
+    /// The paths a failed search tried.
+    fn tried(error: &AocError) -> &[PathBuf] {
+        match error {
+            AocError::MissingInput { tried, .. } => tried,
+            other => panic!("expected a missing input, got {:?}", other),
+        }
+    }
+
+    fn search(start_dir: &Path, crate_dir: &Path) -> Search {
+        Search {
+            start_dir: start_dir.to_path_buf(),
+            crate_dir: Some(crate_dir.to_path_buf()),
+            ..Search::default()
+        }
+    }
+
+    #[test]
+    fn finds_the_workspace_input_from_any_directory_inside_it() {
+        let ws = workspace();
+        let root = ws.path();
+        let expected = root.join("day-01").join(PUZZLE_INPUT);
+        for start in [root, &root.join("day-01"), &root.join("day-01/rs/src")] {
+            let found = search(start, &root.join("day-01/rs")).resolve(1).unwrap();
+            assert_eq!(found, expected, "from {}", start.display());
+        }
+    }
+
+    #[test]
+    fn falls_back_to_the_crate_directory() {
+        let ws = workspace();
+        let root = ws.path();
+        let found = search(root, &root.join("day-02/rs")).resolve(2).unwrap();
+        assert_eq!(found, root.join("day-02/rs").join(PUZZLE_INPUT));
+    }
+
+    #[test]
+    fn finds_the_workspace_through_the_crate_from_outside_it() {
+        let ws = workspace();
+        let elsewhere = tempfile::tempdir().unwrap();
+        let found = search(elsewhere.path(), &ws.path().join("day-01/rs"))
+            .resolve(1)
+            .unwrap();
+        assert_eq!(found, ws.path().join("day-01").join(PUZZLE_INPUT));
+    }
+
+    #[test]
+    fn prefers_the_input_directory_over_the_workspace() {
+        let ws = workspace();
+        let inputs = tempfile::tempdir().unwrap();
+        fs::write(inputs.path().join("day-01.txt"), "3 4\n").unwrap();
+        let found = Search {
+            input_dir: Some(inputs.path().to_path_buf()),
+            ..search(ws.path(), &ws.path().join("day-01/rs"))
+        }
+        .resolve(1)
+        .unwrap();
+        assert_eq!(found, inputs.path().join("day-01.txt"));
+    }
+
+    #[test]
+    fn tries_only_an_explicit_path() {
+        let ws = workspace();
+        let explicit = ws.path().join("missing.txt");
+        let error = Search {
+            explicit: Some(explicit.clone()),
+            ..search(ws.path(), &ws.path().join("day-01/rs"))
+        }
+        .resolve(1)
+        .unwrap_err();
+        assert_eq!(tried(&error), [explicit]);
+    }
+
+    #[test]
+    fn lists_every_location_tried() {
+        let ws = workspace();
+        let inputs = tempfile::tempdir().unwrap();
+        let crate_dir = ws.path().join("day-03/rs");
+        let error = Search {
+            input_dir: Some(inputs.path().to_path_buf()),
+            ..search(&ws.path().join("day-01"), &crate_dir)
+        }
+        .resolve(3)
+        .unwrap_err();
+
+        // Both walks reach the same workspace, so it's tried once
+        let expected = [
+            inputs.path().join("day-03.txt"),
+            ws.path().join("day-03").join(PUZZLE_INPUT),
+            crate_dir.join(PUZZLE_INPUT),
+        ];
+        assert_eq!(tried(&error), expected);
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "no puzzle input found for day 3; tried:\n  {}\n  {}\n  {}",
+                expected[0].display(),
+                expected[1].display(),
+                expected[2].display()
+            )
+        );
+    }
+}
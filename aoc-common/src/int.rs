@@ -0,0 +1,71 @@
+//! Integer types the shared parser can produce.
+//!
+//! Days pick their own widths (`i64` location IDs, `i32` levels, `i128` for
+//! stress inputs), so parsing is generic over `Integer`, which every
+//! primitive integer type implements.
+
+use std::fmt;
+
+/// Primitive integer type that decimal tokens are parsed into.
+pub trait Integer: Copy + Ord + fmt::Display + fmt::Debug {
+    /// The value digits are accumulated from
+    const ZERO: Self;
+
+    /// Type name shown in out-of-range errors
+    const NAME: &'static str;
+
+    /// Longest run of digits that can never overflow, whatever the digits.
+    /// Tokens this short skip the per-digit overflow checks.
+    const SAFE_DIGITS: usize;
+
+    /// Appends a decimal digit (`self * 10 + digit`), returning `None` on
+    /// overflow.
+    fn push_digit(self, digit: u8) -> Option<Self>;
+
+    /// Appends a decimal digit to a negative value (`self * 10 - digit`),
+    /// returning `None` on overflow. Accumulating below zero reaches a
+    /// signed type's minimum, which has no positive counterpart.
+    fn push_negative_digit(self, digit: u8) -> Option<Self>;
+
+    /// Appends a decimal digit without checking for overflow. Only exact
+    /// while at most `SAFE_DIGITS` digits have been pushed.
+    fn push_digit_unchecked(self, digit: u8) -> Self;
+
+    /// Returns `-self`, or `None` if it doesn't fit, as for any nonzero
+    /// unsigned value.
+    fn checked_negate(self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($int:ty),*) => {
+        $(
+            impl Integer for $int {
+                const ZERO: Self = 0;
+                const NAME: &'static str = stringify!($int);
+                const SAFE_DIGITS: usize = <$int>::MAX.ilog10() as usize;
+
+                #[inline]
+                fn push_digit(self, digit: u8) -> Option<Self> {
+                    self.checked_mul(10)?.checked_add(digit as Self)
+                }
+
+                #[inline]
+                fn push_negative_digit(self, digit: u8) -> Option<Self> {
+                    self.checked_mul(10)?.checked_sub(digit as Self)
+                }
+
+                #[inline]
+                fn push_digit_unchecked(self, digit: u8) -> Self {
+                    self.wrapping_mul(10).wrapping_add(digit as Self)
+                }
+
+                #[inline]
+                fn checked_negate(self) -> Option<Self> {
+                    self.checked_neg()
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
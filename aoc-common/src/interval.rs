@@ -0,0 +1,283 @@
+//! Inclusive ranges of integers, and merging sets of them.
+//!
+//! Puzzles that hand out ranges mean both ends, so `Interval` holds both
+//! ends inclusive and never needs a `+ 1` to say what the puzzle said. Two
+//! intervals that merely touch, like 1-3 and 4-6, cover one run of
+//! integers, so merging joins them as it joins overlapping ones.
+
+use std::fmt;
+
+/// The integers from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    start: i64,
+    end: i64,
+}
+
+impl Interval {
+    /// The integers from `start` to `end`, both included.
+    ///
+    /// # Panics
+    /// If `start > end`, since an interval is never empty.
+    #[inline]
+    pub fn new(start: i64, end: i64) -> Self {
+        assert!(start <= end, "interval {}..={} is empty", start, end);
+        Interval { start, end }
+    }
+
+    /// The lowest integer in the interval.
+    #[inline]
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// The highest integer in the interval.
+    #[inline]
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// How many integers the interval holds, which is never zero.
+    ///
+    /// # Panics
+    /// For the interval spanning every `i64`, whose 2^64 integers don't fit
+    /// in a `u64`.
+    #[inline]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.end
+            .abs_diff(self.start)
+            .checked_add(1)
+            .expect("the interval spans every i64")
+    }
+
+    /// Whether `x` is in the interval.
+    #[inline]
+    pub fn contains(&self, x: i64) -> bool {
+        self.start <= x && x <= self.end
+    }
+
+    /// The integers in both intervals, or `None` if they share none.
+    pub fn intersect(&self, other: &Interval) -> Option<Interval> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    /// The integers in either interval, if they form a single interval:
+    /// when the two overlap or one ends right before the other starts.
+    pub fn union_if_overlapping(&self, other: &Interval) -> Option<Interval> {
+        let (first, second) = match self.start <= other.start {
+            true => (self, other),
+            false => (other, self),
+        };
+        // Compared in i128 so an interval ending at i64::MAX can't overflow
+        let touches = i128::from(second.start) <= i128::from(first.end) + 1;
+        touches.then(|| Interval {
+            start: first.start,
+            end: first.end.max(second.end),
+        })
+    }
+
+    /// Splits the interval into the integers below `at` and those from `at`
+    /// up, either of which may be `None` if it would be empty.
+    pub fn split_at(&self, at: i64) -> (Option<Interval>, Option<Interval>) {
+        if at <= self.start {
+            return (None, Some(*self));
+        }
+        if at > self.end {
+            return (Some(*self), None);
+        }
+        (
+            Some(Interval {
+                start: self.start,
+                end: at - 1,
+            }),
+            Some(Interval {
+                start: at,
+                end: self.end,
+            }),
+        )
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+/// Coalesces `intervals` into the fewest intervals covering the same
+/// integers, sorted by `start`.
+///
+/// Joined intervals overlapped or touched, so the result never holds two
+/// intervals that `union_if_overlapping` would join.
+///
+/// # Time Complexity
+/// O(n log n) for the sort, then O(n)
+pub fn merge_intervals(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.sort_unstable();
+    // Coalesced in place: `intervals[..merged]` is the result so far
+    let mut merged = 0;
+    for i in 0..intervals.len() {
+        let next = intervals[i];
+        if merged > 0 {
+            let last = &mut intervals[merged - 1];
+            if let Some(union) = last.union_if_overlapping(&next) {
+                *last = union;
+                continue;
+            }
+        }
+        intervals[merged] = next;
+        merged += 1;
+    }
+    intervals.truncate(merged);
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::Values;
+
+    /// An interval inside `-40..=40`, so checks can walk every integer
+    fn interval(values: &mut Values) -> Interval {
+        let (a, b) = (values.signed(40), values.signed(40));
+        Interval::new(a.min(b), a.max(b))
+    }
+
+    /// Every integer in `-50..=50`, comfortably past where `interval` goes
+    fn universe() -> impl Iterator<Item = i64> {
+        -50..=50
+    }
+
+    #[test]
+    fn merged_intervals_cover_the_same_integers() {
+        let mut values = Values(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..500 {
+            let count = values.below(8) as usize;
+            let intervals: Vec<_> = (0..count).map(|_| interval(&mut values)).collect();
+            let merged = merge_intervals(intervals.clone());
+            for x in universe() {
+                assert_eq!(
+                    merged.iter().any(|i| i.contains(x)),
+                    intervals.iter().any(|i| i.contains(x)),
+                    "{} in {:?} merged to {:?}",
+                    x,
+                    intervals,
+                    merged
+                );
+            }
+            // Sorted with a gap between neighbours, so none could be joined
+            for pair in merged.windows(2) {
+                assert!(pair[0].end + 1 < pair[1].start, "{:?}", merged);
+            }
+        }
+    }
+
+    #[test]
+    fn pairwise_operations_match_their_integers() {
+        let mut values = Values(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let (a, b) = (interval(&mut values), interval(&mut values));
+            let both: Vec<i64> = universe()
+                .filter(|&x| a.contains(x) && b.contains(x))
+                .collect();
+            let either: Vec<i64> = universe()
+                .filter(|&x| a.contains(x) || b.contains(x))
+                .collect();
+            let as_vec = |i: Option<Interval>| -> Vec<i64> {
+                i.map_or(Vec::new(), |i| (i.start..=i.end).collect())
+            };
+
+            assert_eq!(as_vec(a.intersect(&b)), both);
+            assert_eq!(a.intersect(&b), b.intersect(&a));
+            match a.union_if_overlapping(&b) {
+                Some(union) => assert_eq!(as_vec(Some(union)), either),
+                // Only a gap keeps the union from being one interval
+                None => assert!(either.windows(2).any(|w| w[1] > w[0] + 1)),
+            }
+            assert_eq!(a.union_if_overlapping(&b), b.union_if_overlapping(&a));
+
+            let at = values.signed(50);
+            let (below, above) = a.split_at(at);
+            let mut halves = as_vec(below);
+            assert!(halves.iter().all(|&x| x < at));
+            assert!(as_vec(above).iter().all(|&x| x >= at));
+            halves.extend(as_vec(above));
+            assert_eq!(halves, as_vec(Some(a)));
+        }
+    }
+
+    #[test]
+    fn merges_overlapping_nested_and_touching_intervals() {
+        let merged = merge_intervals(vec![
+            Interval::new(10, 14),
+            Interval::new(3, 5),
+            Interval::new(16, 20),
+            Interval::new(12, 18),
+            Interval::new(6, 6),
+            Interval::new(30, 30),
+            Interval::new(31, 35),
+            Interval::new(40, 50),
+            Interval::new(42, 43),
+        ]);
+        assert_eq!(
+            merged,
+            [
+                Interval::new(3, 6),
+                Interval::new(10, 20),
+                Interval::new(30, 35),
+                Interval::new(40, 50)
+            ]
+        );
+        assert!(merge_intervals(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn handles_the_ends_of_i64() {
+        let all = Interval::new(i64::MIN, i64::MAX);
+        let top = Interval::new(i64::MAX, i64::MAX);
+        let bottom = Interval::new(i64::MIN, -1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(bottom.len(), 1 << 63);
+        assert_eq!(Interval::new(i64::MIN, i64::MAX - 1).len(), u64::MAX);
+        assert!(all.contains(i64::MIN) && all.contains(i64::MAX));
+
+        assert_eq!(all.intersect(&top), Some(top));
+        assert_eq!(bottom.intersect(&top), None);
+        assert_eq!(
+            bottom.union_if_overlapping(&Interval::new(0, i64::MAX)),
+            Some(all)
+        );
+        assert_eq!(top.union_if_overlapping(&top), Some(top));
+        assert_eq!(bottom.union_if_overlapping(&top), None);
+        assert_eq!(
+            merge_intervals(vec![top, bottom, Interval::new(0, 5)]).len(),
+            2
+        );
+
+        assert_eq!(all.split_at(i64::MIN), (None, Some(all)));
+        assert_eq!(
+            all.split_at(i64::MAX),
+            (Some(Interval::new(i64::MIN, i64::MAX - 1)), Some(top))
+        );
+        assert_eq!(
+            Interval::new(1, 3).split_at(4),
+            (Some(Interval::new(1, 3)), None)
+        );
+        assert_eq!(top.to_string(), "9223372036854775807..=9223372036854775807");
+    }
+
+    #[test]
+    #[should_panic(expected = "spans every i64")]
+    fn the_length_of_everything_overflows() {
+        Interval::new(i64::MIN, i64::MAX).len();
+    }
+
+    #[test]
+    #[should_panic(expected = "interval 3..=2 is empty")]
+    fn rejects_empty_intervals() {
+        Interval::new(3, 2);
+    }
+}
@@ -0,0 +1,141 @@
+//! Code shared by every day's solution.
+//!
+//! Puzzle inputs are lines of decimal integers, and each day used to carry
+//! its own byte-level parser for them. They live here instead, so every day
+//! gets the same handling of signs, junk bytes, and overflow:
+//! - `parse_int` parses one token into any primitive integer type
+//! - `parse_ints_in_line` appends every blank-separated integer on a line
+//!
+//! Both work on raw bytes without UTF-8 validation or allocation. Errors
+//! carry the offending byte range, so callers can point at it in their own
+//! error types. For whole inputs in the common shapes, `lines_of_ints`
+//! gives one row per line and `columns_of_ints` one list per column, with
+//! errors naming the line and columns at fault. Parsers that walk tokens
+//! themselves use a `Scanner`, which finds token ends with `memchr` and
+//! knows the line and columns of every token it returns. Lines that bury
+//! their integers in text, like `p=0,4 v=3,-3`, give them all up to
+//! `extract_ints`, or to `extract_uints` where a `-` is only punctuation.
+//! Inputs made of sections split into them with `blocks` or
+//! `split_once_blank`. Binaries show a parse error under the line it points
+//! at with `diagnostics::render`.
+//!
+//! Each day also implements `Solver`, which splits a solution into one
+//! parse and two parts reading its result, so any day can be solved and
+//! timed the same way through `solve` or `run_solver`. Every step gets the
+//! run's `AocContext`, built once from the `Config` and the command line,
+//! with the reporter, the metrics, the thread count, and the day's own
+//! options by name. Each part returns an `Answer`, a number, text, or
+//! nothing, which prints and compares the way the puzzle site reads it.
+//! The answers a day is known to give are kept in `answers.toml`, which
+//! `Answers` loads. Days that time more
+//! than that record named phases and counters in `Metrics`, timing a
+//! block with `time_block!`, and `Metrics` renders the performance
+//! breakdown every day prints.
+//!
+//! Nothing prints directly: days hand answers, timings, and notes to a
+//! `Reporter`, which lays them out for people, prints just the answers,
+//! or records them for a test.
+//!
+//! Grid puzzles read their map into a `Grid`, a flat `width` by `height`
+//! block of cells indexed by `(x, y)` or by a signed `Point2`, which moves
+//! around it one `Direction` at a time or `walk`s it to the edge. Maps
+//! that are mostly empty go in a `PointMap` instead, which tracks the
+//! `BoundingBox` of its points and lays them out as a `Grid` on request.
+//! Puzzles that come down to modular arithmetic use `math`, with `gcd`,
+//! `lcm`, and the Chinese remainder theorem, and those about ranges use
+//! `Interval`, whose sets `merge_intervals` coalesces. A `Counter` tallies
+//! how often each value occurs, a `Dsu` groups things that connect
+//! pairwise into regions, and a `BitSet` holds sets of small integers,
+//! like visited cells by index.
+//! Ordering constraints go in a `DiGraph`, whose topological sort names a
+//! cycle when they contradict each other, and shortest routes through a
+//! maze or any other state space come from `search`. Recursions that meet
+//! the same subproblem again cache it in a `Memo`, and brute-force searches
+//! walk `cartesian_power`, `pairs`, or `combinations` one candidate at a
+//! time. Scratch slices needed once per line come from an `Arena`, or from
+//! the per-thread one `with_scratch` lends, which reuses its space instead
+//! of allocating.
+//!
+//! Every day binary parses its command line with clap, flattening in the
+//! `DayArgs` every day shares: input paths, `--part`, `--quiet`,
+//! `--metrics-json`, and `--threads`. The thread count goes to
+//! `init_parallelism`, which sizes the one rayon pool every parallel step
+//! shares, or leaves it out for a single thread so work runs serially.
+//!
+//! `input::resolve` finds a day's puzzle input from the command line,
+//! the configured input directory, or the workspace, so any day runs from
+//! any directory. That directory, like every per-machine setting, comes
+//! from a `Config` layered from `aoc.toml`, the environment, and flags.
+//!
+//! Whatever stops a day, from a missing input to a malformed line or an
+//! unknown option, ends up as an `AocError`, which every binary prints the
+//! same way and turns into its exit code.
+//!
+//! Property tests and benchmarks, here and in every day, draw their inputs
+//! from `values::Values`, so each run sees the same ones.
+
+mod answer;
+mod answers;
+mod arena;
+mod args;
+mod bitset;
+mod blocks;
+mod combinatorics;
+mod config;
+mod context;
+mod counter;
+pub mod diagnostics;
+mod dsu;
+mod error;
+mod graph;
+mod grid;
+pub mod input;
+mod int;
+mod interval;
+pub mod math;
+mod memo;
+mod metrics;
+mod parallel;
+mod parse;
+mod point;
+mod point_map;
+mod report;
+mod scan;
+pub mod search;
+mod solver;
+mod units;
+pub mod values;
+
+pub use answer::Answer;
+pub use answers::{Answers, ANSWERS_FILE};
+pub use arena::{scratch_peak, with_scratch, Arena};
+pub use args::{day_reporter, parse_args, parse_args_from, DayArgs, MetricsOutput, Part};
+pub use bitset::{BitSet, Bits, FixedBitSet};
+pub use blocks::{blocks, split_once_blank};
+pub use combinatorics::{
+    cartesian_power, combinations, pairs, CartesianPower, Combinations, Pairs,
+};
+pub use config::{Config, CONFIG_FILE, SESSION_COOKIE_VAR, THREADS_VAR, YEAR_VAR};
+pub use context::AocContext;
+pub use counter::Counter;
+pub use dsu::{Dsu, KeyedDsu};
+pub use error::AocError;
+pub use graph::{CycleError, DiGraph};
+pub use grid::{Grid, Ray, Walk};
+pub use int::Integer;
+pub use interval::{merge_intervals, Interval};
+pub use memo::{memoize, DenseMemo, Memo, Memoized, MEMO_HITS, MEMO_MISSES};
+pub use metrics::{Clock, Metrics, PhaseGuard, SystemClock};
+pub use parallel::{init_parallelism, parallelism};
+pub use parse::{
+    columns_of_ints, extract_ints, extract_uints, lines_of_ints, parse_int, parse_ints_in_line,
+    IntErrorKind, ParseIntError,
+};
+pub use point::{manhattan, neighbors4, neighbors8, Direction, Point2};
+pub use point_map::{BoundingBox, PointMap};
+pub use report::{
+    HumanReporter, OutputStyle, QuietReporter, RecordingReporter, Reporter, OUTPUT_VAR,
+};
+pub use scan::Scanner;
+pub use solver::{run_solver, solve, ParseInput, Solution, Solver};
+pub use units::{fmt_count_rate, fmt_duration, fmt_rate};
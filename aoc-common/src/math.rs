@@ -0,0 +1,295 @@
+//! Number theory for puzzles that reduce to modular arithmetic.
+//!
+//! Inputs are `i64`, which holds any value a puzzle gives, and the work is
+//! done in `i128`, so products of two values never overflow on the way.
+//! Anything whose result can still overflow, like `lcm` of two large
+//! values or `crt` over many moduli, returns `None` instead.
+
+/// Extended Euclid on `i128`: `(g, x, y)` with `a * x + b * y == g` and
+/// `g >= 0`.
+fn egcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Narrows a result that the bounds of `i64` inputs keep in range, except
+/// for `2^63`, which only `i64::MIN` produces.
+fn narrow(value: i128) -> i64 {
+    i64::try_from(value).expect("result is 2^63, which doesn't fit in i64")
+}
+
+/// The greatest common divisor of `a` and `b`, never negative.
+///
+/// `gcd(0, 0)` is 0, and `gcd(a, 0)` is `|a|`.
+///
+/// # Panics
+/// If the result is `2^63`, which only `gcd(i64::MIN, 0)` and
+/// `gcd(i64::MIN, i64::MIN)` give.
+///
+/// # Time Complexity
+/// O(log min(|a|, |b|))
+pub fn gcd(a: i64, b: i64) -> i64 {
+    narrow(egcd_wide(a.into(), b.into()).0)
+}
+
+/// The least common multiple of `a` and `b`, never negative, or `None` if
+/// it doesn't fit in `i64`.
+///
+/// `lcm(a, 0)` is 0, following `gcd(a, 0) == |a|`.
+pub fn lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    let (a, b) = (i128::from(a).abs(), i128::from(b).abs());
+    i64::try_from(a / egcd_wide(a, b).0 * b).ok()
+}
+
+/// Bézout coefficients: `(g, x, y)` with `a * x + b * y == g`, where `g`
+/// is `gcd(a, b)`.
+///
+/// The coefficients are the small ones Euclid's algorithm finds, with
+/// `|x| <= |b / g|` and `|y| <= |a / g|` unless either input is 0, so they
+/// always fit.
+///
+/// # Panics
+/// As `gcd` does, if `g` is `2^63`.
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = egcd_wide(a.into(), b.into());
+    (narrow(g), narrow(x), narrow(y))
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, or `None` if `a` and `m`
+/// share a factor or `m` isn't positive.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    inverse_wide(a.into(), m.into()).map(narrow)
+}
+
+/// `mod_inverse` on `i128`, for a positive `m`.
+fn inverse_wide(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = egcd_wide(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// The smallest `x >= 0` with `x ≡ residue (mod modulus)` for every pair,
+/// by the Chinese remainder theorem.
+///
+/// Moduli needn't be coprime: two congruences that agree modulo the gcd
+/// of their moduli merge into one modulo their lcm, and two that don't
+/// have no solution. Residues may be negative or larger than their
+/// modulus; with no congruences at all, the answer is 0.
+///
+/// # Returns
+/// `None` if the congruences contradict each other, a modulus isn't
+/// positive, or the combined modulus doesn't fit in `i128`.
+///
+/// # Time Complexity
+/// O(k log M) for k congruences whose lcm is M
+pub fn crt(congruences: &[(i64, i64)]) -> Option<i128> {
+    let (mut x, mut m) = (0i128, 1i128);
+    for &(residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let (r, n) = (
+            i128::from(residue).rem_euclid(modulus.into()),
+            i128::from(modulus),
+        );
+        let g = egcd_wide(m, n).0;
+        let diff = r - x;
+        if diff % g != 0 {
+            return None;
+        }
+        // x + m * t ≡ r (mod n), so t ≡ (diff / g) / (m / g) (mod n / g)
+        let step = n / g;
+        let t = match step {
+            1 => 0,
+            _ => {
+                (diff / g)
+                    .rem_euclid(step)
+                    .checked_mul(inverse_wide(m / g, step)?)?
+                    % step
+            }
+        };
+        x = x.checked_add(m.checked_mul(t)?)?;
+        m = m.checked_mul(step)?;
+        x = x.rem_euclid(m);
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::Values;
+
+    #[test]
+    fn gcd_divides_both_and_is_the_largest_that_does() {
+        let mut values = Values(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..2000 {
+            let (a, b) = (values.signed(1 << 40), values.signed(1 << 20));
+            let g = gcd(a, b);
+            assert!(g >= 0);
+            if g == 0 {
+                assert_eq!((a, b), (0, 0));
+                continue;
+            }
+            assert_eq!((a % g, b % g), (0, 0), "gcd({}, {}) = {}", a, b, g);
+            assert_eq!(gcd(a / g, b / g), 1, "gcd({}, {}) = {}", a, b, g);
+            assert_eq!(gcd(b, a), g);
+        }
+    }
+
+    #[test]
+    fn gcd_and_lcm_at_the_boundaries() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(0, -7), 7);
+        assert_eq!(gcd(-12, -18), 6);
+        assert_eq!(gcd(i64::MAX, i64::MAX - 1), 1);
+        assert_eq!(gcd(i64::MIN, 6), 2);
+        assert_eq!(gcd(i64::MIN, i64::MAX), 1);
+
+        assert_eq!(lcm(0, 5), Some(0));
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(i64::MAX, 1), Some(i64::MAX));
+        assert_eq!(lcm(i64::MAX, 2), None);
+        assert_eq!(lcm(1 << 32, 1 << 31), Some(1 << 32));
+        assert_eq!(lcm(i64::MIN, 1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in i64")]
+    fn gcd_of_the_minimum_and_zero_overflows() {
+        gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    fn lcm_is_a_multiple_of_both_and_pairs_with_gcd() {
+        let mut values = Values(0x2545_f491_4f6c_dd1d);
+        for _ in 0..2000 {
+            let (a, b) = (values.signed(1 << 30), values.signed(1 << 30));
+            let l = lcm(a, b).unwrap();
+            if a == 0 || b == 0 {
+                assert_eq!(l, 0);
+                continue;
+            }
+            assert_eq!((l % a, l % b), (0, 0));
+            assert_eq!(
+                i128::from(l) * i128::from(gcd(a, b)),
+                (i128::from(a) * i128::from(b)).abs()
+            );
+        }
+    }
+
+    #[test]
+    fn egcd_finds_bezout_coefficients() {
+        let mut values = Values(0xdead_beef_cafe_f00d);
+        let mut cases: Vec<(i64, i64)> = (0..2000).map(|_| (values.any(), values.any())).collect();
+        cases.extend([(0, 0), (0, 5), (-5, 0), (i64::MAX, i64::MIN), (1, i64::MIN)]);
+        for (a, b) in cases {
+            let (g, x, y) = egcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(
+                i128::from(a) * i128::from(x) + i128::from(b) * i128::from(y),
+                i128::from(g),
+                "egcd({}, {})",
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn inverts_modulo_m() {
+        let mut values = Values(0x0123_4567_89ab_cdef);
+        for _ in 0..2000 {
+            let (a, m) = (values.signed(1 << 50), values.positive(1 << 40));
+            match mod_inverse(a, m) {
+                Some(x) => {
+                    assert!((0..m).contains(&x));
+                    let product = (i128::from(a) * i128::from(x)).rem_euclid(m.into());
+                    assert_eq!(product, 1 % i128::from(m), "{}^-1 mod {}", a, m);
+                }
+                None => assert_ne!(gcd(a, m), 1, "{} mod {}", a, m),
+            }
+        }
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(-3, 7), Some(2));
+        assert_eq!(mod_inverse(4, 8), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        assert_eq!(mod_inverse(5, 0), None);
+        assert_eq!(mod_inverse(5, -7), None);
+        assert_eq!(mod_inverse(i64::MAX - 1, i64::MAX), Some(i64::MAX - 1));
+    }
+
+    #[test]
+    fn crt_satisfies_every_congruence() {
+        let mut values = Values(0xfeed_face_dead_c0de);
+        for _ in 0..500 {
+            let count = values.positive(4) as usize;
+            let congruences: Vec<(i64, i64)> = (0..count)
+                .map(|_| (values.signed(1 << 40), values.positive(1000)))
+                .collect();
+            let lcm = congruences.iter().fold(1i128, |l, &(_, m)| {
+                let g = i128::from(gcd(l as i64, m));
+                l / g * i128::from(m)
+            });
+            match crt(&congruences) {
+                Some(x) => {
+                    assert!((0..lcm).contains(&x), "{:?} gave {}", congruences, x);
+                    for &(r, m) in &congruences {
+                        assert_eq!(x.rem_euclid(m.into()), i128::from(r).rem_euclid(m.into()));
+                    }
+                }
+                // Only moduli sharing a factor can contradict each other
+                None => assert!(
+                    pairs_contradict(&congruences),
+                    "{:?} has a solution",
+                    congruences
+                ),
+            }
+        }
+    }
+
+    /// Whether two congruences disagree modulo the gcd of their moduli.
+    fn pairs_contradict(congruences: &[(i64, i64)]) -> bool {
+        congruences.iter().enumerate().any(|(i, &(r1, m1))| {
+            congruences[i + 1..]
+                .iter()
+                .any(|&(r2, m2)| (r1 - r2).rem_euclid(gcd(m1, m2)) != 0)
+        })
+    }
+
+    #[test]
+    fn crt_at_the_boundaries() {
+        assert_eq!(crt(&[]), Some(0));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some(23));
+        assert_eq!(crt(&[(-1, 4)]), Some(3));
+        assert_eq!(crt(&[(3, 4), (1, 6)]), Some(7));
+        assert_eq!(crt(&[(3, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(5, 1), (0, 1)]), Some(0));
+        assert_eq!(crt(&[(1, 0)]), None);
+        assert_eq!(crt(&[(1, -3)]), None);
+
+        // Two large coprime moduli need the i128 range
+        let (p, q) = (i64::MAX, i64::MAX - 1);
+        let x = crt(&[(1, p), (2, q)]).unwrap();
+        assert_eq!((x % i128::from(p), x % i128::from(q)), (1, 2));
+        // A third pushes the combined modulus past it
+        assert_eq!(crt(&[(1, p), (2, q), (3, i64::MAX - 2)]), None);
+    }
+}
@@ -0,0 +1,356 @@
+//! Caches for recursive solvers that meet the same subproblem many times.
+//!
+//! A recursion can't hold a borrow of its cache across the recursive call,
+//! so `HashMap::entry` with the computation inside doesn't compile. Here
+//! the computation gets the cache back as an argument, `&mut` for as long
+//! as it runs, and its result is stored once it returns:
+//!
+//! ```
+//! use aoc_common::Memo;
+//!
+//! fn ways(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+//!     memo.get_or_insert_with(n, |memo| match n {
+//!         0 | 1 => 1,
+//!         _ => ways(memo, n - 1) + ways(memo, n - 2),
+//!     })
+//! }
+//!
+//! assert_eq!(ways(&mut Memo::new(), 80), 37_889_062_373_143_906);
+//! ```
+//!
+//! `memoize` wraps a closure the same way, for recursions that don't need
+//! a named function. Every cache counts its hits and misses, which
+//! `count_to` adds to a day's `Metrics`.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::{Clock, Metrics};
+
+/// Counter `count_to` adds cache hits to
+pub const MEMO_HITS: &str = "Memo hits";
+
+/// Counter `count_to` adds cache misses to
+pub const MEMO_MISSES: &str = "Memo misses";
+
+/// Results of a computation by key, in a `HashMap`.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    values: HashMap<K, V>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Memo {
+            values: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The value stored for `key`, or else the one `compute` returns, which
+    /// is stored for next time.
+    ///
+    /// `compute` gets the cache itself, so it can recurse through it. A
+    /// key it stores under is overwritten by its result.
+    pub fn get_or_insert_with(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.values.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = compute(self);
+        self.values.insert(key, value.clone());
+        value
+    }
+
+    /// The value stored for `key`, without counting a hit or miss.
+    #[inline]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.values.get(key)
+    }
+
+    /// Stores `value` for `key`, such as a base case known up front.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.values.insert(key, value);
+    }
+
+    /// Keys stored.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether nothing is stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Lookups answered from the cache.
+    #[inline]
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Lookups that had to compute their value.
+    #[inline]
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Adds the hits and misses to `MEMO_HITS` and `MEMO_MISSES`.
+    pub fn count_to<C: Clock>(&self, metrics: &Metrics<C>) {
+        metrics.count(MEMO_HITS, self.hits);
+        metrics.count(MEMO_MISSES, self.misses);
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+/// Results of a computation by a small integer key, such as an offset
+/// into a string, in a `Vec` that grows to fit the largest key.
+#[derive(Debug, Clone)]
+pub struct DenseMemo<V> {
+    values: Vec<Option<V>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<V: Clone> DenseMemo<V> {
+    /// An empty cache.
+    pub fn new() -> Self {
+        DenseMemo {
+            values: Vec::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// An empty cache with room for keys below `len` without growing.
+    pub fn with_len(len: usize) -> Self {
+        DenseMemo {
+            values: vec![None; len],
+            ..DenseMemo::new()
+        }
+    }
+
+    /// The value stored for `key`, or else the one `compute` returns, as
+    /// `Memo::get_or_insert_with` does.
+    pub fn get_or_insert_with(&mut self, key: usize, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(Some(value)) = self.values.get(key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = compute(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    /// The value stored for `key`, without counting a hit or miss.
+    #[inline]
+    pub fn get(&self, key: usize) -> Option<&V> {
+        self.values.get(key)?.as_ref()
+    }
+
+    /// Stores `value` for `key`, growing to fit it.
+    pub fn insert(&mut self, key: usize, value: V) {
+        if key >= self.values.len() {
+            self.values.resize(key + 1, None);
+        }
+        self.values[key] = Some(value);
+    }
+
+    /// Lookups answered from the cache.
+    #[inline]
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Lookups that had to compute their value.
+    #[inline]
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Adds the hits and misses to `MEMO_HITS` and `MEMO_MISSES`.
+    pub fn count_to<C: Clock>(&self, metrics: &Metrics<C>) {
+        metrics.count(MEMO_HITS, self.hits);
+        metrics.count(MEMO_MISSES, self.misses);
+    }
+}
+
+impl<V: Clone> Default for DenseMemo<V> {
+    fn default() -> Self {
+        DenseMemo::new()
+    }
+}
+
+/// A closure memoized over a `Memo`, from `memoize`.
+pub struct Memoized<K, V, F> {
+    memo: Memo<K, V>,
+    f: F,
+}
+
+/// Memoizes `f`, which gets a function to recurse through and the key to
+/// compute, so a closure can recurse without naming itself:
+///
+/// ```
+/// use aoc_common::memoize;
+///
+/// let mut ways = memoize(|ways: &mut dyn FnMut(u64) -> u64, n: u64| match n {
+///     0 | 1 => 1,
+///     _ => ways(n - 1) + ways(n - 2),
+/// });
+/// assert_eq!(ways.call(80), 37_889_062_373_143_906);
+/// assert_eq!(ways.memo().misses(), 81);
+/// ```
+pub fn memoize<K, V, F>(f: F) -> Memoized<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    Memoized {
+        memo: Memo::new(),
+        f,
+    }
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    /// The value for `key`, from the cache or computed and stored.
+    pub fn call(&mut self, key: K) -> V {
+        recurse(&mut self.memo, &self.f, key)
+    }
+
+    /// The cache, with its hit and miss counts.
+    pub fn memo(&self) -> &Memo<K, V> {
+        &self.memo
+    }
+}
+
+/// One call of a `Memoized` closure, handing it a function that recurses
+/// back through here.
+fn recurse<K, V, F>(memo: &mut Memo<K, V>, f: &F, key: K) -> V
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    memo.get_or_insert_with(key.clone(), |memo| {
+        f(&mut |next| recurse(memo, f, next), key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib(memo: &mut Memo<u32, u64>, n: u32) -> u64 {
+        memo.get_or_insert_with(n, |memo| match n {
+            0 | 1 => n.into(),
+            _ => fib(memo, n - 1) + fib(memo, n - 2),
+        })
+    }
+
+    #[test]
+    fn counts_hits_in_a_fibonacci_recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(&mut memo, 90), 2_880_067_194_370_816_120);
+        // Each n from 0 to 90 is computed once, and each n from 3 up finds
+        // n - 2 already computed on the way to n - 1
+        assert_eq!((memo.misses(), memo.hits()), (91, 88));
+        assert_eq!(memo.len(), 91);
+
+        // A second call is one hit
+        assert_eq!(fib(&mut memo, 90), 2_880_067_194_370_816_120);
+        assert_eq!((memo.misses(), memo.hits()), (91, 89));
+        assert_eq!(memo.get(&10), Some(&55));
+        assert_eq!(memo.get(&91), None);
+    }
+
+    #[test]
+    fn counts_pebbles_with_a_closure() {
+        // Day 11's rules: 0 becomes 1, even-length numbers split in two,
+        // and everything else is multiplied by 2024
+        let mut stones = memoize(|count: &mut dyn FnMut((u64, u8)) -> u64, (stone, blinks)| {
+            if blinks == 0 {
+                return 1;
+            }
+            let digits = stone.checked_ilog10().unwrap_or(0) + 1;
+            match stone {
+                0 => count((1, blinks - 1)),
+                _ if digits % 2 == 0 => {
+                    let half = 10u64.pow(digits / 2);
+                    count((stone / half, blinks - 1)) + count((stone % half, blinks - 1))
+                }
+                _ => count((stone * 2024, blinks - 1)),
+            }
+        });
+        let total: u64 = [125, 17].iter().map(|&s| stones.call((s, 25))).sum();
+        assert_eq!(total, 55312);
+        assert!(stones.memo().hits() > 0);
+        assert_eq!(stones.memo().misses() as usize, stones.memo().len());
+    }
+
+    #[test]
+    fn counts_towel_arrangements_densely() {
+        // Day 19: ways to build a design from the towels, keyed by offset
+        fn ways(memo: &mut DenseMemo<u64>, towels: &[&str], design: &str, at: usize) -> u64 {
+            if at == design.len() {
+                return 1;
+            }
+            memo.get_or_insert_with(at, |memo| {
+                towels
+                    .iter()
+                    .filter(|towel| design[at..].starts_with(*towel))
+                    .map(|towel| ways(memo, towels, design, at + towel.len()))
+                    .sum()
+            })
+        }
+        let towels = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+        let expected = [
+            ("brwrr", 2),
+            ("bggr", 1),
+            ("gbbr", 4),
+            ("rrbgbr", 6),
+            ("ubwu", 0),
+        ];
+        for (design, count) in expected {
+            let mut memo = DenseMemo::with_len(design.len());
+            assert_eq!(ways(&mut memo, &towels, design, 0), count, "{}", design);
+        }
+
+        let mut memo = DenseMemo::new();
+        assert_eq!(ways(&mut memo, &towels, "rrbgbr", 0), 6);
+        assert_eq!(
+            (memo.misses(), memo.get(0), memo.get(99)),
+            (6, Some(&6), None)
+        );
+        assert!(memo.hits() > 0);
+    }
+
+    #[test]
+    fn adds_its_counts_to_metrics() {
+        let mut memo = Memo::new();
+        fib(&mut memo, 10);
+        let metrics = Metrics::new();
+        memo.count_to(&metrics);
+        memo.count_to(&metrics);
+        assert_eq!(metrics.counter(MEMO_MISSES), 22);
+        assert_eq!(metrics.counter(MEMO_HITS), 16);
+    }
+}
@@ -0,0 +1,430 @@
+//! Phase timings and counters for a day's performance breakdown.
+//!
+//! Every day times the same steps (reading, parsing, each part) plus a few
+//! of its own, and prints them the same way. `Metrics` collects named
+//! phases through drop guards, so a phase is timed by holding the guard
+//! `scope` returns for the duration of a block, and `report_to` hands the
+//! breakdown to a `Reporter`.
+//!
+//! `time_block!` wraps a block in a scope and evaluates to the block's
+//! value, so a timed step reads like the untimed one:
+//!
+//! ```
+//! use aoc_common::{time_block, Metrics};
+//!
+//! let metrics = Metrics::new();
+//! let mut total = 0;
+//! for line in ["3 4", "4 3"] {
+//!     // Each line adds to the same `parse` total
+//!     let pair: Vec<u32> = time_block!(metrics, "parse", {
+//!         line.split(' ').map(|n| n.parse().unwrap()).collect()
+//!     });
+//!     total += time_block!(metrics, "part1", { pair[0].abs_diff(pair[1]) });
+//! }
+//! assert_eq!(total, 2);
+//! assert!(metrics.report().contains("Parsing time: "));
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+use super::{fmt_duration, HumanReporter, Reporter};
+
+/// A monotonic time source, so tests can make durations deterministic.
+pub trait Clock {
+    /// Time since some fixed origin; only differences are meaningful.
+    fn now(&self) -> Duration;
+}
+
+/// The real clock, measuring from when it was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// One named phase; re-entering it adds to the same total.
+#[derive(Debug, Clone)]
+struct Phase {
+    name: &'static str,
+    elapsed: Duration,
+    /// Phases already open when this one was first entered
+    depth: usize,
+}
+
+/// Named phase durations and counters, in the order they were first seen.
+///
+/// The phases every day shares are `read`, `parse`, `part1` and `part2`,
+/// which `report` labels as `File read`, `Parsing`, `Part 1` and `Part 2`.
+/// Any other name is a custom phase, shown as written.
+///
+/// # Implementation Details
+/// - Phases and counters are updated through `&self`, so a guard can stay
+///   open while nested phases and counters are recorded
+/// - Names are looked up with a linear scan, which beats hashing for the
+///   handful of phases a day has, even when re-entered once per line;
+///   the scan compares pointers before bytes, so it's usually one compare
+///   per phase
+#[derive(Debug, Default)]
+pub struct Metrics<C = SystemClock> {
+    clock: C,
+    phases: RefCell<Vec<Phase>>,
+    counters: RefCell<Vec<(&'static str, u64)>>,
+    /// Guards currently open
+    depth: Cell<usize>,
+}
+
+/// Times one entry into a phase, adding the duration when dropped.
+#[must_use = "the phase ends as soon as the guard is dropped"]
+pub struct PhaseGuard<'a, C: Clock> {
+    metrics: &'a Metrics<C>,
+    index: usize,
+    start: Duration,
+}
+
+impl<C: Clock> Drop for PhaseGuard<'_, C> {
+    fn drop(&mut self) {
+        let elapsed = self.metrics.clock.now().saturating_sub(self.start);
+        self.metrics.phases.borrow_mut()[self.index].elapsed += elapsed;
+        self.metrics.depth.set(self.metrics.depth.get() - 1);
+    }
+}
+
+impl Metrics {
+    /// Creates empty metrics on the real clock.
+    pub fn new() -> Self {
+        Metrics::default()
+    }
+}
+
+impl<C: Clock> Metrics<C> {
+    /// Creates empty metrics on the given clock.
+    pub fn with_clock(clock: C) -> Self {
+        Metrics {
+            clock,
+            phases: RefCell::default(),
+            counters: RefCell::default(),
+            depth: Cell::new(0),
+        }
+    }
+
+    /// The clock durations are measured on.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Index of the phase called `name`, added at the current depth if new.
+    fn phase_index(&self, name: &'static str) -> usize {
+        let mut phases = self.phases.borrow_mut();
+        match phases.iter().position(|phase| same_name(phase.name, name)) {
+            Some(index) => index,
+            None => {
+                phases.push(Phase {
+                    name,
+                    elapsed: Duration::ZERO,
+                    depth: self.depth.get(),
+                });
+                phases.len() - 1
+            }
+        }
+    }
+
+    /// Starts timing `name` until the returned guard is dropped.
+    ///
+    /// Entering a phase again adds to its total. A phase first entered
+    /// while another's guard is open is nested under it in the report.
+    #[inline]
+    pub fn scope(&self, name: &'static str) -> PhaseGuard<'_, C> {
+        let index = self.phase_index(name);
+        self.depth.set(self.depth.get() + 1);
+        PhaseGuard {
+            metrics: self,
+            index,
+            start: self.clock.now(),
+        }
+    }
+
+    /// Adds a duration measured elsewhere, such as on another thread, to
+    /// the phase `name`.
+    pub fn record(&self, name: &'static str, elapsed: Duration) {
+        let index = self.phase_index(name);
+        self.phases.borrow_mut()[index].elapsed += elapsed;
+    }
+
+    /// Total time spent in `name`, zero if it was never entered.
+    pub fn elapsed(&self, name: &str) -> Duration {
+        self.phases
+            .borrow()
+            .iter()
+            .find(|phase| phase.name == name)
+            .map_or(Duration::ZERO, |phase| phase.elapsed)
+    }
+
+    /// Adds `n` to the counter `name`, creating it at zero if new.
+    pub fn count(&self, name: &'static str, n: u64) {
+        let mut counters = self.counters.borrow_mut();
+        match counters.iter_mut().find(|(counter, _)| *counter == name) {
+            Some((_, value)) => *value += n,
+            None => counters.push((name, n)),
+        }
+    }
+
+    /// Current value of the counter `name`, zero if it was never counted.
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counters
+            .borrow()
+            .iter()
+            .find(|(counter, _)| *counter == name)
+            .map_or(0, |&(_, value)| value)
+    }
+
+    /// Reports the breakdown: one `name: value` note per counter, then
+    /// each phase under its label, nested phases indented by two spaces
+    /// per level.
+    pub fn report_to(&self, out: &mut dyn Reporter) {
+        for (name, value) in self.counters.borrow().iter() {
+            out.note(&format!("{}: {}", name, value));
+        }
+        for phase in self.phases.borrow().iter() {
+            let name = format!(
+                "{:indent$}{}",
+                "",
+                label(phase.name),
+                indent = phase.depth * 2
+            );
+            out.phase(&name, phase.elapsed);
+        }
+    }
+
+    /// Renders the breakdown as `report_to` reports it to a
+    /// `HumanReporter`.
+    pub fn report(&self) -> String {
+        render(|out| self.report_to(out))
+    }
+
+    /// Reports the mean time per item of each phase in `names`, such as
+    /// `Parsing: 26ns/pair`, skipping phases never entered. Reports
+    /// nothing for zero items.
+    pub fn averages_to(&self, names: &[&str], items: usize, unit: &str, out: &mut dyn Reporter) {
+        if items == 0 {
+            return;
+        }
+        let phases = self.phases.borrow();
+        for &name in names {
+            if let Some(phase) = phases.iter().find(|phase| phase.name == name) {
+                let mean = phase.elapsed.div_f64(items as f64);
+                out.note(&format!("{}: {}/{}", label(name), fmt_duration(mean), unit));
+            }
+        }
+    }
+
+    /// Renders the averages as `averages_to` reports them to a
+    /// `HumanReporter`.
+    pub fn averages(&self, names: &[&str], items: usize, unit: &str) -> String {
+        render(|out| self.averages_to(names, items, unit, out))
+    }
+}
+
+/// Times a block as the phase `$name` of `$metrics`, evaluating to the
+/// block's value.
+///
+/// Shorthand for holding `$metrics.scope($name)` across the block, so
+/// entering the same phase again accumulates and a phase first entered
+/// inside another is nested under it. The block is inlined rather than
+/// wrapped in a closure, so `?`, `break`, and `return` inside it act on the
+/// enclosing code, and the time up to that point is still recorded.
+#[macro_export]
+macro_rules! time_block {
+    ($metrics:expr, $name:expr, $body:block) => {{
+        let _phase = ($metrics).scope($name);
+        $body
+    }};
+}
+
+/// What `report` writes to a `HumanReporter`, as a string.
+fn render(report: impl FnOnce(&mut dyn Reporter)) -> String {
+    let mut human = HumanReporter::new(Vec::new());
+    report(&mut human);
+    String::from_utf8(human.into_inner()).expect("reports are built from strings")
+}
+
+/// Whether two phase names are equal, checking for the same literal first
+/// since callers pass the same `&'static str` on every entry.
+#[inline]
+fn same_name(a: &str, b: &str) -> bool {
+    std::ptr::eq(a, b) || a == b
+}
+
+/// The breakdown label of a phase: the shared phases get readable names,
+/// and custom ones are shown as written.
+fn label(name: &str) -> &str {
+    match name {
+        "read" => "File read",
+        "parse" => "Parsing",
+        "part1" => "Part 1",
+        "part2" => "Part 2",
+        custom => custom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clock that only moves when told to.
+    #[derive(Default)]
+    struct ManualClock(Cell<Duration>);
+
+    impl ManualClock {
+        fn advance(&self, millis: u64) {
+            self.0.set(self.0.get() + Duration::from_millis(millis));
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+    }
+
+    fn metrics() -> Metrics<ManualClock> {
+        Metrics::with_clock(ManualClock::default())
+    }
+
+    #[test]
+    fn times_a_phase_until_its_guard_drops() {
+        let metrics = metrics();
+        {
+            let _parse = metrics.scope("parse");
+            metrics.clock().advance(5);
+        }
+        metrics.clock().advance(100);
+        assert_eq!(metrics.elapsed("parse"), Duration::from_millis(5));
+        assert_eq!(metrics.elapsed("part1"), Duration::ZERO);
+    }
+
+    #[test]
+    fn accumulates_repeated_entries() {
+        let metrics = metrics();
+        for millis in [1, 2, 3] {
+            let _part1 = metrics.scope("part1");
+            metrics.clock().advance(millis);
+        }
+        metrics.record("part1", Duration::from_millis(4));
+        assert_eq!(metrics.elapsed("part1"), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn nests_phases_in_the_report() {
+        let metrics = metrics();
+        {
+            let _read = metrics.scope("read");
+            metrics.clock().advance(1);
+        }
+        {
+            let _parse = metrics.scope("parse");
+            metrics.clock().advance(2);
+            {
+                let _dedupe = metrics.scope("Deduplication");
+                metrics.clock().advance(3);
+            }
+        }
+        {
+            let _part2 = metrics.scope("part2");
+            metrics.clock().advance(4);
+        }
+        metrics.count("Total pairs processed", 6);
+        metrics.count("Total pairs processed", 1);
+
+        assert_eq!(metrics.elapsed("parse"), Duration::from_millis(5));
+        assert_eq!(
+            metrics.report(),
+            "Total pairs processed: 7\n\
+             File read time: 1.00ms\n\
+             Parsing time: 5.00ms\n\
+             \x20 Deduplication time: 3.00ms\n\
+             Part 2 time: 4.00ms\n"
+        );
+    }
+
+    #[test]
+    fn time_block_accumulates_across_repeated_entries() {
+        let metrics = metrics();
+        let mut doubled = Vec::new();
+        for n in 1..=4u64 {
+            doubled.push(time_block!(metrics, "part1", {
+                metrics.clock().advance(n);
+                n * 2
+            }));
+            metrics.clock().advance(10);
+        }
+        assert_eq!(doubled, [2, 4, 6, 8]);
+        assert_eq!(metrics.elapsed("part1"), Duration::from_millis(10));
+        assert_eq!(metrics.report(), "Part 1 time: 10.0ms\n");
+    }
+
+    #[test]
+    fn time_block_nests_and_records_early_exits() {
+        fn parse(metrics: &Metrics<ManualClock>, line: &str) -> Result<u32, String> {
+            time_block!(metrics, "parse", {
+                metrics.clock().advance(2);
+                let n = line.parse::<u32>().map_err(|e| e.to_string())?;
+                time_block!(metrics, "Checks", {
+                    metrics.clock().advance(1);
+                });
+                Ok(n)
+            })
+        }
+
+        let metrics = metrics();
+        assert_eq!(parse(&metrics, "7"), Ok(7));
+        // Leaves through `?` before the nested phase, still timed
+        assert!(parse(&metrics, "x").is_err());
+        assert_eq!(parse(&metrics, "9"), Ok(9));
+        assert_eq!(
+            metrics.report(),
+            "Parsing time: 8.00ms\n\
+             \x20 Checks time: 2.00ms\n"
+        );
+    }
+
+    #[test]
+    fn averages_selected_phases_per_item() {
+        let metrics = metrics();
+        {
+            let _parse = metrics.scope("parse");
+            metrics.clock().advance(8);
+        }
+        {
+            let _sort = metrics.scope("Sort");
+            metrics.clock().advance(4);
+        }
+        assert_eq!(
+            metrics.averages(&["parse", "Sort", "part1"], 4, "pair"),
+            "Parsing: 2.00ms/pair\nSort: 1.00ms/pair\n"
+        );
+        assert_eq!(metrics.averages(&["parse"], 0, "pair"), "");
+    }
+
+    #[test]
+    fn counters_start_at_zero() {
+        let metrics = metrics();
+        assert_eq!(metrics.counter("Dampened sequences"), 0);
+        metrics.count("Dampened sequences", 3);
+        assert_eq!(metrics.counter("Dampened sequences"), 3);
+    }
+}
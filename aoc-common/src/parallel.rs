@@ -0,0 +1,89 @@
+//! One place to size the rayon pool a run's parallel work shares.
+//!
+//! `--threads`, `threads` in `aoc.toml`, and `$AOC_THREADS` all end up in
+//! `Config::threads`, and each binary hands that to `init_parallelism`
+//! once while handling its arguments, before any parallel work starts.
+//! Code that could run in parallel asks `parallelism` how many threads it
+//! has rather than rayon, since one thread means no pool at all: the work
+//! runs serially on the calling thread, where it is easiest to debug.
+
+use std::sync::OnceLock;
+
+/// The thread count the first `init_parallelism` asked for, `None` for one
+/// per core
+static REQUESTED: OnceLock<Option<usize>> = OnceLock::new();
+
+/// Sets up the global rayon pool with `threads` workers, or one per core
+/// for `None`.
+///
+/// `Some(1)` builds no pool, and `parallelism` then reports 1, so callers
+/// take their serial paths. Calling this again with the same value does
+/// nothing; `Some(0)` is the same as `None`, as it is to rayon.
+///
+/// # Panics
+/// If called again with a different thread count, since the global pool
+/// can only be built once, or if rayon already started its pool on its
+/// own because parallel work ran first.
+pub fn init_parallelism(threads: Option<usize>) {
+    let threads = threads.filter(|&n| n > 0);
+    let first = *REQUESTED.get_or_init(|| {
+        if threads != Some(1) {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads.unwrap_or(0))
+                .build_global()
+                .expect("init_parallelism must run before any parallel work starts the rayon pool");
+        }
+        threads
+    });
+    if first != threads {
+        panic!(
+            "parallelism was already set to {}, so it can't be set to {}",
+            describe(first),
+            describe(threads)
+        );
+    }
+}
+
+/// Threads the run's parallel work gets: 1 after `init_parallelism(Some(1))`,
+/// and otherwise the size of the rayon pool.
+pub fn parallelism() -> usize {
+    match REQUESTED.get() {
+        Some(Some(1)) => 1,
+        _ => rayon::current_num_threads(),
+    }
+}
+
+/// A requested thread count, as the panic message names it.
+fn describe(threads: Option<usize>) -> String {
+    match threads {
+        Some(1) => "1 thread".to_string(),
+        Some(n) => format!("{} threads", n),
+        None => "one thread per core".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    // The pool is global, so this is the only test that sets it up
+    #[test]
+    fn sizes_the_pool_once() {
+        init_parallelism(Some(3));
+        assert_eq!(parallelism(), 3);
+        assert_eq!(rayon::current_num_threads(), 3);
+        // The same request again is fine, and changes nothing
+        init_parallelism(Some(3));
+        assert_eq!(parallelism(), 3);
+
+        for (other, message) in [
+            (Some(1), "parallelism was already set to 3 threads, so it can't be set to 1 thread"),
+            (None, "parallelism was already set to 3 threads, so it can't be set to one thread per core"),
+        ] {
+            let panic = panic::catch_unwind(|| init_parallelism(other)).unwrap_err();
+            assert_eq!(panic.downcast_ref::<String>().map(String::as_str), Some(message));
+        }
+        assert_eq!(parallelism(), 3);
+    }
+}
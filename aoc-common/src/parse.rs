@@ -0,0 +1,633 @@
+//! Byte-level integer parsing.
+
+use std::fmt;
+use std::ops::Range;
+
+use smallvec::SmallVec;
+
+use super::{AocError, Integer};
+
+/// Describes why a token isn't an integer of the requested type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntErrorKind {
+    /// No bytes at all
+    Empty,
+    /// A byte other than a digit or a leading `-`, or a `-` with no digits
+    /// after it
+    InvalidDigit,
+    /// A well-formed integer that doesn't fit, e.g. in `i32`
+    OutOfRange { width: &'static str },
+}
+
+/// A token that failed to parse, with its byte range in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseIntError {
+    pub kind: IntErrorKind,
+    /// The whole token, 0-based and exclusive, so callers can both report
+    /// and quote it
+    pub span: Range<usize>,
+}
+
+impl fmt::Display for ParseIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            IntErrorKind::Empty => write!(f, "empty integer"),
+            IntErrorKind::InvalidDigit => write!(f, "invalid digit in integer"),
+            IntErrorKind::OutOfRange { width } => {
+                write!(f, "integer out of range for {}", width)
+            }
+        }
+    }
+}
+
+/// Parses a whole token as a decimal integer.
+///
+/// An optional leading `-` makes the value negative; there is no `+`, and
+/// leading zeros are allowed. Negative digits are accumulated below zero,
+/// so a signed type's minimum parses even though its magnitude doesn't fit.
+///
+/// # Implementation Details
+/// - Works on raw bytes, without UTF-8 validation or allocation
+/// - Tokens of at most `T::SAFE_DIGITS` digits, which covers every puzzle
+///   value, accumulate without per-digit overflow checks
+/// - Longer tokens are checked for junk first, so a malformed token is
+///   reported as such even when it is also too long
+///
+/// # Errors
+/// Returns a `ParseIntError` spanning all of `bytes` if it is empty, holds
+/// anything but digits after the sign, or doesn't fit in `T`.
+///
+/// # Time Complexity
+/// O(n) in the token length
+#[inline]
+pub fn parse_int<T: Integer>(bytes: &[u8]) -> Result<T, ParseIntError> {
+    let error = |kind| ParseIntError {
+        kind,
+        span: 0..bytes.len(),
+    };
+    let (negative, digits) = match bytes {
+        [] => return Err(error(IntErrorKind::Empty)),
+        [b'-', digits @ ..] => (true, digits),
+        _ => (false, bytes),
+    };
+    if digits.is_empty() {
+        return Err(error(IntErrorKind::InvalidDigit));
+    }
+    let out_of_range = || error(IntErrorKind::OutOfRange { width: T::NAME });
+
+    if digits.len() <= T::SAFE_DIGITS {
+        let mut value = T::ZERO;
+        for &b in digits {
+            let digit = b.wrapping_sub(b'0');
+            if digit > 9 {
+                return Err(error(IntErrorKind::InvalidDigit));
+            }
+            value = value.push_digit_unchecked(digit);
+        }
+        return match negative {
+            // Only a nonzero unsigned value has no negation
+            true => value.checked_negate().ok_or_else(out_of_range),
+            false => Ok(value),
+        };
+    }
+
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return Err(error(IntErrorKind::InvalidDigit));
+    }
+    let mut value = T::ZERO;
+    for &b in digits {
+        let digit = b - b'0';
+        let next = if negative {
+            value.push_negative_digit(digit)
+        } else {
+            value.push_digit(digit)
+        };
+        value = next.ok_or_else(out_of_range)?;
+    }
+    Ok(value)
+}
+
+/// Whether `b` separates integers on a line: a space, a tab, or a stray
+/// `\r` left by CRLF endings.
+#[inline]
+fn is_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t' || b == b'\r'
+}
+
+/// Parses every integer on a line, without its terminator, into `out`.
+///
+/// Integers are separated by runs of spaces and tabs, and leading or
+/// trailing blanks are ignored, so a blank line appends nothing. Each
+/// token is parsed with `parse_int`.
+///
+/// # Implementation Details
+/// - Appends to the caller's buffer so its capacity can be reused across
+///   lines
+/// - Integers before a malformed token are still appended
+///
+/// # Errors
+/// Returns the `ParseIntError` of the first malformed token, with its span
+/// relative to the start of `bytes`.
+///
+/// # Time Complexity
+/// O(n) in the line length
+#[inline]
+pub fn parse_ints_in_line<T: Integer>(bytes: &[u8], out: &mut Vec<T>) -> Result<(), ParseIntError> {
+    let mut i = 0;
+    loop {
+        while i < bytes.len() && is_blank(bytes[i]) {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return Ok(());
+        }
+        let start = i;
+        while i < bytes.len() && !is_blank(bytes[i]) {
+            i += 1;
+        }
+        let value = parse_int(&bytes[start..i]).map_err(|e| ParseIntError {
+            kind: e.kind,
+            span: start..i,
+        })?;
+        out.push(value);
+    }
+}
+
+/// Pulls every integer out of a line of mixed text, in order.
+///
+/// Any run of ASCII digits is an integer, whatever surrounds it, so
+/// `p=0,4 v=3,-3` gives `[0, 4, 3, -3]` and `Button A: X+94, Y+34` gives
+/// `[94, 34]`. A `-` is a sign only directly before a digit: `5-3` is
+/// `[5, -3]`, and a `-` anywhere else is text like any other. Puzzles
+/// whose dashes are ranges or separators want `extract_uints` instead.
+///
+/// # Implementation Details
+/// - Scans bytes without regex or UTF-8 decoding; digits are ASCII, so a
+///   multi-byte character never looks like one
+/// - Each run is parsed with `parse_int`, so leading zeros are allowed
+/// - Up to eight integers, as many as most puzzle lines hold, stay inline
+///   without allocating
+///
+/// # Panics
+/// If an integer doesn't fit in an `i64`.
+///
+/// # Time Complexity
+/// O(n) in the line length
+pub fn extract_ints(line: &str) -> SmallVec<[i64; 8]> {
+    extract(line, true)
+}
+
+/// Pulls every run of digits out of a line of mixed text, in order,
+/// treating every `-` as text: `1-3 a` gives `[1, 3]`.
+///
+/// # Panics
+/// If an integer doesn't fit in a `u64`.
+///
+/// # Time Complexity
+/// O(n) in the line length
+pub fn extract_uints(line: &str) -> SmallVec<[u64; 8]> {
+    extract(line, false)
+}
+
+/// Parses each run of digits in `line`, with a `-` just before it when
+/// `signed`.
+#[inline]
+fn extract<T: Integer>(line: &str, signed: bool) -> SmallVec<[T; 8]> {
+    let bytes = line.as_bytes();
+    let mut out = SmallVec::new();
+    let mut i = 0;
+    while let Some(offset) = bytes[i..].iter().position(u8::is_ascii_digit) {
+        let digits = i + offset;
+        let mut end = digits + 1;
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+        // The byte before a run is never a digit, so it's a sign or text
+        let start = match signed && digits > 0 && bytes[digits - 1] == b'-' {
+            true => digits - 1,
+            false => digits,
+        };
+        let value = parse_int(&bytes[start..end])
+            .unwrap_or_else(|e| panic!("`{}` in `{}`: {}", &line[start..end], line, e));
+        out.push(value);
+        i = end;
+    }
+    out
+}
+
+/// A parse failure at `span` of `line`, quoting the token there.
+fn line_error(line: usize, bytes: &[u8], span: Range<usize>, why: LineFault) -> AocError {
+    let token = String::from_utf8_lossy(&bytes[span.clone()]);
+    let message = match why {
+        LineFault::Int(IntErrorKind::OutOfRange { width }) => {
+            format!("`{}` is out of range for {}", token, width)
+        }
+        LineFault::Int(IntErrorKind::Empty | IntErrorKind::InvalidDigit) => {
+            format!("could not parse `{}` as an integer", token)
+        }
+        LineFault::MissingColumns { expected, found } => {
+            format!("expected {} numbers, found {}", expected, found)
+        }
+        LineFault::ExtraColumn { expected } => {
+            format!("unexpected `{}` after {} numbers", token, expected)
+        }
+    };
+    AocError::parse(line, span.start + 1..span.end + 1, message)
+}
+
+/// What was wrong with a line of integers.
+enum LineFault {
+    /// A token that isn't an integer of the requested type
+    Int(IntErrorKind),
+    /// Fewer integers than the input's column count, pointing at the last
+    MissingColumns { expected: usize, found: usize },
+    /// A token following the last column
+    ExtraColumn { expected: usize },
+}
+
+/// Returns the byte range of the first token at or after `i`, or `None` at
+/// the end of the line.
+#[inline]
+fn next_token(bytes: &[u8], mut i: usize) -> Option<Range<usize>> {
+    while i < bytes.len() && is_blank(bytes[i]) {
+        i += 1;
+    }
+    if i == bytes.len() {
+        return None;
+    }
+    let start = i;
+    while i < bytes.len() && !is_blank(bytes[i]) {
+        i += 1;
+    }
+    Some(start..i)
+}
+
+/// Parses each non-blank line of `input` into its own row of integers.
+///
+/// Integers are separated by runs of spaces and tabs, and rows may differ
+/// in length. Blank lines are skipped, and `\r\n` endings parse the same as
+/// `\n`.
+///
+/// # Implementation Details
+/// - Tokens are parsed in place with `parse_int`; the only allocations are
+///   the rows themselves, each sized like the row before it
+///
+/// # Errors
+/// Returns `AocError::Parse` naming the line and columns of the first
+/// malformed token.
+///
+/// # Time Complexity
+/// O(n) in the input length
+pub fn lines_of_ints<T: Integer>(input: &str) -> Result<Vec<Vec<T>>, AocError> {
+    let mut rows = Vec::new();
+    let mut width = 0;
+    for (i, line) in input.split('\n').enumerate() {
+        let bytes = line.as_bytes();
+        let mut row = Vec::with_capacity(width);
+        parse_ints_in_line(bytes, &mut row)
+            .map_err(|e| line_error(i + 1, bytes, e.span, LineFault::Int(e.kind)))?;
+        if !row.is_empty() {
+            width = row.len();
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+/// Parses an input of `n` integer columns into one list per column.
+///
+/// Every non-blank line must hold exactly `n` integers separated by runs
+/// of spaces and tabs; blank lines are skipped, and `\r\n` endings parse
+/// the same as `\n`.
+///
+/// # Implementation Details
+/// - Tokens are parsed in place with `parse_int` straight into their
+///   column, so nothing is allocated per line or per token
+/// - Columns are sized up front from the length of the first line
+///
+/// # Errors
+/// Returns `AocError::Parse` naming the line and columns of the first
+/// malformed token, the last integer of a short line, or the first token
+/// past column `n`.
+///
+/// # Time Complexity
+/// O(n) in the input length
+pub fn columns_of_ints<T: Integer>(input: &str, n: usize) -> Result<Vec<Vec<T>>, AocError> {
+    let mut columns = vec![Vec::new(); n];
+    for (i, line) in input.split('\n').enumerate() {
+        let bytes = line.as_bytes();
+        let line_number = i + 1;
+        if i == 0 {
+            // Lines of a column input are about as long as each other
+            let lines = input.len() / (bytes.len() + 1) + 1;
+            columns.iter_mut().for_each(|column| column.reserve(lines));
+        }
+        let mut end = 0;
+        let mut last = 0..0;
+        for (found, column) in columns.iter_mut().enumerate() {
+            let Some(span) = next_token(bytes, end) else {
+                if found == 0 {
+                    break;
+                }
+                let why = LineFault::MissingColumns { expected: n, found };
+                return Err(line_error(line_number, bytes, last, why));
+            };
+            let value = parse_int(&bytes[span.clone()]).map_err(|e| {
+                line_error(line_number, bytes, span.clone(), LineFault::Int(e.kind))
+            })?;
+            column.push(value);
+            end = span.end;
+            last = span;
+        }
+        if let Some(extra) = next_token(bytes, end) {
+            let why = LineFault::ExtraColumn { expected: n };
+            return Err(line_error(line_number, bytes, extra, why));
+        }
+    }
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind<T: Integer>(token: &str) -> IntErrorKind {
+        parse_int::<T>(token.as_bytes()).unwrap_err().kind
+    }
+
+    #[test]
+    fn parses_signed_and_unsigned() {
+        assert_eq!(parse_int::<i32>(b"42"), Ok(42));
+        assert_eq!(parse_int::<i32>(b"-42"), Ok(-42));
+        assert_eq!(parse_int::<u64>(b"42"), Ok(42));
+        assert_eq!(parse_int::<i128>(b"-0"), Ok(0));
+        assert_eq!(parse_int::<u8>(b"-0"), Ok(0));
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert_eq!(kind::<i32>(""), IntErrorKind::Empty);
+        assert_eq!(kind::<u8>(""), IntErrorKind::Empty);
+    }
+
+    #[test]
+    fn rejects_lone_minus() {
+        assert_eq!(kind::<i32>("-"), IntErrorKind::InvalidDigit);
+        assert_eq!(kind::<u32>("-"), IntErrorKind::InvalidDigit);
+    }
+
+    #[test]
+    fn rejects_junk() {
+        for token in ["+1", "1-", "--1", "1x", "x", " 1", "1 ", "1.0", "1_000"] {
+            assert_eq!(kind::<i64>(token), IntErrorKind::InvalidDigit, "{token}");
+        }
+        // Junk wins over overflow, even past the unchecked fast path
+        assert_eq!(kind::<i8>("99999x"), IntErrorKind::InvalidDigit);
+    }
+
+    #[test]
+    fn parses_bounds_of_every_width() {
+        fn bounds<T: Integer>(min: T, max: T) {
+            assert_eq!(parse_int::<T>(min.to_string().as_bytes()), Ok(min));
+            assert_eq!(parse_int::<T>(max.to_string().as_bytes()), Ok(max));
+        }
+        bounds(i8::MIN, i8::MAX);
+        bounds(i32::MIN, i32::MAX);
+        bounds(i64::MIN, i64::MAX);
+        bounds(i128::MIN, i128::MAX);
+        bounds(u8::MIN, u8::MAX);
+        bounds(u64::MIN, u64::MAX);
+        bounds(u128::MIN, u128::MAX);
+    }
+
+    #[test]
+    fn rejects_one_past_the_bounds() {
+        let range = |width| IntErrorKind::OutOfRange { width };
+        assert_eq!(kind::<i32>("2147483648"), range("i32"));
+        assert_eq!(kind::<i32>("-2147483649"), range("i32"));
+        assert_eq!(kind::<i8>("128"), range("i8"));
+        assert_eq!(kind::<i8>("-129"), range("i8"));
+        assert_eq!(kind::<u8>("256"), range("u8"));
+        assert_eq!(kind::<u32>("-1"), range("u32"));
+        assert_eq!(kind::<i64>("9223372036854775808"), range("i64"));
+        assert_eq!(kind::<i64>("-9223372036854775809"), range("i64"));
+        assert_eq!(
+            kind::<u128>("340282366920938463463374607431768211456"),
+            range("u128")
+        );
+    }
+
+    #[test]
+    fn allows_leading_zeros() {
+        assert_eq!(parse_int::<i32>(b"007"), Ok(7));
+        assert_eq!(parse_int::<i32>(b"-007"), Ok(-7));
+        assert_eq!(parse_int::<i32>(b"0000"), Ok(0));
+        // Longer than any i32, but the value itself fits
+        assert_eq!(parse_int::<i32>(b"000000000002147483647"), Ok(i32::MAX));
+        assert_eq!(parse_int::<i32>(b"-000000000002147483648"), Ok(i32::MIN));
+        assert_eq!(parse_int::<u8>(b"000255"), Ok(255));
+    }
+
+    #[test]
+    fn matches_std_on_every_i8_and_u8() {
+        for value in -1000..1000 {
+            let token = value.to_string();
+            assert_eq!(
+                parse_int::<i8>(token.as_bytes()).ok(),
+                token.parse::<i8>().ok()
+            );
+            assert_eq!(
+                parse_int::<u8>(token.as_bytes()).ok(),
+                token.parse::<u8>().ok().filter(|_| value >= 0)
+            );
+        }
+    }
+
+    #[test]
+    fn parses_a_line() {
+        let mut out = Vec::<i32>::new();
+        parse_ints_in_line(b"  7 -6\t4 2 1\r", &mut out).unwrap();
+        assert_eq!(out, [7, -6, 4, 2, 1]);
+
+        out.clear();
+        parse_ints_in_line(b" \t\r", &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn reports_the_malformed_token_in_a_line() {
+        let mut out = Vec::<i32>::new();
+        let error = parse_ints_in_line(b"1 2 3x 4", &mut out).unwrap_err();
+        assert_eq!(error.kind, IntErrorKind::InvalidDigit);
+        assert_eq!(error.span, 4..6);
+        assert_eq!(out, [1, 2]);
+
+        let mut out = Vec::<i8>::new();
+        let error = parse_ints_in_line(b"1 -", &mut out).unwrap_err();
+        assert_eq!(error.kind, IntErrorKind::InvalidDigit);
+        assert_eq!(error.span, 2..3);
+
+        let error = parse_ints_in_line(b"1 300", &mut out).unwrap_err();
+        assert_eq!(error.kind, IntErrorKind::OutOfRange { width: "i8" });
+        assert_eq!(error.span, 2..5);
+    }
+
+    #[test]
+    fn parses_ragged_lines_into_rows() {
+        let rows = lines_of_ints::<i64>("7 6 4 2 1\n1 2\n\n  9\t-7 0 \n").unwrap();
+        assert_eq!(rows, [vec![7, 6, 4, 2, 1], vec![1, 2], vec![9, -7, 0]]);
+        assert!(lines_of_ints::<i64>("").unwrap().is_empty());
+        assert!(lines_of_ints::<i64>("\n \n\t\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_crlf_like_lf() {
+        let input = "3   4\r\n-4   3\r\n\r\n2   -5\r\n";
+        let lf = input.replace("\r\n", "\n");
+        assert_eq!(
+            lines_of_ints::<i64>(input).unwrap(),
+            lines_of_ints::<i64>(&lf).unwrap()
+        );
+        assert_eq!(
+            columns_of_ints::<i64>(input, 2).unwrap(),
+            [vec![3, -4, 2], vec![4, 3, -5]]
+        );
+    }
+
+    #[test]
+    fn reports_the_line_and_columns_of_a_bad_token() {
+        let error = lines_of_ints::<i64>("1 2 3\n\n4  5x 6\n").unwrap_err();
+        assert!(
+            matches!(error, AocError::Parse { line: 3, ref columns, .. } if *columns == (4..6))
+        );
+        assert_eq!(
+            error.to_string(),
+            "line 3, columns 4-5: could not parse `5x` as an integer"
+        );
+
+        let error = columns_of_ints::<i8>("1 2\n3 -200\n", 2).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, columns 3-6: `-200` is out of range for i8"
+        );
+    }
+
+    #[test]
+    fn rejects_lines_off_the_column_count() {
+        let error = columns_of_ints::<i64>("1 2\n3  \n", 2).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, columns 1-1: expected 2 numbers, found 1"
+        );
+
+        let error = columns_of_ints::<i64>("1 2\n\n3 4 -5\n", 2).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, columns 5-6: unexpected `-5` after 2 numbers"
+        );
+    }
+
+    #[test]
+    fn extracts_ints_from_puzzle_lines() {
+        let cases: [(&str, &[i64]); 8] = [
+            ("p=0,4 v=3,-3", &[0, 4, 3, -3]),
+            ("p=-10,-4 v=-99,100", &[-10, -4, -99, 100]),
+            ("mul(44,46)", &[44, 46]),
+            (
+                "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]",
+                &[2, 4, 3, 7, 5, 5, 32, 64],
+            ),
+            ("Button A: X+94, Y+34", &[94, 34]),
+            ("Prize: X=8400, Y=5400", &[8400, 5400]),
+            ("Register A: 729", &[729]),
+            ("190: 10 19", &[190, 10, 19]),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(extract_ints(line)[..], *expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn extracts_a_sign_only_right_before_digits() {
+        let cases: [(&str, &[i64]); 10] = [
+            ("", &[]),
+            ("no numbers here", &[]),
+            ("-", &[]),
+            ("- 5", &[5]),
+            ("-5-", &[-5]),
+            ("--5", &[-5]),
+            ("5-3", &[5, -3]),
+            ("a-b-0", &[0]),
+            ("+7", &[7]),
+            ("-007 and 0x1f", &[-7, 0, 1]),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(extract_ints(line)[..], *expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn extracts_uints_ignoring_every_dash() {
+        assert_eq!(extract_uints("1-3 a: abcde")[..], [1, 3]);
+        assert_eq!(extract_uints("p=0,4 v=3,-3")[..], [0, 4, 3, 3]);
+        assert_eq!(extract_uints("#1 @ 1,3: 4x4")[..], [1, 1, 3, 4, 4]);
+        assert_eq!(extract_uints("18446744073709551615-0")[..], [u64::MAX, 0]);
+        assert!(extract_uints("- -- -").is_empty());
+    }
+
+    #[test]
+    fn extracts_around_multibyte_text_and_past_the_inline_capacity() {
+        assert_eq!(extract_ints("µ12é-3 ½→-4")[..], [12, -3, -4]);
+        let line = (1..=20).map(|i| format!("n{}", -i)).collect::<String>();
+        let ints = extract_ints(&line);
+        assert!(ints.spilled());
+        assert_eq!(ints[..], (1..=20).map(|i| -i).collect::<Vec<i64>>()[..]);
+        let bounds = format!("{} .. {}", i64::MIN, i64::MAX);
+        assert_eq!(extract_ints(&bounds)[..], [i64::MIN, i64::MAX]);
+    }
+
+    #[test]
+    fn extracts_what_a_char_by_char_scan_finds() {
+        /// The rule the byte scan implements, one `char` at a time.
+        fn reference(line: &str) -> Vec<i64> {
+            let chars: Vec<char> = line.chars().collect();
+            let mut out = Vec::new();
+            let mut i = 0;
+            while i < chars.len() {
+                if !chars[i].is_ascii_digit() {
+                    i += 1;
+                    continue;
+                }
+                let negative = i > 0 && chars[i - 1] == '-';
+                let mut value = 0i64;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    value = value * 10 + chars[i].to_digit(10).unwrap() as i64;
+                    i += 1;
+                }
+                out.push(if negative { -value } else { value });
+            }
+            out
+        }
+
+        // Every string of up to six characters from a small alphabet
+        let alphabet = ['-', '0', '7', ',', 'x', 'é'];
+        let mut line = String::new();
+        for n in 0..6u32.pow(6) {
+            line.clear();
+            let mut rest = n;
+            while rest > 0 {
+                line.push(alphabet[rest as usize % 6]);
+                rest /= 6;
+            }
+            assert_eq!(extract_ints(&line)[..], reference(&line)[..], "{}", line);
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`99999999999999999999` in `x=99999999999999999999`: integer out of range for i64"
+    )]
+    fn panics_on_an_integer_too_big_to_extract() {
+        extract_ints("x=99999999999999999999");
+    }
+}
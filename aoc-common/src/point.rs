@@ -0,0 +1,333 @@
+//! Signed 2D points and the eight compass directions between grid cells.
+//!
+//! Coordinates follow `Grid`: `x` grows to the right and `y` grows down,
+//! so `North` is `(0, -1)`. Points are signed so a step off the edge of a
+//! grid is still a point, which `Grid` then reports as out of bounds.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or offset on the plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point2 {
+    /// The origin, and the zero offset
+    pub const ZERO: Point2 = Point2 { x: 0, y: 0 };
+
+    /// Creates a point at `(x, y)`.
+    #[inline]
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point2 { x, y }
+    }
+
+    /// The point one step away in `direction`.
+    #[inline]
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+}
+
+/// Points sort in reading order, by row and then by column, the same order
+/// `Grid` iterates its cells in.
+impl Ord for Point2 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Point2 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Point2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// Converts grid coordinates, which always fit since a grid can't hold
+/// more than `isize::MAX` bytes.
+impl From<(usize, usize)> for Point2 {
+    #[inline]
+    fn from((x, y): (usize, usize)) -> Self {
+        Point2::new(x as i64, y as i64)
+    }
+}
+
+impl Add for Point2 {
+    type Output = Point2;
+
+    #[inline]
+    fn add(self, other: Point2) -> Point2 {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Add<Direction> for Point2 {
+    type Output = Point2;
+
+    #[inline]
+    fn add(self, direction: Direction) -> Point2 {
+        self + direction.delta()
+    }
+}
+
+impl AddAssign for Point2 {
+    #[inline]
+    fn add_assign(&mut self, other: Point2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Point2;
+
+    #[inline]
+    fn sub(self, other: Point2) -> Point2 {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point2 {
+    #[inline]
+    fn sub_assign(&mut self, other: Point2) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Point2 {
+    type Output = Point2;
+
+    #[inline]
+    fn neg(self) -> Point2 {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Point2 {
+    type Output = Point2;
+
+    #[inline]
+    fn mul(self, factor: i64) -> Point2 {
+        Point2::new(self.x * factor, self.y * factor)
+    }
+}
+
+/// The taxicab distance between `a` and `b`: steps needed moving only in
+/// the four cardinal directions.
+#[inline]
+pub fn manhattan(a: Point2, b: Point2) -> i64 {
+    (a.x - b.x).abs() + (a.y - b.y).abs()
+}
+
+/// The four points sharing an edge with `p`, clockwise from north.
+#[inline]
+pub fn neighbors4(p: Point2) -> [Point2; 4] {
+    Direction::CARDINAL.map(|direction| p + direction)
+}
+
+/// The eight points sharing an edge or a corner with `p`, clockwise from
+/// north.
+#[inline]
+pub fn neighbors8(p: Point2) -> [Point2; 8] {
+    Direction::ALL.map(|direction| p + direction)
+}
+
+/// One of the eight compass directions, in clockwise order from north.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The four directions along the grid's axes, clockwise from north
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The direction `eighths` eighth-turns clockwise from this one.
+    #[inline]
+    fn rotate(self, eighths: usize) -> Direction {
+        Direction::ALL[(self as usize + eighths) % 8]
+    }
+
+    /// The offset of one step, with `y` growing down.
+    #[inline]
+    pub fn delta(self) -> Point2 {
+        let (x, y) = match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        };
+        Point2::new(x, y)
+    }
+
+    /// The direction after a quarter turn counterclockwise.
+    #[inline]
+    pub fn turn_left(self) -> Direction {
+        self.rotate(6)
+    }
+
+    /// The direction after a quarter turn clockwise.
+    #[inline]
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// The direction pointing the other way.
+    #[inline]
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// Whether this is one of the four diagonals.
+    #[inline]
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeSet, HashMap};
+
+    #[test]
+    fn does_vector_arithmetic() {
+        let a = Point2::new(3, -4);
+        let b = Point2::new(-1, 2);
+        assert_eq!(a + b, Point2::new(2, -2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(-a, Point2::new(-3, 4));
+        assert_eq!(a * 3, Point2::new(9, -12));
+        assert_eq!(Direction::East.delta() * -2, Point2::new(-2, 0));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        assert_eq!(Point2::from((2, 5)), Point2::new(2, 5));
+        assert_eq!(a.to_string(), "(3, -4)");
+    }
+
+    #[test]
+    fn measures_manhattan_distance() {
+        let a = Point2::new(1, 1);
+        assert_eq!(manhattan(a, a), 0);
+        assert_eq!(manhattan(a, Point2::new(4, -3)), 7);
+        assert_eq!(manhattan(Point2::new(4, -3), a), 7);
+        assert_eq!(manhattan(Point2::ZERO, Point2::new(-5, -5)), 10);
+    }
+
+    #[test]
+    fn steps_with_y_growing_down() {
+        let p = Point2::new(5, 5);
+        assert_eq!(p.step(Direction::North), Point2::new(5, 4));
+        assert_eq!(p + Direction::SouthWest, Point2::new(4, 6));
+        assert_eq!(Point2::ZERO + Direction::West, Point2::new(-1, 0));
+        for direction in Direction::ALL {
+            let delta = direction.delta();
+            assert_eq!(delta.x.abs().max(delta.y.abs()), 1, "{:?}", direction);
+            assert_eq!(manhattan(Point2::ZERO, delta) == 2, direction.is_diagonal());
+        }
+    }
+
+    #[test]
+    fn turns_and_reverses() {
+        use Direction::*;
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(West.turn_right(), North);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(NorthEast.turn_left(), NorthWest);
+        assert_eq!(SouthEast.turn_right(), SouthWest);
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(direction.opposite().delta(), -direction.delta());
+            assert_eq!(
+                direction.turn_right().is_diagonal(),
+                direction.is_diagonal()
+            );
+        }
+    }
+
+    #[test]
+    fn lists_neighbors_clockwise() {
+        let p = Point2::new(0, 0);
+        assert_eq!(
+            neighbors4(p),
+            [
+                Point2::new(0, -1),
+                Point2::new(1, 0),
+                Point2::new(0, 1),
+                Point2::new(-1, 0)
+            ]
+        );
+        let all = neighbors8(p);
+        assert_eq!(all[1], Point2::new(1, -1));
+        assert!(neighbors4(p).iter().all(|n| all.contains(n)));
+        assert!(all.iter().all(|&n| n != p && manhattan(n, p) <= 2));
+    }
+
+    #[test]
+    fn keys_maps_and_sets_in_reading_order() {
+        let points = [
+            Point2::new(2, 1),
+            Point2::new(0, 1),
+            Point2::new(5, 0),
+            Point2::new(0, 1),
+        ];
+        let sorted: Vec<_> = points
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        assert_eq!(
+            sorted,
+            [Point2::new(5, 0), Point2::new(0, 1), Point2::new(2, 1)]
+        );
+
+        let mut visits = HashMap::new();
+        for p in points {
+            *visits.entry(p).or_insert(0) += 1;
+        }
+        assert_eq!(visits[&Point2::new(0, 1)], 2);
+        assert_eq!(visits.len(), 3);
+    }
+}
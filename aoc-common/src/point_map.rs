@@ -0,0 +1,373 @@
+//! Values at scattered points, with the rectangle they cover.
+//!
+//! Some maps are mostly empty, like day 8's antennas or day 14's robots on
+//! a floor a hundred tiles wide, and read better as a `HashMap<Point2, T>`
+//! than as a `Grid` of placeholders; others want the grid's flat storage.
+//! A `PointMap` is the sparse one, with `HashMap`'s `get`, `insert`, and
+//! `iter`, and it keeps the `BoundingBox` of its points as they come and
+//! go. Points may be negative, as they are once something walks off the
+//! map. `to_dense` lays any box of it out as a `Grid`, and `from_grid`
+//! goes the other way, so a solution can switch representation in one
+//! call rather than being rewritten.
+
+use std::collections::hash_map::{self, HashMap};
+
+use super::{Grid, Point2};
+
+/// The smallest rectangle holding a set of points, both corners inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    /// The top-left corner, the least `x` and the least `y`
+    pub min: Point2,
+    /// The bottom-right corner, the greatest `x` and the greatest `y`
+    pub max: Point2,
+}
+
+impl BoundingBox {
+    /// The box holding just `p`.
+    #[inline]
+    pub fn at(p: Point2) -> Self {
+        BoundingBox { min: p, max: p }
+    }
+
+    /// The box of a `width` by `height` grid, with its top-left cell at
+    /// the origin.
+    ///
+    /// # Returns
+    /// `None` for a grid with no cells, which no box holds.
+    pub fn of_grid<T>(grid: &Grid<T>) -> Option<Self> {
+        (!grid.is_empty()).then(|| BoundingBox {
+            min: Point2::ZERO,
+            max: Point2::from((grid.width() - 1, grid.height() - 1)),
+        })
+    }
+
+    /// Columns the box spans.
+    #[inline]
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x) as usize + 1
+    }
+
+    /// Rows the box spans.
+    #[inline]
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y) as usize + 1
+    }
+
+    /// Whether `p` lies inside the box or on its edge.
+    #[inline]
+    pub fn contains(&self, p: Point2) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// Grows the box just enough to hold `p`.
+    #[inline]
+    pub fn include(&mut self, p: Point2) {
+        self.min = Point2::new(self.min.x.min(p.x), self.min.y.min(p.y));
+        self.max = Point2::new(self.max.x.max(p.x), self.max.y.max(p.y));
+    }
+
+    /// Whether `p` lies on the edge of the box, so removing it may shrink
+    /// the box.
+    #[inline]
+    fn on_edge(&self, p: Point2) -> bool {
+        p.x == self.min.x || p.x == self.max.x || p.y == self.min.y || p.y == self.max.y
+    }
+}
+
+/// A value at each of a sparse set of points, in a `HashMap` that knows
+/// the bounding box of its keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointMap<T> {
+    values: HashMap<Point2, T>,
+    /// Box of exactly the points in `values`, `None` while it's empty
+    bounds: Option<BoundingBox>,
+}
+
+impl<T> PointMap<T> {
+    /// An empty map.
+    pub fn new() -> Self {
+        PointMap {
+            values: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// The value at `p`, if there is one.
+    #[inline]
+    pub fn get(&self, p: Point2) -> Option<&T> {
+        self.values.get(&p)
+    }
+
+    /// The value at `p` to change in place, if there is one.
+    #[inline]
+    pub fn get_mut(&mut self, p: Point2) -> Option<&mut T> {
+        self.values.get_mut(&p)
+    }
+
+    /// Whether there is a value at `p`.
+    #[inline]
+    pub fn contains_key(&self, p: Point2) -> bool {
+        self.values.contains_key(&p)
+    }
+
+    /// Puts `value` at `p`, growing the bounding box to hold it.
+    ///
+    /// # Returns
+    /// The value `p` held before, if any.
+    pub fn insert(&mut self, p: Point2, value: T) -> Option<T> {
+        self.include(p);
+        self.values.insert(p, value)
+    }
+
+    /// The value at `p`, first putting `default()` there if it had none,
+    /// so a tally reads `*map.get_or_insert_with(p, || 0) += 1`.
+    pub fn get_or_insert_with(&mut self, p: Point2, default: impl FnOnce() -> T) -> &mut T {
+        self.include(p);
+        self.values.entry(p).or_insert_with(default)
+    }
+
+    /// Takes the value at `p` out of the map, shrinking the bounding box
+    /// if it was the last point on one of its edges.
+    ///
+    /// # Time Complexity
+    /// O(1), or O(n) to find the new box when `p` was on an edge of the
+    /// old one
+    pub fn remove(&mut self, p: Point2) -> Option<T> {
+        let value = self.values.remove(&p)?;
+        if self.bounds.is_some_and(|bounds| bounds.on_edge(p)) {
+            self.bounds = bounding_box_of(self.values.keys().copied());
+        }
+        Some(value)
+    }
+
+    /// Points with a value.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether no point has a value.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The smallest box holding every point with a value, or `None` for an
+    /// empty map.
+    #[inline]
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.bounds
+    }
+
+    /// Each point with its value, in arbitrary order.
+    pub fn iter(&self) -> hash_map::Iter<'_, Point2, T> {
+        self.values.iter()
+    }
+
+    /// Each point with its value to change in place, in arbitrary order.
+    pub fn iter_mut(&mut self) -> hash_map::IterMut<'_, Point2, T> {
+        self.values.iter_mut()
+    }
+
+    /// Points with a value, in arbitrary order.
+    pub fn keys(&self) -> hash_map::Keys<'_, Point2, T> {
+        self.values.keys()
+    }
+
+    /// Values, in arbitrary order.
+    pub fn values(&self) -> hash_map::Values<'_, Point2, T> {
+        self.values.values()
+    }
+
+    /// Lays the cells of `bounds` out as a grid, with `bounds.min` at
+    /// `(0, 0)`, each cell holding its point's value or else `fill`.
+    ///
+    /// Points outside `bounds` are left out, so a box smaller than the
+    /// map's crops it. `map.to_dense(map.bounding_box()?, fill)` keeps
+    /// every point.
+    ///
+    /// # Time Complexity
+    /// O(w * h + n)
+    pub fn to_dense(&self, bounds: BoundingBox, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let mut grid = Grid::new(bounds.width(), bounds.height(), fill);
+        for (&p, value) in &self.values {
+            if let Some(cell) = grid.get_point_mut(p - bounds.min) {
+                *cell = value.clone();
+            }
+        }
+        grid
+    }
+
+    /// Collects the cells of `grid` that `keep` accepts, at their grid
+    /// coordinates: day 8's antennas are the cells that aren't `.`.
+    pub fn from_grid(grid: &Grid<T>, mut keep: impl FnMut(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        grid.iter_with_pos()
+            .filter(|(_, value)| keep(value))
+            .map(|(pos, value)| (Point2::from(pos), value.clone()))
+            .collect()
+    }
+
+    /// Grows the box to hold `p`, starting one if this is the first point.
+    #[inline]
+    fn include(&mut self, p: Point2) {
+        match &mut self.bounds {
+            Some(bounds) => bounds.include(p),
+            None => self.bounds = Some(BoundingBox::at(p)),
+        }
+    }
+}
+
+/// The box of `points`, or `None` if there are none.
+fn bounding_box_of(mut points: impl Iterator<Item = Point2>) -> Option<BoundingBox> {
+    let mut bounds = BoundingBox::at(points.next()?);
+    points.for_each(|p| bounds.include(p));
+    Some(bounds)
+}
+
+impl<T> Default for PointMap<T> {
+    fn default() -> Self {
+        PointMap::new()
+    }
+}
+
+impl<T> FromIterator<(Point2, T)> for PointMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2, T)>>(entries: I) -> Self {
+        let mut map = PointMap::new();
+        map.extend(entries);
+        map
+    }
+}
+
+impl<T> Extend<(Point2, T)> for PointMap<T> {
+    fn extend<I: IntoIterator<Item = (Point2, T)>>(&mut self, entries: I) {
+        for (p, value) in entries {
+            self.insert(p, value);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PointMap<T> {
+    type Item = (&'a Point2, &'a T);
+    type IntoIter = hash_map::Iter<'a, Point2, T>;
+
+    /// Each point with its value, in arbitrary order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<T> IntoIterator for PointMap<T> {
+    type Item = (Point2, T);
+    type IntoIter = hash_map::IntoIter<Point2, T>;
+
+    /// Each point with its value, in arbitrary order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i64, y: i64) -> Point2 {
+        Point2::new(x, y)
+    }
+
+    #[test]
+    fn tracks_the_box_of_negative_points() {
+        let mut map = PointMap::new();
+        assert_eq!(map.bounding_box(), None);
+        map.insert(p(-3, 2), 'a');
+        assert_eq!(map.bounding_box(), Some(BoundingBox::at(p(-3, 2))));
+        map.insert(p(4, -5), 'b');
+        map.insert(p(0, 0), 'c');
+        let bounds = map.bounding_box().unwrap();
+        assert_eq!((bounds.min, bounds.max), (p(-3, -5), p(4, 2)));
+        assert_eq!((bounds.width(), bounds.height()), (8, 8));
+        assert!(bounds.contains(p(-3, -5)) && bounds.contains(p(4, 2)));
+        assert!(!bounds.contains(p(5, 0)) && !bounds.contains(p(0, -6)));
+
+        // Replacing a value leaves the box alone
+        assert_eq!(map.insert(p(-3, 2), 'z'), Some('a'));
+        assert_eq!(map.get(p(-3, 2)), Some(&'z'));
+        assert_eq!(map.bounding_box(), Some(bounds));
+        assert_eq!(map.get(p(1, 1)), None);
+    }
+
+    #[test]
+    fn shrinks_the_box_as_points_leave() {
+        let mut map: PointMap<u32> = [(p(-2, -2), 1), (p(5, 1), 2), (p(1, 3), 3), (p(1, 1), 4)]
+            .into_iter()
+            .collect();
+        assert_eq!(map.bounding_box().unwrap().min, p(-2, -2));
+
+        // An interior point leaves the box as it was
+        assert_eq!(map.remove(p(1, 1)), Some(4));
+        assert_eq!(map.bounding_box().unwrap().min, p(-2, -2));
+        // A corner takes its edges with it
+        assert_eq!(map.remove(p(-2, -2)), Some(1));
+        let bounds = map.bounding_box().unwrap();
+        assert_eq!((bounds.min, bounds.max), (p(1, 1), p(5, 3)));
+        assert_eq!(map.remove(p(-2, -2)), None);
+
+        map.remove(p(5, 1));
+        assert_eq!(map.bounding_box(), Some(BoundingBox::at(p(1, 3))));
+        map.remove(p(1, 3));
+        assert!(map.is_empty());
+        assert_eq!(map.bounding_box(), None);
+
+        // Tallies grow the box like inserts
+        *map.get_or_insert_with(p(-7, 0), || 0) += 1;
+        *map.get_or_insert_with(p(-7, 0), || 0) += 1;
+        assert_eq!(map.get(p(-7, 0)), Some(&2));
+        assert_eq!(map.bounding_box(), Some(BoundingBox::at(p(-7, 0))));
+    }
+
+    #[test]
+    fn lays_out_densely_with_a_fill_value() {
+        let map: PointMap<char> = [(p(-1, -1), 'A'), (p(1, 0), '0'), (p(2, 1), 'A')]
+            .into_iter()
+            .collect();
+        let bounds = map.bounding_box().unwrap();
+        let grid = map.to_dense(bounds, '.');
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid.render(|&c| c), "A...\n..0.\n...A\n");
+
+        // A smaller box crops, and a larger one pads with the fill
+        let crop = BoundingBox {
+            min: p(0, -1),
+            max: p(2, 0),
+        };
+        assert_eq!(map.to_dense(crop, '.').render(|&c| c), "...\n.0.\n");
+        let pad = BoundingBox {
+            min: p(-2, -1),
+            max: p(2, 2),
+        };
+        assert_eq!(
+            map.to_dense(pad, '#').render(|&c| c),
+            "#A###\n###0#\n####A\n#####\n"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_a_grid() {
+        let grid: Grid<u8> = "..a.\n.0..\n...a\n".parse().unwrap();
+        let antennas = PointMap::from_grid(&grid, |&c| c != b'.');
+        assert_eq!(antennas.len(), 3);
+        assert_eq!(antennas.get(p(2, 0)), Some(&b'a'));
+        let bounds = antennas.bounding_box().unwrap();
+        assert_eq!((bounds.min, bounds.max), (p(1, 0), p(3, 2)));
+
+        let whole = BoundingBox::of_grid(&grid).unwrap();
+        assert_eq!(antennas.to_dense(whole, b'.'), grid);
+        assert_eq!(BoundingBox::of_grid(&Grid::<u8>::new(0, 0, 0)), None);
+    }
+}
@@ -0,0 +1,268 @@
+//! Where a day's output goes: answers, timings, and everything else.
+//!
+//! Days describe their output to a `Reporter` instead of printing it, so
+//! the same run can be read by a person, piped into a script that only
+//! wants the answers, or checked by a test. `HumanReporter` prints the
+//! sectioned summary every day has always printed, `QuietReporter` prints
+//! only the answers, one per line, and `RecordingReporter` keeps
+//! everything for tests to inspect.
+
+use std::env;
+use std::io::{self, Stdout, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::{fmt_duration, Answer, AocError};
+
+/// Environment variable choosing the output style, `human` or `quiet`,
+/// when no command-line flag does
+pub const OUTPUT_VAR: &str = "AOC_OUTPUT";
+
+/// Receives a day's output as it is produced.
+///
+/// Output errors, such as a closed pipe, are ignored, so a reader that
+/// stops early doesn't turn a finished run into a failed one.
+pub trait Reporter {
+    /// Starts a titled group of lines, such as `Results`.
+    fn section(&mut self, title: &str);
+
+    /// Reports the answer to `part`, with `label` describing it for
+    /// people, e.g. `Part 1 - Safe reports`.
+    fn answer(&mut self, part: u8, label: &str, value: &Answer);
+
+    /// Reports the time spent in a phase, named by its display label.
+    fn phase(&mut self, name: &str, duration: Duration);
+
+    /// Reports a line of detail that is neither an answer nor a timing.
+    fn note(&mut self, message: &str);
+
+    /// Reports each line of `text` as a note.
+    fn notes(&mut self, text: &str) {
+        for line in text.lines() {
+            self.note(line);
+        }
+    }
+}
+
+/// Prints everything, in the sectioned layout every day uses.
+#[derive(Debug)]
+pub struct HumanReporter<W = Stdout> {
+    out: W,
+}
+
+impl HumanReporter {
+    /// A reporter printing to standard output.
+    pub fn stdout() -> Self {
+        HumanReporter::new(io::stdout())
+    }
+}
+
+impl<W: Write> HumanReporter<W> {
+    /// A reporter writing to `out`.
+    pub fn new(out: W) -> Self {
+        HumanReporter { out }
+    }
+
+    /// The writer, with everything reported so far.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Reporter for HumanReporter<W> {
+    fn section(&mut self, title: &str) {
+        let _ = writeln!(self.out, "\n{}:", title);
+    }
+
+    fn answer(&mut self, _part: u8, label: &str, value: &Answer) {
+        let _ = writeln!(self.out, "{}: {}", label, value);
+    }
+
+    fn phase(&mut self, name: &str, duration: Duration) {
+        let _ = writeln!(self.out, "{} time: {}", name, fmt_duration(duration));
+    }
+
+    fn note(&mut self, message: &str) {
+        let _ = writeln!(self.out, "{}", message);
+    }
+}
+
+/// Prints only the answers, one value per line, for scripts.
+#[derive(Debug)]
+pub struct QuietReporter<W = Stdout> {
+    out: W,
+}
+
+impl QuietReporter {
+    /// A reporter printing to standard output.
+    pub fn stdout() -> Self {
+        QuietReporter::new(io::stdout())
+    }
+}
+
+impl<W: Write> QuietReporter<W> {
+    /// A reporter writing to `out`.
+    pub fn new(out: W) -> Self {
+        QuietReporter { out }
+    }
+
+    /// The writer, with every answer reported so far.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Reporter for QuietReporter<W> {
+    fn section(&mut self, _title: &str) {}
+
+    fn answer(&mut self, _part: u8, _label: &str, value: &Answer) {
+        let _ = writeln!(self.out, "{}", value);
+    }
+
+    fn phase(&mut self, _name: &str, _duration: Duration) {}
+
+    fn note(&mut self, _message: &str) {}
+}
+
+/// Keeps everything reported, for tests to assert on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordingReporter {
+    pub sections: Vec<String>,
+    /// Each answer's part and value
+    pub answers: Vec<(u8, String)>,
+    pub phases: Vec<(String, Duration)>,
+    pub notes: Vec<String>,
+}
+
+impl RecordingReporter {
+    /// Creates a reporter that has recorded nothing.
+    pub fn new() -> Self {
+        RecordingReporter::default()
+    }
+}
+
+impl Reporter for RecordingReporter {
+    fn section(&mut self, title: &str) {
+        self.sections.push(title.to_string());
+    }
+
+    fn answer(&mut self, part: u8, _label: &str, value: &Answer) {
+        self.answers.push((part, value.to_string()));
+    }
+
+    fn phase(&mut self, name: &str, duration: Duration) {
+        self.phases.push((name.to_string(), duration));
+    }
+
+    fn note(&mut self, message: &str) {
+        self.notes.push(message.to_string());
+    }
+}
+
+/// Which reporter a binary prints through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputStyle {
+    /// `HumanReporter`
+    #[default]
+    Human,
+    /// `QuietReporter`
+    Quiet,
+}
+
+impl OutputStyle {
+    /// The style named by `$AOC_OUTPUT`, or `Human` if it's unset.
+    ///
+    /// # Errors
+    /// Returns `AocError::BadArgument` if the variable names no style.
+    pub fn from_env() -> Result<Self, AocError> {
+        match env::var(OUTPUT_VAR) {
+            Ok(style) => style.parse(),
+            Err(_) => Ok(OutputStyle::Human),
+        }
+    }
+
+    /// A reporter of this style printing to standard output.
+    pub fn reporter(self) -> Box<dyn Reporter> {
+        match self {
+            OutputStyle::Human => Box::new(HumanReporter::stdout()),
+            OutputStyle::Quiet => Box::new(QuietReporter::stdout()),
+        }
+    }
+}
+
+impl FromStr for OutputStyle {
+    type Err = AocError;
+
+    fn from_str(style: &str) -> Result<Self, Self::Err> {
+        match style {
+            "human" => Ok(OutputStyle::Human),
+            "quiet" => Ok(OutputStyle::Quiet),
+            other => Err(AocError::BadArgument(format!(
+                "{} must be human or quiet (got `{}`)",
+                OUTPUT_VAR, other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reports a short day's worth of output.
+    fn report(out: &mut dyn Reporter) {
+        out.section("Results");
+        out.answer(1, "Part 1 - Total distance", &Answer::U64(11));
+        out.note("Reports fixed by dampener: 2");
+        out.section("Performance Breakdown");
+        out.phase("Parsing", Duration::from_micros(5));
+        out.notes("Parsing: 1µs/pair\nPart 1: 2µs/pair\n");
+    }
+
+    #[test]
+    fn prints_the_human_layout() {
+        let mut human = HumanReporter::new(Vec::new());
+        report(&mut human);
+        assert_eq!(
+            String::from_utf8(human.into_inner()).unwrap(),
+            "\nResults:\n\
+             Part 1 - Total distance: 11\n\
+             Reports fixed by dampener: 2\n\
+             \nPerformance Breakdown:\n\
+             Parsing time: 5.00µs\n\
+             Parsing: 1µs/pair\n\
+             Part 1: 2µs/pair\n"
+        );
+    }
+
+    #[test]
+    fn prints_only_answers_when_quiet() {
+        let mut quiet = QuietReporter::new(Vec::new());
+        report(&mut quiet);
+        quiet.answer(2, "Part 2", &Answer::from("text"));
+        assert_eq!(quiet.into_inner(), b"11\ntext\n");
+    }
+
+    #[test]
+    fn records_what_was_reported() {
+        let mut recording = RecordingReporter::new();
+        report(&mut recording);
+        assert_eq!(recording.answers, [(1, "11".to_string())]);
+        assert_eq!(recording.sections, ["Results", "Performance Breakdown"]);
+        assert_eq!(
+            recording.phases,
+            [("Parsing".to_string(), Duration::from_micros(5))]
+        );
+        assert_eq!(recording.notes.len(), 3);
+    }
+
+    #[test]
+    fn parses_output_styles() {
+        assert_eq!("quiet".parse::<OutputStyle>().unwrap(), OutputStyle::Quiet);
+        assert_eq!("human".parse::<OutputStyle>().unwrap(), OutputStyle::Human);
+        assert_eq!(
+            "json".parse::<OutputStyle>().unwrap_err().to_string(),
+            "AOC_OUTPUT must be human or quiet (got `json`)"
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! A cursor over input bytes that hands out tokens and integers and knows
+//! where it is.
+//!
+//! Days used to walk their input with `while` loops over blank bytes, each
+//! keeping its own index and working out columns for errors by hand.
+//! `Scanner` does that walk once. It finds the end of a long token with
+//! `memchr`, so the token costs a vectorized search instead of a loop over
+//! its bytes, and it tracks the line and column it's at, so any token it
+//! hands out can be pointed at in an error without rescanning.
+
+use std::ops::Range;
+
+use memchr::{memchr, memchr3, memrchr};
+
+use super::{parse_int, Integer, ParseIntError};
+
+/// Token length up to which `Scanner` looks for the end byte by byte
+const SHORT_TOKEN: usize = 16;
+
+/// Which bytes separate tokens, looked up instead of compared four times
+const WHITESPACE: [bool; 256] = {
+    let mut table = [false; 256];
+    table[b' ' as usize] = true;
+    table[b'\t' as usize] = true;
+    table[b'\r' as usize] = true;
+    table[b'\n' as usize] = true;
+    table
+};
+
+/// Whether `b` separates tokens.
+#[inline]
+fn is_whitespace(b: u8) -> bool {
+    WHITESPACE[b as usize]
+}
+
+/// Reads whitespace-separated tokens from a byte slice.
+///
+/// Whitespace is spaces, tabs, `\r`, and `\n`. Lines and columns are
+/// 1-based, and columns count bytes, which is how every day's errors
+/// already point into a line.
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    bytes: &'a [u8],
+    /// Offset of the next unread byte
+    pos: usize,
+    /// Line holding `pos`
+    line: usize,
+    /// Offset of the first byte of that line
+    line_start: usize,
+    /// The last token handed out
+    token: Range<usize>,
+    /// Line that token was on
+    token_line: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// A scanner at the start of `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Scanner {
+            bytes,
+            pos: 0,
+            line: 1,
+            line_start: 0,
+            token: 0..0,
+            token_line: 0,
+        }
+    }
+
+    /// Offset of the next unread byte.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Line of the next unread byte.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Column of the next unread byte.
+    #[inline]
+    pub fn col(&self) -> usize {
+        self.pos - self.line_start + 1
+    }
+
+    /// Whether every byte is read.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    /// Whether no token has been read yet from the current line, so the
+    /// next token starts a new one.
+    #[inline]
+    pub fn at_line_start(&self) -> bool {
+        self.token_line != self.line
+    }
+
+    /// Skips spaces, tabs, and line endings, counting the lines passed.
+    #[inline]
+    pub fn skip_whitespace(&mut self) {
+        let bytes = self.bytes;
+        let mut pos = self.pos;
+        while pos < bytes.len() && is_whitespace(bytes[pos]) {
+            if bytes[pos] == b'\n' {
+                self.line += 1;
+                self.line_start = pos + 1;
+            }
+            pos += 1;
+        }
+        self.pos = pos;
+    }
+
+    /// The next run of non-whitespace bytes, or `None` at the end.
+    ///
+    /// # Implementation Details
+    /// - The first `SHORT_TOKEN` bytes are checked one at a time, since
+    ///   most puzzle tokens are a few digits, shorter than a `memchr` call
+    ///   takes to pay for itself
+    /// - Past that, `memchr3` finds the space, tab, or newline ending the
+    ///   token, and `memchr` then cuts it at a stray `\r`, which is rare
+    ///   enough that a fourth needle isn't worth searching for everywhere
+    #[inline]
+    pub fn next_token(&mut self) -> Option<&'a [u8]> {
+        self.skip_whitespace();
+        let bytes = self.bytes;
+        let start = self.pos;
+        if start == bytes.len() {
+            return None;
+        }
+        let short_end = bytes.len().min(start + SHORT_TOKEN);
+        let mut end = start + 1;
+        while end < short_end && !is_whitespace(bytes[end]) {
+            end += 1;
+        }
+        if end == short_end && end < bytes.len() && !is_whitespace(bytes[end]) {
+            end = memchr3(b' ', b'\t', b'\n', &bytes[end..]).map_or(bytes.len(), |i| end + i);
+            if let Some(cr) = memchr(b'\r', &bytes[start..end]) {
+                end = start + cr;
+            }
+        }
+        self.pos = end;
+        self.token = start..end;
+        self.token_line = self.line;
+        Some(&bytes[start..end])
+    }
+
+    /// Parses the next token as an integer with `parse_int`, or returns
+    /// `None` at the end.
+    ///
+    /// # Errors
+    /// Returns the `ParseIntError` for a malformed token, spanning its
+    /// bytes in the whole input; the scanner has moved past it.
+    #[inline]
+    pub fn next_int<T: Integer>(&mut self) -> Result<Option<T>, ParseIntError> {
+        let Some(token) = self.next_token() else {
+            return Ok(None);
+        };
+        parse_int(token).map(Some).map_err(|e| ParseIntError {
+            kind: e.kind,
+            span: self.token.clone(),
+        })
+    }
+
+    /// Byte range of the last token in the whole input.
+    #[inline]
+    pub fn token_span(&self) -> Range<usize> {
+        self.token.clone()
+    }
+
+    /// Line of the last token, or 0 before the first.
+    #[inline]
+    pub fn token_line(&self) -> usize {
+        self.token_line
+    }
+
+    /// Columns of the last token in its line, inclusive-exclusive like
+    /// `AocError::Parse`'s.
+    #[inline]
+    pub fn token_columns(&self) -> Range<usize> {
+        // Once the scan has moved past the token's line, find where it began
+        let line_start = match self.token_line == self.line {
+            true => self.line_start,
+            false => memrchr(b'\n', &self.bytes[..self.token.start]).map_or(0, |i| i + 1),
+        };
+        self.token.start - line_start + 1..self.token.end - line_start + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntErrorKind;
+
+    #[test]
+    fn splits_tokens_on_any_whitespace() {
+        let mut scanner = Scanner::new(b"  ab\tc \r\n\n d\re  ");
+        let tokens: Vec<_> = std::iter::from_fn(|| scanner.next_token()).collect();
+        assert_eq!(tokens, [&b"ab"[..], b"c", b"d", b"e"]);
+        assert!(scanner.is_empty());
+        assert_eq!(Scanner::new(b"").next_token(), None);
+        assert_eq!(Scanner::new(b" \n\t").next_token(), None);
+    }
+
+    #[test]
+    fn tracks_lines_and_columns() {
+        let mut scanner = Scanner::new(b"12 345\n\n  -6 7\n");
+        assert!(scanner.at_line_start());
+        assert_eq!(scanner.next_int::<i32>(), Ok(Some(12)));
+        assert_eq!((scanner.token_line(), scanner.token_columns()), (1, 1..3));
+        assert!(!scanner.at_line_start());
+        assert_eq!(scanner.next_int::<i32>(), Ok(Some(345)));
+        assert_eq!((scanner.line(), scanner.col()), (1, 7));
+
+        scanner.skip_whitespace();
+        assert_eq!((scanner.line(), scanner.col()), (3, 3));
+        assert!(scanner.at_line_start());
+        assert_eq!(scanner.next_int::<i32>(), Ok(Some(-6)));
+        assert_eq!((scanner.token_line(), scanner.token_columns()), (3, 3..5));
+        assert_eq!(scanner.token_span(), 10..12);
+        assert_eq!(scanner.next_int::<i32>(), Ok(Some(7)));
+        assert_eq!(scanner.next_int::<i32>(), Ok(None));
+        assert_eq!(scanner.line(), 4);
+        // The last token is still located after the scan moved past its line
+        assert_eq!((scanner.token_line(), scanner.token_columns()), (3, 6..7));
+    }
+
+    #[test]
+    fn reports_a_bad_integer_where_it_is() {
+        let mut scanner = Scanner::new(b"1 2\n3 4x 5\n");
+        let mut values = Vec::new();
+        let error = loop {
+            match scanner.next_int::<u8>() {
+                Ok(Some(value)) => values.push(value),
+                Ok(None) => panic!("no error"),
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(values, [1, 2, 3]);
+        assert_eq!(error.kind, IntErrorKind::InvalidDigit);
+        assert_eq!(error.span, 6..8);
+        assert_eq!((scanner.token_line(), scanner.token_columns()), (2, 3..5));
+        assert_eq!(scanner.next_int::<u8>(), Ok(Some(5)));
+
+        let mut scanner = Scanner::new(b"300");
+        let error = scanner.next_int::<u8>().unwrap_err();
+        assert_eq!(error.kind, IntErrorKind::OutOfRange { width: "u8" });
+    }
+
+    #[test]
+    fn finds_the_end_of_long_tokens() {
+        let digits = "9".repeat(SHORT_TOKEN * 6);
+        let input = format!("{} {}\r\n", digits, digits);
+        let mut scanner = Scanner::new(input.as_bytes());
+        assert_eq!(scanner.next_token(), Some(digits.as_bytes()));
+        assert_eq!(scanner.token_columns(), 1..97);
+        assert_eq!(scanner.next_token(), Some(digits.as_bytes()));
+        assert_eq!(scanner.token_columns(), 98..194);
+
+        // A token ending right at the short scan's limit, and a long one
+        // cut by a stray `\r`
+        let exact = "8".repeat(SHORT_TOKEN);
+        let input = format!("{} {}\r{}", exact, digits, exact);
+        let mut scanner = Scanner::new(input.as_bytes());
+        assert_eq!(scanner.next_token(), Some(exact.as_bytes()));
+        assert_eq!(scanner.next_token(), Some(digits.as_bytes()));
+        assert_eq!(scanner.next_token(), Some(exact.as_bytes()));
+        assert_eq!(scanner.next_token(), None);
+        assert_eq!(scanner.next_token(), None);
+    }
+}
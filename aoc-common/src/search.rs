@@ -0,0 +1,559 @@
+//! Shortest paths over any state space: breadth-first search, Dijkstra,
+//! and A*.
+//!
+//! States are any `Eq + Hash + Clone` value, from a `Point2` to a position
+//! with a facing and a step count, and a search is given a closure
+//! listing each state's neighbors rather than a graph built up front.
+//! Every state seen is numbered as it's found, so the bookkeeping behind
+//! the search indexes vectors and hashes each state only on discovery.
+//!
+//! `bfs`, `dijkstra`, and `astar` stop at the first goal and return the
+//! path to it. `bfs_all` and `dijkstra_all` explore everything reachable
+//! and return `Distances`, which also keeps every predecessor on a best
+//! path, for puzzles that count the cells on any shortest route.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::ops::Add;
+
+/// A path cost: anything non-negative that adds up and compares, with
+/// `Default` as zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C: Copy + Ord + Add<Output = C> + Default> Cost for C {}
+
+/// A best path found by a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    /// Total cost of the path's steps
+    pub cost: C,
+    /// Every state from the start to the goal, both included
+    pub states: Vec<S>,
+}
+
+/// The multiply-rotate hash rustc uses for its own tables, which is far
+/// cheaper than the default SipHash on the small states searches use and,
+/// since puzzle inputs aren't adversarial, needn't resist collisions.
+#[derive(Default)]
+struct StateHasher(u64);
+
+impl Hasher for StateHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, word: u32) {
+        self.write_u64(word.into());
+    }
+
+    #[inline]
+    fn write_u8(&mut self, byte: u8) {
+        self.write_u64(byte.into());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, word: usize) {
+        self.write_u64(word as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// State ids, keyed by `StateHasher`
+type Ids<S> = HashMap<S, usize, BuildHasherDefault<StateHasher>>;
+
+/// Parent of the start, which has none
+const NO_PARENT: usize = usize::MAX;
+
+/// Best costs from a start to every state reached, and how each was
+/// reached.
+#[derive(Debug, Clone)]
+pub struct Distances<S, C> {
+    ids: Ids<S>,
+    states: Vec<S>,
+    costs: Vec<C>,
+    /// One predecessor on a best path to each state
+    parent: Vec<usize>,
+    /// The other predecessors on a best path, when they're kept
+    also: Vec<Vec<usize>>,
+}
+
+impl<S: Eq + Hash + Clone, C: Cost> Distances<S, C> {
+    fn new(start: S, all: bool) -> Self {
+        Distances {
+            ids: Ids::from_iter([(start.clone(), 0)]),
+            states: vec![start],
+            costs: vec![C::default()],
+            parent: vec![NO_PARENT],
+            also: match all {
+                true => vec![Vec::new()],
+                false => Vec::new(),
+            },
+        }
+    }
+
+    /// Records that `state` is reached from state `from` at `cost`.
+    ///
+    /// # Returns
+    /// The id of `state`, if this is the best way to it found so far and
+    /// it should be explored from.
+    fn relax(&mut self, from: usize, state: S, cost: C) -> Option<usize> {
+        let id = match self.ids.get(&state) {
+            Some(&id) => id,
+            None => {
+                let id = self.states.len();
+                self.ids.insert(state.clone(), id);
+                self.states.push(state);
+                self.costs.push(cost);
+                self.parent.push(from);
+                if !self.also.is_empty() {
+                    self.also.push(Vec::new());
+                }
+                return Some(id);
+            }
+        };
+        if cost < self.costs[id] {
+            self.costs[id] = cost;
+            self.parent[id] = from;
+            if let Some(also) = self.also.get_mut(id) {
+                also.clear();
+            }
+            return Some(id);
+        }
+        if cost == self.costs[id] && self.parent[id] != from {
+            if let Some(also) = self.also.get_mut(id) {
+                if !also.contains(&from) {
+                    also.push(from);
+                }
+            }
+        }
+        None
+    }
+
+    /// The best path to state `id`.
+    fn path(&self, id: usize) -> Path<S, C> {
+        let mut states = Vec::new();
+        let mut at = id;
+        while at != NO_PARENT {
+            states.push(self.states[at].clone());
+            at = self.parent[at];
+        }
+        states.reverse();
+        Path {
+            cost: self.costs[id],
+            states,
+        }
+    }
+
+    /// Ids of the states state `id` is reached from on a best path.
+    fn best_predecessors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        let first = Some(self.parent[id]).filter(|&p| p != NO_PARENT);
+        let others = self.also.get(id).into_iter().flatten().copied();
+        first.into_iter().chain(others)
+    }
+
+    /// States reached, the start included.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Whether nothing was reached, which can't happen: the start always is.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// The best cost to `state`, or `None` if it wasn't reached.
+    #[inline]
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.ids.get(state).map(|&id| self.costs[id])
+    }
+
+    /// Every state reached with its best cost, in the order found.
+    pub fn iter(&self) -> impl Iterator<Item = (&S, C)> {
+        self.states.iter().zip(self.costs.iter().copied())
+    }
+
+    /// One best path to `state`, or `None` if it wasn't reached.
+    pub fn path_to(&self, state: &S) -> Option<Path<S, C>> {
+        self.ids.get(state).map(|&id| self.path(id))
+    }
+
+    /// The states `state` is reached from on some best path: all of them
+    /// from `bfs_all` and `dijkstra_all`, none for the start.
+    pub fn predecessors(&self, state: &S) -> Vec<&S> {
+        let Some(&id) = self.ids.get(state) else {
+            return Vec::new();
+        };
+        self.best_predecessors(id)
+            .map(|p| &self.states[p])
+            .collect()
+    }
+
+    /// Every state on any best path to the cheapest of `targets`, the
+    /// targets themselves and the start included.
+    ///
+    /// Targets not reached, or reached at more than the lowest cost among
+    /// them, are left out, so a goal with several states, like an end
+    /// tile facing any way, can list them all.
+    pub fn on_best_paths<'a>(&self, targets: impl IntoIterator<Item = &'a S>) -> HashSet<&S>
+    where
+        S: 'a,
+    {
+        let reached: Vec<usize> = targets
+            .into_iter()
+            .filter_map(|target| self.ids.get(target).copied())
+            .collect();
+        let Some(best) = reached.iter().map(|&id| self.costs[id]).min() else {
+            return HashSet::new();
+        };
+        let mut stack: Vec<usize> = reached
+            .into_iter()
+            .filter(|&id| self.costs[id] == best)
+            .collect();
+        let mut seen = vec![false; self.len()];
+        let mut on_path = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut seen[id], true) {
+                continue;
+            }
+            on_path.insert(&self.states[id]);
+            stack.extend(self.best_predecessors(id));
+        }
+        on_path
+    }
+}
+
+/// Breadth-first search, where every step costs 1; `visit` gets each
+/// state the first time it comes off the queue and stops the search by
+/// returning `true`.
+fn breadth_first<S, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut visit: impl FnMut(&S) -> bool,
+    all: bool,
+) -> (Distances<S, usize>, Option<usize>)
+where
+    S: Eq + Hash + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = Distances::new(start, all);
+    let mut queue = VecDeque::from([0]);
+    while let Some(id) = queue.pop_front() {
+        if visit(&distances.states[id]) {
+            return (distances, Some(id));
+        }
+        let next_cost = distances.costs[id] + 1;
+        for next in neighbors(&distances.states[id]) {
+            if let Some(next) = distances.relax(id, next, next_cost) {
+                queue.push_back(next);
+            }
+        }
+    }
+    (distances, None)
+}
+
+/// Best-first search ordered by cost plus `heuristic`, which is Dijkstra
+/// when the heuristic is always zero; `visit` is called as for
+/// `breadth_first`.
+fn best_first<S, C, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut visit: impl FnMut(&S) -> bool,
+    all: bool,
+) -> (Distances<S, C>, Option<usize>)
+where
+    S: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut distances = Distances::new(start, all);
+    // Lowest estimate first, then the deepest, which is nearest a goal
+    let mut frontier =
+        BinaryHeap::from([(Reverse(heuristic(&distances.states[0])), C::default(), 0)]);
+    while let Some((_, cost, id)) = frontier.pop() {
+        // A cheaper way here was queued after this entry
+        if cost > distances.costs[id] {
+            continue;
+        }
+        if visit(&distances.states[id]) {
+            return (distances, Some(id));
+        }
+        for (next, step) in neighbors(&distances.states[id]) {
+            let next_cost = cost + step;
+            let estimate = next_cost + heuristic(&next);
+            if let Some(next) = distances.relax(id, next, next_cost) {
+                frontier.push((Reverse(estimate), next_cost, next));
+            }
+        }
+    }
+    (distances, None)
+}
+
+/// The shortest path from `start` to the nearest state satisfying
+/// `is_goal`, counting steps, or `None` if no goal is reachable.
+///
+/// # Time Complexity
+/// O(V + E) over the states and steps explored
+pub fn bfs<S, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Eq + Hash + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let (distances, goal) = breadth_first(start, neighbors, is_goal, false);
+    goal.map(|goal| distances.path(goal))
+}
+
+/// Step counts from `start` to every reachable state, keeping every
+/// predecessor on a shortest path.
+pub fn bfs_all<S, I>(start: S, neighbors: impl FnMut(&S) -> I) -> Distances<S, usize>
+where
+    S: Eq + Hash + Clone,
+    I: IntoIterator<Item = S>,
+{
+    breadth_first(start, neighbors, |_| false, true).0
+}
+
+/// The cheapest path from `start` to a state satisfying `is_goal`, or
+/// `None` if no goal is reachable.
+///
+/// `neighbors` lists each state reachable in one step with that step's
+/// cost, which must not be negative.
+///
+/// # Time Complexity
+/// O(E log V) over the states and steps explored
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let (distances, goal) = best_first(start, neighbors, |_| C::default(), is_goal, false);
+    goal.map(|goal| distances.path(goal))
+}
+
+/// Best costs from `start` to every reachable state, keeping every
+/// predecessor on a cheapest path.
+pub fn dijkstra_all<S, C, I>(start: S, neighbors: impl FnMut(&S) -> I) -> Distances<S, C>
+where
+    S: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    best_first(start, neighbors, |_| C::default(), |_| false, true).0
+}
+
+/// `dijkstra` guided by `heuristic`, an estimate of the cost left to a
+/// goal that explores states nearer one first.
+///
+/// The path found is the cheapest as long as the heuristic never
+/// overestimates; one that does may return a dearer path, never a
+/// cheaper one. States can be explored again when the heuristic is
+/// inconsistent, so it needn't be.
+pub fn astar<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Eq + Hash + Clone,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let (distances, goal) = best_first(start, neighbors, heuristic, is_goal, false);
+    goal.map(|goal| distances.path(goal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::values::Values;
+    use crate::{manhattan, neighbors4, Grid, Point2};
+
+    /// A small weighted graph where every cheapest path from `a` runs
+    /// through `b`: `a` to `d` costs 2, `a` to `e` costs 3 against 5
+    /// through `c`, and `c` costs 4.
+    fn weighted(state: &char) -> Vec<(char, u32)> {
+        match state {
+            'a' => vec![('b', 1), ('c', 4)],
+            'b' => vec![('d', 1), ('e', 2)],
+            'c' => vec![('e', 1), ('d', 5)],
+            'e' => vec![('d', 3)],
+            _ => vec![],
+        }
+    }
+
+    /// Open cells of a maze drawn with `#` walls
+    fn maze(rows: &str) -> Grid<u8> {
+        rows.parse().unwrap()
+    }
+
+    fn open_neighbors(grid: &Grid<u8>) -> impl FnMut(&Point2) -> Vec<Point2> + '_ {
+        |&p| {
+            neighbors4(p)
+                .into_iter()
+                .filter(|&n| grid.get_point(n).is_some_and(|&c| c != b'#'))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn finds_the_cheapest_weighted_path() {
+        let path = dijkstra('a', weighted, |&s| s == 'd').unwrap();
+        assert_eq!(
+            path,
+            Path {
+                cost: 2,
+                states: vec!['a', 'b', 'd']
+            }
+        );
+        let path = dijkstra('a', weighted, |&s| s == 'e').unwrap();
+        assert_eq!(
+            path,
+            Path {
+                cost: 3,
+                states: vec!['a', 'b', 'e']
+            }
+        );
+        assert_eq!(dijkstra('d', weighted, |&s| s == 'a'), None);
+        // The start can be the goal
+        assert_eq!(
+            dijkstra('c', weighted, |&s| s == 'c').unwrap().states,
+            ['c']
+        );
+
+        let distances = dijkstra_all('a', weighted);
+        let costs: Vec<_> = ['a', 'b', 'c', 'd', 'e']
+            .iter()
+            .map(|s| distances.cost(s))
+            .collect();
+        assert_eq!(costs, [Some(0), Some(1), Some(4), Some(2), Some(3)]);
+        assert_eq!(distances.cost(&'z'), None);
+        assert_eq!(distances.len(), 5);
+    }
+
+    #[test]
+    fn walks_a_maze_breadth_first() {
+        let grid = maze("S..#\n.#.#\n.#..\n...E\n");
+        let (start, end) = (Point2::new(0, 0), Point2::new(3, 3));
+        let path = bfs(start, open_neighbors(&grid), |&p| p == end).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.states.len(), 7);
+        assert!(path.states.windows(2).all(|w| manhattan(w[0], w[1]) == 1));
+
+        let walled = maze("S#.\n##.\n..E\n");
+        assert_eq!(bfs(start, open_neighbors(&walled), |&p| p == end), None);
+
+        let distances = bfs_all(start, open_neighbors(&grid));
+        assert_eq!(distances.cost(&end), Some(6));
+        assert_eq!(distances.cost(&Point2::new(3, 0)), None);
+        assert_eq!(distances.path_to(&end).unwrap().cost, 6);
+    }
+
+    #[test]
+    fn finds_every_tile_on_a_best_path() {
+        // Two equally short ways around the middle wall, and a dead end
+        let grid = maze("S...\n.##.\n...E\n#.##\n");
+        let (start, end) = (Point2::new(0, 0), Point2::new(3, 2));
+        let distances = bfs_all(start, open_neighbors(&grid));
+        assert_eq!(distances.cost(&end), Some(5));
+        let tiles = distances.on_best_paths([&end]);
+        assert_eq!(tiles.len(), 10);
+        assert!(!tiles.contains(&Point2::new(1, 3)));
+        assert_eq!(distances.predecessors(&end).len(), 2);
+        assert!(distances.predecessors(&start).is_empty());
+
+        // Only the cheapest of several targets counts, here the dead end
+        let dead_end = Point2::new(1, 3);
+        assert_eq!(distances.cost(&dead_end), Some(4));
+        assert_eq!(distances.on_best_paths([&end, &dead_end]).len(), 5);
+        assert!(distances.on_best_paths([&Point2::new(9, 9)]).is_empty());
+
+        // Weighted ties: `a -> b -> e` and `a -> c -> e` both cost 3
+        let tied = |s: &char| match s {
+            'a' => vec![('b', 1), ('c', 2)],
+            'b' => vec![('e', 2)],
+            'c' => vec![('e', 1)],
+            _ => vec![],
+        };
+        let distances = dijkstra_all('a', tied);
+        let mut preds = distances.predecessors(&'e');
+        preds.sort();
+        assert_eq!(preds, [&'b', &'c']);
+        assert_eq!(distances.on_best_paths([&'e']).len(), 4);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra_with_an_admissible_heuristic() {
+        let mut values = Values(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..100 {
+            // A random cost per cell, for entering it, and a few walls
+            let side = 12;
+            let cells: Vec<u32> = (0..side * side).map(|_| values.below(10) as u32).collect();
+            let grid = Grid::from_vec(side, cells).unwrap();
+            let neighbors = |&p: &Point2| -> Vec<(Point2, u32)> {
+                neighbors4(p)
+                    .into_iter()
+                    .filter_map(|n| grid.get_point(n).filter(|&&c| c != 0).map(|&c| (n, c)))
+                    .collect()
+            };
+            let end = Point2::new(side as i64 - 1, side as i64 - 1);
+            let is_goal = |p: &Point2| *p == end;
+
+            let best = dijkstra(Point2::ZERO, neighbors, is_goal).map(|p| p.cost);
+            // Every step costs at least 1, so distance never overestimates
+            let admissible = |p: &Point2| manhattan(*p, end) as u32;
+            let guided = astar(Point2::ZERO, neighbors, admissible, is_goal);
+            assert_eq!(guided.as_ref().map(|p| p.cost), best);
+            if let Some(path) = guided {
+                let cost: u32 = path.states[1..].iter().map(|&p| grid[p]).sum();
+                assert_eq!(cost, path.cost);
+            }
+
+            // A wild guess can cost optimality but never beats Dijkstra
+            let mut guess = Values(values.value());
+            let wild = |_: &Point2| guess.below(40) as u32;
+            let guessed = astar(Point2::ZERO, neighbors, wild, is_goal).map(|p| p.cost);
+            assert_eq!(guessed.is_some(), best.is_some());
+            assert!(best <= guessed, "{:?} > {:?}", best, guessed);
+        }
+    }
+
+    #[test]
+    fn bfs_matches_dijkstra_on_unit_steps() {
+        let grid = maze("S.#.....\n.##.###.\n....#...\n.##...#E\n");
+        let end = Point2::new(7, 3);
+        let steps = bfs(Point2::ZERO, open_neighbors(&grid), |&p| p == end).unwrap();
+        let mut open = open_neighbors(&grid);
+        let unit = |p: &Point2| open(p).into_iter().map(|n| (n, 1usize)).collect::<Vec<_>>();
+        let weighted = dijkstra(Point2::ZERO, unit, |&p| p == end).unwrap();
+        assert_eq!(steps.cost, weighted.cost);
+        assert_eq!(steps.cost, 12);
+    }
+}
@@ -0,0 +1,484 @@
+//! The interface every day's solution implements.
+//!
+//! A day parses its input once into whatever shape both parts want, then
+//! answers each part from that shared value. `solve` times the three steps
+//! and `run_solver` reports them, so a caller that only wants the answers
+//! doesn't repeat either. Answers come back as `Answer`s, so the runner
+//! decides how they print. Every step also gets the run's `AocContext`,
+//! for the options that change how a day parses or what it counts, and
+//! for notes and metrics beyond the answers.
+//!
+//! The parsed value may borrow from the input, so a day whose input is
+//! mostly names, like towel patterns or wire identifiers, can keep slices
+//! of it rather than a `String` per token. Its `Parsed` type then
+//! implements `ParseInput`, which ties it to the input it was parsed from,
+//! and `solve` keeps that input borrowed until both parts have run.
+
+use std::fmt;
+use std::time::Duration;
+
+use super::{time_block, Answer, AocContext, AocError, Metrics, Part};
+
+/// One day's puzzle, split into parsing and the two parts.
+pub trait Solver {
+    /// Day of the Advent calendar, 1-25
+    const DAY: u8;
+
+    /// The parsed input both parts read, which may borrow from the input
+    /// for `'a`
+    type Parsed<'a>;
+
+    /// Why an input couldn't be parsed or answered
+    type Error: fmt::Display;
+
+    /// Parses the whole puzzle input.
+    fn parse<'a>(
+        &self,
+        input: &'a str,
+        ctx: &mut AocContext<'_>,
+    ) -> Result<Self::Parsed<'a>, Self::Error>;
+
+    /// Answers Part 1 from the parsed input.
+    fn part1(
+        &self,
+        parsed: &Self::Parsed<'_>,
+        ctx: &mut AocContext<'_>,
+    ) -> Result<Answer, Self::Error>;
+
+    /// Answers Part 2 from the same parsed input.
+    fn part2(
+        &self,
+        parsed: &Self::Parsed<'_>,
+        ctx: &mut AocContext<'_>,
+    ) -> Result<Answer, Self::Error>;
+}
+
+/// A parsed input built from the input text alone, borrowing from it for
+/// `'a` where it can.
+///
+/// A day whose parse doesn't depend on its settings implements this for
+/// its `Parsed` type and has `Solver::parse` call it, so the type can also
+/// be parsed without a solver, as a test would.
+pub trait ParseInput<'a>: Sized {
+    /// Parses the whole puzzle input.
+    ///
+    /// # Errors
+    /// Returns `AocError::Parse` at the first line that doesn't fit.
+    fn parse(input: &'a str) -> Result<Self, AocError>;
+}
+
+/// Both answers for one input, with how long each step took.
+///
+/// A part the context didn't ask for is `Answer::None`, and took no time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub day: u8,
+    pub part1: Answer,
+    pub part2: Answer,
+    pub parse_time: Duration,
+    pub part1_time: Duration,
+    pub part2_time: Duration,
+}
+
+impl Solution {
+    /// Time spent across parsing and both parts.
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.part1_time + self.part2_time
+    }
+}
+
+/// Parses `input` once and answers the parts `ctx.part()` names from it,
+/// timing each step.
+///
+/// The parsed value, and whatever it borrows from `input`, is dropped only
+/// after the last part.
+///
+/// # Implementation Details
+/// - The three steps are timed apart from `ctx.metrics`, which the solver
+///   itself writes to while they run
+///
+/// # Errors
+/// Returns the solver's error from whichever step failed first.
+pub fn solve<S: Solver>(
+    solver: &S,
+    input: &str,
+    ctx: &mut AocContext<'_>,
+) -> Result<Solution, S::Error> {
+    let metrics = Metrics::new();
+    let part = ctx.part();
+    let parsed = time_block!(metrics, "parse", { solver.parse(input, ctx)? });
+    let part1 = match part {
+        Part::One | Part::Both => time_block!(metrics, "part1", { solver.part1(&parsed, ctx)? }),
+        Part::Two => Answer::None,
+    };
+    let part2 = match part {
+        Part::Two | Part::Both => time_block!(metrics, "part2", { solver.part2(&parsed, ctx)? }),
+        Part::One => Answer::None,
+    };
+
+    Ok(Solution {
+        day: S::DAY,
+        part1,
+        part2,
+        parse_time: metrics.elapsed("parse"),
+        part1_time: metrics.elapsed("part1"),
+        part2_time: metrics.elapsed("part2"),
+    })
+}
+
+/// Solves `input` and reports the answers and timings to `ctx.out`, for
+/// the parts `ctx.part()` names.
+///
+/// # Errors
+/// Returns the solver's error, having reported nothing but the notes the
+/// solver made before it failed.
+pub fn run_solver<S: Solver>(
+    solver: &S,
+    input: &str,
+    ctx: &mut AocContext<'_>,
+) -> Result<Solution, S::Error> {
+    let solution = solve(solver, input, ctx)?;
+    let part = ctx.part();
+    let out = &mut *ctx.out;
+
+    out.section("Results");
+    let day = solution.day;
+    // A part with nothing to answer isn't reported at all
+    for (part, answer) in [(1, &solution.part1), (2, &solution.part2)] {
+        if answer.is_some() {
+            out.answer(part, &format!("Day {} Part {}", day, part), answer);
+        }
+    }
+
+    out.section("Performance Breakdown");
+    out.phase("Parsing", solution.parse_time);
+    if part.reports_part1() {
+        out.phase("Part 1", solution.part1_time);
+    }
+    if part.runs_part2() {
+        out.phase("Part 2", solution.part2_time);
+    }
+    out.phase("Total", solution.total_time());
+
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AocError, Config, RecordingReporter};
+    use std::cell::Cell;
+
+    /// Sums the numbers on each line for Part 1 and takes their maximum
+    /// for Part 2, leaving out negative numbers with `positive`.
+    struct Sum;
+
+    impl Solver for Sum {
+        const DAY: u8 = 9;
+        type Parsed<'a> = Vec<i64>;
+        type Error = AocError;
+
+        fn parse<'a>(
+            &self,
+            input: &'a str,
+            ctx: &mut AocContext<'_>,
+        ) -> Result<Self::Parsed<'a>, Self::Error> {
+            ctx.expect_options(Self::DAY, &["positive"])?;
+            let positive = ctx.flag("positive")?;
+            let mut numbers = Vec::new();
+            for (i, line) in input.lines().enumerate() {
+                let n: i64 = line
+                    .trim()
+                    .parse()
+                    .map_err(|e| AocError::parse(i + 1, 1..line.len() + 1, e))?;
+                if positive && n < 0 {
+                    ctx.metrics.count("dropped", 1);
+                    ctx.out.note(&format!("Dropped {} on line {}", n, i + 1));
+                } else {
+                    numbers.push(n);
+                }
+            }
+            Ok(numbers)
+        }
+
+        fn part1(
+            &self,
+            numbers: &Self::Parsed<'_>,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            Ok(numbers.iter().sum::<i64>().into())
+        }
+
+        fn part2(
+            &self,
+            numbers: &Self::Parsed<'_>,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            Ok(numbers.iter().max().copied().unwrap_or(0).into())
+        }
+    }
+
+    /// Wraps a solver, counting calls to each step and checking that both
+    /// parts read the value returned by the one parse.
+    struct Counting<S> {
+        inner: S,
+        parses: Cell<usize>,
+        parts: Cell<usize>,
+    }
+
+    impl<S: Solver> Counting<S> {
+        fn new(inner: S) -> Self {
+            Counting {
+                inner,
+                parses: Cell::new(0),
+                parts: Cell::new(0),
+            }
+        }
+    }
+
+    impl<S: Solver> Solver for Counting<S> {
+        const DAY: u8 = S::DAY;
+        /// The inner parse, tagged with which call produced it
+        type Parsed<'a> = (usize, S::Parsed<'a>);
+        type Error = S::Error;
+
+        fn parse<'a>(
+            &self,
+            input: &'a str,
+            ctx: &mut AocContext<'_>,
+        ) -> Result<Self::Parsed<'a>, Self::Error> {
+            self.parses.set(self.parses.get() + 1);
+            Ok((self.parses.get(), self.inner.parse(input, ctx)?))
+        }
+
+        fn part1(
+            &self,
+            (call, parsed): &Self::Parsed<'_>,
+            ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            assert_eq!(*call, self.parses.get(), "part 1 read a stale parse");
+            self.parts.set(self.parts.get() + 1);
+            self.inner.part1(parsed, ctx)
+        }
+
+        fn part2(
+            &self,
+            (call, parsed): &Self::Parsed<'_>,
+            ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            assert_eq!(*call, self.parses.get(), "part 2 read a stale parse");
+            self.parts.set(self.parts.get() + 1);
+            self.inner.part2(parsed, ctx)
+        }
+    }
+
+    /// Day 19's towels: the patterns on offer and the designs to make, both
+    /// left as slices of the input.
+    struct Towels<'a> {
+        patterns: Vec<&'a str>,
+        designs: Vec<&'a str>,
+    }
+
+    impl<'a> ParseInput<'a> for Towels<'a> {
+        fn parse(input: &'a str) -> Result<Self, AocError> {
+            let (patterns, designs) = input.split_once("\n\n").ok_or_else(|| {
+                let last = input.lines().last().unwrap_or("");
+                AocError::parse(
+                    input.lines().count(),
+                    1..last.len() + 1,
+                    "expected a blank line after the patterns",
+                )
+            })?;
+            Ok(Towels {
+                patterns: patterns.split(", ").collect(),
+                designs: designs.lines().collect(),
+            })
+        }
+    }
+
+    impl Towels<'_> {
+        /// Ways to lay `design` out of patterns, counted by how much of it
+        /// each prefix covers.
+        fn arrangements(&self, design: &str) -> u64 {
+            let mut ways = vec![0; design.len() + 1];
+            ways[0] = 1;
+            for end in 1..=design.len() {
+                for pattern in &self.patterns {
+                    if design[..end].ends_with(pattern) {
+                        ways[end] += ways[end - pattern.len()];
+                    }
+                }
+            }
+            ways[design.len()]
+        }
+    }
+
+    /// Counts the possible designs for Part 1 and every arrangement of
+    /// them for Part 2.
+    struct Onsen;
+
+    impl Solver for Onsen {
+        const DAY: u8 = 19;
+        type Parsed<'a> = Towels<'a>;
+        type Error = AocError;
+
+        fn parse<'a>(
+            &self,
+            input: &'a str,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Self::Parsed<'a>, Self::Error> {
+            Towels::parse(input)
+        }
+
+        fn part1(
+            &self,
+            towels: &Self::Parsed<'_>,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            let possible = towels
+                .designs
+                .iter()
+                .filter(|design| towels.arrangements(design) > 0)
+                .count();
+            Ok(possible.into())
+        }
+
+        fn part2(
+            &self,
+            towels: &Self::Parsed<'_>,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            let ways: u64 = towels
+                .designs
+                .iter()
+                .map(|design| towels.arrangements(design))
+                .sum();
+            Ok(ways.into())
+        }
+    }
+
+    const TOWELS: &str = "r, wr, b, g, bwu, rb, gb, br\n\n\
+                          brwrr\nbggr\ngbbr\nrrbgbr\nubwu\nbwurrg\nbrgr\nbbrgwb\n";
+
+    #[test]
+    fn borrows_tokens_from_the_input() {
+        // Owned, like an input read from a file, and alive past both parts
+        let input = TOWELS.to_string();
+        let towels = Towels::parse(&input).unwrap();
+        let bytes = input.as_bytes().as_ptr_range();
+        assert!(towels
+            .patterns
+            .iter()
+            .chain(&towels.designs)
+            .all(|token| bytes.contains(&token.as_ptr())));
+        assert_eq!(towels.patterns.len(), 8);
+        assert_eq!(towels.designs[..2], ["brwrr", "bggr"]);
+
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        let solution = solve(&Onsen, &input, &mut ctx).unwrap();
+        assert_eq!(
+            (solution.part1, solution.part2),
+            (Answer::U64(6), Answer::U64(16))
+        );
+        assert_eq!(
+            Towels::parse("r, wr\n").err().unwrap().to_string(),
+            "line 1, columns 1-5: expected a blank line after the patterns"
+        );
+    }
+
+    #[test]
+    fn parses_once_for_both_parts() {
+        let solver = Counting::new(Sum);
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        let solution = solve(&solver, "3\n-1\n7\n", &mut ctx).unwrap();
+        assert_eq!(
+            (solution.part1, solution.part2),
+            (Answer::I64(9), Answer::I64(7))
+        );
+        assert_eq!(solution.day, 9);
+        assert_eq!(solver.parses.get(), 1);
+        assert_eq!(solver.parts.get(), 2);
+    }
+
+    #[test]
+    fn reports_parsing_apart_from_each_part() {
+        let solver = Counting::new(Sum);
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        let solution = run_solver(&solver, "4\n5\n", &mut ctx).unwrap();
+        assert_eq!(solver.parses.get(), 1);
+        assert_eq!(out.answers, [(1, "9".to_string()), (2, "5".to_string())]);
+
+        let phases: Vec<_> = out.phases.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(phases, ["Parsing", "Part 1", "Part 2", "Total"]);
+        assert_eq!(out.phases[0].1, solution.parse_time);
+        assert_eq!(out.phases[3].1, solution.total_time());
+    }
+
+    #[test]
+    fn stops_at_a_parse_error_without_reporting() {
+        let solver = Counting::new(Sum);
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        let error = run_solver(&solver, "1\nx\n", &mut ctx).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, columns 1-1: invalid digit found in string"
+        );
+        assert_eq!((solver.parses.get(), solver.parts.get()), (1, 0));
+        assert_eq!(out, RecordingReporter::new());
+    }
+
+    #[test]
+    fn reads_options_and_reports_through_the_context() {
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out).with_option("positive", "");
+        let solution = run_solver(&Sum, "4\n-9\n5\n-1\n", &mut ctx).unwrap();
+        assert_eq!(
+            (solution.part1, solution.part2),
+            (Answer::I64(9), Answer::I64(5))
+        );
+        assert_eq!(ctx.metrics.counter("dropped"), 2);
+        assert_eq!(out.notes, ["Dropped -9 on line 2", "Dropped -1 on line 4"]);
+        assert_eq!(out.answers, [(1, "9".to_string()), (2, "5".to_string())]);
+
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out).with_option("postive", "");
+        assert_eq!(
+            solve(&Sum, "1\n", &mut ctx).unwrap_err().to_string(),
+            "unknown option `postive` for day 9; expected one of: positive"
+        );
+    }
+
+    #[test]
+    fn answers_only_the_requested_part() {
+        for (part, answers, phases) in [
+            (
+                Part::One,
+                vec![(1, "9".to_string())],
+                ["Parsing", "Part 1", "Total"],
+            ),
+            (
+                Part::Two,
+                vec![(2, "5".to_string())],
+                ["Parsing", "Part 2", "Total"],
+            ),
+        ] {
+            let solver = Counting::new(Sum);
+            let mut out = RecordingReporter::new();
+            let mut ctx = AocContext::new(Config::default(), &mut out).with_part(part);
+            let solution = run_solver(&solver, "4\n5\n", &mut ctx).unwrap();
+            assert_eq!(solver.parts.get(), 1);
+            assert_eq!(out.answers, answers);
+            let names: Vec<_> = out.phases.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, phases);
+            let skipped = match part {
+                Part::One => (&solution.part2, solution.part2_time),
+                _ => (&solution.part1, solution.part1_time),
+            };
+            assert_eq!(skipped, (&Answer::None, Duration::ZERO));
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Durations and rates written for people rather than for `Debug`.
+//!
+//! `Duration`'s `Debug` output keeps every digit, so `1.234567ms` and
+//! `812ns` sit side by side in a breakdown and are hard to compare at a
+//! glance. These keep three significant figures in the largest unit that
+//! fits, and rates scale to a metric prefix, so a report reads `1.23ms`,
+//! `142.3 MB/s`, or `1.2M lines/s`. None of them divides by a zero
+//! duration or overflows on a huge one: a rate over no time at all is
+//! written `n/a`, and the arithmetic is done in `f64`.
+
+use std::time::Duration;
+
+/// What a rate over a zero duration is written as
+const NO_RATE: &str = "n/a";
+
+/// Prefixes for each power of a thousand, up to 10^18
+const PREFIXES: [&str; 7] = ["", "k", "M", "G", "T", "P", "E"];
+
+/// Sub-second units with their length in nanoseconds, smallest first
+const SUBSECOND_UNITS: [(f64, &str); 3] = [(1e3, "µs"), (1e6, "ms"), (1e9, "s")];
+
+/// Writes a duration to three significant figures, in the largest unit
+/// that leaves at least one whole unit: `812ns`, `1.23ms`, `45.6s`.
+///
+/// Whole nanoseconds are written exactly. From a minute up, the duration
+/// is whole minutes and seconds, then whole hours and minutes: `2m 05s`,
+/// `3h 07m`.
+pub fn fmt_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1000 {
+        return format!("{}ns", nanos);
+    }
+    let secs = duration.as_secs();
+    if secs < 60 {
+        for (scale, unit) in SUBSECOND_UNITS {
+            let value = nanos as f64 / scale;
+            // 999.7µs would round to 1000µs, which is 1.00ms
+            if value < 999.5 || unit == "s" {
+                return format!("{}{}", three_figures(value), unit);
+            }
+        }
+    }
+    if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Writes how many bytes a second `bytes` over `duration` is, to one
+/// decimal with a decimal prefix: `142.3 MB/s`.
+pub fn fmt_rate(bytes: u64, duration: Duration) -> String {
+    match per_second(bytes, duration) {
+        Some(rate) => {
+            let (value, prefix) = scaled(rate);
+            format!("{:.1} {}B/s", value, prefix)
+        }
+        None => NO_RATE.to_string(),
+    }
+}
+
+/// Writes how many `unit`s a second `items` over `duration` is, to one
+/// decimal with a prefix past a thousand: `1.2M lines/s`, `812.0 lines/s`.
+pub fn fmt_count_rate(items: u64, duration: Duration, unit: &str) -> String {
+    match per_second(items, duration) {
+        Some(rate) => {
+            let (value, prefix) = scaled(rate);
+            format!("{:.1}{} {}/s", value, prefix, unit)
+        }
+        None => NO_RATE.to_string(),
+    }
+}
+
+/// `amount` per second over `duration`, or `None` for no time at all.
+fn per_second(amount: u64, duration: Duration) -> Option<f64> {
+    (!duration.is_zero()).then(|| amount as f64 / duration.as_secs_f64())
+}
+
+/// `value` divided by the largest power of a thousand that leaves it at
+/// least one, with that power's prefix; the last prefix takes everything
+/// above it.
+fn scaled(mut value: f64) -> (f64, &'static str) {
+    let mut prefix = PREFIXES[0];
+    for next in &PREFIXES[1..] {
+        // 999.96 would show as 1000.0, which is 1.0 of the next prefix
+        if value < 999.95 {
+            break;
+        }
+        value /= 1000.0;
+        prefix = next;
+    }
+    (value, prefix)
+}
+
+/// `value`, from 1 to just under 1000, to three significant figures.
+fn three_figures(value: f64) -> String {
+    for decimals in [2, 1] {
+        let text = format!("{:.*}", decimals, value);
+        // `9.99` fits, but `9.996` rounds up to `10.00`, one figure too many
+        if text.len() <= 4 {
+            return text;
+        }
+    }
+    format!("{:.0}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_durations_to_three_figures() {
+        let cases = [
+            (Duration::ZERO, "0ns"),
+            (Duration::from_nanos(812), "812ns"),
+            (Duration::from_nanos(999), "999ns"),
+            (Duration::from_nanos(1000), "1.00µs"),
+            (Duration::from_nanos(123_456), "123µs"),
+            (Duration::from_nanos(9_996), "10.0µs"),
+            (Duration::from_nanos(99_960), "100µs"),
+            (Duration::from_nanos(999_400), "999µs"),
+            (Duration::from_nanos(999_700), "1.00ms"),
+            (Duration::from_micros(1_234), "1.23ms"),
+            (Duration::from_millis(45_600), "45.6s"),
+            (Duration::from_secs(59), "59.0s"),
+            (Duration::from_secs(60), "1m 00s"),
+            (Duration::from_secs(125), "2m 05s"),
+            (Duration::from_secs(3 * 3600 + 7 * 60 + 59), "3h 07m"),
+            (Duration::MAX, "5124095576030431h 00m"),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(fmt_duration(duration), expected, "{:?}", duration);
+        }
+    }
+
+    #[test]
+    fn writes_rates_with_decimal_prefixes() {
+        let second = Duration::from_secs(1);
+        assert_eq!(fmt_rate(142_300_000, second), "142.3 MB/s");
+        assert_eq!(fmt_rate(512, second), "512.0 B/s");
+        assert_eq!(fmt_rate(999_960, second), "1.0 MB/s");
+        assert_eq!(fmt_rate(19_000, Duration::from_millis(10)), "1.9 MB/s");
+        assert_eq!(fmt_rate(0, second), "0.0 B/s");
+        assert_eq!(fmt_rate(1000, Duration::ZERO), "n/a");
+        // Past the largest prefix, the value just grows
+        assert_eq!(
+            fmt_rate(u64::MAX, Duration::from_nanos(1)),
+            "18446744073.7 EB/s"
+        );
+        assert_eq!(fmt_rate(u64::MAX, Duration::MAX), "1.0 B/s");
+    }
+
+    #[test]
+    fn writes_count_rates_with_a_unit() {
+        let second = Duration::from_secs(1);
+        assert_eq!(fmt_count_rate(1_200_000, second, "lines"), "1.2M lines/s");
+        assert_eq!(fmt_count_rate(812, second, "lines"), "812.0 lines/s");
+        assert_eq!(
+            fmt_count_rate(1000, Duration::from_micros(250), "pairs"),
+            "4.0M pairs/s"
+        );
+        assert_eq!(fmt_count_rate(7, Duration::ZERO, "reports"), "n/a");
+    }
+}
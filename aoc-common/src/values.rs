@@ -0,0 +1,45 @@
+//! Deterministic pseudo-random values for property tests and benchmarks,
+//! so they're repeatable and need no randomness dependency.
+
+/// An xorshift generator over `u64`, seeded per test or benchmark with any
+/// nonzero value.
+pub struct Values(pub u64);
+
+/// Never ends: every call gives the next value, uniform over `u64`.
+impl Iterator for Values {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        Some(self.0)
+    }
+}
+
+impl Values {
+    /// The next value, uniform over `u64`.
+    pub fn value(&mut self) -> u64 {
+        self.next().expect("the values never end")
+    }
+
+    /// Any `i64`
+    pub fn any(&mut self) -> i64 {
+        self.value() as i64
+    }
+
+    /// A value in `0..limit`, for a nonzero `limit`
+    pub fn below(&mut self, limit: u64) -> u64 {
+        self.value() % limit
+    }
+
+    /// A value in `-limit..=limit`, for a `limit` below `i64::MAX`
+    pub fn signed(&mut self, limit: i64) -> i64 {
+        (self.value() % (2 * limit as u64 + 1)) as i64 - limit
+    }
+
+    /// A value in `1..=limit`
+    pub fn positive(&mut self, limit: i64) -> i64 {
+        (self.value() % limit as u64) as i64 + 1
+    }
+}
@@ -0,0 +1,51 @@
+//! Bakes each compiled-in day's `puzzle_input.txt` into the runner when the
+//! `embed-inputs` feature is on.
+//!
+//! The table is written to `$OUT_DIR/embedded.rs` as `(day, path, text)`
+//! entries, each text an `include_str!` of the file, and it's empty without
+//! the feature. A day whose file is missing is left out with a warning,
+//! so the build still succeeds and the runner reads that day from disk.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+/// File name of an input kept next to a day's code
+const PUZZLE_INPUT: &str = "puzzle_input.txt";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let mut table = String::from("&[\n");
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+        let workspace = manifest_dir
+            .parent()
+            .expect("the runner lives inside the workspace");
+        for day in 1..=25u8 {
+            if env::var_os(format!("CARGO_FEATURE_DAY{:02}", day)).is_none() {
+                continue;
+            }
+            // The places `input::resolve` looks inside the workspace
+            let dir = workspace.join(format!("day-{:02}", day));
+            let candidates = [dir.join(PUZZLE_INPUT), dir.join("rs").join(PUZZLE_INPUT)];
+            for path in &candidates {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+            match candidates.iter().find(|path| path.is_file()) {
+                Some(path) => {
+                    let path = format!("{:?}", path.display().to_string());
+                    writeln!(table, "    ({}, {}, include_str!({})),", day, path, path).unwrap();
+                }
+                None => println!(
+                    "cargo:warning=no input to embed for day {}; it will be read from disk",
+                    day
+                ),
+            }
+        }
+    }
+    table.push(']');
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("embedded.rs"), table).unwrap();
+}
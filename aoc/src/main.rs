@@ -0,0 +1,158 @@
+//! One binary for every day: `aoc run --day N [--part 1|2|both] [INPUT]`,
+//! or `aoc run --all`.
+//!
+//! The input is the one baked in by the `embed-inputs` feature, or else
+//! found the way each day binary finds its own: from the given path, `-`
+//! for stdin, the configured input directory, or the workspace. Where it
+//! came from is reported first, then the answers and timings, through the
+//! `Reporter` chosen by `--quiet` or `$AOC_OUTPUT`.
+//!
+//! `run` spells `--part`, `--quiet`, and `--threads` the way every day
+//! binary does; `--metrics-json` stays with the day binaries, which
+//! collect the metrics it writes. Each `--set` passes the day one of its
+//! own options, such as `--set max-step=5` or `--set lenient` for day 2,
+//! through the run's `AocContext`.
+//!
+//! `aoc run --all` solves every compiled-in day from its own input instead,
+//! and prints one table of the answers and timings. A day that fails is a
+//! row with its error, and the run exits 1 at the end if any day did.
+
+mod registry;
+mod source;
+mod summary;
+
+use aoc_common::{init_parallelism, parse_args, AocContext, AocError, Config, OutputStyle, Part};
+use clap::{ArgGroup, Parser, Subcommand};
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Every day's solution behind one command.
+#[derive(Debug, Parser)]
+#[command(name = "aoc")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// What the runner does.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Solve one day's puzzle, or every day's
+    Run(RunArgs),
+}
+
+/// Command-line options for `aoc run`.
+#[derive(Debug, clap::Args)]
+#[command(group(ArgGroup::new("days").required(true).args(["day", "all"])))]
+struct RunArgs {
+    /// Day to solve
+    #[arg(long, value_name = "N")]
+    day: Option<u8>,
+    /// Solve every day from its own input and summarize them in a table
+    #[arg(long, conflicts_with_all = ["input", "quiet", "options"])]
+    all: bool,
+    /// Puzzle input path, `-` for stdin; without one, the day's own input
+    #[arg(value_name = "INPUT")]
+    input: Option<String>,
+    /// Answers to compute
+    #[arg(long, value_enum, default_value_t = Part::Both, hide_possible_values = true)]
+    part: Part,
+    /// Print only the answers (or set $AOC_OUTPUT=quiet)
+    #[arg(long)]
+    quiet: bool,
+    /// Worker threads, overriding `threads` in aoc.toml and $AOC_THREADS
+    #[arg(long, value_name = "N")]
+    threads: Option<NonZeroUsize>,
+    /// One of the day's own options, as KEY=VALUE or a bare KEY for a flag
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    options: Vec<String>,
+}
+
+/// Errors are printed to stderr and the process exits nonzero: 2 for bad
+/// usage, including a day that isn't compiled in, and 1 for anything else.
+fn main() -> ExitCode {
+    let result = parse_args::<Cli>().and_then(|cli| match cli.command {
+        Command::Run(args) => run(args),
+    });
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+/// Solves the requested day, or every day for `--all`.
+fn run(args: RunArgs) -> Result<ExitCode, AocError> {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("the runner lives inside the workspace");
+    match args.day {
+        Some(number) => run_day(number, args, workspace),
+        None => run_all(args, workspace),
+    }
+}
+
+/// Finds day `number` and its input and solves the requested parts.
+fn run_day(number: u8, args: RunArgs, workspace: &Path) -> Result<ExitCode, AocError> {
+    let day = registry::find(number)?;
+    let crate_dir = workspace.join(day.crate_path);
+    let flags = Config {
+        threads: args.threads,
+        ..Config::default()
+    };
+    let config = Config::load(&crate_dir, flags)?;
+    init_parallelism(config.threads.map(NonZeroUsize::get));
+
+    let (input, source) = source::load(day.number, args.input.as_deref(), &crate_dir, &config)?;
+
+    let style = match args.quiet {
+        true => OutputStyle::Quiet,
+        false => OutputStyle::from_env()?,
+    };
+    let mut out = style.reporter();
+    let mut ctx = AocContext::new(config, &mut *out).with_part(args.part);
+    ctx.set_options(&args.options)?;
+    ctx.out.note(&format!("Input: {}", source));
+    (day.run)(&input, &mut ctx).map_err(|e| e.in_input(&input))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Solves every compiled-in day and prints the summary table, failing at
+/// the end if any day did.
+///
+/// # Errors
+/// Returns `AocError::Config` if the settings don't load; a day's own
+/// errors are rows of the table.
+fn run_all(args: RunArgs, workspace: &Path) -> Result<ExitCode, AocError> {
+    let flags = Config {
+        threads: args.threads,
+        ..Config::default()
+    };
+    let config = Config::load(workspace, flags)?;
+    init_parallelism(config.threads.map(NonZeroUsize::get));
+
+    let rows = summary::run_days(registry::DAYS, &config, args.part, |day| {
+        let crate_dir = workspace.join(day.crate_path);
+        source::load(day.number, None, &crate_dir, &config).map(|(input, _)| input)
+    });
+    print!("{}", summary::table(&rows));
+
+    let failed: Vec<String> = rows
+        .iter()
+        .filter(|row| row.outcome.is_err())
+        .map(|row| row.day.to_string())
+        .collect();
+    if failed.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    eprintln!(
+        "Error: {} of {} days failed: {}",
+        failed.len(),
+        rows.len(),
+        failed.join(", ")
+    );
+    Ok(ExitCode::FAILURE)
+}
@@ -0,0 +1,160 @@
+//! The days compiled into this runner.
+//!
+//! Every day sits behind a cargo feature named after it, `day01`, `day02`,
+//! and so on, all enabled by default through `all-days`. `days!` lists each
+//! day once, and only the days whose feature is enabled are compiled into
+//! `DAYS`, so a runner built with `--no-default-features --features day02`
+//! links day 2 and nothing else.
+
+use aoc_common::{run_solver, AocContext, AocError, Solution, Solver};
+
+/// A day this runner can solve.
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    /// Day of the Advent calendar, 1-25
+    pub number: u8,
+    /// The day's crate, relative to the workspace root, where it may keep
+    /// its input
+    pub crate_path: &'static str,
+    /// Solves a whole input and reports the answers and timings
+    pub run: fn(&str, &mut AocContext<'_>) -> Result<Solution, AocError>,
+}
+
+/// A day the runner has a feature for, whether or not it was enabled.
+#[derive(Debug, Clone, Copy)]
+struct Feature {
+    day: u8,
+    name: &'static str,
+    enabled: bool,
+}
+
+/// Declares every day as `number => "feature", "crate/path", Solver;`.
+macro_rules! days {
+    ($($number:literal => $feature:literal, $path:literal, $solver:path;)*) => {
+        /// Every day compiled in, in calendar order
+        pub const DAYS: &[Day] = &[$(
+            #[cfg(feature = $feature)]
+            Day {
+                number: $number,
+                crate_path: $path,
+                run: run::<$solver>,
+            },
+        )*];
+
+        /// Every day with a feature, compiled in or not
+        const FEATURES: &[Feature] = &[$(
+            Feature {
+                day: $number,
+                name: $feature,
+                enabled: cfg!(feature = $feature),
+            },
+        )*];
+    };
+}
+
+days! {
+    1 => "day01", "day-01/rs", day_01::Day01;
+    2 => "day02", "day-02/rs", day_02::Day02;
+}
+
+/// Solves `input` with `S`, set up by the options in `ctx`.
+#[allow(dead_code)] // unused in a runner built without any day
+pub fn run<S>(input: &str, ctx: &mut AocContext<'_>) -> Result<Solution, AocError>
+where
+    S: Solver<Error = AocError> + Default,
+{
+    run_solver(&S::default(), input, ctx)
+}
+
+/// The compiled-in day numbered `number`.
+///
+/// # Errors
+/// Returns `AocError::BadArgument` naming the feature to enable if the day
+/// exists but wasn't compiled in, or listing the available days if it
+/// doesn't exist at all.
+pub fn find(number: u8) -> Result<&'static Day, AocError> {
+    match FEATURES.iter().find(|feature| feature.day == number) {
+        Some(feature) if feature.enabled => Ok(DAYS
+            .iter()
+            .find(|day| day.number == number)
+            .expect("every enabled day is compiled in")),
+        Some(feature) => Err(AocError::BadArgument(format!(
+            "day {} isn't compiled into this runner; rebuild it with `--features {}`",
+            number, feature.name
+        ))),
+        None => Err(AocError::BadArgument(format!(
+            "no solution for day {}; available days: {}",
+            number,
+            available()
+        ))),
+    }
+}
+
+/// The compiled-in day numbers, comma-separated, or `none`.
+fn available() -> String {
+    if DAYS.is_empty() {
+        return "none".to_string();
+    }
+    DAYS.iter()
+        .map(|day| day.number.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiles_in_exactly_the_enabled_days() {
+        let compiled: Vec<u8> = DAYS.iter().map(|day| day.number).collect();
+        let enabled: Vec<u8> = FEATURES
+            .iter()
+            .filter(|feature| feature.enabled)
+            .map(|feature| feature.day)
+            .collect();
+        assert_eq!(compiled, enabled);
+        for feature in FEATURES {
+            assert_eq!(find(feature.day).is_ok(), feature.enabled, "{:?}", feature);
+            assert_eq!(feature.name, format!("day{:02}", feature.day));
+        }
+    }
+
+    #[test]
+    fn names_the_feature_a_missing_day_needs() {
+        let error = find(25).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("no solution for day 25; available days: "),
+            "{}",
+            error
+        );
+        for feature in FEATURES.iter().filter(|feature| !feature.enabled) {
+            let message = find(feature.day).unwrap_err().to_string();
+            assert!(message.contains(&format!("`--features {}`", feature.name)));
+        }
+    }
+
+    #[cfg(feature = "day01")]
+    #[test]
+    fn runs_a_compiled_in_day() {
+        let mut out = aoc_common::RecordingReporter::new();
+        let mut ctx = AocContext::new(aoc_common::Config::default(), &mut out);
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        let solution = (find(1).unwrap().run)(input, &mut ctx).unwrap();
+        assert_eq!(solution.day, 1);
+        assert_eq!(out.answers, [(1, "11".to_string()), (2, "31".to_string())]);
+    }
+
+    #[cfg(feature = "day02")]
+    #[test]
+    fn passes_options_to_the_day() {
+        let mut out = aoc_common::RecordingReporter::new();
+        let mut ctx = AocContext::new(aoc_common::Config::default(), &mut out);
+        ctx.set_options(["max-step=5", "lenient"]).unwrap();
+        let solution = (find(2).unwrap().run)("1 5 10\n1 x\n", &mut ctx).unwrap();
+        assert_eq!(solution.part1, aoc_common::Answer::U64(1));
+        assert_eq!(out.notes, ["1 line skipped: 2"]);
+    }
+}
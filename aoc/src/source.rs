@@ -0,0 +1,135 @@
+//! Where the runner reads a day's input from.
+//!
+//! With the `embed-inputs` feature, `build.rs` bakes each compiled-in day's
+//! `puzzle_input.txt` into the binary, for profiling or for machines
+//! without the inputs checked out. Days whose file was missing at build
+//! time, and every day in the default build, are read from disk through
+//! `aoc_common::input::resolve` instead. An input named on the command line
+//! always wins over an embedded one.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use aoc_common::{AocError, Config};
+
+/// Inputs baked in at build time as `(day, path, text)`, empty unless the
+/// `embed-inputs` feature is on
+const EMBEDDED: &[(u8, &str, &str)] = include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
+
+/// Where an input was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Baked into the binary from this path at build time
+    Embedded(&'static str),
+    /// Read from this path at run time
+    Disk(PathBuf),
+    /// Read from standard input
+    Stdin,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Embedded(path) => write!(f, "embedded from {}", path),
+            Source::Disk(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+/// Reads the input for `day` and says where it came from.
+///
+/// # Arguments
+/// * `arg` - Input path from the command line, `-` for stdin, if any
+/// * `crate_dir` - The day's crate directory, for the disk lookup
+/// * `config` - The runner's settings, for `input_dir`
+///
+/// # Errors
+/// Returns `AocError::MissingInput` if nothing is embedded for the day and
+/// no input is found on disk, or `AocError::Io` if reading fails.
+pub fn load(
+    day: u8,
+    arg: Option<&str>,
+    crate_dir: &Path,
+    config: &Config,
+) -> Result<(String, Source), AocError> {
+    if let Some((path, text)) = embedded(EMBEDDED, day, arg) {
+        return Ok((text.to_string(), Source::Embedded(path)));
+    }
+    if arg == Some("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        return Ok((input, Source::Stdin));
+    }
+
+    let path = aoc_common::input::resolve(day, arg.map(Path::new), crate_dir, config)?;
+    let input = fs::read_to_string(&path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("failed to open {}: {}", path.display(), e),
+        )
+    })?;
+    Ok((input, Source::Disk(path)))
+}
+
+/// The path and text embedded for `day` in `table`, unless `arg` names
+/// another input.
+fn embedded(
+    table: &[(u8, &'static str, &'static str)],
+    day: u8,
+    arg: Option<&str>,
+) -> Option<(&'static str, &'static str)> {
+    if arg.is_some() {
+        return None;
+    }
+    table
+        .iter()
+        .find(|(number, _, _)| *number == day)
+        .map(|&(_, path, text)| (path, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &[(u8, &str, &str)] = &[(2, "day-02/rs/puzzle_input.txt", "7 6 4 2 1\n")];
+
+    #[test]
+    fn prefers_an_embedded_input_unless_one_is_named() {
+        assert_eq!(
+            embedded(TABLE, 2, None),
+            Some(("day-02/rs/puzzle_input.txt", "7 6 4 2 1\n"))
+        );
+        assert_eq!(embedded(TABLE, 2, Some("other.txt")), None);
+        assert_eq!(embedded(TABLE, 2, Some("-")), None);
+        assert_eq!(embedded(TABLE, 1, None), None);
+    }
+
+    #[test]
+    fn embeds_only_with_the_feature() {
+        if !cfg!(feature = "embed-inputs") {
+            assert!(EMBEDDED.is_empty());
+        }
+        for &(day, path, text) in EMBEDDED {
+            assert!(path.ends_with("puzzle_input.txt"), "day {}: {}", day, path);
+            assert_eq!(fs::read_to_string(path).unwrap(), text, "day {}", day);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_the_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input.txt");
+        fs::write(&path, "1 2\n").unwrap();
+        let arg = path.to_str();
+        let (input, source) = load(1, arg, dir.path(), &Config::default()).unwrap();
+        assert_eq!(input, "1 2\n");
+        assert_eq!(source, Source::Disk(path.clone()));
+        assert_eq!(source.to_string(), path.display().to_string());
+
+        let missing = load(25, None, dir.path(), &Config::default()).unwrap_err();
+        assert!(matches!(missing, AocError::MissingInput { day: 25, .. }));
+    }
+}
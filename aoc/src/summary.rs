@@ -0,0 +1,408 @@
+//! Every day at once, for `aoc run --all`.
+//!
+//! Each compiled-in day is loaded and solved in calendar order with its
+//! own `AocContext`, and what it reports is dropped: the run ends with one
+//! table of every day's answers and timings instead. A day that can't be
+//! loaded or solved becomes a row naming its error, and the days after it
+//! still run.
+//!
+//! The table's columns are as wide as their widest cell, so answers of any
+//! length line up. Answers are left-aligned and times right-aligned, and
+//! the last row totals the times of the days that were solved.
+
+use std::io;
+use std::time::Duration;
+
+use aoc_common::{
+    fmt_duration, Answer, AocContext, AocError, Config, Part, QuietReporter, Solution,
+};
+
+use crate::registry::Day;
+
+/// The table's column headings, in order
+const HEADINGS: [&str; COLUMNS] = [
+    "Day",
+    "Part 1",
+    "Part 2",
+    "Parse",
+    "Part 1 time",
+    "Part 2 time",
+    "Total",
+];
+
+/// Columns in the table
+const COLUMNS: usize = 7;
+
+/// Which columns are right-aligned: the day and the four times
+const RIGHT_ALIGNED: [bool; COLUMNS] = [true, false, false, true, true, true, true];
+
+/// Space between two columns
+const GAP: &str = "  ";
+
+/// How one day's run went.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// Day of the Advent calendar, 1-25
+    pub day: u8,
+    /// The day's answers and timings, or its error on one line
+    pub outcome: Result<Solution, String>,
+}
+
+/// Loads and solves each of `days` in turn, answering the parts `part`
+/// names.
+///
+/// # Arguments
+/// * `config` - The settings every day runs with
+/// * `load` - Reads a day's input
+///
+/// # Returns
+/// One row per day, in the order of `days`, whether it was solved or not.
+pub fn run_days(
+    days: &[Day],
+    config: &Config,
+    part: Part,
+    mut load: impl FnMut(&Day) -> Result<String, AocError>,
+) -> Vec<Row> {
+    days.iter()
+        .map(|day| {
+            let outcome = load(day).and_then(|input| {
+                let mut out = QuietReporter::new(io::sink());
+                let mut ctx = AocContext::new(config.clone(), &mut out).with_part(part);
+                (day.run)(&input, &mut ctx)
+            });
+            Row {
+                day: day.number,
+                outcome: outcome.map_err(|e| one_line(&e.to_string())),
+            }
+        })
+        .collect()
+}
+
+/// Lays `rows` out as a table under a heading row, ending with the total
+/// time of every solved day.
+///
+/// A failed day's row is its number and then its error, which runs past
+/// the columns rather than widening them. A part that wasn't asked for is
+/// `-` for both its answer and its time, and so is its total when no day
+/// ran it.
+pub fn table(rows: &[Row]) -> String {
+    let mut lines = vec![Line::Cells(HEADINGS.map(String::from))];
+    let mut totals = [Duration::ZERO; 4];
+    // Whether any solved day ran each part
+    let mut parts_ran = [false; 2];
+    for row in rows {
+        lines.push(match &row.outcome {
+            Ok(solution) => {
+                let times = [
+                    solution.parse_time,
+                    solution.part1_time,
+                    solution.part2_time,
+                    solution.total_time(),
+                ];
+                for (total, time) in totals.iter_mut().zip(times) {
+                    *total += time;
+                }
+                parts_ran[0] |= ran(&solution.part1, solution.part1_time);
+                parts_ran[1] |= ran(&solution.part2, solution.part2_time);
+                Line::Cells([
+                    row.day.to_string(),
+                    answer(&solution.part1),
+                    answer(&solution.part2),
+                    fmt_duration(solution.parse_time),
+                    time(&solution.part1, solution.part1_time),
+                    time(&solution.part2, solution.part2_time),
+                    fmt_duration(solution.total_time()),
+                ])
+            }
+            Err(message) => Line::Failure(row.day, message.clone()),
+        });
+    }
+    let [parse, mut part1, mut part2, total] = totals.map(fmt_duration);
+    for (cell, ran) in [&mut part1, &mut part2].into_iter().zip(parts_ran) {
+        if !ran {
+            *cell = "-".to_string();
+        }
+    }
+    lines.push(Line::Cells([
+        "Total".to_string(),
+        String::new(),
+        String::new(),
+        parse,
+        part1,
+        part2,
+        total,
+    ]));
+
+    let mut widths = [0; COLUMNS];
+    for line in &lines {
+        if let Line::Cells(cells) = line {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+    }
+
+    let mut table = String::new();
+    for line in &lines {
+        let text = match line {
+            Line::Cells(cells) => cells
+                .iter()
+                .enumerate()
+                .map(|(column, cell)| match RIGHT_ALIGNED[column] {
+                    true => format!("{:>1$}", cell, widths[column]),
+                    false => format!("{:<1$}", cell, widths[column]),
+                })
+                .collect::<Vec<_>>()
+                .join(GAP),
+            Line::Failure(day, message) => {
+                format!("{:>3$}{}error: {}", day, GAP, message, widths[0])
+            }
+        };
+        table.push_str(text.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// One row of the table before it's padded.
+enum Line {
+    /// A cell per column
+    Cells([String; COLUMNS]),
+    /// A day and the error it failed with
+    Failure(u8, String),
+}
+
+/// An answer as its cell, `-` if there is none.
+fn answer(value: &Answer) -> String {
+    match value {
+        Answer::None => "-".to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Whether a part ran, judged by its answer and its time: a part that
+/// wasn't asked for has neither.
+fn ran(value: &Answer, duration: Duration) -> bool {
+    !matches!(value, Answer::None) || !duration.is_zero()
+}
+
+/// A part's time as its cell, `-` if the part didn't run.
+fn time(value: &Answer, duration: Duration) -> String {
+    if ran(value, duration) {
+        fmt_duration(duration)
+    } else {
+        "-".to_string()
+    }
+}
+
+/// `message` with its lines joined by spaces, such as the paths a
+/// missing input lists one per line.
+fn one_line(message: &str) -> String {
+    message
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use aoc_common::Solver;
+    use std::path::PathBuf;
+
+    /// Sums a list of numbers, and finds the largest.
+    #[derive(Default)]
+    struct Sums;
+
+    impl Solver for Sums {
+        const DAY: u8 = 3;
+        type Parsed<'a> = Vec<u64>;
+        type Error = AocError;
+
+        fn parse<'a>(
+            &self,
+            input: &'a str,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Self::Parsed<'a>, Self::Error> {
+            input
+                .lines()
+                .enumerate()
+                .map(|(i, line)| {
+                    line.parse()
+                        .map_err(|e| AocError::parse(i + 1, 1..line.len() + 1, e))
+                })
+                .collect()
+        }
+
+        fn part1(
+            &self,
+            numbers: &Self::Parsed<'_>,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            Ok(numbers.iter().sum::<u64>().into())
+        }
+
+        fn part2(
+            &self,
+            numbers: &Self::Parsed<'_>,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            Ok(numbers.iter().max().copied().unwrap_or(0).into())
+        }
+    }
+
+    /// Finds the longest word, and counts them.
+    #[derive(Default)]
+    struct Words;
+
+    impl Solver for Words {
+        const DAY: u8 = 11;
+        type Parsed<'a> = Vec<&'a str>;
+        type Error = AocError;
+
+        fn parse<'a>(
+            &self,
+            input: &'a str,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Self::Parsed<'a>, Self::Error> {
+            Ok(input.split_whitespace().collect())
+        }
+
+        fn part1(
+            &self,
+            words: &Self::Parsed<'_>,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            let longest = words.iter().max_by_key(|word| word.len());
+            Ok(longest.copied().unwrap_or_default().into())
+        }
+
+        fn part2(
+            &self,
+            words: &Self::Parsed<'_>,
+            _ctx: &mut AocContext<'_>,
+        ) -> Result<Answer, Self::Error> {
+            Ok(words.len().into())
+        }
+    }
+
+    /// The two fakes as registered days, each once solved and once failed
+    const DAYS: &[Day] = &[
+        Day {
+            number: 3,
+            crate_path: "day-03/rs",
+            run: registry::run::<Sums>,
+        },
+        Day {
+            number: 11,
+            crate_path: "day-11/rs",
+            run: registry::run::<Words>,
+        },
+        Day {
+            number: 12,
+            crate_path: "day-12/rs",
+            run: registry::run::<Sums>,
+        },
+        Day {
+            number: 25,
+            crate_path: "day-25/rs",
+            run: registry::run::<Words>,
+        },
+    ];
+
+    /// Each fake day's input, and none for day 25.
+    fn load(day: &Day) -> Result<String, AocError> {
+        match day.number {
+            3 => Ok("40\n2\n".to_string()),
+            11 => Ok("ice snowflake sleigh\n".to_string()),
+            12 => Ok("7\nseven\n".to_string()),
+            day => Err(AocError::MissingInput {
+                day,
+                tried: vec![PathBuf::from("day-25/rs/puzzle_input.txt")],
+            }),
+        }
+    }
+
+    /// `rows` with each solved day's steps taking `times`, in order, so
+    /// the table doesn't depend on how fast the test runs; a part that
+    /// didn't run keeps taking no time.
+    fn with_times(mut rows: Vec<Row>, times: &[[Duration; 3]]) -> Vec<Row> {
+        let solved = rows.iter_mut().filter_map(|row| row.outcome.as_mut().ok());
+        for (solution, &[parse, part1, part2]) in solved.zip(times) {
+            solution.parse_time = parse;
+            if solution.part1 != Answer::None {
+                solution.part1_time = part1;
+            }
+            if solution.part2 != Answer::None {
+                solution.part2_time = part2;
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn runs_every_day_past_the_failures() {
+        let rows = run_days(DAYS, &Config::default(), Part::Both, load);
+        let days: Vec<u8> = rows.iter().map(|row| row.day).collect();
+        assert_eq!(days, [3, 11, 12, 25]);
+        let solved = rows[1].outcome.as_ref().unwrap();
+        assert_eq!(solved.part1, Answer::from("snowflake"));
+        assert_eq!(solved.part2, Answer::from(3u64));
+        assert_eq!(
+            rows[2].outcome,
+            Err("line 2, columns 1-5: invalid digit found in string".to_string())
+        );
+        assert_eq!(
+            rows[3].outcome,
+            Err("no puzzle input found for day 25; tried: day-25/rs/puzzle_input.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn lines_up_the_table() {
+        let rows = run_days(DAYS, &Config::default(), Part::Both, load);
+        let rows = with_times(
+            rows,
+            &[
+                [
+                    Duration::from_nanos(812),
+                    Duration::from_micros(1500),
+                    Duration::from_micros(40),
+                ],
+                [
+                    Duration::from_micros(2250),
+                    Duration::from_micros(3),
+                    Duration::from_millis(1200),
+                ],
+            ],
+        );
+        assert_eq!(
+            table(&rows).lines().collect::<Vec<_>>(),
+            [
+                "  Day  Part 1     Part 2   Parse  Part 1 time  Part 2 time   Total",
+                "    3  42         40       812ns       1.50ms       40.0µs  1.54ms",
+                "   11  snowflake  3       2.25ms       3.00µs        1.20s   1.20s",
+                "   12  error: line 2, columns 1-5: invalid digit found in string",
+                "   25  error: no puzzle input found for day 25; tried: day-25/rs/puzzle_input.txt",
+                "Total                     2.25ms       1.50ms        1.20s   1.20s",
+            ]
+        );
+    }
+
+    #[test]
+    fn dashes_the_part_that_was_not_asked_for() {
+        let rows = run_days(&DAYS[..1], &Config::default(), Part::One, load);
+        let rows = with_times(rows, &[[Duration::from_micros(5); 3]]);
+        assert_eq!(
+            table(&rows).lines().collect::<Vec<_>>(),
+            [
+                "  Day  Part 1  Part 2   Parse  Part 1 time  Part 2 time   Total",
+                "    3  42      -       5.00µs       5.00µs            -  10.0µs",
+                "Total                  5.00µs       5.00µs            -  10.0µs",
+            ]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! The runner's command line, run as a user would run it.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// The puzzle's day 1 example
+const DAY_01_EXAMPLE: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+/// The runner, set up to run from the workspace root with the default
+/// output style.
+fn aoc(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_aoc"));
+    command
+        .args(args)
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+        .env_remove("AOC_OUTPUT")
+        .stdin(Stdio::null());
+    command
+}
+
+/// Runs the runner with `args` and nothing on stdin.
+fn run(args: &[&str]) -> Output {
+    aoc(args).output().expect("aoc runs")
+}
+
+/// Both output streams as text.
+fn text(output: &Output) -> (String, String) {
+    (
+        String::from_utf8(output.stdout.clone()).unwrap(),
+        String::from_utf8(output.stderr.clone()).unwrap(),
+    )
+}
+
+#[test]
+fn runs_one_part_of_the_example() {
+    let output = run(&[
+        "run",
+        "--day",
+        "2",
+        "--part",
+        "1",
+        "day-02/example_input.txt",
+    ]);
+    assert!(output.status.success());
+    let (stdout, stderr) = text(&output);
+    assert!(stderr.is_empty(), "{}", stderr);
+    assert!(
+        stdout.starts_with("Input: day-02/example_input.txt\n\nResults:\nDay 2 Part 1: 2\n\n"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Part 2"), "{}", stdout);
+    let phases: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| *line != "Performance Breakdown:")
+        .skip(1)
+        .map(|line| line.split(':').next().unwrap())
+        .collect();
+    assert_eq!(phases, ["Parsing time", "Part 1 time", "Total time"]);
+
+    let output = run(&["run", "--day", "2", "--quiet", "day-02/example_input.txt"]);
+    assert_eq!(text(&output).0, "2\n4\n");
+}
+
+#[test]
+fn reads_the_input_from_stdin() {
+    let mut child = aoc(&["run", "--day", "1", "--part", "2", "--quiet", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("aoc runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(DAY_01_EXAMPLE.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(text(&output).0, "31\n");
+}
+
+#[test]
+fn passes_day_options_through() {
+    let output = run(&[
+        "run",
+        "--day",
+        "2",
+        "--quiet",
+        "--set",
+        "max-step=4",
+        "--set=allow-plateaus",
+        "day-02/example_input.txt",
+    ]);
+    assert!(output.status.success(), "{:?}", text(&output));
+    // The same answers as `day-02 --max-step 4 --allow-plateaus`
+    assert_eq!(text(&output).0, "4\n5\n");
+
+    let output = run(&[
+        "run",
+        "--day",
+        "1",
+        "--quiet",
+        "--set",
+        "cols=0,2",
+        "day-01/three_columns_input.txt",
+    ]);
+    assert!(output.status.success(), "{:?}", text(&output));
+    // The same answers as `day-01 --cols 0,2`
+    assert_eq!(text(&output).0, "11\n31\n");
+
+    let output = run(&["run", "--day", "1", "--set", "cols=3", "-"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        text(&output)
+            .1
+            .ends_with("Error: option `cols`: `3`: expected two column indices like 0,2\n"),
+        "{:?}",
+        text(&output)
+    );
+}
+
+#[test]
+fn lists_the_available_days_for_an_unknown_one() {
+    let output = run(&["run", "--day", "7"]);
+    assert_eq!(output.status.code(), Some(2));
+    let (stdout, stderr) = text(&output);
+    assert!(stdout.is_empty());
+    assert_eq!(
+        stderr,
+        "Error: no solution for day 7; available days: 1, 2\n"
+    );
+}
+
+#[test]
+fn rejects_a_run_without_a_day() {
+    let output = run(&["run"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        text(&output).1,
+        "Error: the following required arguments were not provided:\n  \
+         <--day <N>|--all>\n\n\
+         Usage: aoc run <--day <N>|--all> [INPUT]\n\n\
+         For more information, try '--help'.\n"
+    );
+
+    let output = run(&["run", "--all", "--day", "1"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        text(&output)
+            .1
+            .starts_with("Error: the argument '--all' cannot be used with '--day <N>'"),
+        "{:?}",
+        text(&output)
+    );
+
+    let output = run(&["2"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        text(&output)
+            .1
+            .starts_with("Error: unrecognized subcommand '2'"),
+        "{:?}",
+        text(&output)
+    );
+}
+
+#[test]
+fn summarizes_every_day() {
+    let output = run(&["run", "--all", "--part", "1"]);
+    assert!(output.status.success(), "{:?}", text(&output));
+    let (stdout, stderr) = text(&output);
+    assert!(stderr.is_empty(), "{}", stderr);
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(
+        rows[0],
+        [
+            "Day", "Part", "1", "Part", "2", "Parse", "Part", "1", "time", "Part", "2", "time",
+            "Total"
+        ]
+    );
+    let days: Vec<&str> = rows[1..].iter().map(|row| row[0]).collect();
+    assert_eq!(days, ["1", "2", "Total"]);
+    for row in &rows[1..3] {
+        assert_eq!(row.len(), 7, "{:?}", row);
+        assert_eq!([row[2], row[5]], ["-", "-"], "{:?}", row);
+    }
+
+    // Every row is as wide as the heading's columns
+    let header = stdout.lines().next().unwrap();
+    let total_at = header.rfind("Total").unwrap() + "Total".len();
+    for line in stdout.lines() {
+        assert_eq!(line.chars().count(), total_at, "{:?}", line);
+    }
+}
@@ -0,0 +1,41 @@
+//! Times day 1's distance sum over sorted lists at both location widths.
+//!
+//! Run with `cargo bench -p day-01 --bench distance`. The lists hold
+//! five-digit IDs, like the puzzle's, sorted up front so only
+//! `sorted_total_distance` is measured; `--wide` swaps `i64` for `i128`.
+
+use aoc_common::values::Values;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use day_01::sorted_total_distance;
+use std::hint::black_box;
+
+/// Pairs per list
+const PAIRS: usize = 1_000_000;
+
+/// `PAIRS` sorted IDs below 100000.
+fn sorted_ids(seed: u64) -> Vec<i64> {
+    let mut values = Values(seed);
+    let mut ids: Vec<i64> = (0..PAIRS).map(|_| values.below(100_000) as i64).collect();
+    ids.sort_unstable();
+    ids
+}
+
+fn distance(c: &mut Criterion) {
+    let left = sorted_ids(0x2024_1201);
+    let right = sorted_ids(0x1201_2024);
+    let wide = |ids: &[i64]| ids.iter().map(|&id| i128::from(id)).collect::<Vec<_>>();
+    let (wide_left, wide_right) = (wide(&left), wide(&right));
+
+    let mut group = c.benchmark_group("sorted_total_distance");
+    group.throughput(Throughput::Elements(PAIRS as u64));
+    group.bench_function("i64", |b| {
+        b.iter(|| sorted_total_distance(black_box(&left), black_box(&right)).unwrap())
+    });
+    group.bench_function("i128", |b| {
+        b.iter(|| sorted_total_distance(black_box(&wide_left), black_box(&wide_right)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, distance);
+criterion_main!(benches);
@@ -0,0 +1,174 @@
+//! Heap-based streaming solver.
+//!
+//! Instead of collecting both lists and sorting them, every parsed value is
+//! pushed straight into a min-heap. Draining the two heaps in lockstep yields
+//! the sorted pairs for Part 1, and their value runs feed a streaming merge
+//! for Part 2, so the unsorted vectors never exist.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+
+use super::{check_list_lengths, parse_line, score_runs, Columns, Layout};
+use super::{Distance, DistanceOverflow, Location, ParseError, SimilarityOverflow};
+use aoc_common::AocError;
+
+/// How the two lists are put in order before pairing.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SolveStrategy {
+    /// Collect both lists into vectors and sort them
+    #[default]
+    Sort,
+    /// Push values into min-heaps while parsing and drain them pairwise
+    Heap,
+}
+
+impl fmt::Display for SolveStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SolveStrategy::Sort => "sort",
+            SolveStrategy::Heap => "heap",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Min-heap of location IDs
+pub type MinHeap<T> = BinaryHeap<Reverse<T>>;
+
+/// Parses two columns of location IDs straight into a pair of min-heaps.
+///
+/// Accepts the same input as `parse_bytes`, but parses on the calling thread
+/// since the heaps can't be built in independent chunks and then joined
+/// without a second pass.
+///
+/// # Errors
+/// Returns the first `ParseError` in input order.
+pub fn parse_into_heaps<T: Location>(
+    bytes: &[u8],
+    columns: Columns,
+) -> Result<(MinHeap<T>, MinHeap<T>), ParseError> {
+    let layout = Layout::detect(bytes, columns);
+    let mut left_heap = BinaryHeap::new();
+    let mut right_heap = BinaryHeap::new();
+
+    for (i, line) in bytes.split(|&b| b == b'\n').enumerate() {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if let Some((left, right)) = parse_line(line, i + 1, layout)? {
+            left_heap.push(Reverse(left));
+            right_heap.push(Reverse(right));
+        }
+    }
+
+    Ok((left_heap, right_heap))
+}
+
+/// Completed value runs of the two drained lists awaiting a match.
+///
+/// Runs arrive in ascending order on each side. A run is discarded once the
+/// other side has moved past its value, and scored when both sides have a
+/// complete run of the same value.
+struct RunMerger<T> {
+    left: VecDeque<(T, usize)>,
+    right: VecDeque<(T, usize)>,
+    score: T,
+}
+
+impl<T: Location> RunMerger<T> {
+    /// Scores and discards runs until one side has none left.
+    ///
+    /// # Errors
+    /// Returns `SimilarityOverflow` if the score doesn't fit in `T`.
+    fn merge(&mut self) -> Result<(), SimilarityOverflow> {
+        while let (Some(&(left, left_count)), Some(&(right, right_count))) =
+            (self.left.front(), self.right.front())
+        {
+            if left < right {
+                self.left.pop_front();
+            } else if right < left {
+                self.right.pop_front();
+            } else {
+                self.score = score_runs(self.score, left, left_count, right_count)?;
+                self.left.pop_front();
+                self.right.pop_front();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Extends the current run with `value`, or queues the finished run and
+/// starts a new one.
+#[inline]
+fn track_run<T: Location>(
+    run: &mut Option<(T, usize)>,
+    queue: &mut VecDeque<(T, usize)>,
+    value: T,
+) {
+    match run {
+        Some((current, count)) if *current == value => *count += 1,
+        _ => {
+            if let Some(done) = run.replace((value, 1)) {
+                queue.push_back(done);
+            }
+        }
+    }
+}
+
+/// Drains both heaps pairwise, computing both answers in one pass.
+///
+/// # Implementation Details
+/// - Each step pops the smallest remaining value from each heap, which is
+///   exactly the next sorted pair for Part 1
+/// - The popped values are grouped into runs of equal values, and completed
+///   runs are merged across the two sides as they arrive for Part 2; only
+///   runs whose match hasn't been drained yet are buffered
+/// - The distance sum is overflow-checked like `total_distance`, and the
+///   score like `similarity_score`
+///
+/// # Returns
+/// The total distance and the similarity score.
+///
+/// # Errors
+/// Returns `LengthMismatch` if the heaps differ in size,
+/// `DistanceOverflow` naming the pair at which the sum overflowed, or
+/// `SimilarityOverflow` if the score doesn't fit in `T`.
+///
+/// # Time Complexity
+/// O(n log n) for the pops
+pub fn drain_heaps<T: Location>(
+    mut left_heap: MinHeap<T>,
+    mut right_heap: MinHeap<T>,
+) -> Result<(T::Distance, T), AocError> {
+    check_list_lengths(left_heap.as_slice(), right_heap.as_slice())?;
+
+    let mut total = T::Distance::default();
+    let mut merger = RunMerger {
+        left: VecDeque::new(),
+        right: VecDeque::new(),
+        score: T::default(),
+    };
+    let mut left_run = None;
+    let mut right_run = None;
+
+    let mut pair = 0;
+    while let (Some(Reverse(left)), Some(Reverse(right))) = (left_heap.pop(), right_heap.pop()) {
+        total = total
+            .checked_sum(left.distance(right))
+            .ok_or(DistanceOverflow {
+                pair,
+                width: T::Distance::NAME,
+            })?;
+        pair += 1;
+
+        track_run(&mut left_run, &mut merger.left, left);
+        track_run(&mut right_run, &mut merger.right, right);
+        merger.merge()?;
+    }
+
+    merger.left.extend(left_run);
+    merger.right.extend(right_run);
+    merger.merge()?;
+
+    Ok((total, merger.score))
+}
@@ -0,0 +1,1830 @@
+//! Day 1: Historian Hysteria - Parts 1 & 2
+//!
+//! Pairs up two lists of location IDs read side by side from the puzzle input:
+//! Part 1: Sum of distances between the lists sorted and paired smallest-first
+//! Part 2: Similarity score weighting each left ID by its count in the right list
+//!
+//! `Day01` implements `aoc_common::Solver`, parsing and sorting the lists
+//! once so both parts read the same sorted lists.
+//!
+//! IDs may be negative throughout: distances are exact absolute differences
+//! and negative left IDs contribute negative similarity. The mixed-sign
+//! fixture `day-01/mixed_signs_input.txt` gives a total distance of 10 and a
+//! similarity score of -11.
+//!
+//! Inputs may carry more than two columns; any two can be paired up. The
+//! fixture `day-01/three_columns_input.txt` holds the puzzle example in its
+//! first and last columns, giving 11 and 31 with columns 0 and 2.
+//!
+//! Performance optimizations:
+//! - Byte-level parsing with the shared `aoc_common::parse_int`
+//! - Parallel parsing of newline-aligned chunks for large inputs
+//! - Optional radix/counting sort for values with a bounded range
+//! - Part 2 reuses the sorted lists with a two-pointer merge instead of a hash map
+//!
+//! Time Complexity:
+//! - O(n log n) dominated by sorting, O(n) with the radix path
+//!
+//! Space Complexity:
+//! - O(n) for the two lists
+
+mod heap;
+mod location;
+mod solver;
+
+pub use heap::{drain_heaps, parse_into_heaps, MinHeap, SolveStrategy};
+pub use location::{Distance, Location};
+pub use solver::{Day01, SortedLists};
+
+use aoc_common::{parse_int, AocError, Counter, IntErrorKind};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::hash::Hash;
+use std::io::Read;
+use std::ops::Range;
+use std::str::FromStr;
+use std::thread;
+
+/// Describes what was wrong with a line of puzzle input.
+#[derive(Debug, PartialEq)]
+pub enum ParseErrorKind {
+    /// A token that isn't a valid integer
+    InvalidNumber(String),
+    /// An integer too large for the active width, e.g. `i64`
+    OutOfRange { token: String, width: &'static str },
+    /// A line holding fewer numbers than the input's column count
+    MissingNumber { expected: usize, found: usize },
+    /// A token following the last column of a line
+    UnexpectedToken { token: String, expected: usize },
+}
+
+/// A parse failure pinned to its position in the input.
+///
+/// Lines and columns are 1-based; `columns` is an inclusive-exclusive byte
+/// range into the line, rendered as an inclusive range for humans.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub columns: Range<usize>,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::InvalidNumber(token) => {
+                write!(f, "could not parse `{}` as an integer", token)
+            }
+            ParseErrorKind::OutOfRange { token, width } => {
+                write!(f, "`{}` is out of range for {}", token, width)
+            }
+            ParseErrorKind::MissingNumber { expected, found } => {
+                write!(f, "expected {} numbers, found {}", expected, found)
+            }
+            ParseErrorKind::UnexpectedToken { token, expected } => {
+                write!(f, "unexpected `{}` after {} numbers", token, expected)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, columns {}-{}: {}",
+            self.line,
+            self.columns.start,
+            self.columns.end - 1,
+            self.kind
+        )
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(e: ParseError) -> Self {
+        AocError::parse(e.line, e.columns, e.kind)
+    }
+}
+
+/// The left and right lists ended up with different numbers of entries,
+/// so they can't be paired up.
+#[derive(Debug, PartialEq)]
+pub struct LengthMismatch {
+    pub left_len: usize,
+    pub right_len: usize,
+    /// 1-based position in the longer list of its first entry left without
+    /// a partner
+    pub entry: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "left list has {} entries but right list has {}; entry {} has no pair",
+            self.left_len, self.right_len, self.entry
+        )
+    }
+}
+
+/// The running distance sum no longer fits in the active distance type.
+#[derive(Debug, PartialEq)]
+pub struct DistanceOverflow {
+    /// Index of the sorted pair whose distance overflowed the sum
+    pub pair: usize,
+    /// Name of the distance type, e.g. `u64`
+    pub width: &'static str,
+}
+
+impl fmt::Display for DistanceOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "total distance overflows {} at sorted pair {}",
+            self.width,
+            self.pair + 1
+        )
+    }
+}
+
+/// The similarity score no longer fits in the location type.
+#[derive(Debug, PartialEq)]
+pub struct SimilarityOverflow {
+    /// Name of the location type, e.g. `i64`
+    pub width: &'static str,
+}
+
+impl fmt::Display for SimilarityOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "similarity score overflows {}", self.width)
+    }
+}
+
+impl From<LengthMismatch> for AocError {
+    fn from(e: LengthMismatch) -> Self {
+        AocError::Unsolvable(e.to_string())
+    }
+}
+
+impl From<DistanceOverflow> for AocError {
+    fn from(e: DistanceOverflow) -> Self {
+        AocError::Unsolvable(e.to_string())
+    }
+}
+
+impl From<SimilarityOverflow> for AocError {
+    fn from(e: SimilarityOverflow) -> Self {
+        AocError::Unsolvable(e.to_string())
+    }
+}
+
+/// Whether `b` separates fields on a line: any run of spaces and tabs.
+#[inline]
+fn is_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+/// Whether `b` terminates a number token.
+#[inline]
+fn is_token_end(b: u8) -> bool {
+    is_blank(b) || b == b','
+}
+
+/// Returns the index of the first byte at or after `i` that isn't a space or tab.
+#[inline]
+fn skip_blanks(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && is_blank(bytes[i]) {
+        i += 1;
+    }
+    i
+}
+
+/// Whether `i` is past the last byte of a line whose terminator was stripped.
+#[inline]
+fn at_line_end(bytes: &[u8], i: usize) -> bool {
+    i >= bytes.len()
+}
+
+/// Returns the byte range of the next token at or after `i`, or an empty
+/// range at the end of the line. A stray delimiter such as a second comma
+/// is kept as the first byte of the token so it gets reported.
+#[inline]
+fn next_token(bytes: &[u8], i: usize) -> Range<usize> {
+    let token_start = skip_blanks(bytes, i);
+    if at_line_end(bytes, token_start) {
+        return token_start..token_start;
+    }
+    let mut token_end = token_start + 1;
+    while token_end < bytes.len() && !is_token_end(bytes[token_end]) {
+        token_end += 1;
+    }
+    token_start..token_end
+}
+
+/// Parses the integer token at the start of `bytes`.
+///
+/// The token ends at the first space, tab, comma, or the end of the line,
+/// and is parsed by `aoc_common::parse_int`, with an optional leading `-`.
+///
+/// # Returns
+/// The parsed value and the number of bytes consumed.
+///
+/// # Errors
+/// Returns a `ParseError` covering the whole token if it is empty, contains
+/// a non-digit byte, or doesn't fit in `T`. The error's line is 0 and its
+/// columns are relative to `bytes`; callers relocate it with `at`.
+#[inline]
+pub fn parse_number<T: Location>(bytes: &[u8]) -> Result<(T, usize), ParseError> {
+    let token_len = next_token(bytes, 0).len();
+    let token = &bytes[..token_len];
+    let value = parse_int(token).map_err(|e| {
+        let token = String::from_utf8_lossy(token).into_owned();
+        ParseError {
+            line: 0,
+            columns: 1..token_len + 1,
+            kind: match e.kind {
+                IntErrorKind::OutOfRange { width } => ParseErrorKind::OutOfRange { token, width },
+                IntErrorKind::Empty | IntErrorKind::InvalidDigit => {
+                    ParseErrorKind::InvalidNumber(token)
+                }
+            },
+        }
+    })?;
+    Ok((value, token_len))
+}
+
+impl ParseError {
+    /// Moves an error reported relative to a token onto its real line and
+    /// byte offset within that line.
+    fn at(mut self, line: usize, offset: usize) -> Self {
+        self.line = line;
+        self.columns = self.columns.start + offset..self.columns.end + offset;
+        self
+    }
+}
+
+/// The two columns of the input that feed the left and right lists.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Columns {
+    /// 0-based index of the left list's column
+    pub left: usize,
+    /// 0-based index of the right list's column
+    pub right: usize,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Columns { left: 0, right: 1 }
+    }
+}
+
+impl FromStr for Columns {
+    type Err = String;
+
+    /// Parses two 0-based indices separated by a comma, as in `0,2`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(',').map(|(a, b)| (a.parse(), b.parse())) {
+            Some((Ok(left), Ok(right))) => Ok(Columns { left, right }),
+            _ => Err("expected two column indices like 0,2".to_string()),
+        }
+    }
+}
+
+/// Column count every line must have, plus the two columns to keep.
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    count: usize,
+    columns: Columns,
+}
+
+impl Layout {
+    /// Takes the column count from the first non-blank line, raised to cover
+    /// the selected columns so a short first line is reported by `parse_line`.
+    fn detect(bytes: &[u8], columns: Columns) -> Self {
+        let detected = bytes
+            .split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .find(|line| !at_line_end(line, skip_blanks(line, 0)))
+            .map_or(0, count_columns);
+        Layout {
+            count: detected.max(columns.left.max(columns.right) + 1),
+            columns,
+        }
+    }
+}
+
+/// Returns the index just past the separator following a field that ended
+/// at `i`: spaces and tabs, optionally around a single comma.
+#[inline]
+fn skip_separator(bytes: &[u8], i: usize) -> usize {
+    let i = skip_blanks(bytes, i);
+    if bytes.get(i) == Some(&b',') {
+        skip_blanks(bytes, i + 1)
+    } else {
+        i
+    }
+}
+
+/// Counts the fields of a line without parsing them.
+fn count_columns(bytes: &[u8]) -> usize {
+    let mut count = 0;
+    let mut i = skip_blanks(bytes, 0);
+    while !at_line_end(bytes, i) {
+        count += 1;
+        i = skip_separator(bytes, next_token(bytes, i).end);
+    }
+    count
+}
+
+/// Parses one line, without its terminator, holding a row of location IDs.
+///
+/// Fields may be separated by any mix of spaces and tabs, or by a single
+/// comma with optional blanks around it, e.g. `3   4`, `3\t4`, `3, 4`.
+/// Every field is parsed, but only the two selected columns are kept.
+///
+/// # Returns
+/// `None` for a blank line, otherwise the left and right numbers.
+///
+/// # Errors
+/// Returns a `ParseError` naming `line_number` and the columns of the first
+/// malformed token, the last number of a short line, or the first token
+/// past the expected column count.
+#[inline]
+fn parse_line<T: Location>(
+    bytes: &[u8],
+    line_number: usize,
+    layout: Layout,
+) -> Result<Option<(T, T)>, ParseError> {
+    // Leading indentation is skipped
+    let mut start = skip_blanks(bytes, 0);
+    if at_line_end(bytes, start) {
+        return Ok(None);
+    }
+
+    let mut pair = (T::default(), T::default());
+    let mut column = 0;
+    loop {
+        let (value, len) = parse_number(&bytes[start..]).map_err(|e| e.at(line_number, start))?;
+        if column == layout.columns.left {
+            pair.0 = value;
+        }
+        if column == layout.columns.right {
+            pair.1 = value;
+        }
+        column += 1;
+
+        let end = start + len;
+        if column == layout.count {
+            let extra_range = next_token(bytes, end);
+            if !extra_range.is_empty() {
+                return Err(ParseError {
+                    line: line_number,
+                    columns: extra_range.start + 1..extra_range.end + 1,
+                    kind: ParseErrorKind::UnexpectedToken {
+                        token: String::from_utf8_lossy(&bytes[extra_range]).into_owned(),
+                        expected: layout.count,
+                    },
+                });
+            }
+            return Ok(Some(pair));
+        }
+
+        let next = skip_separator(bytes, end);
+        if at_line_end(bytes, next) {
+            return Err(ParseError {
+                line: line_number,
+                columns: start + 1..end + 1,
+                kind: ParseErrorKind::MissingNumber {
+                    expected: layout.count,
+                    found: column,
+                },
+            });
+        }
+        start = next;
+    }
+}
+
+/// Parses every line of a newline-aligned chunk of the input.
+///
+/// Lines are split on `\n` with a trailing `\r` stripped, so Windows line
+/// endings parse the same as Unix ones. Blank lines, including a trailing one,
+/// are skipped, and the last line doesn't need a terminator.
+///
+/// Line numbers in errors are relative to the start of the chunk.
+fn parse_chunk<T: Location>(chunk: &[u8], layout: Layout) -> Result<(Vec<T>, Vec<T>), ParseError> {
+    // Pre-allocate vectors to avoid resizing; puzzle lines are about 14 bytes
+    let mut left_numbers = Vec::with_capacity(chunk.len() / 14 + 1);
+    let mut right_numbers = Vec::with_capacity(chunk.len() / 14 + 1);
+
+    for (i, line) in chunk.split(|&b| b == b'\n').enumerate() {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if let Some((left, right)) = parse_line(line, i + 1, layout)? {
+            left_numbers.push(left);
+            right_numbers.push(right);
+        }
+    }
+
+    Ok((left_numbers, right_numbers))
+}
+
+/// Smallest chunk worth handing to its own thread
+const MIN_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Splits `bytes` into at most `chunks` contiguous ranges that each end just
+/// after a newline (or at the end of the input), so no line is ever split.
+///
+/// Ranges are non-empty and together cover the whole input in order.
+fn chunk_ranges(bytes: &[u8], chunks: usize) -> Vec<Range<usize>> {
+    let target = bytes.len().div_ceil(chunks.max(1)).max(1);
+    let mut ranges = Vec::with_capacity(chunks);
+    let mut start = 0;
+    while start < bytes.len() {
+        let probe = (start + target).min(bytes.len());
+        let end = match bytes[probe - 1..].iter().position(|&b| b == b'\n') {
+            Some(offset) => probe + offset,
+            None => bytes.len(),
+        };
+        ranges.push(start..end);
+        start = end;
+    }
+    ranges
+}
+
+/// Reads the whole input and parses it with one thread per available core.
+///
+/// # Errors
+/// Returns an IO error if reading fails, or the first `ParseError` in input order.
+pub fn parse_input<T: Location>(mut reader: impl Read) -> Result<(Vec<T>, Vec<T>), AocError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    Ok(parse_bytes(&bytes, threads, Columns::default())?)
+}
+
+/// Parses two columns of location IDs from an in-memory input.
+///
+/// The input may hold any number of columns, taken from the first non-blank
+/// line; `columns` picks the two that become the left and right lists.
+///
+/// # Implementation Details
+/// - Works on the raw bytes, skipping UTF-8 validation
+/// - Splits large inputs into newline-aligned chunks parsed on up to
+///   `threads` worker threads, then concatenates the per-chunk vectors in order
+/// - Inputs too small to benefit are parsed on the calling thread
+/// - Skips blank lines; every other line must hold the same number of
+///   integers as the first
+///
+/// # Errors
+/// Returns a `ParseError` naming the line and columns of the first malformed
+/// token or ragged line in input order, including a first line too short
+/// for the selected columns.
+pub fn parse_bytes<T: Location>(
+    bytes: &[u8],
+    threads: usize,
+    columns: Columns,
+) -> Result<(Vec<T>, Vec<T>), ParseError> {
+    let layout = Layout::detect(bytes, columns);
+    let chunks = threads.min(bytes.len() / MIN_CHUNK_BYTES).max(1);
+    let ranges = chunk_ranges(bytes, chunks);
+    if ranges.len() <= 1 {
+        return parse_chunk(bytes, layout);
+    }
+
+    let results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = ranges
+            .iter()
+            .map(|range| scope.spawn(|| parse_chunk(&bytes[range.clone()], layout)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("parser thread panicked"))
+            .collect()
+    });
+
+    let total_pairs = results
+        .iter()
+        .map(|result| result.as_ref().map_or(0, |(left, _)| left.len()))
+        .sum();
+    let mut left_numbers = Vec::with_capacity(total_pairs);
+    let mut right_numbers = Vec::with_capacity(total_pairs);
+    for (range, result) in ranges.iter().zip(results) {
+        match result {
+            Ok((left, right)) => {
+                left_numbers.extend_from_slice(&left);
+                right_numbers.extend_from_slice(&right);
+            }
+            Err(e) => {
+                // Rebase the chunk-relative line onto the whole input
+                let lines_before = bytes[..range.start].iter().filter(|&&b| b == b'\n').count();
+                return Err(ParseError {
+                    line: e.line + lines_before,
+                    ..e
+                });
+            }
+        }
+    }
+
+    Ok((left_numbers, right_numbers))
+}
+
+/// Checks that both lists hold the same number of entries.
+///
+/// The parser rejects lines with a single number, naming the offending line,
+/// so this guards the pairing step against lists built any other way
+/// instead of letting it panic or silently truncate.
+///
+/// # Errors
+/// Returns `LengthMismatch` with both lengths and the position of the
+/// first unpaired entry, the one after the shorter list runs out.
+pub fn check_list_lengths<T>(
+    left_numbers: &[T],
+    right_numbers: &[T],
+) -> Result<(), LengthMismatch> {
+    if left_numbers.len() == right_numbers.len() {
+        Ok(())
+    } else {
+        Err(LengthMismatch {
+            left_len: left_numbers.len(),
+            right_len: right_numbers.len(),
+            entry: left_numbers.len().min(right_numbers.len()) + 1,
+        })
+    }
+}
+
+/// Sorting algorithm used for the two lists.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SortStrategy {
+    /// The standard library's `sort_unstable`
+    #[default]
+    Std,
+    /// LSD radix sort over the bytes of the value range
+    Radix,
+    /// Counting sort, chosen by the radix path when the value range is small
+    Counting,
+}
+
+impl fmt::Display for SortStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SortStrategy::Std => "std",
+            SortStrategy::Radix => "radix",
+            SortStrategy::Counting => "counting",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Largest value range the radix path hands to counting sort instead
+const COUNTING_SORT_MAX_RANGE: usize = 1 << 16;
+
+/// Sorts `numbers` in ascending order using the requested strategy.
+///
+/// # Returns
+/// The strategy actually used, since the radix path falls back to
+/// `sort_unstable` for values it can't handle and to counting sort for
+/// small ranges.
+pub fn sort_numbers<T: Location>(numbers: &mut [T], strategy: SortStrategy) -> SortStrategy {
+    match strategy {
+        // Use unstable sort for better performance on integers
+        // Stable sort not needed since we only care about relative positions
+        SortStrategy::Std => {
+            numbers.sort_unstable();
+            SortStrategy::Std
+        }
+        SortStrategy::Radix | SortStrategy::Counting => radix_sort(numbers),
+    }
+}
+
+/// Sorts values whose range fits in a `u32` without comparisons.
+///
+/// # Implementation Details
+/// - Converts the values to `u32` keys offset from the minimum, so negative
+///   values sort the same way as non-negative ones, and only runs as many
+///   8-bit digit passes as the maximum key needs
+/// - Uses counting sort when the whole range fits in a small count table
+/// - Falls back to `sort_unstable` when `max - min` exceeds `u32::MAX`
+///
+/// # Time Complexity
+/// O(n * d) where d is the number of bytes in the maximum value
+///
+/// # Space Complexity
+/// O(n) for the key and scatter buffers
+fn radix_sort<T: Location>(numbers: &mut [T]) -> SortStrategy {
+    let Some(&base) = numbers.iter().min() else {
+        return SortStrategy::Radix;
+    };
+    let Some(mut keys) = numbers
+        .iter()
+        .map(|n| n.radix_key(base))
+        .collect::<Option<Vec<u32>>>()
+    else {
+        numbers.sort_unstable();
+        return SortStrategy::Std;
+    };
+    let max = keys.iter().copied().max().unwrap_or(0);
+
+    if (max as usize) < COUNTING_SORT_MAX_RANGE {
+        let mut counts = vec![0usize; max as usize + 1];
+        for &key in &keys {
+            counts[key as usize] += 1;
+        }
+        let mut i = 0;
+        for (key, &count) in counts.iter().enumerate() {
+            numbers[i..i + count].fill(T::from_radix_key(base, key as u32));
+            i += count;
+        }
+        return SortStrategy::Counting;
+    }
+
+    let passes = (u32::BITS - max.leading_zeros()).div_ceil(8);
+    let mut scratch = vec![0u32; keys.len()];
+    for pass in 0..passes {
+        let shift = pass * 8;
+        let mut offsets = [0usize; 256];
+        for &key in &keys {
+            offsets[((key >> shift) & 0xff) as usize] += 1;
+        }
+        let mut total = 0;
+        for offset in offsets.iter_mut() {
+            let count = *offset;
+            *offset = total;
+            total += count;
+        }
+        for &key in &keys {
+            let digit = ((key >> shift) & 0xff) as usize;
+            scratch[offsets[digit]] = key;
+            offsets[digit] += 1;
+        }
+        std::mem::swap(&mut keys, &mut scratch);
+    }
+
+    for (number, &key) in numbers.iter_mut().zip(&keys) {
+        *number = T::from_radix_key(base, key);
+    }
+    SortStrategy::Radix
+}
+
+/// Collects the per-pair distances `|left[i] - right[i]|` of the sorted lists.
+pub fn pair_distances<T: Location>(left_numbers: &[T], right_numbers: &[T]) -> Vec<T::Distance> {
+    left_numbers
+        .iter()
+        .zip(right_numbers)
+        .map(|(&left, &right)| left.distance(right))
+        .collect()
+}
+
+/// Width in characters of the longest histogram bar
+const HISTOGRAM_BAR_WIDTH: usize = 40;
+
+/// Renders a bucketed histogram of `distances` as text.
+///
+/// # Implementation Details
+/// - Buckets of equal width cover `0..=max`, computed in `u128` with the
+///   width derived by division so even `u128::MAX` ranges can't overflow
+/// - Uses fewer buckets than requested when the range holds fewer values
+/// - Collapses to a single bucket when every distance is equal
+/// - Each row shows the inclusive bucket range, its count, and a bar of `#`
+///   scaled to the fullest bucket
+///
+/// The output depends only on its arguments, one line per bucket.
+pub fn render_histogram<D: Distance>(distances: &[D], buckets: usize) -> String {
+    let (Some(min), Some(max)) = (
+        distances.iter().map(|d| d.to_u128()).min(),
+        distances.iter().map(|d| d.to_u128()).max(),
+    ) else {
+        return "No pairs\n".to_string();
+    };
+
+    let (low, width) = if min == max {
+        (min, 1)
+    } else {
+        let requested = (buckets.max(1) as u128).min(max.saturating_add(1));
+        // A width just above max / requested keeps every index below requested
+        (0, max / requested + 1)
+    };
+    let buckets = ((max - low) / width + 1) as usize;
+
+    let mut counts = vec![0usize; buckets];
+    for distance in distances {
+        counts[((distance.to_u128() - low) / width) as usize] += 1;
+    }
+
+    let range_width = max.to_string().len();
+    let fullest = *counts.iter().max().unwrap();
+    let count_width = fullest.to_string().len();
+
+    let mut out = String::new();
+    for (b, &count) in counts.iter().enumerate() {
+        let start = low + b as u128 * width;
+        let end = start.saturating_add(width - 1).min(max);
+        let bar_len = count * HISTOGRAM_BAR_WIDTH / fullest;
+        out.push_str(&format!(
+            "{:>rw$}-{:<rw$} | {:>cw$} {}\n",
+            start,
+            end,
+            count,
+            "#".repeat(bar_len),
+            rw = range_width,
+            cw = count_width
+        ));
+    }
+    out
+}
+
+/// Summary of the per-pair distances `|left[i] - right[i]|` of the sorted lists.
+#[derive(Debug, PartialEq)]
+pub struct DistanceStats<T: Location> {
+    pub min: T::Distance,
+    pub max: T::Distance,
+    pub mean: f64,
+    /// Average of the two middle distances for an even number of pairs
+    pub median: f64,
+    /// Nearest-rank 95th percentile
+    pub p95: T::Distance,
+    /// Index of the first pair with the largest distance
+    pub max_index: usize,
+    pub max_left: T,
+    pub max_right: T,
+}
+
+/// Computes distance statistics over the sorted lists.
+///
+/// # Implementation Details
+/// - One pass over both slices collects the distances and tracks the
+///   min, max, sum, and the index of the largest distance; the input
+///   vectors are only borrowed
+/// - Median and percentile use `select_nth_unstable` on the distance
+///   buffer instead of a full sort
+///
+/// # Returns
+/// `None` when there are no pairs.
+///
+/// # Time Complexity
+/// O(n) expected
+///
+/// # Space Complexity
+/// O(n) for the distance buffer
+pub fn distance_stats<T: Location>(
+    left_numbers: &[T],
+    right_numbers: &[T],
+) -> Option<DistanceStats<T>> {
+    if left_numbers.is_empty() {
+        return None;
+    }
+
+    let mut distances = Vec::with_capacity(left_numbers.len());
+    let mut min = left_numbers[0].distance(right_numbers[0]);
+    let mut max = min;
+    let mut max_index = 0;
+    let mut sum = 0f64;
+    for (i, (&left, &right)) in left_numbers.iter().zip(right_numbers).enumerate() {
+        let distance = left.distance(right);
+        min = min.min(distance);
+        if distance > max {
+            max = distance;
+            max_index = i;
+        }
+        // Summed as f64 since a u128 sum of u128 distances could overflow
+        sum += distance.to_u128() as f64;
+        distances.push(distance);
+    }
+
+    let n = distances.len();
+    let p95_rank = (n * 95).div_ceil(100).max(1) - 1;
+    let p95 = *distances.select_nth_unstable(p95_rank).1;
+    let upper_mid = *distances.select_nth_unstable(n / 2).1;
+    let median = if n % 2 == 1 {
+        upper_mid.to_u128() as f64
+    } else {
+        // The lower middle is the largest value left of the upper middle
+        let lower_mid = *distances[..n / 2].iter().max().unwrap();
+        (lower_mid.to_u128() as f64 + upper_mid.to_u128() as f64) / 2.0
+    };
+
+    Some(DistanceStats {
+        min,
+        max,
+        mean: sum / n as f64,
+        median,
+        p95,
+        max_index,
+        max_left: left_numbers[max_index],
+        max_right: right_numbers[max_index],
+    })
+}
+
+/// One of the largest per-pair distances of the sorted lists.
+#[derive(Debug, PartialEq)]
+pub struct TopDistance<T: Location> {
+    /// Index of the pair in sorted order
+    pub index: usize,
+    pub distance: T::Distance,
+    pub left: T,
+    pub right: T,
+}
+
+/// Finds the `n` pairs with the largest distances, largest first.
+///
+/// # Implementation Details
+/// - Keeps a min-heap of at most `n` candidates and replaces its smallest
+///   entry when a larger distance comes along, instead of sorting every
+///   distance
+/// - Ties keep the earlier pair
+///
+/// # Returns
+/// Every pair when `n` exceeds the number of pairs.
+///
+/// # Time Complexity
+/// O(m log n) for m pairs
+///
+/// # Space Complexity
+/// O(n) for the heap
+pub fn top_distances<T: Location>(
+    left_numbers: &[T],
+    right_numbers: &[T],
+    n: usize,
+) -> Vec<TopDistance<T>> {
+    // Reversed so the heap top is the smallest distance, and among equal
+    // distances the latest pair, which is the first to evict
+    let mut heap = BinaryHeap::with_capacity(n.min(left_numbers.len()) + 1);
+    for (index, (&left, &right)) in left_numbers.iter().zip(right_numbers).enumerate() {
+        let candidate = Reverse((left.distance(right), Reverse(index)));
+        if heap.len() < n {
+            heap.push(candidate);
+        } else if heap.peek().is_some_and(|smallest| candidate < *smallest) {
+            heap.pop();
+            heap.push(candidate);
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((distance, Reverse(index)))| TopDistance {
+            index,
+            distance,
+            left: left_numbers[index],
+            right: right_numbers[index],
+        })
+        .collect()
+}
+
+/// Overlap between the two sorted lists, for spotting a column pasted twice.
+#[derive(Debug, PartialEq)]
+pub struct DuplicateStats<T: Location> {
+    /// Pairs with `left[i] == right[i]` after sorting
+    pub equal_pairs: usize,
+    /// Distinct values present in both lists
+    pub shared_values: usize,
+    /// Value with the highest combined count across both lists, the smallest
+    /// on ties, with its left and right counts
+    pub most_common: Option<(T, usize, usize)>,
+}
+
+/// Computes duplicate statistics over the sorted lists.
+///
+/// # Implementation Details
+/// - One zipped pass counts the equal sorted pairs
+/// - A two-pointer walk over the value runs of both slices counts shared
+///   values and finds the most frequent value, without a hash map
+///
+/// # Time Complexity
+/// O(n)
+///
+/// # Space Complexity
+/// O(1)
+pub fn duplicate_stats<T: Location>(left_numbers: &[T], right_numbers: &[T]) -> DuplicateStats<T> {
+    debug_assert!(left_numbers.is_sorted() && right_numbers.is_sorted());
+
+    let equal_pairs = left_numbers
+        .iter()
+        .zip(right_numbers)
+        .filter(|(left, right)| left == right)
+        .count();
+
+    let run_end = |numbers: &[T], start: usize, value: T| {
+        let mut end = start;
+        while end < numbers.len() && numbers[end] == value {
+            end += 1;
+        }
+        end
+    };
+
+    let mut shared_values = 0;
+    let mut most_common: Option<(T, usize, usize)> = None;
+    let mut i = 0;
+    let mut j = 0;
+    while i < left_numbers.len() || j < right_numbers.len() {
+        let value = match (left_numbers.get(i), right_numbers.get(j)) {
+            (Some(&left), Some(&right)) => left.min(right),
+            (Some(&left), None) => left,
+            (None, Some(&right)) => right,
+            (None, None) => unreachable!(),
+        };
+        let left_end = run_end(left_numbers, i, value);
+        let right_end = run_end(right_numbers, j, value);
+        let (left_count, right_count) = (left_end - i, right_end - j);
+        (i, j) = (left_end, right_end);
+
+        if left_count > 0 && right_count > 0 {
+            shared_values += 1;
+        }
+        if most_common.is_none_or(|(_, l, r)| left_count + right_count > l + r) {
+            most_common = Some((value, left_count, right_count));
+        }
+    }
+
+    DuplicateStats {
+        equal_pairs,
+        shared_values,
+        most_common,
+    }
+}
+
+/// Pairs up the lists smallest-first and sums the distances between pairs.
+///
+/// Both lists are sorted in place first unless they already are, so callers
+/// that sorted with a particular `SortStrategy` don't pay for a second sort.
+///
+/// # Implementation Details
+/// - Distances use the unsigned type of the same width, which holds the exact
+///   distance even for values at opposite ends of the range
+/// - The running sum uses `checked_add` and reports an error rather than
+///   wrapping silently in release builds
+/// - `sorted_total_distance` checks the lengths and cuts the right list to
+///   the left's, so `zip` walks both without per-element bounds checks
+///
+/// # Errors
+/// Returns `LengthMismatch` if the lists can't be paired up, or
+/// `DistanceOverflow` naming the pair at which the sum overflowed.
+pub fn total_distance<T: Location>(
+    left_numbers: &mut [T],
+    right_numbers: &mut [T],
+) -> Result<T::Distance, AocError> {
+    for numbers in [&mut *left_numbers, &mut *right_numbers] {
+        if !numbers.is_sorted() {
+            numbers.sort_unstable();
+        }
+    }
+    sorted_total_distance(left_numbers, right_numbers)
+}
+
+/// Sums the distances between two lists already sorted ascending, as
+/// `total_distance` does after sorting them.
+///
+/// # Errors
+/// Returns `LengthMismatch` if the lists can't be paired up, or
+/// `DistanceOverflow` naming the pair at which the sum overflowed.
+pub fn sorted_total_distance<T: Location>(
+    left_numbers: &[T],
+    right_numbers: &[T],
+) -> Result<T::Distance, AocError> {
+    debug_assert!(left_numbers.is_sorted() && right_numbers.is_sorted());
+    check_list_lengths(left_numbers, right_numbers)?;
+    let right_numbers = &right_numbers[..left_numbers.len()];
+
+    let mut total = T::Distance::default();
+    for (i, (&left, &right)) in left_numbers.iter().zip(right_numbers).enumerate() {
+        total = total
+            .checked_sum(left.distance(right))
+            .ok_or(DistanceOverflow {
+                pair: i,
+                width: T::Distance::NAME,
+            })?;
+    }
+    Ok(total)
+}
+
+/// Computes the similarity score between the two sorted lists.
+///
+/// Both slices must be sorted ascending, as they are after `total_distance`.
+/// Each number in the left list is multiplied by the number of times it
+/// appears in the right list, and the products are summed.
+///
+/// # Implementation Details
+/// - Walks both sorted slices with two indices, like the merge step of a merge sort
+/// - Counts the run of equal values on each side and adds
+///   `value * left_run * right_run` once per shared value
+/// - Reuses the sorts already paid for in Part 1, so no frequency map is allocated
+/// - Each product and the running sum are overflow-checked, like the
+///   distance sum
+///
+/// # Errors
+/// Returns `SimilarityOverflow` if a product or the sum doesn't fit in `T`.
+///
+/// # Time Complexity
+/// O(n + m) where n and m are the lengths of the left and right lists
+///
+/// # Space Complexity
+/// O(1)
+pub fn similarity_score<T: Location>(
+    left_numbers: &[T],
+    right_numbers: &[T],
+) -> Result<T, SimilarityOverflow> {
+    debug_assert!(left_numbers.is_sorted() && right_numbers.is_sorted());
+
+    let mut score = T::default();
+    let mut i = 0;
+    let mut j = 0;
+    while i < left_numbers.len() && j < right_numbers.len() {
+        let left = left_numbers[i];
+        let right = right_numbers[j];
+        if left < right {
+            i += 1;
+        } else if right < left {
+            j += 1;
+        } else {
+            let left_run_start = i;
+            while i < left_numbers.len() && left_numbers[i] == left {
+                i += 1;
+            }
+            let right_run_start = j;
+            while j < right_numbers.len() && right_numbers[j] == right {
+                j += 1;
+            }
+            score = score_runs(score, left, i - left_run_start, j - right_run_start)?;
+        }
+    }
+    Ok(score)
+}
+
+/// Adds `value * left_run * right_run` to `score`, the similarity a value
+/// shared by both lists contributes.
+///
+/// # Errors
+/// Returns `SimilarityOverflow` if the product or the sum doesn't fit in `T`.
+#[inline]
+fn score_runs<T: Location>(
+    score: T,
+    value: T,
+    left_run: usize,
+    right_run: usize,
+) -> Result<T, SimilarityOverflow> {
+    value
+        .checked_product(T::from_count(left_run))
+        .and_then(|product| product.checked_product(T::from_count(right_run)))
+        .and_then(|product| score.checked_sum(product))
+        .ok_or(SimilarityOverflow { width: T::NAME })
+}
+
+/// Computes the similarity score by counting the right list, in any order.
+///
+/// This is the score as the puzzle states it, and gives the same answer as
+/// `similarity_score`, which is faster once the lists are sorted anyway.
+///
+/// # Implementation Details
+/// - Counts both lists, then adds `value * left_count * right_count` for
+///   each shared value in ascending order, the same checked steps as
+///   `similarity_score`, so the two overflow on exactly the same inputs
+///
+/// # Errors
+/// Returns `SimilarityOverflow` if a product or the sum doesn't fit in `T`.
+///
+/// # Time Complexity
+/// O(n + m) expected for the hashing, plus O(k log k) to order the k
+/// distinct left values
+///
+/// # Space Complexity
+/// O(n + m) for the counts
+pub fn counted_similarity_score<T: Location + Hash>(
+    left_numbers: &[T],
+    right_numbers: &[T],
+) -> Result<T, SimilarityOverflow> {
+    let left_counts: Counter<T> = left_numbers.iter().copied().collect();
+    let right_counts: Counter<T> = right_numbers.iter().copied().collect();
+    left_counts
+        .sorted()
+        .into_iter()
+        .filter_map(|(&value, left_count)| {
+            let right_count = right_counts.count(&value);
+            (right_count > 0).then_some((value, left_count, right_count))
+        })
+        .try_fold(T::default(), |score, (value, left_count, right_count)| {
+            score_runs(score, value, left_count as usize, right_count as usize)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fixed linear congruential sequence of values below `range`.
+    struct Values(u64);
+
+    impl Values {
+        fn next(&mut self, range: u64) -> i64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            ((self.0 >> 33) % range) as i64
+        }
+
+        /// `len` values in `-range/2..range/2`, so both signs and plenty
+        /// of repeats turn up.
+        fn list(&mut self, len: usize, range: u64) -> Vec<i64> {
+            (0..len)
+                .map(|_| self.next(range) - (range / 2) as i64)
+                .collect()
+        }
+    }
+
+    /// The similarity score as the puzzle states it, one count per left ID.
+    fn naive_similarity(left: &[i64], right: &[i64]) -> i64 {
+        left.iter()
+            .map(|&l| l * right.iter().filter(|&&r| r == l).count() as i64)
+            .sum()
+    }
+
+    /// Both lists as min-heaps, for `drain_heaps`.
+    fn heaps<T: Location>(left: &[T], right: &[T]) -> (MinHeap<T>, MinHeap<T>) {
+        (
+            left.iter().copied().map(std::cmp::Reverse).collect(),
+            right.iter().copied().map(std::cmp::Reverse).collect(),
+        )
+    }
+
+    #[test]
+    fn merged_similarity_matches_the_naive_count() {
+        let mut values = Values(0x2024_1201);
+        for round in 0..200 {
+            let len = round % 40;
+            let range = [4, 16, 1000][round % 3];
+            let mut left = values.list(len, range);
+            let mut right = values.list(len, range);
+            let expected = naive_similarity(&left, &right);
+
+            let (left_heap, right_heap) = heaps(&left, &right);
+            let (_, drained) = drain_heaps(left_heap, right_heap).unwrap();
+            assert_eq!(drained, expected, "{:?} {:?}", left, right);
+
+            left.sort_unstable();
+            right.sort_unstable();
+            assert_eq!(
+                similarity_score(&left, &right),
+                Ok(expected),
+                "{:?} {:?}",
+                left,
+                right
+            );
+        }
+    }
+
+    #[test]
+    fn similarity_overflow_is_an_error() {
+        // A single product too large for the type
+        let (left, right) = ([i32::MAX], [i32::MAX, i32::MAX]);
+        assert_eq!(
+            similarity_score(&left, &right),
+            Err(SimilarityOverflow { width: "i32" })
+        );
+        let (left_heap, right_heap) = heaps(&left, &[i32::MAX, i32::MAX]);
+        assert!(drain_heaps(left_heap, right_heap).is_err());
+
+        // Products that fit, but not their sum
+        let values = [1 << 30, (1 << 30) + 1];
+        assert_eq!(
+            similarity_score(&values, &values),
+            Err(SimilarityOverflow { width: "i32" })
+        );
+        let (left_heap, right_heap) = heaps(&values, &values);
+        assert_eq!(
+            drain_heaps(left_heap, right_heap).unwrap_err().to_string(),
+            "similarity score overflows i32"
+        );
+
+        assert_eq!(
+            counted_similarity_score(&values, &values),
+            Err(SimilarityOverflow { width: "i32" })
+        );
+
+        // The same lists fit once widened
+        let wide = [1i64 << 30, (1 << 30) + 1];
+        assert_eq!(similarity_score(&wide, &wide), Ok((1 << 31) + 1));
+    }
+
+    #[test]
+    fn counting_overflows_exactly_when_the_merge_does() {
+        let mut values = Values(0x2024_1278);
+        for round in 0..500 {
+            // Few distinct IDs near the ends of the range, so some runs
+            // overflow and some cancel out
+            let pick = |values: &mut Values| match values.next(4) {
+                0 => i32::MIN / 3,
+                1 => -(1 << 29),
+                2 => 1 << 29,
+                _ => i32::MAX / 3,
+            };
+            let len = 1 + round % 12;
+            let left: Vec<i32> = (0..len).map(|_| pick(&mut values)).collect();
+            let right: Vec<i32> = (0..len).map(|_| pick(&mut values)).collect();
+            let counted = counted_similarity_score(&left, &right);
+
+            let (mut sorted_left, mut sorted_right) = (left.clone(), right.clone());
+            sorted_left.sort_unstable();
+            sorted_right.sort_unstable();
+            assert_eq!(
+                counted,
+                similarity_score(&sorted_left, &sorted_right),
+                "{:?} {:?}",
+                left,
+                right
+            );
+        }
+    }
+
+    #[test]
+    fn length_mismatch_names_the_first_unpaired_entry() {
+        assert_eq!(check_list_lengths(&[3, 4, 2], &[4, 3, 5]), Ok(()));
+        let mismatch = check_list_lengths(&[3, 4, 2, 1], &[4, 3]).unwrap_err();
+        assert_eq!(
+            mismatch,
+            LengthMismatch {
+                left_len: 4,
+                right_len: 2,
+                entry: 3,
+            }
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            "left list has 4 entries but right list has 2; entry 3 has no pair"
+        );
+        assert_eq!(
+            total_distance::<i64>(&mut [], &mut [7])
+                .unwrap_err()
+                .to_string(),
+            "left list has 0 entries but right list has 1; entry 1 has no pair"
+        );
+    }
+
+    #[test]
+    fn a_truncated_input_fails_on_its_last_line() {
+        // A half-downloaded input ends partway through a pair
+        let error = parse_bytes::<i64>(b"3   4\n4   3\n\n2", 1, Columns::default()).unwrap_err();
+        assert_eq!(error.line, 4);
+        assert_eq!(
+            error.to_string(),
+            "line 4, columns 1-1: expected 2 numbers, found 1"
+        );
+    }
+
+    /// Parses `input` serially into `i64` lists with the default columns.
+    fn parse(input: &str) -> Result<(Vec<i64>, Vec<i64>), ParseError> {
+        parse_bytes(input.as_bytes(), 1, Columns::default())
+    }
+
+    #[test]
+    fn reports_malformed_lines_with_their_position() {
+        let error = parse("3   4\n4   4x3\n").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError {
+                line: 2,
+                columns: 5..8,
+                kind: ParseErrorKind::InvalidNumber("4x3".to_string()),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "line 2, columns 5-7: could not parse `4x3` as an integer"
+        );
+        assert_eq!(
+            parse("3 4\n4 x\n").unwrap_err().to_string(),
+            "line 2, columns 3-3: could not parse `x` as an integer"
+        );
+
+        // A line with one number, and one with a third token
+        assert_eq!(
+            parse("3   4\n4\n").unwrap_err().to_string(),
+            "line 2, columns 1-1: expected 2 numbers, found 1"
+        );
+        assert_eq!(
+            parse("3   4\n4   3   9\n").unwrap_err().to_string(),
+            "line 2, columns 9-9: unexpected `9` after 2 numbers"
+        );
+    }
+
+    #[test]
+    fn parses_a_number_token() {
+        assert_eq!(parse_number::<i64>(b"0"), Ok((0, 1)));
+        assert_eq!(parse_number::<i64>(b"90210   4"), Ok((90210, 5)));
+        assert_eq!(parse_number::<i64>(b"-17,3"), Ok((-17, 3)));
+        assert_eq!(parse_number::<i64>(b"007\t1"), Ok((7, 3)));
+
+        // Errors cover the whole token, relative to the slice
+        for garbage in [&b"4x3 5"[..], b"-", b"+5", b"1.5", b"\xff9"] {
+            let error = parse_number::<i64>(garbage).unwrap_err();
+            let len = next_token(garbage, 0).len();
+            assert_eq!(error.line, 0, "{:?}", garbage);
+            assert_eq!(error.columns, 1..len + 1, "{:?}", garbage);
+            assert!(
+                matches!(error.kind, ParseErrorKind::InvalidNumber(_)),
+                "{:?}",
+                garbage
+            );
+        }
+        assert_eq!(
+            parse_number::<i64>(b"4x3 5").unwrap_err().kind,
+            ParseErrorKind::InvalidNumber("4x3".to_string())
+        );
+        assert_eq!(
+            parse_number::<i64>(b"").unwrap_err().kind,
+            ParseErrorKind::InvalidNumber(String::new())
+        );
+    }
+
+    #[test]
+    fn distance_overflow_is_an_error() {
+        // The widest single distance still fits the unsigned type
+        assert_eq!(
+            total_distance(&mut [i64::MIN], &mut [i64::MAX]).unwrap(),
+            u64::MAX
+        );
+        let error = total_distance(&mut [i64::MIN, i64::MIN], &mut [i64::MAX, i64::MAX]);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "total distance overflows u64 at sorted pair 2"
+        );
+        let error = sorted_total_distance(&[i64::MIN + 1, -1], &[i64::MAX, i64::MAX]);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "total distance overflows u64 at sorted pair 2"
+        );
+    }
+
+    #[test]
+    fn solves_the_puzzle_input() {
+        let input = include_bytes!("../../puzzle_input.txt");
+        let (mut left, mut right) = parse_bytes::<i64>(input, 4, Columns::default()).unwrap();
+        assert_eq!(total_distance(&mut left, &mut right).unwrap(), 2367773);
+        assert_eq!(similarity_score(&left, &right), Ok(21271939));
+    }
+
+    /// Sorts a copy of `numbers` with `strategy`, checking it against
+    /// `sort_unstable`, and returns the strategy used.
+    fn sorts_like_std<T: Location>(numbers: &[T], strategy: SortStrategy) -> SortStrategy {
+        let mut expected = numbers.to_vec();
+        expected.sort_unstable();
+        let mut sorted = numbers.to_vec();
+        let used = sort_numbers(&mut sorted, strategy);
+        assert_eq!(sorted, expected, "{:?}", numbers);
+        used
+    }
+
+    #[test]
+    fn radix_sort_matches_the_std_sort() {
+        let mut values = Values(0x2024_1209);
+        for round in 0..100 {
+            let len = [0, 1, 2, 17, 1000][round % 5];
+            // Small ranges go to counting sort, wide ones to the radix passes
+            let small = values.list(len, 50_000);
+            let used = sorts_like_std(&small, SortStrategy::Radix);
+            if len > 0 {
+                assert_eq!(used, SortStrategy::Counting);
+            }
+            let wide: Vec<i64> = small.iter().map(|v| v * 80_000).collect();
+            let used = sorts_like_std(&wide, SortStrategy::Radix);
+            if len > 2 {
+                assert_eq!(used, SortStrategy::Radix);
+            }
+            let narrow: Vec<i32> = wide.iter().map(|&v| v as i32 / 2).collect();
+            sorts_like_std(&narrow, SortStrategy::Counting);
+            let huge: Vec<i128> = wide.iter().map(|&v| v as i128 * 1000).collect();
+            sorts_like_std(&huge, SortStrategy::Radix);
+        }
+
+        // Ranges past u32 fall back to the std sort
+        let extremes = [i64::MAX, 0, i64::MIN, -1, 1];
+        assert_eq!(
+            sorts_like_std(&extremes, SortStrategy::Radix),
+            SortStrategy::Std
+        );
+        let extremes = [i32::MAX, 0, i32::MIN, -1, 1, i32::MIN];
+        assert_eq!(
+            sorts_like_std(&extremes, SortStrategy::Radix),
+            SortStrategy::Radix
+        );
+        assert_eq!(
+            sorts_like_std(&[5, 3, 5], SortStrategy::Std),
+            SortStrategy::Std
+        );
+    }
+
+    /// Checks that `ranges` are non-empty, cover `bytes` in order, and
+    /// each end just after a newline or at the end of the input.
+    fn assert_aligned(bytes: &[u8], ranges: &[Range<usize>]) {
+        let mut expected_start = 0;
+        for range in ranges {
+            assert_eq!(range.start, expected_start, "{:?}", ranges);
+            assert!(!range.is_empty(), "{:?}", ranges);
+            assert!(
+                range.end == bytes.len() || bytes[range.end - 1] == b'\n',
+                "{:?} splits a line",
+                range
+            );
+            expected_start = range.end;
+        }
+        assert_eq!(expected_start, bytes.len(), "{:?}", ranges);
+    }
+
+    #[test]
+    fn chunks_end_on_line_boundaries() {
+        for bytes in [
+            &b"3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n"[..],
+            b"3   4\n4   3\n2   5\n1   3\n3   9\n3   3",
+            b"3   4\r\n\r\n4   3\r\n",
+            b"\n\n\n",
+            b"12345",
+        ] {
+            for chunks in 0..=bytes.len() + 1 {
+                let ranges = chunk_ranges(bytes, chunks);
+                assert!(ranges.len() <= chunks.max(1), "{:?}", ranges);
+                assert_aligned(bytes, &ranges);
+            }
+        }
+        assert!(chunk_ranges(b"", 4).is_empty());
+        // Every chunk of this input is exactly two lines
+        assert_eq!(chunk_ranges(b"1 2\n3 4\n5 6\n7 8\n", 2), [0..8, 8..16]);
+        // The last line needs no newline to end its chunk
+        assert_eq!(chunk_ranges(b"1 2\n3 4\n5 6", 2), [0..8, 8..11]);
+        // A probe landing on a newline ends the chunk there
+        assert_eq!(chunk_ranges(b"1 2\n3 4\n", 4), [0..4, 4..8]);
+    }
+
+    #[test]
+    fn threaded_parsing_matches_serial() {
+        let mut values = Values(0x2024_1210);
+        let mut input = String::new();
+        while input.len() < 6 * MIN_CHUNK_BYTES {
+            input.push_str(&format!(
+                "{}   {}\n",
+                values.next(100_000),
+                values.next(100_000) - 50_000
+            ));
+        }
+        for input in [input.clone(), input.trim_end().to_string()] {
+            let serial = parse_bytes::<i64>(input.as_bytes(), 1, Columns::default()).unwrap();
+            for threads in [2, 3, 4, 8] {
+                let threaded = parse_bytes(input.as_bytes(), threads, Columns::default());
+                assert_eq!(threaded.as_ref(), Ok(&serial), "{} threads", threads);
+            }
+        }
+
+        // Errors late in the input name their line in the whole input
+        let lines = input.lines().count();
+        let broken = format!("{}4   x\n", input);
+        let serial = parse_bytes::<i64>(broken.as_bytes(), 1, Columns::default()).unwrap_err();
+        assert_eq!(serial.line, lines + 1);
+        for threads in [2, 4, 8] {
+            let threaded = parse_bytes::<i64>(broken.as_bytes(), threads, Columns::default());
+            assert_eq!(threaded.unwrap_err(), serial, "{} threads", threads);
+        }
+    }
+
+    /// The puzzle example's lists, each sorted
+    fn example() -> (Vec<i64>, Vec<i64>) {
+        (vec![1, 2, 3, 3, 3, 4], vec![3, 3, 3, 4, 5, 9])
+    }
+
+    #[test]
+    fn summarizes_the_distances() {
+        let (left, right) = example();
+        let stats = distance_stats(&left, &right).unwrap();
+        assert_eq!(
+            stats,
+            DistanceStats {
+                min: 0,
+                max: 5,
+                mean: 11.0 / 6.0,
+                median: 1.5,
+                p95: 5,
+                max_index: 5,
+                max_left: 4,
+                max_right: 9,
+            }
+        );
+
+        // An odd count has a middle distance; the first largest is kept
+        let stats = distance_stats::<i64>(&[1, 2, 10, 20, 30], &[4, 4, 10, 23, 30]).unwrap();
+        assert_eq!((stats.min, stats.max, stats.median), (0, 3, 2.0));
+        assert_eq!(
+            (stats.max_index, stats.max_left, stats.max_right),
+            (0, 1, 4)
+        );
+        assert_eq!(stats.p95, 3);
+
+        // Of 20 distances 0..20, the 95th percentile is the 19th
+        let left: Vec<i64> = (0..20).collect();
+        let stats = distance_stats(&left, &[0; 20]).unwrap();
+        assert_eq!((stats.p95, stats.median, stats.mean), (18, 9.5, 9.5));
+        assert_eq!(distance_stats::<i64>(&[], &[]), None);
+    }
+
+    /// The puzzle's worked example
+    const EXAMPLE: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+    #[test]
+    fn solves_the_example() {
+        let (mut left, mut right) = parse_input::<i64>(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(left, [3, 4, 2, 1, 3, 3]);
+        assert_eq!(right, [4, 3, 5, 3, 9, 3]);
+        assert_eq!(total_distance(&mut left, &mut right).unwrap(), 11);
+        assert_eq!((left.clone(), right.clone()), example());
+        assert_eq!(similarity_score(&left, &right), Ok(31));
+    }
+
+    #[test]
+    fn solves_empty_and_single_line_inputs() {
+        for input in ["", "\n", "  \n\n"] {
+            let (mut left, mut right) = parse_input::<i64>(input.as_bytes()).unwrap();
+            assert!(left.is_empty() && right.is_empty(), "{:?}", input);
+            assert_eq!(total_distance(&mut left, &mut right).unwrap(), 0);
+            assert_eq!(similarity_score(&left, &right), Ok(0));
+        }
+
+        let (mut left, mut right) = parse_input::<i64>(&b"7   3"[..]).unwrap();
+        assert_eq!((&left[..], &right[..]), (&[7][..], &[3][..]));
+        assert_eq!(total_distance(&mut left, &mut right).unwrap(), 4);
+        assert_eq!(similarity_score(&left, &right), Ok(0));
+        let (left, right) = parse_input::<i64>(&b"5   5\n"[..]).unwrap();
+        assert_eq!(similarity_score(&left, &right), Ok(5));
+    }
+
+    /// Total distance of `input`, parsed serially.
+    fn distance_of(input: &str) -> Result<u64, AocError> {
+        let (mut left, mut right) = parse(input)?;
+        total_distance(&mut left, &mut right)
+    }
+
+    #[test]
+    fn accepts_blank_and_comma_delimiters() {
+        for input in [
+            EXAMPLE,
+            "3\t4\n4\t3\n2\t5\n1\t3\n3\t9\n3\t3\n",
+            "3 \t 4\n4\t\t3\n2 5\n1\t 3\n3  \t9\n3 3\n",
+            "3,4\n4, 3\n2 ,5\n1 , 3\n3,\t9\n3\t,3\n",
+            "  3   4\n\t4   3\n 2,5\n1   3\n3   9\n3   3\n",
+        ] {
+            assert_eq!(distance_of(input).unwrap(), 11, "{:?}", input);
+        }
+
+        for (input, message) in [
+            (
+                "3   4\n4;3\n",
+                "line 2, columns 1-3: could not parse `4;3` as an integer",
+            ),
+            (
+                "3,4\n4,,3\n",
+                "line 2, columns 3-4: could not parse `,3` as an integer",
+            ),
+            (
+                "3, 4\n4 , , 3\n",
+                "line 2, columns 5-5: could not parse `,` as an integer",
+            ),
+            (
+                "3   4\n4   3,\n",
+                "line 2, columns 6-6: unexpected `,` after 2 numbers",
+            ),
+        ] {
+            let error = distance_of(input).unwrap_err();
+            assert_eq!(error.to_string(), message, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn line_endings_do_not_change_the_answers() {
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        for input in [
+            crlf.as_str(),
+            &format!("{}\n", EXAMPLE),
+            &format!("{}\r\n\r\n", crlf),
+            EXAMPLE.trim_end(),
+            crlf.trim_end(),
+            &format!("\n{}", EXAMPLE),
+        ] {
+            assert_eq!(
+                parse(input).unwrap(),
+                parse(EXAMPLE).unwrap(),
+                "{:?}",
+                input
+            );
+            let (left_heap, right_heap) =
+                parse_into_heaps::<i64>(input.as_bytes(), Columns::default()).unwrap();
+            assert_eq!(
+                drain_heaps(left_heap, right_heap).unwrap(),
+                (11, 31),
+                "{:?}",
+                input
+            );
+        }
+
+        // A stray `\r` inside a line is still an error
+        assert_eq!(
+            parse("3   4\r\n4\r3\r\n").unwrap_err().to_string(),
+            "line 2, columns 1-3: could not parse `4\r3` as an integer"
+        );
+    }
+
+    #[test]
+    fn renders_a_histogram_of_the_distances() {
+        let full = "#".repeat(HISTOGRAM_BAR_WIDTH);
+        let half = "#".repeat(HISTOGRAM_BAR_WIDTH / 2);
+        // The example's distances, one bucket per value
+        assert_eq!(
+            render_histogram(&[2u64, 1, 0, 1, 2, 5], 16)
+                .lines()
+                .collect::<Vec<_>>(),
+            [
+                format!("0-0 | 1 {}", half),
+                format!("1-1 | 2 {}", full),
+                format!("2-2 | 2 {}", full),
+                "3-3 | 0 ".to_string(),
+                "4-4 | 0 ".to_string(),
+                format!("5-5 | 1 {}", half),
+            ]
+        );
+        assert_eq!(
+            render_histogram(&[3u32, 250, 999, 1000], 3)
+                .lines()
+                .collect::<Vec<_>>(),
+            [
+                format!("   0-333  | 2 {}", full),
+                " 334-667  | 0 ".to_string(),
+                format!(" 668-1000 | 2 {}", full),
+            ]
+        );
+
+        // Equal distances collapse to one bucket
+        assert_eq!(
+            render_histogram(&[7u64, 7, 7], 16),
+            format!("7-7 | 3 {}\n", full)
+        );
+        assert_eq!(render_histogram::<u64>(&[], 16), "No pairs\n");
+    }
+
+    #[test]
+    fn renders_the_widest_range_without_overflow() {
+        let max = u128::MAX;
+        let histogram = render_histogram(&[0, max, max / 2, 1], 4);
+        let rows: Vec<&str> = histogram.lines().collect();
+        assert_eq!(rows.len(), 4);
+        assert!(
+            rows[0].starts_with(&format!("{:>39}-{:<39} | 2 ", 0, max / 4)),
+            "{}",
+            histogram
+        );
+        assert!(
+            rows[3].starts_with(&format!("{}-{} | 1 ", max / 4 * 3 + 3, max)),
+            "{}",
+            histogram
+        );
+        assert!(rows[2].ends_with("| 0 "), "{}", histogram);
+    }
+
+    #[test]
+    fn wide_locations_parse_past_i64() {
+        let big = 1i128 << 70;
+        let input = format!("{}   -{}\n1   2\n", big, big);
+        let (mut left, mut right) =
+            parse_bytes::<i128>(input.as_bytes(), 1, Columns::default()).unwrap();
+        assert_eq!(
+            total_distance(&mut left, &mut right).unwrap(),
+            2 * big as u128 - 1
+        );
+
+        let error = parse(&input).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::OutOfRange {
+                token: big.to_string(),
+                width: "i64",
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            format!("line 1, columns 1-22: `{}` is out of range for i64", big)
+        );
+        // The limits themselves still fit
+        let limits = format!("{}   {}\n", i64::MIN, i64::MAX);
+        assert_eq!(parse(&limits).unwrap(), (vec![i64::MIN], vec![i64::MAX]));
+    }
+
+    #[test]
+    fn unequal_lists_are_not_summed_as_far_as_they_pair() {
+        // Zipping alone would stop at the shorter list and answer 1
+        for (left, right) in [(&[1, 5][..], &[2][..]), (&[1][..], &[2, 5][..])] {
+            let error = sorted_total_distance::<i64>(left, right).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "left list has {} entries but right list has {}; entry 2 has no pair",
+                    left.len(),
+                    right.len()
+                )
+            );
+        }
+        assert_eq!(sorted_total_distance::<i64>(&[1, 5], &[2, 9]).unwrap(), 5);
+    }
+
+    #[test]
+    fn solves_lists_of_mixed_signs() {
+        let input = include_str!("../../mixed_signs_input.txt");
+        let (mut left, mut right) = parse(input).unwrap();
+        assert_eq!(left, [-3, 7, -1, 0, -3, 2]);
+        assert_eq!(right, [-1, -3, 5, -3, 2, -8]);
+        let (left_heap, right_heap) = heaps(&left, &right);
+
+        // Paired up: |-3 - -8| + |-3 - -3| + |-1 - -3| + |0 - -1| + |2 - 2| + |7 - 5|
+        assert_eq!(total_distance(&mut left, &mut right).unwrap(), 10);
+        // Both -3s appear twice on the right, -1 and 2 once each
+        assert_eq!(similarity_score(&left, &right).unwrap(), -11);
+        assert_eq!(counted_similarity_score(&left, &right).unwrap(), -11);
+        assert_eq!(drain_heaps(left_heap, right_heap).unwrap(), (10, -11));
+    }
+
+    #[test]
+    fn solves_a_chosen_pair_of_columns() {
+        let input = include_str!("../../three_columns_input.txt");
+        let columns = Columns { left: 0, right: 2 };
+        let (mut left, mut right) = parse_bytes::<i64>(input.as_bytes(), 1, columns).unwrap();
+        assert_eq!(left, [3, 4, 2, 1, 3, 3]);
+        assert_eq!(right, [4, 3, 5, 3, 9, 3]);
+        assert_eq!(total_distance(&mut left, &mut right).unwrap(), 11);
+        assert_eq!(similarity_score(&left, &right).unwrap(), 31);
+
+        // Every line needs the first line's three columns, even unselected ones
+        let ragged = "3   10   4\n4   20\n2   30   5\n";
+        for columns in [columns, Columns { left: 0, right: 1 }] {
+            let error = parse_bytes::<i64>(ragged.as_bytes(), 1, columns).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "line 2, columns 5-6: expected 3 numbers, found 2"
+            );
+        }
+        let error = parse_bytes::<i64>(b"3   10\n", 1, columns).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, columns 5-6: expected 3 numbers, found 2"
+        );
+    }
+
+    #[test]
+    fn heap_strategy_matches_sorting() {
+        let mut values = Values(0x2024_1221);
+        for round in 0..200 {
+            let len = round % 50;
+            let range = [8, 100, 1 << 40][round % 3];
+            let left = values.list(len, range);
+            let right = values.list(len, range);
+            let input: String = left
+                .iter()
+                .zip(&right)
+                .map(|(l, r)| format!("{}   {}\n", l, r))
+                .collect();
+
+            let (mut sorted_left, mut sorted_right) = parse(&input).unwrap();
+            let distance = total_distance(&mut sorted_left, &mut sorted_right).unwrap();
+            let score = similarity_score(&sorted_left, &sorted_right).unwrap();
+            let (left_heap, right_heap) =
+                parse_into_heaps::<i64>(input.as_bytes(), Columns::default()).unwrap();
+            assert_eq!(
+                drain_heaps(left_heap, right_heap).unwrap(),
+                (distance, score),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn lists_the_largest_distances_first() {
+        // Distances 2, 1, 0, 1, 2, 5 in sorted order
+        let (left, right) = example();
+        let top = |index: usize, distance: u64| TopDistance {
+            index,
+            distance,
+            left: left[index],
+            right: right[index],
+        };
+        // The tie between pairs 0 and 4 goes to the earlier one
+        assert_eq!(
+            top_distances(&left, &right, 3),
+            [top(5, 5), top(0, 2), top(4, 2)]
+        );
+        assert_eq!(top_distances(&left, &right, 2), [top(5, 5), top(0, 2)]);
+        assert_eq!(
+            top_distances(&left, &right, 10),
+            [
+                top(5, 5),
+                top(0, 2),
+                top(4, 2),
+                top(1, 1),
+                top(3, 1),
+                top(2, 0)
+            ]
+        );
+        assert_eq!(top_distances(&left, &right, 0), []);
+        assert_eq!(top_distances::<i64>(&[], &[], 3), []);
+    }
+}
@@ -0,0 +1,111 @@
+//! Integer widths the solver can run on.
+//!
+//! The puzzle's location IDs fit in a few digits, but synthetic stress inputs
+//! can carry 18-19 digit values whose distance sum outgrows `u64`. Every
+//! parsing and solving function is generic over `Location`, so a run can pick
+//! `i64` (the default) or `i128` without duplicating the pipeline.
+
+use aoc_common::{Answer, Integer};
+use std::fmt;
+use std::ops::{Add, Mul};
+
+/// Unsigned type holding per-pair distances and their sum.
+pub trait Distance: Copy + Ord + Default + Send + fmt::Display + fmt::Debug + Into<Answer> {
+    /// Type name shown in overflow errors
+    const NAME: &'static str;
+
+    /// Adds two distances, returning `None` on overflow.
+    fn checked_sum(self, other: Self) -> Option<Self>;
+
+    /// Widens the distance for width-independent arithmetic.
+    fn to_u128(self) -> u128;
+}
+
+/// Signed integer type used for location IDs.
+///
+/// Parsing comes from `aoc_common::Integer`, which also names the type in
+/// out-of-range errors.
+pub trait Location:
+    Integer + Default + Send + Sync + Add<Output = Self> + Mul<Output = Self> + Into<Answer>
+{
+    /// Unsigned type of the same width, which holds any `|a - b|` exactly
+    type Distance: Distance;
+
+    /// Returns `|self - other|` without overflowing.
+    fn distance(self, other: Self) -> Self::Distance;
+
+    /// Converts to a `u32` radix key relative to `base`, the list minimum, or
+    /// `None` if the offset doesn't fit. Offsetting keeps negative values
+    /// sortable without comparisons.
+    fn radix_key(self, base: Self) -> Option<u32>;
+
+    /// Converts back from a radix key produced by `radix_key` with `base`.
+    fn from_radix_key(base: Self, key: u32) -> Self;
+
+    /// Converts a run length into a multiplier for the similarity score.
+    fn from_count(count: usize) -> Self;
+
+    /// Adds two values, returning `None` on overflow.
+    fn checked_sum(self, other: Self) -> Option<Self>;
+
+    /// Multiplies two values, returning `None` on overflow.
+    fn checked_product(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_location {
+    ($signed:ty, $unsigned:ty) => {
+        impl Distance for $unsigned {
+            const NAME: &'static str = stringify!($unsigned);
+
+            #[inline]
+            fn checked_sum(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+
+            #[inline]
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+        }
+
+        impl Location for $signed {
+            type Distance = $unsigned;
+
+            #[inline]
+            fn distance(self, other: Self) -> $unsigned {
+                self.abs_diff(other)
+            }
+
+            #[inline]
+            fn radix_key(self, base: Self) -> Option<u32> {
+                u32::try_from(self.abs_diff(base)).ok()
+            }
+
+            #[inline]
+            fn from_radix_key(base: Self, key: u32) -> Self {
+                // Wraps through the intermediate for i32, landing on the
+                // original value since it lies within the type's range
+                base.wrapping_add(key as $signed)
+            }
+
+            #[inline]
+            fn from_count(count: usize) -> Self {
+                <$signed>::try_from(count).expect("run length exceeds the location type")
+            }
+
+            #[inline]
+            fn checked_sum(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+
+            #[inline]
+            fn checked_product(self, other: Self) -> Option<Self> {
+                self.checked_mul(other)
+            }
+        }
+    };
+}
+
+impl_location!(i32, u32);
+impl_location!(i64, u64);
+impl_location!(i128, u128);
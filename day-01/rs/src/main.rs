@@ -1,111 +1,632 @@
+use aoc_common::{
+    day_reporter, fmt_count_rate, fmt_rate, init_parallelism, parallelism, parse_args, time_block,
+    AocError, Config, DayArgs, MetricsOutput, OutputStyle, Part, Reporter, Solver,
+};
+use clap::Parser;
+use day_01::{
+    distance_stats, drain_heaps, duplicate_stats, pair_distances, parse_bytes, parse_into_heaps,
+    render_histogram, similarity_score, sort_numbers, top_distances, total_distance, Columns,
+    Day01, Location, SolveStrategy, SortStrategy,
+};
+use serde::{Serialize, Serializer};
+use std::alloc::{GlobalAlloc, Layout, System};
 use std::fs::File;
-use std::io::{self, BufRead, BufReader};
-use std::time::Instant;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Heap bytes currently allocated by the process
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+/// Highest value `ALLOCATED` has reached
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// System allocator wrapper that tracks peak heap usage, so the sort and
+/// heap strategies can be compared on memory as well as time.
+struct TrackingAllocator;
+
+impl TrackingAllocator {
+    fn grow(size: usize) {
+        let now = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_ALLOCATED.fetch_max(now, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                Self::grow(new_size - layout.size());
+            } else {
+                Self::shrink(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static GLOBAL: TrackingAllocator = TrackingAllocator;
+
+/// Day 1: Historian Hysteria. Pairs up two lists of location IDs by rank
+/// for their total distance, then scores how often each left ID appears on
+/// the right.
+#[derive(Debug, Parser)]
+#[command(name = "day-01")]
+struct Cli {
+    #[command(flatten)]
+    common: DayArgs,
+    /// Columns feeding the left and right lists, 0-based
+    #[arg(long, value_name = "A,B", default_value = "0,1")]
+    cols: Columns,
+    /// Sort the lists (sort) or stream them through heaps (heap)
+    #[arg(long, value_name = "NAME", value_parser = parse_strategy, default_value = "sort")]
+    strategy: SolveStrategy,
+    /// Sort with the standard library (std) or a radix sort (radix)
+    #[arg(long, value_name = "NAME", value_parser = parse_sort, default_value = "std")]
+    sort: SortStrategy,
+    /// Print per-pair distance statistics
+    #[arg(long)]
+    stats: bool,
+    /// Print overlap between the sorted lists
+    #[arg(long)]
+    dupes: bool,
+    /// Print the N pairs with the largest distances
+    #[arg(long, value_name = "N")]
+    top: Option<NonZeroUsize>,
+    /// Print a distance histogram with N buckets, 16 without one
+    #[arg(long, value_name = "N", num_args = 0..=1,
+          default_missing_value = DEFAULT_HISTOGRAM_BUCKETS)]
+    histogram: Option<NonZeroUsize>,
+    /// Parse and solve with i128 instead of i64
+    #[arg(long)]
+    wide: bool,
+}
+
+/// Command-line options for a run, checked for conflicts.
+#[derive(Debug, Default)]
+struct Args {
+    /// Input path, `-` for stdin, or `None` for the default location
+    input: Option<String>,
+    /// Columns feeding the left and right lists (`--cols A,B`)
+    columns: Columns,
+    /// Sort the lists or stream them through heaps (`--strategy sort|heap`)
+    strategy: SolveStrategy,
+    /// Requested sort strategy (`--sort radix|std`)
+    sort: SortStrategy,
+    /// Parser thread count (`--threads N`, or `threads` in the config),
+    /// defaulting to one per core, as set up by `init_parallelism`
+    threads: Option<usize>,
+    /// Print per-pair distance statistics (`--stats`)
+    stats: bool,
+    /// Print overlap between the sorted lists (`--dupes`)
+    dupes: bool,
+    /// Print the pairs with the largest distances (`--top N`)
+    top: Option<usize>,
+    /// Print a distance histogram with this many buckets (`--histogram [N]`)
+    histogram: Option<usize>,
+    /// Parse and solve with `i128` instead of `i64` (`--wide`)
+    wide: bool,
+    /// Answers to compute (`--part 1|2|both`)
+    part: Part,
+    /// Where to write the metrics as JSON (`--metrics-json [PATH]`)
+    metrics_json: Option<MetricsOutput>,
+    /// Print only the answers (`--quiet`, or `$AOC_OUTPUT`)
+    output: OutputStyle,
+}
+
+/// Bucket count used when `--histogram` is given without a value
+const DEFAULT_HISTOGRAM_BUCKETS: &str = "16";
+
+/// Parses `--strategy sort|heap`.
+fn parse_strategy(value: &str) -> Result<SolveStrategy, String> {
+    match value {
+        "sort" => Ok(SolveStrategy::Sort),
+        "heap" => Ok(SolveStrategy::Heap),
+        _ => Err("expected sort or heap".to_string()),
+    }
+}
+
+/// Parses `--sort radix|std`.
+fn parse_sort(value: &str) -> Result<SortStrategy, String> {
+    match value {
+        "std" => Ok(SortStrategy::Std),
+        "radix" => Ok(SortStrategy::Radix),
+        _ => Err("expected radix or std".to_string()),
+    }
+}
+
+impl Args {
+    /// Checks the parsed options against each other.
+    ///
+    /// # Returns
+    /// The options, with the settings given as flags for `Config::load`.
+    fn from_cli(cli: Cli) -> Result<(Self, Config), AocError> {
+        let flags = cli.common.config_flags();
+        let parsed = Args {
+            input: cli.common.single_input()?.map(str::to_string),
+            columns: cli.cols,
+            strategy: cli.strategy,
+            sort: cli.sort,
+            threads: cli.common.threads.map(NonZeroUsize::get),
+            stats: cli.stats,
+            dupes: cli.dupes,
+            top: cli.top.map(NonZeroUsize::get),
+            histogram: cli.histogram.map(NonZeroUsize::get),
+            wide: cli.wide,
+            part: cli.common.part,
+            output: cli.common.output_style()?,
+            metrics_json: cli.common.metrics_json,
+        };
+        // The heap strategy never holds the sorted lists these reports read
+        if parsed.strategy == SolveStrategy::Heap
+            && (parsed.stats || parsed.dupes || parsed.histogram.is_some() || parsed.top.is_some())
+        {
+            return Err(AocError::BadArgument(
+                "--stats, --dupes, --histogram, and --top require --strategy sort".to_string(),
+            ));
+        }
+        Ok((parsed, flags))
+    }
+}
+
+/// Counter of the pairs parsed, shown first in the breakdown
+const PAIRS: &str = "Total pairs processed";
+/// Time spent sorting both lists with `--strategy sort`
+const SORT: &str = "Sort";
+/// Time `--strategy heap` spent popping both heaps in order and answering
+/// both parts from them
+const HEAP_DRAIN: &str = "Heap drain";
+
+/// Performance metrics and answers for each phase of the solution
+#[derive(Default, Debug)]
+struct Metrics<T: Location> {
+    strategy: SolveStrategy,
+    /// Durations of `read`, `parse`, `part1`, `part2`, and the custom `SORT`
+    /// and `HEAP_DRAIN` phases, and the `PAIRS` count
+    timings: aoc_common::Metrics,
+    parser_threads: usize,
+    left_sort_strategy: SortStrategy,
+    right_sort_strategy: SortStrategy,
+    peak_allocated: usize,
+    total_distance: T::Distance,
+    similarity_score: T,
+}
+
+impl<T: Location> Metrics<T> {
+    /// Pairs parsed from the input
+    fn total_pairs(&self) -> usize {
+        self.timings.counter(PAIRS) as usize
+    }
+}
+
+/// Opens the puzzle input named on the command line, returning it with
+/// its path, `-` for standard input.
+///
+/// Reads from standard input when the argument is `-`, or when no argument
+/// is given and stdin is piped. Otherwise opens the given path, or without
+/// one the first input `aoc_common::input::resolve` finds for this day.
+fn open_input(arg: Option<&str>, config: &Config) -> Result<(Box<dyn BufRead>, String), AocError> {
+    let use_stdin = match arg {
+        Some("-") => true,
+        Some(_) => false,
+        None => !io::stdin().is_terminal(),
+    };
+    if use_stdin {
+        return Ok((Box::new(BufReader::new(io::stdin())), "-".to_string()));
+    }
+
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let path = aoc_common::input::resolve(Day01::DAY, arg.map(Path::new), crate_dir, config)?;
+    let file = File::open(&path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("failed to open {}: {}", path.display(), e),
+        )
+    })?;
+    Ok((Box::new(BufReader::new(file)), path.display().to_string()))
+}
 
 /// Fast solution using minimal allocations and simple operations.
 ///
 /// # Memory optimizations:
 /// - Pre-allocated vectors with capacity to avoid reallocations
-/// - Reusable String buffers for parsing to minimize allocations
+/// - Whole input read into one buffer shared by the parser threads
 /// - Direct byte manipulation instead of string operations
-/// - Single pass through input file with minimal buffering
 ///
 /// # Performance optimizations:
-/// - Single pass through file without backtracking
+/// - Parallel parsing of newline-aligned chunks for large inputs
 /// - Direct number parsing without string splits or iterator overhead
-/// - Manual byte-level parsing avoiding UTF-8 validation overhead
-/// - Avoids string allocations by reusing buffers
-/// - Uses primitive array operations instead of iterators for tight loops
+/// - Manual byte-level tokenizing without per-token allocations
 /// - Avoids unnecessary bounds checks in hot loops
-/// - Uses unstable sort for better performance on integers
-/// - Direct array indexing instead of iterator chains for calculations
+/// - Uses unstable sort for integers, or a radix sort with `--sort radix`
+/// - `--strategy heap` streams values through min-heaps instead of sorting,
+///   with peak heap allocation reported for comparing the two
 ///
 /// # Complexity:
 /// - Time: O(n log n) dominated by sorting
 /// - Space: O(n) for storing input vectors
-/// - Memory: O(1) additional space during parsing
+/// - Memory: O(file size) for the shared input buffer
 ///
-/// # Implementation details:
-/// The parser uses direct byte manipulation and manual string building to minimize
-/// allocations and avoid UTF-8 validation overhead. Numbers are parsed directly from
-/// bytes without intermediate string representations where possible.
-fn main() -> io::Result<()> {
+/// # Error Handling
+/// Errors are printed to stderr and the process exits nonzero: 2 for bad
+/// usage and 1 for anything else.
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+/// Reads the input and solves it at the integer width chosen by `--wide`.
+fn run() -> Result<(), AocError> {
     let start = Instant::now();
+    let (mut args, flags) = Args::from_cli(parse_args()?)?;
+    let config = Config::load(Path::new(env!("CARGO_MANIFEST_DIR")), flags)?;
+    args.threads = config.threads.map(NonZeroUsize::get);
+    init_parallelism(args.threads);
+    let mut json = args
+        .metrics_json
+        .as_ref()
+        .map(MetricsOutput::open)
+        .transpose()?;
+    let mut out = day_reporter(args.output, args.metrics_json.as_ref());
+    let timings = aoc_common::Metrics::new();
 
-    // Pre-allocate vectors to avoid resizing
-    let mut left_numbers = Vec::with_capacity(1000);
-    let mut right_numbers = Vec::with_capacity(1000);
-    // Single reusable buffer for number parsing
-    let mut number_buffer = String::with_capacity(16);
-
-    let file = File::open("../puzzle_input.txt")?;
-    // Use buffered reader for efficient IO
-    let mut reader = BufReader::new(file);
-    // Reuse line buffer to avoid allocations
-    let mut line = String::with_capacity(32);
-
-    // Fast parsing using manual byte operations
-    while reader.read_line(&mut line)? > 0 {
-        let bytes = line.as_bytes();
-        let mut i = 0;
-
-        // Skip leading whitespace using byte comparisons
-        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
-            i += 1;
-        }
+    // File reading into one buffer shared by the parser threads
+    let mut input = Vec::new();
+    let name = time_block!(timings, "read", {
+        let (mut reader, name) = open_input(args.input.as_deref(), &config)?;
+        reader.read_to_end(&mut input)?;
+        name
+    });
 
-        // Parse first number directly from bytes
-        while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
-            // Avoid UTF-8 validation by direct byte-to-char conversion
-            number_buffer.push(bytes[i] as char);
-            i += 1;
-        }
-        if !number_buffer.is_empty() {
-            // Parse number and clear buffer for reuse
-            left_numbers.push(number_buffer.parse::<i64>().unwrap());
-            number_buffer.clear();
-        }
+    let run = Run {
+        args: &args,
+        input: &name,
+        start,
+        json: json.as_mut(),
+    };
+    let solved = if args.wide {
+        solve::<i128>(run, &input, timings, &mut *out)
+    } else {
+        solve::<i64>(run, &input, timings, &mut *out)
+    };
+    // Parse errors are shown under the line they point at
+    solved.map_err(|e| e.in_input(&String::from_utf8_lossy(&input)))
+}
 
-        // Skip inter-number whitespace
-        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
-            i += 1;
-        }
+/// What `solve` needs about the run besides the input bytes.
+struct Run<'a> {
+    args: &'a Args,
+    /// Input path, `-` for stdin
+    input: &'a str,
+    start: Instant,
+    /// Destination of `--metrics-json`, opened once
+    json: Option<&'a mut Box<dyn Write>>,
+}
 
-        // Parse second number using same buffer
-        while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
-            number_buffer.push(bytes[i] as char);
-            i += 1;
-        }
-        if !number_buffer.is_empty() {
-            right_numbers.push(number_buffer.parse::<i64>().unwrap());
-            number_buffer.clear();
+/// Solves the parts `--part` asks for on `i64` or `i128` location IDs and
+/// reports the results to `out`.
+fn solve<T: Location + Serialize>(
+    run: Run<'_>,
+    input: &[u8],
+    timings: aoc_common::Metrics,
+    out: &mut dyn Reporter,
+) -> Result<(), AocError>
+where
+    T::Distance: Serialize,
+{
+    let Run { args, start, .. } = run;
+    let mut metrics = Metrics::<T> {
+        strategy: args.strategy,
+        timings,
+        ..Metrics::default()
+    };
+
+    let lists = match args.strategy {
+        SolveStrategy::Sort => Some(solve_sorted(args, input, &mut metrics)?),
+        SolveStrategy::Heap => {
+            solve_heap(args, input, &mut metrics)?;
+            None
         }
+    };
+    metrics.peak_allocated = PEAK_ALLOCATED.load(Ordering::Relaxed);
 
-        // Clear line buffer for reuse
-        line.clear();
+    // Report results
+    out.section("Results");
+    if args.part.reports_part1() {
+        out.answer(1, "Part 1 - Total distance", &metrics.total_distance.into());
+    }
+    if args.part.runs_part2() {
+        out.answer(
+            2,
+            "Part 2 - Similarity score",
+            &metrics.similarity_score.into(),
+        );
     }
 
-    println!("Parsing completed in {:?}", start.elapsed());
-    let sort_start = Instant::now();
+    if let Some((left_numbers, right_numbers)) = &lists {
+        report_extras(args, &metrics, left_numbers, right_numbers, out);
+    }
 
-    // Use unstable sort for better performance on integers
-    // Stable sort not needed since we only care about relative positions
-    left_numbers.sort_unstable();
-    right_numbers.sort_unstable();
+    out.section("Performance Breakdown");
+    out.note(&format!("Strategy: {}", metrics.strategy));
+    if metrics.strategy == SolveStrategy::Sort {
+        out.note(&format!("Parser threads: up to {}", metrics.parser_threads));
+        out.note(&format!(
+            "Sort strategies: {} left, {} right",
+            metrics.left_sort_strategy, metrics.right_sort_strategy
+        ));
+    }
+    metrics.timings.report_to(out);
+    out.note(&format!(
+        "Peak heap allocation: {:.2} MiB",
+        metrics.peak_allocated as f64 / (1024.0 * 1024.0)
+    ));
+    let total = start.elapsed();
+    out.phase("Total", total);
+    out.note(&format!(
+        "Throughput: {}, {}",
+        fmt_rate(input.len() as u64, total),
+        fmt_count_rate(metrics.total_pairs() as u64, total, "pairs")
+    ));
 
-    println!("Sorting completed in {:?}", sort_start.elapsed());
-    let calc_start = Instant::now();
+    out.section("Per-pair averages");
+    metrics.timings.averages_to(
+        &["parse", SORT, "part1", "part2", HEAP_DRAIN],
+        metrics.total_pairs(),
+        "pair",
+        out,
+    );
 
-    // Direct array indexing for fastest possible access
-    // Avoid iterator overhead in tight calculation loop
-    let mut total = 0i64;
-    for i in 0..left_numbers.len() {
-        total += (left_numbers[i] - right_numbers[i]).abs();
+    if let Some(writer) = run.json {
+        write_metrics_json(&mut **writer, run.input, args, &metrics, start.elapsed())?;
     }
+    Ok(())
+}
+
+/// Serializes a duration as its whole number of nanoseconds.
+fn nanoseconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_nanos())
+}
+
+/// The JSON object written by `--metrics-json`, with the same answer and
+/// timing fields as every day's.
+#[derive(Serialize)]
+#[serde(bound(serialize = "T: Serialize, T::Distance: Serialize"))]
+struct MetricsReport<'a, T: Location> {
+    /// Input path, `-` for stdin
+    input: &'a str,
+    /// Seconds since the Unix epoch when the run finished
+    timestamp: u64,
+    /// Part 1 answer, `null` if `--part` skipped it
+    part1: Option<T::Distance>,
+    /// Part 2 answer, `null` if `--part` skipped it
+    part2: Option<T>,
+    #[serde(serialize_with = "nanoseconds")]
+    total_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    file_read_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    parsing_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    sort_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    part1_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    part2_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    heap_drain_time: Duration,
+    strategy: String,
+    parser_threads: usize,
+    total_pairs: usize,
+    peak_allocated: usize,
+}
+
+/// Writes the metrics of the run as one JSON object on one line.
+fn write_metrics_json<T: Location + Serialize>(
+    writer: &mut dyn Write,
+    input: &str,
+    args: &Args,
+    metrics: &Metrics<T>,
+    total_time: Duration,
+) -> Result<(), AocError>
+where
+    T::Distance: Serialize,
+{
+    let timings = &metrics.timings;
+    let report = MetricsReport::<T> {
+        input,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs()),
+        part1: args.part.reports_part1().then_some(metrics.total_distance),
+        part2: args.part.runs_part2().then_some(metrics.similarity_score),
+        total_time,
+        file_read_time: timings.elapsed("read"),
+        parsing_time: timings.elapsed("parse"),
+        sort_time: timings.elapsed(SORT),
+        part1_time: timings.elapsed("part1"),
+        part2_time: timings.elapsed("part2"),
+        heap_drain_time: timings.elapsed(HEAP_DRAIN),
+        strategy: metrics.strategy.to_string(),
+        parser_threads: metrics.parser_threads,
+        total_pairs: metrics.total_pairs(),
+        peak_allocated: metrics.peak_allocated,
+    };
+    serde_json::to_writer(&mut *writer, &report).map_err(io::Error::from)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Parses into vectors, sorts them, and solves both parts.
+///
+/// # Returns
+/// The sorted lists, for the optional reports.
+fn solve_sorted<T: Location>(
+    args: &Args,
+    input: &[u8],
+    metrics: &mut Metrics<T>,
+) -> Result<(Vec<T>, Vec<T>), AocError> {
+    metrics.parser_threads = parallelism();
+    let timings = &metrics.timings;
+
+    // Parsing
+    let (mut left_numbers, mut right_numbers) = time_block!(timings, "parse", {
+        parse_bytes(input, metrics.parser_threads, args.columns)?
+    });
+    timings.count(PAIRS, left_numbers.len() as u64);
+
+    // Sorting
+    let (left_sort, right_sort) = time_block!(timings, SORT, {
+        (
+            sort_numbers(&mut left_numbers, args.sort),
+            sort_numbers(&mut right_numbers, args.sort),
+        )
+    });
+
+    // Part 1
+    let distance = if args.part.reports_part1() {
+        time_block!(timings, "part1", {
+            total_distance(&mut left_numbers, &mut right_numbers)?
+        })
+    } else {
+        T::Distance::default()
+    };
 
-    println!("Calculation completed in {:?}", calc_start.elapsed());
-    println!("Total time: {:?}", start.elapsed());
-    println!("Result: {}", total);
+    // Part 2, reusing the lists sorted for Part 1
+    let score = if args.part.runs_part2() {
+        time_block!(timings, "part2", {
+            similarity_score(&left_numbers, &right_numbers)?
+        })
+    } else {
+        T::default()
+    };
+
+    metrics.left_sort_strategy = left_sort;
+    metrics.right_sort_strategy = right_sort;
+    metrics.total_distance = distance;
+    metrics.similarity_score = score;
+
+    Ok((left_numbers, right_numbers))
+}
+
+/// Parses into min-heaps and drains them to solve both parts at once.
+fn solve_heap<T: Location>(
+    args: &Args,
+    input: &[u8],
+    metrics: &mut Metrics<T>,
+) -> Result<(), AocError> {
+    metrics.parser_threads = 1;
+    let timings = &metrics.timings;
+    let (left_heap, right_heap) =
+        time_block!(timings, "parse", { parse_into_heaps(input, args.columns)? });
+    timings.count(PAIRS, left_heap.len() as u64);
+
+    // Both parts come out of one drain
+    let answers = time_block!(timings, HEAP_DRAIN, { drain_heaps(left_heap, right_heap)? });
+    (metrics.total_distance, metrics.similarity_score) = answers;
 
     Ok(())
 }
+
+/// Reports the optional `--stats`, `--dupes`, `--histogram`, and `--top`
+/// sections.
+fn report_extras<T: Location>(
+    args: &Args,
+    metrics: &Metrics<T>,
+    left_numbers: &[T],
+    right_numbers: &[T],
+    out: &mut dyn Reporter,
+) {
+    if args.stats {
+        out.section("Distance Statistics");
+        match distance_stats(left_numbers, right_numbers) {
+            Some(stats) => {
+                out.note(&format!("Min: {}", stats.min));
+                out.note(&format!("Max: {}", stats.max));
+                out.note(&format!("Mean: {:.3}", stats.mean));
+                out.note(&format!("Median: {}", stats.median));
+                out.note(&format!("95th percentile: {}", stats.p95));
+                out.note(&format!(
+                    "Largest distance: index {} ({} vs {})",
+                    stats.max_index, stats.max_left, stats.max_right
+                ));
+            }
+            None => out.note("No pairs"),
+        }
+    }
+
+    if args.dupes {
+        let dupes = duplicate_stats(left_numbers, right_numbers);
+        out.section("Duplicate Statistics");
+        out.note(&format!(
+            "Equal sorted pairs: {} of {}",
+            dupes.equal_pairs,
+            metrics.total_pairs()
+        ));
+        out.note(&format!("Values in both lists: {}", dupes.shared_values));
+        match dupes.most_common {
+            Some((value, left_count, right_count)) => out.note(&format!(
+                "Most frequent value: {} ({} left, {} right)",
+                value, left_count, right_count
+            )),
+            None => out.note("Most frequent value: none"),
+        }
+    }
+
+    if let Some(buckets) = args.histogram {
+        out.section("Distance Histogram");
+        let distances = pair_distances(left_numbers, right_numbers);
+        out.notes(&render_histogram(&distances, buckets));
+    }
+
+    if let Some(n) = args.top {
+        out.section("Largest Distances");
+        for (rank, top) in top_distances(left_numbers, right_numbers, n)
+            .iter()
+            .enumerate()
+        {
+            out.note(&format!(
+                "#{}: index {} ({} vs {}), distance {}",
+                rank + 1,
+                top.index,
+                top.left,
+                top.right,
+                top.distance
+            ));
+        }
+    }
+}
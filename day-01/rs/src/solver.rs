@@ -0,0 +1,174 @@
+//! Day 1 behind the shared `Solver` interface.
+//!
+//! Parsing also sorts both lists, so Part 1 pairs them up directly. Part 2
+//! counts the right list with an `aoc_common::Counter`, as the puzzle
+//! states it; the binary keeps the merge over its sorted lists, which
+//! skips the hashing and is what its timings report.
+//!
+//! The lists are parsed with `parse_bytes`, like the binary's, so the
+//! runner takes the same inputs: blank, tab, or comma separated, with the
+//! `cols` option choosing the columns as `--cols` does, and large inputs
+//! parsed on the context's threads.
+
+use aoc_common::{Answer, AocContext, AocError, Solver};
+
+use super::{
+    counted_similarity_score, parse_bytes, sort_numbers, sorted_total_distance, Columns,
+    SortStrategy,
+};
+
+/// The left and right lists of location IDs, each sorted ascending.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SortedLists<T> {
+    pub left: Vec<T>,
+    pub right: Vec<T>,
+}
+
+/// Options `Day01` reads from its context
+const OPTIONS: [&str; 1] = ["cols"];
+
+/// Day 1 with the puzzle's rules: two columns of `i64` IDs.
+///
+/// Options, named like the binary's flags:
+/// - `cols=A,B` pairs up columns A and B, 0-based, instead of the first two
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Day01;
+
+impl Solver for Day01 {
+    const DAY: u8 = 1;
+    type Parsed<'a> = SortedLists<i64>;
+    type Error = AocError;
+
+    fn parse<'a>(
+        &self,
+        input: &'a str,
+        ctx: &mut AocContext<'_>,
+    ) -> Result<Self::Parsed<'a>, Self::Error> {
+        ctx.expect_options(Self::DAY, &OPTIONS)?;
+        let columns = ctx.option::<Columns>("cols")?.unwrap_or_default();
+        let (mut left, mut right) = parse_bytes(input.as_bytes(), ctx.threads(), columns)?;
+        sort_numbers(&mut left, SortStrategy::default());
+        sort_numbers(&mut right, SortStrategy::default());
+        Ok(SortedLists { left, right })
+    }
+
+    fn part1(
+        &self,
+        lists: &Self::Parsed<'_>,
+        _ctx: &mut AocContext<'_>,
+    ) -> Result<Answer, Self::Error> {
+        Ok(sorted_total_distance(&lists.left, &lists.right)?.into())
+    }
+
+    fn part2(
+        &self,
+        lists: &Self::Parsed<'_>,
+        _ctx: &mut AocContext<'_>,
+    ) -> Result<Answer, Self::Error> {
+        Ok(counted_similarity_score(&lists.left, &lists.right)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::similarity_score;
+    use aoc_common::{run_solver, solve, Config, RecordingReporter};
+
+    const EXAMPLE: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+    /// Parses `input` in a context without options.
+    fn parse(input: &str) -> SortedLists<i64> {
+        let mut out = RecordingReporter::new();
+        Day01
+            .parse(input, &mut AocContext::new(Config::default(), &mut out))
+            .unwrap()
+    }
+
+    #[test]
+    fn solves_the_example() {
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        let lists = Day01.parse(EXAMPLE, &mut ctx).unwrap();
+        assert_eq!(Day01.part1(&lists, &mut ctx).unwrap(), Answer::U64(11));
+        assert_eq!(Day01.part2(&lists, &mut ctx).unwrap(), Answer::I64(31));
+    }
+
+    #[test]
+    fn solves_mixed_signs_through_solve() {
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        let input = include_str!("../../mixed_signs_input.txt");
+        let solution = solve(&Day01, input, &mut ctx).unwrap();
+        assert_eq!(solution.day, 1);
+        assert_eq!(solution.part1, Answer::U64(10));
+        assert_eq!(solution.part2, Answer::I64(-11));
+    }
+
+    #[test]
+    fn counting_agrees_with_the_merge() {
+        for input in [EXAMPLE, include_str!("../../mixed_signs_input.txt")] {
+            let lists = parse(input);
+            assert_eq!(
+                counted_similarity_score(&lists.left, &lists.right),
+                similarity_score(&lists.left, &lists.right)
+            );
+        }
+        // Counting needs no sorting
+        assert_eq!(counted_similarity_score(&[3, 4, 2], &[3, 3, 4, 9]), Ok(10));
+        assert_eq!(counted_similarity_score::<i64>(&[5], &[]), Ok(0));
+    }
+
+    #[test]
+    fn reports_only_the_two_answers() {
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        run_solver(&Day01, EXAMPLE, &mut ctx).unwrap();
+        assert_eq!(out.answers, [(1, "11".to_string()), (2, "31".to_string())]);
+        assert!(out.notes.is_empty());
+        assert_eq!(out.phases.len(), 4);
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        let error = solve(&Day01, "3 4\n4 x\n", &mut ctx).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, columns 3-3: could not parse `x` as an integer"
+        );
+
+        let mut ctx = ctx.with_option("cols", "3");
+        assert_eq!(
+            solve(&Day01, EXAMPLE, &mut ctx).unwrap_err().to_string(),
+            "option `cols`: `3`: expected two column indices like 0,2"
+        );
+
+        let mut ctx = ctx.with_option("cols", "0,1").with_option("wide", "");
+        assert_eq!(
+            solve(&Day01, EXAMPLE, &mut ctx).unwrap_err().to_string(),
+            "unknown option `wide` for day 1; expected one of: cols"
+        );
+    }
+
+    #[test]
+    fn parses_the_inputs_the_binary_does() {
+        let mut out = RecordingReporter::new();
+        let mut ctx = AocContext::new(Config::default(), &mut out);
+        for input in [
+            "3, 4\n4, 3\n2, 5\n1, 3\n3, 9\n3, 3\n",
+            "3\t4\r\n4 \t 3\r\n2\t5\r\n1\t3\r\n3\t9\r\n3\t3\r\n",
+        ] {
+            let solution = solve(&Day01, input, &mut ctx).unwrap();
+            assert_eq!(solution.part1, Answer::U64(11), "{:?}", input);
+            assert_eq!(solution.part2, Answer::I64(31), "{:?}", input);
+        }
+
+        let mut ctx = ctx.with_option("cols", "0,2");
+        let input = include_str!("../../three_columns_input.txt");
+        let solution = solve(&Day01, input, &mut ctx).unwrap();
+        assert_eq!(solution.part1, Answer::U64(11));
+        assert_eq!(solution.part2, Answer::I64(31));
+    }
+}
@@ -0,0 +1,77 @@
+//! The binary's command line, run as a user would run it.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the built `day-01` with `args` from the fixtures directory.
+fn day01(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_day-01"))
+        .args(args)
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+        .output()
+        .expect("day-01 runs")
+}
+
+/// Runs the built `day-01` with `args`, piping `input` to it as stdin.
+fn day01_stdin(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_day-01"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("day-01 runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().expect("day-01 finishes")
+}
+
+#[test]
+fn help_describes_the_day_and_its_own_options() {
+    let output = day01(&["--help"]);
+    assert!(output.status.success());
+    let help = String::from_utf8(output.stdout).unwrap();
+    assert!(help.starts_with("Day 1: Historian Hysteria."), "{}", help);
+    assert!(
+        help.contains("      --strategy <NAME>        Sort the lists (sort) or stream them through heaps (heap) [default: sort]\n"),
+        "{}",
+        help
+    );
+}
+
+#[test]
+fn fails_on_a_malformed_line_without_panicking() {
+    let output = day01_stdin(&["-"], "3   4\n4   4x3\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.starts_with("Error: line 2, columns 5-7: could not parse `4x3` as an integer\n"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+}
+
+#[test]
+fn wide_solves_ids_past_i64() {
+    let input = "1180591620717411303424   3\n4   1180591620717411303420\n";
+    let output = day01_stdin(&["-", "--wide", "--quiet"], input);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "5\n0\n");
+
+    let output = day01_stdin(&["-", "--quiet"], input);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.starts_with(
+            "Error: line 1, columns 1-22: `1180591620717411303424` is out of range for i64\n"
+        ),
+        "{}",
+        stderr
+    );
+}
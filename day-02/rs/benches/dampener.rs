@@ -0,0 +1,68 @@
+//! Compares day 2's dampener on short reports, answered from bitmasks,
+//! with the scan over the differences that longer reports take.
+//!
+//! Run with `cargo bench -p day-02 --bench dampener`. Reports hold 5 to 8
+//! levels like the puzzle's, stepping mostly one way, and only the ones
+//! that fail Part 1 are kept, since those are all the dampener sees. Both
+//! paths must fix the same reports before they are timed.
+
+use aoc_common::values::Values;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use day_02::{
+    dampener_removal, validate_sequence, validate_sequence_with_dampener, StepBounds,
+    ValidationResult, Violation,
+};
+use std::hint::black_box;
+
+/// Puzzle-shaped reports generated, before keeping the unsafe ones
+const REPORTS: usize = 1_000_000;
+
+/// The unsafe reports among `REPORTS`, each with its first violation.
+fn unsafe_reports(bounds: StepBounds) -> Vec<(Vec<i32>, Violation)> {
+    let mut values = Values(0x2024_1202);
+    (0..REPORTS)
+        .filter_map(|_| {
+            let direction = if values.below(2) == 0 { 1 } else { -1 };
+            let mut level = values.below(100) as i32;
+            let levels: Vec<i32> = (0..5 + values.below(4))
+                .map(|_| {
+                    let current = level;
+                    level += match values.below(8) {
+                        0 => values.below(9) as i32 - 4,
+                        _ => direction * (1 + values.below(3) as i32),
+                    };
+                    current
+                })
+                .collect();
+            match validate_sequence(&levels, bounds) {
+                ValidationResult::Valid => None,
+                ValidationResult::Invalid(violation) => Some((levels, violation)),
+            }
+        })
+        .collect()
+}
+
+fn dampener(c: &mut Criterion) {
+    let bounds = StepBounds::default();
+    let reports = unsafe_reports(bounds);
+    let masks = |(levels, violation): &(Vec<i32>, Violation)| {
+        validate_sequence_with_dampener(black_box(levels), *violation, bounds).is_valid()
+    };
+    let scan = |(levels, violation): &(Vec<i32>, Violation)| {
+        dampener_removal(black_box(levels), *violation, bounds).is_some()
+    };
+    assert!(reports.iter().map(masks).eq(reports.iter().map(scan)));
+
+    let mut group = c.benchmark_group("dampener");
+    group.throughput(Throughput::Elements(reports.len() as u64));
+    group.bench_function("scan", |b| {
+        b.iter(|| reports.iter().filter(|report| scan(report)).count())
+    });
+    group.bench_function("bitmasks", |b| {
+        b.iter(|| reports.iter().filter(|report| masks(report)).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dampener);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+//! Compares parsing day 2's reports into inline `Report`s with
+//! `aoc_common::lines_of_ints`, which gives each row its own `Vec`.
+//!
+//! Run with `cargo bench -p day-02 --bench parse`. Reports hold 5 to 8
+//! levels like the puzzle's, with one in a hundred holding 40 to spill the
+//! inline buffer. Both keep every report, so the allocations are what
+//! differ, and both must parse the same levels before they are timed.
+
+use aoc_common::values::Values;
+use aoc_common::{lines_of_ints, ParseInput};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use day_02::Reports;
+use std::hint::black_box;
+
+/// Reports in the input
+const REPORTS: usize = 200_000;
+
+/// `REPORTS` reports, one per line, with levels separated by `delimiter`.
+fn input(delimiter: &str) -> String {
+    let mut values = Values(0x2024_1202);
+    let mut input = String::new();
+    for _ in 0..REPORTS {
+        let len = match values.below(100) {
+            0 => 40,
+            _ => 5 + values.below(4),
+        };
+        let levels: Vec<String> = (0..len).map(|_| values.below(100).to_string()).collect();
+        input.push_str(&levels.join(delimiter));
+        input.push('\n');
+    }
+    input
+}
+
+fn parse(c: &mut Criterion) {
+    let spaced = input(" ");
+    let reports = Reports::parse(&spaced).unwrap();
+    let rows = lines_of_ints::<i32>(&spaced).unwrap();
+    assert!(reports
+        .0
+        .iter()
+        .map(|r| &r[..])
+        .eq(rows.iter().map(|r| &r[..])));
+
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Elements(REPORTS as u64));
+    group.bench_function("lines_of_ints", |b| {
+        b.iter(|| lines_of_ints::<i32>(black_box(&spaced)).unwrap())
+    });
+    group.bench_function("Reports", |b| {
+        b.iter(|| Reports::parse(black_box(&spaced)).unwrap())
+    });
+    // The same reports with commas, which `lines_of_ints` doesn't read
+    let commas = input(", ");
+    assert_eq!(Reports::parse(&commas).unwrap(), reports);
+    group.bench_function("Reports, commas", |b| {
+        b.iter(|| Reports::parse(black_box(&commas)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
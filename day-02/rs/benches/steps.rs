@@ -0,0 +1,62 @@
+//! Times day 2's Part 1 check on puzzle-shaped reports at both level
+//! widths.
+//!
+//! Run with `cargo bench -p day-02 --bench steps`. Reports hold 5 to 8
+//! levels, stepping mostly one way so most are safe, and `validate_sequence`
+//! finds each one's first violation with the puzzle's rules; `--wide`
+//! swaps `i32` levels for `i64`, whose differences are `i128`.
+
+use aoc_common::values::Values;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use day_02::{validate_sequence, Level, StepBounds};
+use std::hint::black_box;
+
+/// Reports per width
+const REPORTS: usize = 1_000_000;
+
+fn reports() -> Vec<Vec<i32>> {
+    let mut values = Values(0x2024_1258);
+    (0..REPORTS)
+        .map(|_| {
+            let direction = if values.below(2) == 0 { 1 } else { -1 };
+            let mut level = values.below(100) as i32;
+            (0..5 + values.below(4))
+                .map(|_| {
+                    let current = level;
+                    level += match values.below(8) {
+                        0 => values.below(9) as i32 - 4,
+                        _ => direction * (1 + values.below(3) as i32),
+                    };
+                    current
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// How many of `reports` are safe.
+fn safe<T: Level>(reports: &[Vec<T>], bounds: StepBounds) -> usize {
+    reports
+        .iter()
+        .filter(|levels| validate_sequence(black_box(levels), bounds).is_valid())
+        .count()
+}
+
+fn steps(c: &mut Criterion) {
+    let bounds = StepBounds::default();
+    let narrow = reports();
+    let wide: Vec<Vec<i64>> = narrow
+        .iter()
+        .map(|levels| levels.iter().map(|&level| i64::from(level)).collect())
+        .collect();
+    assert_eq!(safe(&narrow, bounds), safe(&wide, bounds));
+
+    let mut group = c.benchmark_group("validate_sequence");
+    group.throughput(Throughput::Elements(REPORTS as u64));
+    group.bench_function("i32", |b| b.iter(|| safe(&narrow, bounds)));
+    group.bench_function("i64", |b| b.iter(|| safe(&wide, bounds)));
+    group.finish();
+}
+
+criterion_group!(benches, steps);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+//! Feeds arbitrary bytes to `LineParser`, one line at a time.
+//!
+//! Every line must either parse into a report or fail with a `ParseError`
+//! pointing inside that line; nothing may panic. A parsed report must also
+//! round-trip: written back out with either delimiter and parsed by a fresh
+//! parser, it gives the same levels, and parsing it as `i64` agrees.
+//!
+//! Run with `cargo fuzz run line_parser` from `day-02/rs`; the seeds in
+//! `corpus/line_parser` cover the example input and blank-handling edge
+//! cases such as double spaces, trailing whitespace, and CRLF endings.
+
+#![no_main]
+
+use day_02::{LineParser, ParseError, Report};
+use libfuzzer_sys::fuzz_target;
+
+/// Parses `bytes` as a single line with a parser that hasn't seen any input.
+fn parse_fresh(bytes: &[u8]) -> Result<Report<i64>, ParseError> {
+    let mut numbers = Report::new();
+    LineParser::default().parse_line(bytes, 1, &mut numbers)?;
+    Ok(numbers)
+}
+
+fuzz_target!(|data: &[u8]| {
+    // One parser for the whole input, so delimiter tracking across lines
+    // is exercised too
+    let mut parser = LineParser::default();
+    let mut numbers: Report = Report::new();
+    for (i, line) in data.split(|&b| b == b'\n').enumerate() {
+        let line_number = i + 1;
+        numbers.clear();
+        match parser.parse_line(line, line_number, &mut numbers) {
+            Ok(()) => {
+                let wide = parse_fresh(line).expect("levels that fit i32 fit i64");
+                assert!(numbers.iter().map(|&n| i64::from(n)).eq(wide));
+
+                for separator in [" ", ", "] {
+                    let levels: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+                    let written = levels.join(separator);
+                    let reparsed = parse_fresh(written.as_bytes())
+                        .unwrap_or_else(|e| panic!("`{}` failed to re-parse: {}", written, e));
+                    assert!(
+                        numbers.iter().map(|&n| i64::from(n)).eq(reparsed),
+                        "`{}` re-parsed differently",
+                        written
+                    );
+                }
+            }
+            Err(error) => {
+                assert_eq!(error.line, line_number);
+                assert!(
+                    1 <= error.columns.start
+                        && error.columns.start < error.columns.end
+                        && error.columns.end <= line.len() + 1,
+                    "columns {:?} outside a line of {} bytes",
+                    error.columns,
+                    line.len()
+                );
+                // Rendering the error must not panic either
+                let _ = error.to_string();
+            }
+        }
+    }
+});
@@ -0,0 +1,99 @@
+//! Integer types reports can be validated as.
+//!
+//! The puzzle's levels are small numbers parsed as `i32`, but
+//! other tooling may hand over `i64` levels, or levels produced lazily by an
+//! iterator. Parsing, validation, and the dampener are all generic over
+//! `Level`, so those callers, and the binary's `--wide` mode, share the same
+//! rules without converting or collecting first.
+
+use aoc_common::Integer;
+use std::hash::Hash;
+
+use super::StepBounds;
+
+/// Signed integer type holding one level.
+///
+/// Parsing comes from `aoc_common::Integer`, whose `ZERO` doubles as the
+/// plateau difference.
+pub trait Level: Integer + Hash + Send + Sync {
+    /// Twice as wide as the level, so the difference between any two levels
+    /// fits exactly: `i32::MIN` and `i32::MAX` are 4294967295 apart.
+    type Diff: Step;
+
+    /// Returns `next - self`, exactly.
+    fn step_to(self, next: Self) -> Self::Diff;
+}
+
+/// Signed integer type holding the difference between two levels.
+pub trait Step: Integer + Send + Sync {
+    /// Returns `|self|`.
+    fn magnitude(self) -> Self;
+
+    /// Returns a difference as a step in the given direction: itself when
+    /// `increasing`, negated otherwise. A step that moves the right way by
+    /// an allowed amount is then within the same positive bounds either way.
+    fn towards(self, increasing: bool) -> Self;
+
+    /// Converts a step bound. Bounds are positive `i32`s, so this is exact
+    /// for every implementing type.
+    fn from_bound(bound: i32) -> Self;
+}
+
+macro_rules! impl_level {
+    ($($signed:ty => $diff:ty),*) => {
+        $(
+            impl Level for $signed {
+                type Diff = $diff;
+
+                #[inline]
+                fn step_to(self, next: Self) -> $diff {
+                    <$diff>::from(next) - <$diff>::from(self)
+                }
+            }
+
+            // Differences of levels are at most twice the level's range, so
+            // none of these can overflow
+            impl Step for $diff {
+                #[inline]
+                fn magnitude(self) -> Self {
+                    self.abs()
+                }
+
+                #[inline]
+                fn towards(self, increasing: bool) -> Self {
+                    if increasing {
+                        self
+                    } else {
+                        -self
+                    }
+                }
+
+                #[inline]
+                fn from_bound(bound: i32) -> Self {
+                    bound.into()
+                }
+            }
+        )*
+    };
+}
+
+impl_level!(i32 => i64, i64 => i128);
+
+/// Bounds converted once to the difference type, for use inside hot loops.
+#[derive(Clone, Copy)]
+pub(crate) struct LevelBounds<T> {
+    pub(crate) min: T,
+    pub(crate) max: T,
+    pub(crate) allow_plateaus: bool,
+}
+
+impl<T: Step> From<StepBounds> for LevelBounds<T> {
+    #[inline]
+    fn from(bounds: StepBounds) -> Self {
+        LevelBounds {
+            min: T::from_bound(bounds.min),
+            max: T::from_bound(bounds.max),
+            allow_plateaus: bounds.allow_plateaus,
+        }
+    }
+}
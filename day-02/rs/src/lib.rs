@@ -0,0 +1,2584 @@
+//! Day 2: Red-Nosed Reports - Parts 1 & 2
+//!
+//! This solution validates sequences with two different rule sets:
+//! Part 1: Basic validation of strictly increasing/decreasing sequences
+//! Part 2: Allows removal of one number to make an invalid sequence valid,
+//! or up to N numbers with `--dampener N`
+//!
+//! `Day02` implements `aoc_common::Solver`, parsing the reports once and
+//! answering both parts from them with the puzzle's rules.
+//!
+//! Reports may hold any number of levels. The fixture
+//! `day-02/mixed_lengths_input.txt` has reports of 2, 5, and 9 levels, of
+//! which 1 is safe and 3 are safe with the dampener.
+//!
+//! Levels are separated by whitespace or by commas, detected from the first
+//! line that shows a delimiter. The fixtures `day-02/example_input.txt` and
+//! `day-02/example_comma_input.txt` hold the puzzle example in each format
+//! and both give 2 and 4; `day-02/mixed_delimiters_input.txt` switches
+//! format on line 3 and is rejected there.
+//!
+//! Reports may also be very long. The fixture `day-02/long_reports_input.txt`
+//! holds three reports of 100,000 levels: one safe, one fixed by removing a
+//! single spike, and one with two violations far apart that needs
+//! `--dampener 2`, so it gives 1 and 2, or 3 with two removals. Levels are
+//! `i32` by default; `--wide` parses them as `i64` for values beyond that.
+//!
+//! Levels may be negative, written with a leading `-`; a bare `-` is a
+//! parse error. The fixture `day-02/negative_levels_input.txt` has reports
+//! crossing zero and at the `i32` limits, whose differences are taken as
+//! `i64` so they fit: 5 are safe and 7 with the dampener.
+//!
+//! Each failing report has one first `Violation`, and the binary counts
+//! failures by its kind. The fixture `day-02/violations_input.txt` has one
+//! safe report, and 2 plateaus, 3 jumps too large, and 1 direction change
+//! as first violations; 5 of its 7 reports are safe with the dampener.
+//! `dampener_fixes` gives every level whose removal fixes a report, and the
+//! lowest is the one counted by index. In `day-02/removal_index_input.txt`,
+//! the 7 fixed reports remove index 0 once, 1 twice, 2 twice, 3 once, and 4
+//! once, ties going to the lower index.
+//!
+//! With `--allow-plateaus`, equal adjacent levels pass: they are exempt from
+//! the minimum step and the direction comes from the first step that moves,
+//! so `1 1 2 3` and `1 1 1 1` are safe. The dampener and larger removal
+//! budgets follow the same rule.
+//!
+//! With `--dedupe`, identical reports are validated once and counted by
+//! multiplicity. The fixture `day-02/duplicates_input.txt` repeats safe,
+//! fixable, and unsafe reports: 8 reports, 4 unique, 3 safe, 6 with the
+//! dampener.
+//!
+//! Performance optimizations:
+//! - Inline validation functions
+//! - Early returns for known cases
+//! - Reuse of vectors to minimize allocations
+//! - Reports stored inline in a `SmallVec`, so typical lines don't allocate
+//! - The binary's dampener takes its scratch slices from a per-thread
+//!   `aoc_common::Arena` (`validate_with_removals_in`), so long reports stop
+//!   allocating once each thread's arena fits the longest
+//! - Both rules checked on adjacent differences, computed as levels arrive
+//! - Reports validated in parallel with rayon (`--serial` to disable)
+//! - For Part 2: Skip rechecking already valid sequences
+//! - Optionally validate duplicate reports once (`--dedupe`)
+//! - For Part 2: Only try removals next to the first violation; removing a
+//!   level merges two differences, so candidates are checked without
+//!   rebuilding the sequence or rescanning the valid prefix
+//! - For Part 2 on reports of up to 9 levels: one bitmask per direction of
+//!   the differences that fit it, so the dampener is a few mask operations
+//!
+//! Time Complexity (per sequence):
+//! - Part 1: O(k) where k is sequence length
+//! - Part 2: O(k), at most three candidate removals
+//! - Larger dampeners: O(k * r) for r allowed removals, one pass keeping
+//!   the fewest removals that reach each level
+//!
+//! Space Complexity:
+//! - O(1) extra for validation; the dampener keeps the differences in an
+//!   inline buffer that only spills to the heap for long reports
+//! - Larger dampeners: O(k), two removal counts per level and no recursion
+
+mod level;
+mod solver;
+
+pub use level::{Level, Step};
+pub use solver::{Day02, Reports};
+
+use aoc_common::{parse_int, AocError, Arena, IntErrorKind, Integer, Scanner};
+use level::LevelBounds;
+use smallvec::{smallvec, SmallVec};
+use std::collections::hash_map::{Entry, HashMap};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{AddAssign, Range};
+
+/// Levels of one report, stored inline up to 8 levels since puzzle reports
+/// hold 5 to 8, and spilling to the heap only for longer ones
+pub type Report<T = i32> = SmallVec<[T; 8]>;
+
+/// Inclusive bounds on the magnitude of each adjacent difference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepBounds {
+    pub min: i32,
+    pub max: i32,
+    /// Let equal adjacent levels through. Such a step is exempt from `min`
+    /// and has no direction, so the direction comes from the first step
+    /// that moves.
+    pub allow_plateaus: bool,
+}
+
+impl Default for StepBounds {
+    /// The puzzle's rule: adjacent levels differ by at least 1 and at most 3
+    fn default() -> Self {
+        StepBounds {
+            min: 1,
+            max: 3,
+            allow_plateaus: false,
+        }
+    }
+}
+
+impl fmt::Display for StepBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)?;
+        if self.allow_plateaus {
+            write!(f, ", plateaus allowed")?;
+        }
+        Ok(())
+    }
+}
+
+impl StepBounds {
+    /// Checks that some step could satisfy the bounds.
+    ///
+    /// # Errors
+    /// Returns a `BoundsError` if `min` is below 1 or `max` below `min`.
+    pub fn check(self) -> Result<Self, BoundsError> {
+        if self.min < 1 {
+            Err(BoundsError::MinTooSmall { min: self.min })
+        } else if self.max < self.min {
+            Err(BoundsError::MaxBelowMin {
+                min: self.min,
+                max: self.max,
+            })
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// Step bounds that `StepBounds::check` rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundsError {
+    /// The smallest step is below 1; equal levels pass with
+    /// `allow_plateaus` instead
+    MinTooSmall { min: i32 },
+    /// The largest step is below the smallest
+    MaxBelowMin { min: i32, max: i32 },
+}
+
+impl BoundsError {
+    /// Describes the error naming the bounds `min` and `max`, as the
+    /// caller spells them, such as `--min-step` on the command line.
+    pub fn describe(&self, min: &str, max: &str) -> String {
+        match *self {
+            BoundsError::MinTooSmall { min: value } => {
+                format!("{} must be at least 1 (got {})", min, value)
+            }
+            BoundsError::MaxBelowMin {
+                min: min_value,
+                max: max_value,
+            } => format!(
+                "{} must be at least {} ({} < {})",
+                max, min, max_value, min_value
+            ),
+        }
+    }
+}
+
+/// Why a sequence failed validation.
+///
+/// Each variant's `index` names the first violating pair, between the levels
+/// at `index` and `index + 1`. Differences are exact, in `Level::Diff`, so
+/// `i64` for parsed `i32` reports.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Violation<T: Level = i32> {
+    /// Two adjacent levels are equal
+    Plateau { index: usize },
+    /// Adjacent levels move in the right direction but by less than the
+    /// minimum step, only possible when it is above 1
+    StepTooSmall { index: usize, diff: T::Diff },
+    /// Adjacent levels move in the right direction but by more than the
+    /// maximum step
+    JumpTooLarge { index: usize, diff: T::Diff },
+    /// Adjacent levels move against the direction set by the first pair,
+    /// or by the first that moves when plateaus are allowed
+    DirectionChange { index: usize },
+}
+
+impl<T: Level> Violation<T> {
+    /// Index of the first violating pair
+    pub fn index(self) -> usize {
+        match self {
+            Violation::Plateau { index }
+            | Violation::StepTooSmall { index, .. }
+            | Violation::JumpTooLarge { index, .. }
+            | Violation::DirectionChange { index } => index,
+        }
+    }
+}
+
+/// Represents the validation result of a sequence.
+/// Using an enum rather than bool provides more clarity and extensibility.
+#[derive(Debug, PartialEq)]
+pub enum ValidationResult<T: Level = i32> {
+    /// Sequence follows all safety rules
+    Valid,
+    /// Sequence violates at least one safety rule
+    Invalid(Violation<T>),
+}
+
+impl<T: Level> ValidationResult<T> {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, ValidationResult::Valid)
+    }
+}
+
+/// A violation described in terms of the levels it involves, such as
+/// `levels[2]=7 → levels[3]=3 decreases by 4, more than 3`.
+pub struct Explanation<'a, T: Level = i32> {
+    pub numbers: &'a [T],
+    pub violation: Violation<T>,
+    pub bounds: StepBounds,
+}
+
+impl<T: Level> fmt::Display for Explanation<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pair = |f: &mut fmt::Formatter<'_>, i: usize| {
+            write!(
+                f,
+                "levels[{}]={} → levels[{}]={}",
+                i,
+                self.numbers[i],
+                i + 1,
+                self.numbers[i + 1]
+            )
+        };
+        let movement = |diff: T::Diff| {
+            if diff > T::Diff::ZERO {
+                "increases"
+            } else {
+                "decreases"
+            }
+        };
+
+        pair(f, self.violation.index())?;
+        match self.violation {
+            Violation::Plateau { .. } => write!(f, " stays the same"),
+            Violation::StepTooSmall { diff, .. } => write!(
+                f,
+                " {} by {}, less than {}",
+                movement(diff),
+                diff.magnitude(),
+                self.bounds.min
+            ),
+            Violation::JumpTooLarge { diff, .. } => write!(
+                f,
+                " {} by {}, more than {}",
+                movement(diff),
+                diff.magnitude(),
+                self.bounds.max
+            ),
+            Violation::DirectionChange { index } => {
+                let step = |i: usize| self.numbers[i].step_to(self.numbers[i + 1]);
+                let diff = step(index);
+                write!(f, " {} by {}, but ", movement(diff), diff.magnitude())?;
+                // The pair that set the direction, after any allowed plateaus
+                let first = (0..index).find(|&i| step(i) != T::Diff::ZERO).unwrap_or(0);
+                pair(f, first)?;
+                write!(f, " {}", movement(step(first)))
+            }
+        }
+    }
+}
+
+/// Adjacent differences of one report, inline like `Report`
+type Diffs<T> = SmallVec<[T; 8]>;
+
+/// Validates a sequence given as its adjacent differences.
+///
+/// Both rules are statements about the differences alone: they must share
+/// a sign, set by the first one, and their magnitudes must be within
+/// `bounds`. Allowed plateaus are skipped, so the sign comes from the first
+/// non-zero difference. The returned index counts positions in `diffs`,
+/// which is also the index of the first level of the violating pair.
+///
+/// # Implementation Details
+/// - Each difference is turned into a step in the sequence's direction
+///   with `Level::towards`, so decreasing sequences check the same
+///   `min..=max` range as increasing ones
+/// - That single range check accepts every valid step; only a step outside
+///   it is classified, in `check_step`, which is where plateaus, direction
+///   changes, and the two bounds are told apart
+/// - The direction is settled before the main loop, so the loop itself
+///   carries no "direction not known yet" test
+#[inline]
+fn validate_diffs<T: Level>(
+    diffs: impl Iterator<Item = T::Diff>,
+    bounds: LevelBounds<T::Diff>,
+) -> ValidationResult<T> {
+    let mut diffs = diffs.enumerate();
+    // Plateaus never set the direction, so it comes from the first step
+    // that moves, which is checked like the rest
+    let increasing = loop {
+        let Some((index, diff)) = diffs.next() else {
+            return ValidationResult::Valid;
+        };
+        if diff == T::Diff::ZERO {
+            if bounds.allow_plateaus {
+                continue;
+            }
+            return ValidationResult::Invalid(Violation::Plateau { index });
+        }
+        let increasing = diff > T::Diff::ZERO;
+        if let Some(violation) = check_step(index, diff, increasing, bounds) {
+            return ValidationResult::Invalid(violation);
+        }
+        break increasing;
+    };
+
+    for (index, diff) in diffs {
+        if let Some(violation) = check_step(index, diff, increasing, bounds) {
+            return ValidationResult::Invalid(violation);
+        }
+    }
+    ValidationResult::Valid
+}
+
+/// Checks the difference at `index` against `bounds` in a known direction.
+///
+/// # Returns
+/// The violation, or `None` if the step fits or is an allowed plateau.
+#[inline(always)]
+fn check_step<T: Level>(
+    index: usize,
+    diff: T::Diff,
+    increasing: bool,
+    bounds: LevelBounds<T::Diff>,
+) -> Option<Violation<T>> {
+    let step = diff.towards(increasing);
+    if (bounds.min..=bounds.max).contains(&step) {
+        return None;
+    }
+
+    // `bounds.min` is at least 1, so a plateau always lands here
+    if step == T::Diff::ZERO {
+        (!bounds.allow_plateaus).then_some(Violation::Plateau { index })
+    } else if step < T::Diff::ZERO {
+        Some(Violation::DirectionChange { index })
+    } else if step < bounds.min {
+        Some(Violation::StepTooSmall { index, diff })
+    } else {
+        Some(Violation::JumpTooLarge { index, diff })
+    }
+}
+
+/// Validates levels of any `Level` type, as produced by an iterator.
+///
+/// Applies the same rules as `validate_sequence`, which is this function
+/// over a slice.
+///
+/// # Implementation Details
+/// - Folds over the levels carrying the previous one, so each difference is
+///   computed as its level arrives and nothing is collected
+/// - Stops pulling levels at the first violation
+///
+/// # Time Complexity
+/// O(n) where n is the number of levels consumed
+///
+/// # Space Complexity
+/// O(1)
+#[inline]
+pub fn validate_levels<T: Level>(
+    levels: impl IntoIterator<Item = T>,
+    bounds: StepBounds,
+) -> ValidationResult<T> {
+    let mut levels = levels.into_iter();
+    let Some(first) = levels.next() else {
+        return ValidationResult::Valid;
+    };
+    let diffs = levels.scan(first, |previous, level| {
+        let diff = previous.step_to(level);
+        *previous = level;
+        Some(diff)
+    });
+    validate_diffs(diffs, bounds.into())
+}
+
+/// Validates if a sequence of numbers follows the reactor safety rules.
+///
+/// # Rules
+/// 1. Numbers must be strictly increasing or decreasing (no plateaus, unless
+///    `bounds.allow_plateaus`)
+/// 2. Adjacent differences must be within `bounds`, 1 to 3 inclusive by default
+///
+/// Sequences with fewer than two levels have no pairs to break either rule
+/// and are always valid.
+///
+/// # Implementation Details
+/// - Checks the adjacent differences in a single pass, without storing them
+/// - Determines direction (increasing/decreasing) from first pair
+/// - Early returns for invalid cases to avoid unnecessary computation
+///
+/// # Arguments
+/// * `numbers` - Slice of integers representing the sequence to validate
+/// * `bounds` - Allowed magnitudes of adjacent differences
+///
+/// # Returns
+/// * `ValidationResult::Valid` if sequence follows all rules
+/// * `ValidationResult::Invalid` with the reason and position of the first violation
+///
+/// # Time Complexity
+/// O(n) where n is length of sequence
+///
+/// # Space Complexity
+/// O(1) - only uses a few variables regardless of input size
+#[inline]
+pub fn validate_sequence<T: Level>(numbers: &[T], bounds: StepBounds) -> ValidationResult<T> {
+    validate_levels(numbers.iter().copied(), bounds)
+}
+
+/// Checks one difference against the rules for a fixed direction.
+#[inline]
+fn diff_fits<D: Step>(diff: D, increasing: bool, bounds: LevelBounds<D>) -> bool {
+    let step = diff.towards(increasing);
+    (bounds.min..=bounds.max).contains(&step) || (step == D::ZERO && bounds.allow_plateaus)
+}
+
+/// Checks whether a sequence, given as its levels and their differences,
+/// becomes valid when the level at `skip` is removed.
+///
+/// Removing an inner level merges the two differences around it into one,
+/// and removing the first or last level drops the outer difference, so the
+/// sequence is never rebuilt.
+///
+/// # Implementation Details
+/// - The caller guarantees that `diffs[..skip - 1]` is already valid, as it
+///   lies before the first violation; those differences are not rechecked
+/// - The direction comes from the first non-zero difference left after the
+///   removal; without allowed plateaus that is simply the first one
+/// - The merged difference is taken between the levels either side of
+///   `skip`, which costs the same as adding up the two it replaces
+/// - Only the merged difference and the ones after it are scanned, stopping
+///   at the first that breaks the rules
+///
+/// # Time Complexity
+/// O(n - skip) where n is length of sequence
+///
+/// # Space Complexity
+/// O(1) - no copy of the sequence is made
+#[inline]
+fn fits_without<T: Level>(
+    numbers: &[T],
+    diffs: &[T::Diff],
+    skip: usize,
+    bounds: LevelBounds<T::Diff>,
+) -> bool {
+    let merged =
+        (skip > 0 && skip < diffs.len()).then(|| numbers[skip - 1].step_to(numbers[skip + 1]));
+    let after = &diffs[(skip + 1).min(diffs.len())..];
+    let moves = |diff: &T::Diff| *diff != T::Diff::ZERO;
+    let first = diffs[..skip.saturating_sub(1)]
+        .iter()
+        .copied()
+        .find(moves)
+        .or(merged.filter(moves))
+        .or_else(|| after.iter().copied().find(moves));
+
+    // With no step that moves, only plateaus are left to check, and they
+    // fit in either direction
+    let increasing = first.is_some_and(|first| first > T::Diff::ZERO);
+    merged.is_none_or(|diff| diff_fits(diff, increasing, bounds))
+        && after
+            .iter()
+            .all(|&diff| diff_fits(diff, increasing, bounds))
+}
+
+/// Validates a sequence allowing one number to be removed to make it valid.
+///
+/// Only called for sequences that already failed `validate_sequence`, which
+/// reports the first violating pair `(i, i + 1)`.
+///
+/// # Implementation Details
+/// - Only removing level `i - 1`, `i`, or `i + 1` can fix the sequence:
+///   every pair before the violation is already valid, and removing `i - 1`
+///   covers the case where the direction was inferred from a bad first pair
+/// - With allowed plateaus, the first pair may set the direction from
+///   further back, so removing level 0 is tried as well
+/// - Computes the differences once into an inline buffer, then checks each
+///   candidate with `fits_without`, which merges two of them in place of
+///   rebuilding the sequence and skips the known-valid prefix
+/// - Early returns on first valid combination found
+/// - Reports of up to `MASKED_LEVELS` levels, which covers every puzzle
+///   report, are answered by `masked_fix` from one bitmask per direction
+///   instead, without storing the differences
+///
+/// # Arguments
+/// * `numbers` - Original sequence to validate
+/// * `violation` - Its first violation
+/// * `bounds` - Allowed magnitudes of adjacent differences
+///
+/// # Returns
+/// * `ValidationResult::Valid` if removing one number makes the sequence valid
+/// * `ValidationResult::Invalid` with `violation` otherwise
+///
+/// # Time Complexity
+/// O(n) where n is sequence length
+#[inline]
+pub fn validate_sequence_with_dampener<T: Level>(
+    numbers: &[T],
+    violation: Violation<T>,
+    bounds: StepBounds,
+) -> ValidationResult<T> {
+    let fixed = if numbers.len() <= MASKED_LEVELS {
+        masked_fix(numbers, bounds.into())
+    } else {
+        dampener_removal(numbers, violation, bounds).is_some()
+    };
+    if fixed {
+        ValidationResult::Valid
+    } else {
+        ValidationResult::Invalid(violation)
+    }
+}
+
+/// Longest report `masked_fix` answers: its 8 differences fit a `u16` mask
+/// with room to shift
+const MASKED_LEVELS: usize = 9;
+
+/// Whether removing at most one level makes a short sequence valid,
+/// answered from a mask per direction of the differences that fit it.
+///
+/// A sequence is valid exactly when every difference fits one direction,
+/// since an allowed plateau fits both. Removing the first or last level
+/// drops its difference, and removing inner level `k` merges differences
+/// `k - 1` and `k`, so a removal works when the differences it drops or
+/// merges are the only broken ones and any merge fits.
+///
+/// # Implementation Details
+/// - Every difference is classified once per direction, branch-free, into
+///   bit `i` of that direction's mask
+/// - Only the two merges around the lowest broken difference can cover it,
+///   so at most two merges are computed per direction
+/// - Needs no violation and no candidate order, as only whether some
+///   removal works is asked
+///
+/// # Time Complexity
+/// O(n) where n is sequence length, at most `MASKED_LEVELS`
+#[inline]
+fn masked_fix<T: Level>(numbers: &[T], bounds: LevelBounds<T::Diff>) -> bool {
+    debug_assert!(numbers.len() <= MASKED_LEVELS);
+    // One level left is always valid
+    if numbers.len() <= 2 {
+        return true;
+    }
+    let mut fits = [0u16; 2];
+    for (i, pair) in numbers.windows(2).enumerate() {
+        let diff = pair[0].step_to(pair[1]);
+        for (increasing, mask) in [false, true].into_iter().zip(&mut fits) {
+            *mask |= u16::from(diff_fits(diff, increasing, bounds)) << i;
+        }
+    }
+
+    let diffs = numbers.len().saturating_sub(1);
+    let all = (1u16 << diffs) - 1;
+    [false, true]
+        .into_iter()
+        .zip(fits)
+        .any(|(increasing, fits)| {
+            let broken = all & !fits;
+            // Removing the first or last level, or none
+            if broken == 0 || broken == 1 || broken == 1 << (diffs - 1) {
+                return true;
+            }
+            let lowest = broken.trailing_zeros() as usize;
+            (lowest.max(1)..=(lowest + 1).min(diffs - 1)).any(|k| {
+                broken & !(3 << (k - 1)) == 0
+                    && diff_fits(numbers[k - 1].step_to(numbers[k + 1]), increasing, bounds)
+            })
+        })
+}
+
+/// Finds the level whose removal fixes a sequence that failed
+/// `validate_sequence`, trying the candidates around `violation` in order.
+///
+/// This is the dampener's scan over the differences for reports of any
+/// length; `validate_sequence_with_dampener` only uses it for reports too
+/// long for `masked_fix`.
+///
+/// # Returns
+/// The index of the first candidate that works, or `None` if none does.
+#[inline]
+pub fn dampener_removal<T: Level>(
+    numbers: &[T],
+    violation: Violation<T>,
+    bounds: StepBounds,
+) -> Option<usize> {
+    first_fix(numbers, &adjacent_diffs(numbers), violation, bounds)
+}
+
+/// The first candidate around `violation` whose removal `numbers`, with
+/// `diffs` their adjacent differences, fit without.
+#[inline]
+fn first_fix<T: Level>(
+    numbers: &[T],
+    diffs: &[T::Diff],
+    violation: Violation<T>,
+    bounds: StepBounds,
+) -> Option<usize> {
+    let bounds = LevelBounds::from(bounds);
+    removal_candidates(numbers.len(), violation, bounds.allow_plateaus)
+        .find(|&skip| fits_without(numbers, diffs, skip, bounds))
+}
+
+/// Finds every single level whose removal fixes a sequence that failed
+/// `validate_sequence`.
+///
+/// Only the candidates `validate_sequence_with_dampener` tries can work, so
+/// this checks each of them instead of stopping at the first, for callers
+/// that want to show which fix was taken and whether it was the only one.
+///
+/// # Returns
+/// The indices of the levels whose removal makes the sequence valid, in
+/// ascending order; empty if the dampener can't fix it.
+///
+/// # Time Complexity
+/// O(n) where n is sequence length, at most four candidates
+pub fn dampener_fixes<T: Level>(
+    numbers: &[T],
+    violation: Violation<T>,
+    bounds: StepBounds,
+) -> SmallVec<[usize; 4]> {
+    let diffs = adjacent_diffs(numbers);
+    let bounds = LevelBounds::from(bounds);
+    removal_candidates(numbers.len(), violation, bounds.allow_plateaus)
+        .filter(|&skip| fits_without(numbers, &diffs, skip, bounds))
+        .collect()
+}
+
+/// Differences between adjacent levels, for checking removal candidates.
+#[inline]
+fn adjacent_diffs<T: Level>(numbers: &[T]) -> Diffs<T::Diff> {
+    // Filled by index rather than collected, which keeps the inline buffer
+    // free of per-element capacity checks. Long reports spill it to the heap
+    // once, and every candidate shares it.
+    let mut diffs: Diffs<T::Diff> = smallvec![T::Diff::ZERO; numbers.len() - 1];
+    fill_diffs(numbers, &mut diffs);
+    diffs
+}
+
+/// Writes the differences between adjacent levels into `diffs`, one
+/// shorter than `numbers`.
+#[inline]
+fn fill_diffs<T: Level>(numbers: &[T], diffs: &mut [T::Diff]) {
+    for (diff, pair) in diffs.iter_mut().zip(numbers.windows(2)) {
+        *diff = pair[0].step_to(pair[1]);
+    }
+}
+
+/// Levels whose single removal could fix a sequence of `len` levels that
+/// first fails at `violation`, each given once and in ascending order, so
+/// the first that works is the lowest.
+#[inline]
+fn removal_candidates<T: Level>(
+    len: usize,
+    violation: Violation<T>,
+    allow_plateaus: bool,
+) -> impl Iterator<Item = usize> {
+    let index = violation.index();
+    let around = index.saturating_sub(1)..=(index + 1).min(len - 1);
+    // Only the first level's removal can change the direction the levels
+    // before `index - 1` set; without plateaus that pair is `index - 1`
+    let first = (allow_plateaus && index >= 2).then_some(0);
+    first.into_iter().chain(around)
+}
+
+/// Index of the `KeepCosts` slot for kept levels that are all equal so
+/// far, with no direction yet; `0` and `1` are decreasing and increasing
+const UNMOVED: usize = 2;
+
+/// Checks one adjacent pair against `bounds`, regardless of the direction
+/// before it.
+///
+/// # Returns
+/// The `KeepCosts` slot the pair continues: its direction (`1` for
+/// increasing), `UNMOVED` for an allowed plateau, which keeps whatever
+/// direction came before, or `None` if it breaks a rule.
+#[inline]
+fn step_slot<T: Level>(from: T, to: T, bounds: LevelBounds<T::Diff>) -> Option<usize> {
+    let diff = from.step_to(to);
+    if diff == T::Diff::ZERO {
+        return bounds.allow_plateaus.then_some(UNMOVED);
+    }
+    (bounds.min..=bounds.max)
+        .contains(&diff.magnitude())
+        .then_some((diff > T::Diff::ZERO) as usize)
+}
+
+/// Fewest removals before each level for it to be kept, indexed by level
+/// then by direction, `usize::MAX` where it can't be
+type KeepCosts = SmallVec<[[usize; 3]; 8]>;
+
+/// Fills `KeepCosts` for up to `removals` removals, left to right.
+///
+/// Keeping a level with fewer removals behind it leaves open every option
+/// that more removals would, so only the cheapest way to reach each
+/// (level, direction) pair matters. Any level can be the first kept one,
+/// with every level before it removed. After that, a level is reached from
+/// one of the `removals + 1` levels before it: a moving pair continues that
+/// level's direction or sets the first one, and an allowed plateau carries
+/// the direction over unchanged. Larger budgets therefore stay linear in
+/// the report length and never recurse.
+///
+/// # Time Complexity
+/// O(n * r) where n is sequence length and r is `removals`
+///
+/// # Space Complexity
+/// O(n)
+fn keep_costs<T: Level>(numbers: &[T], bounds: StepBounds, removals: usize) -> KeepCosts {
+    let mut table: KeepCosts = smallvec![[usize::MAX; 3]; numbers.len()];
+    // Filled as a slice, which skips the inline-or-heap check per access
+    fill_keep_costs(numbers, bounds, removals, &mut table);
+    table
+}
+
+/// Fills `costs`, one entry per level and all `usize::MAX`, as `keep_costs`
+/// describes.
+fn fill_keep_costs<T: Level>(
+    numbers: &[T],
+    bounds: StepBounds,
+    removals: usize,
+    costs: &mut [[usize; 3]],
+) {
+    let bounds = LevelBounds::from(bounds);
+    for (first, slots) in costs.iter_mut().enumerate() {
+        slots[UNMOVED] = first;
+    }
+    // Any level up to `removals` can be the first kept one
+    let mut reachable = removals.min(numbers.len() - 1);
+    for next in 1..numbers.len() {
+        if next > reachable + removals + 1 {
+            // No kept level lies within reach, so none further can be kept
+            break;
+        }
+        for last in next.saturating_sub(removals + 1)..next {
+            // A pair fits or not regardless of the direction before it; its
+            // own direction picks which ways of keeping `last` it extends
+            let Some(slot) = step_slot(numbers[last], numbers[next], bounds) else {
+                continue;
+            };
+            let gap = next - last - 1;
+            for to in 0..3 {
+                let from = match slot {
+                    UNMOVED => costs[last][to],
+                    up if up == to => costs[last][up].min(costs[last][UNMOVED]),
+                    _ => continue,
+                };
+                let removed = from.saturating_add(gap);
+                if removed <= removals && removed < costs[next][to] {
+                    costs[next][to] = removed;
+                    reachable = reachable.max(next);
+                }
+            }
+        }
+    }
+}
+
+/// The last kept level of a cheapest fix within `removals`, with its
+/// `KeepCosts` slot and the total removed.
+fn cheapest_end(costs: &[[usize; 3]], removals: usize) -> Option<(usize, usize, usize)> {
+    let len = costs.len();
+    let mut best = None;
+    // Only the last `removals + 1` levels can end a fix within the budget
+    let start = len.saturating_sub(removals + 1);
+    for (last, slots) in costs.iter().enumerate().skip(start) {
+        for (slot, &cost) in slots.iter().enumerate() {
+            let total = cost.saturating_add(len - 1 - last);
+            if total <= removals && best.is_none_or(|(_, _, best)| total < best) {
+                best = Some((last, slot, total));
+            }
+        }
+    }
+    best
+}
+
+/// Validates a sequence allowing up to `removals` levels to be removed.
+///
+/// `removals` of 0 and 1 are Part 1 and Part 2 exactly: the first violation
+/// is returned as-is, or handed to `validate_sequence_with_dampener`.
+///
+/// # Implementation Details
+/// - Larger budgets search over which levels to keep, each kept level
+///   followed by one within reach of the remaining budget
+/// - Tracks the fewest removals reaching each (level, direction) pair
+///   with `keep_costs`, bounding the work to O(n * k) instead of trying
+///   every subset of removals
+///
+/// # Arguments
+/// * `numbers` - Original sequence to validate
+/// * `violation` - Its first violation, from `validate_sequence`
+/// * `bounds` - Allowed magnitudes of adjacent differences
+/// * `removals` - Maximum number of levels to remove
+///
+/// # Returns
+/// * `ValidationResult::Valid` if removing at most `removals` levels makes the sequence valid
+/// * `ValidationResult::Invalid` with `violation` otherwise
+pub fn validate_with_removals<T: Level>(
+    numbers: &[T],
+    violation: Violation<T>,
+    bounds: StepBounds,
+    removals: usize,
+) -> ValidationResult<T> {
+    match removals {
+        0 => return ValidationResult::Invalid(violation),
+        1 => return validate_sequence_with_dampener(numbers, violation, bounds),
+        _ => {}
+    }
+
+    // Keeping a single level is always valid
+    if numbers.len() <= removals + 1 {
+        return ValidationResult::Valid;
+    }
+    let costs = keep_costs(numbers, bounds, removals);
+    match cheapest_end(&costs, removals) {
+        Some(_) => ValidationResult::Valid,
+        None => ValidationResult::Invalid(violation),
+    }
+}
+
+/// Validates a sequence allowing up to `removals` levels to be removed, as
+/// `validate_with_removals` does, with its scratch slices in `arena`.
+///
+/// The differences and `KeepCosts` live in `arena` instead of an inline
+/// buffer, so a long report allocates nothing once `arena` has grown to
+/// fit it. Callers validating one report after another reset `arena`
+/// between them, or use `aoc_common::with_scratch`, which does.
+pub fn validate_with_removals_in<T: Level>(
+    numbers: &[T],
+    violation: Violation<T>,
+    bounds: StepBounds,
+    removals: usize,
+    arena: &Arena,
+) -> ValidationResult<T> {
+    let fixed = match removals {
+        0 => false,
+        1 if numbers.len() <= MASKED_LEVELS => masked_fix(numbers, bounds.into()),
+        1 => {
+            let diffs = arena.alloc_slice(numbers.len() - 1, T::Diff::ZERO);
+            fill_diffs(numbers, diffs);
+            first_fix(numbers, diffs, violation, bounds).is_some()
+        }
+        _ if numbers.len() <= removals + 1 => true,
+        _ => {
+            let costs = arena.alloc_slice(numbers.len(), [usize::MAX; 3]);
+            fill_keep_costs(numbers, bounds, removals, costs);
+            cheapest_end(costs, removals).is_some()
+        }
+    };
+    if fixed {
+        ValidationResult::Valid
+    } else {
+        ValidationResult::Invalid(violation)
+    }
+}
+
+/// Finds which levels to remove to make an invalid sequence valid, using the
+/// same search as `validate_with_removals` so the two always agree.
+///
+/// # Implementation Details
+/// - A single removal comes straight from `dampener_removal`
+/// - Larger budgets compute the same costs, then follow them back from
+///   the cheapest end, so the plan removes as few levels as possible
+///
+/// # Returns
+/// The removed indices in ascending order, or `None` if at most `removals`
+/// removals can't fix the sequence.
+pub fn removal_plan<T: Level>(
+    numbers: &[T],
+    violation: Violation<T>,
+    bounds: StepBounds,
+    removals: usize,
+) -> Option<Vec<usize>> {
+    match removals {
+        0 => return None,
+        1 => return dampener_removal(numbers, violation, bounds).map(|skip| vec![skip]),
+        _ => {}
+    }
+
+    let costs = keep_costs(numbers, bounds, removals);
+    let (end, mut slot, _) = cheapest_end(&costs, removals)?;
+    let level_bounds = LevelBounds::from(bounds);
+    let mut removed: Vec<usize> = (end + 1..numbers.len()).collect();
+    let mut last = end;
+    // Walk back to the level and slot each cost came from, mirroring
+    // `keep_costs`, until reaching the first kept level
+    while !(slot == UNMOVED && costs[last][slot] == last) {
+        let cost = costs[last][slot];
+        let (previous, from) = (last.saturating_sub(cost + 1)..last)
+            .find_map(|previous| {
+                let sources: &[usize] =
+                    match step_slot(numbers[previous], numbers[last], level_bounds)? {
+                        UNMOVED => &[slot],
+                        up if up == slot => &[up, UNMOVED],
+                        _ => &[],
+                    };
+                let gap = last - previous - 1;
+                sources
+                    .iter()
+                    .find(|&&from| costs[previous][from].saturating_add(gap) == cost)
+                    .map(|&from| (previous, from))
+            })
+            .expect("every kept level's cost comes from a level before it");
+        removed.extend(previous + 1..last);
+        (last, slot) = (previous, from);
+    }
+    removed.extend(0..last);
+    removed.sort_unstable();
+    Some(removed)
+}
+
+/// Describes what was wrong with a line of puzzle input.
+#[derive(Debug, PartialEq)]
+pub enum ParseErrorKind {
+    /// A token that isn't an integer, including a bare `-`
+    InvalidNumber(String),
+    /// A level too large for the level type, e.g. `i32`
+    OutOfRange { token: String, width: &'static str },
+    /// Nothing but blanks between two commas, or at either end of a
+    /// comma-separated line
+    MissingLevel,
+    /// A line separated differently from the reports before it
+    MixedDelimiters {
+        expected: Delimiter,
+        found: Delimiter,
+        /// Line that set the input's delimiter
+        first_line: usize,
+    },
+}
+
+/// A parse failure pinned to its position in the input.
+///
+/// Lines and columns are 1-based; `columns` is an inclusive-exclusive byte
+/// range into the line, rendered as an inclusive range for humans.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub columns: Range<usize>,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::InvalidNumber(token) => {
+                write!(f, "could not parse `{}` as a level", token)
+            }
+            ParseErrorKind::OutOfRange { token, width } => {
+                write!(f, "`{}` is out of range for {}", token, width)
+            }
+            ParseErrorKind::MissingLevel => write!(f, "empty level next to this comma"),
+            ParseErrorKind::MixedDelimiters {
+                expected,
+                found,
+                first_line,
+            } => write!(
+                f,
+                "{} levels, but line {} set the input as {}",
+                found, first_line, expected
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, columns {}-{}: {}",
+            self.line,
+            self.columns.start,
+            self.columns.end - 1,
+            self.kind
+        )
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(e: ParseError) -> Self {
+        AocError::parse(e.line, e.columns, e.kind)
+    }
+}
+
+/// Parses one non-empty level token with `aoc_common::parse_int`.
+///
+/// A leading `-` makes the level negative, down to the type's minimum; a
+/// `-` anywhere else, or with no digits after it, is malformed.
+///
+/// # Errors
+/// Returns the error kind for a non-digit byte, a bare `-`, or a value that
+/// doesn't fit in `T`.
+#[inline]
+fn parse_level<T: Level>(token: &[u8]) -> Result<T, ParseErrorKind> {
+    parse_int(token).map_err(|e| level_error(token, e.kind))
+}
+
+/// Converts a shared parser failure on `token` into this crate's error kind.
+fn level_error(token: &[u8], kind: IntErrorKind) -> ParseErrorKind {
+    let token = String::from_utf8_lossy(token).into_owned();
+    match kind {
+        IntErrorKind::OutOfRange { width } => ParseErrorKind::OutOfRange { token, width },
+        IntErrorKind::Empty | IntErrorKind::InvalidDigit => ParseErrorKind::InvalidNumber(token),
+    }
+}
+
+/// Whether `b` is blank space around levels: a space, a tab, or a stray `\r`.
+#[inline]
+fn is_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t' || b == b'\r'
+}
+
+/// How the levels of a report are separated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Delimiter {
+    /// Runs of spaces and tabs, as in the puzzle input
+    Whitespace,
+    /// Commas, optionally padded with spaces or tabs
+    Comma,
+}
+
+impl fmt::Display for Delimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Delimiter::Whitespace => "whitespace-separated",
+            Delimiter::Comma => "comma-separated",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Delimiter {
+    /// Detects how one line is separated, with the byte offset of its first
+    /// delimiter.
+    ///
+    /// Any comma makes the line comma-separated. Otherwise a line needs two
+    /// levels to show a delimiter, so one with fewer reads the same either
+    /// way and gives `None`.
+    #[inline]
+    fn detect(bytes: &[u8]) -> Option<(Delimiter, usize)> {
+        if let Some(at) = bytes.iter().position(|&b| b == b',') {
+            return Some((Delimiter::Comma, at));
+        }
+        let start = bytes.iter().position(|&b| !is_blank(b))?;
+        let gap = start + bytes[start..].iter().position(|&b| is_blank(b))?;
+        bytes[gap..]
+            .iter()
+            .any(|&b| !is_blank(b))
+            .then_some((Delimiter::Whitespace, gap))
+    }
+}
+
+/// Parses report lines, detecting the delimiter from the first line that
+/// shows one and holding the rest of the input to it.
+#[derive(Debug, Default)]
+pub struct LineParser {
+    /// The input's delimiter and the line that set it
+    delimiter: Option<(Delimiter, usize)>,
+}
+
+impl LineParser {
+    /// Parses one line of levels into `numbers`.
+    ///
+    /// Levels may be separated by any run of spaces and tabs, or by commas
+    /// padded with them. Leading or trailing whitespace (including a `\r`
+    /// left by CRLF endings) is ignored, so a blank line leaves `numbers`
+    /// empty.
+    ///
+    /// # Implementation Details
+    /// - Works on the raw bytes without UTF-8 validation or per-token strings
+    /// - Appends to the caller's buffer so its capacity can be reused
+    /// - Reports may hold any number of levels, of any `Level` type
+    ///
+    /// # Errors
+    /// Returns a `ParseError` naming `line_number` and the byte range of the
+    /// first malformed level, or of the first delimiter if the line is
+    /// separated differently from the lines before it.
+    #[inline]
+    pub fn parse_line<T: Level>(
+        &mut self,
+        bytes: &[u8],
+        line_number: usize,
+        numbers: &mut Report<T>,
+    ) -> Result<(), ParseError> {
+        let Some((expected, first_line)) = self.delimiter else {
+            let delimiter = match Delimiter::detect(bytes) {
+                Some((found, _)) => {
+                    self.delimiter = Some((found, line_number));
+                    found
+                }
+                // At most one level, which reads the same either way
+                None => Delimiter::Whitespace,
+            };
+            return parse_delimited(bytes, line_number, numbers, delimiter);
+        };
+
+        // A line in the other format fails to parse, so only failures are
+        // checked for a different delimiter
+        parse_delimited(bytes, line_number, numbers, expected).map_err(|error| {
+            match Delimiter::detect(bytes) {
+                Some((found, at)) if found != expected => ParseError {
+                    line: line_number,
+                    columns: at + 1..at + 2,
+                    kind: ParseErrorKind::MixedDelimiters {
+                        expected,
+                        found,
+                        first_line,
+                    },
+                },
+                _ => error,
+            }
+        })
+    }
+}
+
+/// Parses one line with a known delimiter.
+#[inline]
+fn parse_delimited<T: Level>(
+    bytes: &[u8],
+    line_number: usize,
+    numbers: &mut Report<T>,
+    delimiter: Delimiter,
+) -> Result<(), ParseError> {
+    match delimiter {
+        Delimiter::Whitespace => parse_blank_separated(bytes, line_number, numbers),
+        Delimiter::Comma => parse_comma_separated(bytes, line_number, numbers),
+    }
+}
+
+/// Parses levels separated by runs of blanks.
+///
+/// Reports live in a `SmallVec`, so this walks the line with a `Scanner`
+/// rather than using `aoc_common::parse_ints_in_line`, which fills a `Vec`.
+#[inline]
+fn parse_blank_separated<T: Level>(
+    bytes: &[u8],
+    line_number: usize,
+    numbers: &mut Report<T>,
+) -> Result<(), ParseError> {
+    let mut scanner = Scanner::new(bytes);
+    while let Some(token) = scanner.next_token() {
+        let num = parse_level(token).map_err(|kind| ParseError {
+            line: line_number,
+            columns: scanner.token_columns(),
+            kind,
+        })?;
+        numbers.push(num);
+    }
+    Ok(())
+}
+
+/// Parses levels separated by commas, trimming blanks around each level.
+#[inline]
+fn parse_comma_separated<T: Level>(
+    bytes: &[u8],
+    line_number: usize,
+    numbers: &mut Report<T>,
+) -> Result<(), ParseError> {
+    // A blank line has no comma to point at; it's just not a report
+    if bytes.iter().all(|&b| is_blank(b)) {
+        return Ok(());
+    }
+    let mut start = 0;
+    for field in bytes.split(|&b| b == b',') {
+        let end = start + field.len();
+        let Some(first) = field.iter().position(|&b| !is_blank(b)) else {
+            // Point at the comma after the empty field, or before the last one
+            let comma = if end < bytes.len() { end } else { start - 1 };
+            return Err(ParseError {
+                line: line_number,
+                columns: comma + 1..comma + 2,
+                kind: ParseErrorKind::MissingLevel,
+            });
+        };
+        let last = field.iter().rposition(|&b| !is_blank(b)).unwrap_or(first) + 1;
+        let num = parse_level(&field[first..last]).map_err(|kind| ParseError {
+            line: line_number,
+            columns: start + first + 1..start + last + 1,
+            kind,
+        })?;
+        numbers.push(num);
+        start = end + 1;
+    }
+    Ok(())
+}
+
+/// Both puzzle answers for one input.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SafeCounts {
+    /// Reports safe under the Part 1 rules
+    pub part1: usize,
+    /// Reports safe with the Problem Dampener removing up to one level
+    pub part2: usize,
+}
+
+impl AddAssign for SafeCounts {
+    fn add_assign(&mut self, other: SafeCounts) {
+        self.part1 += other.part1;
+        self.part2 += other.part2;
+    }
+}
+
+/// Totals the answers of several inputs solved separately.
+impl Sum for SafeCounts {
+    fn sum<I: Iterator<Item = SafeCounts>>(counts: I) -> SafeCounts {
+        let mut total = SafeCounts::default();
+        for count in counts {
+            total += count;
+        }
+        total
+    }
+}
+
+/// Hands each report of `input` to `visit` in order, skipping blank lines,
+/// or the `ParseError` of a line that doesn't parse.
+///
+/// Lines are parsed with one `LineParser` into one reused `Report`, so a
+/// visitor that keeps a report clones it.
+///
+/// # Errors
+/// Stops at the first error `visit` returns, and returns it.
+pub fn for_each_report<T: Level, E>(
+    input: &str,
+    mut visit: impl FnMut(Result<&Report<T>, ParseError>) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut parser = LineParser::default();
+    let mut numbers = Report::new();
+    for (i, line) in input.lines().enumerate() {
+        numbers.clear();
+        match parser.parse_line(line.as_bytes(), i + 1, &mut numbers) {
+            Ok(()) if numbers.is_empty() => {}
+            Ok(()) => visit(Ok(&numbers))?,
+            Err(error) => visit(Err(error))?,
+        }
+    }
+    Ok(())
+}
+
+/// Describes the lines a lenient parse skipped, such as
+/// `3 lines skipped: 14, 203, 977`, or `None` if there were none.
+pub fn skipped_lines_note(lines: &[usize]) -> Option<String> {
+    if lines.is_empty() {
+        return None;
+    }
+    let numbers: Vec<String> = lines.iter().map(|n| n.to_string()).collect();
+    Some(format!(
+        "{} {} skipped: {}",
+        lines.len(),
+        if lines.len() == 1 { "line" } else { "lines" },
+        numbers.join(", ")
+    ))
+}
+
+/// Solves both parts for a whole puzzle input with the puzzle's rules.
+///
+/// This is the entry point for callers that only want the answers; the
+/// binary layers its options, timing, and parallelism on the same
+/// functions.
+///
+/// # Implementation Details
+/// - Parses line by line with `LineParser`, so either delimiter works
+/// - Runs the dampener only on reports that fail Part 1, reusing the
+///   first violation
+///
+/// # Errors
+/// Returns the first `ParseError` in input order.
+///
+/// # Time Complexity
+/// O(n) where n is input length
+pub fn count_safe(input: &str) -> Result<SafeCounts, ParseError> {
+    let bounds = StepBounds::default();
+    let mut counts = SafeCounts::default();
+    for_each_report::<i32, _>(input, |report| {
+        let numbers = report?;
+        match validate_sequence(numbers, bounds) {
+            ValidationResult::Valid => {
+                counts.part1 += 1;
+                counts.part2 += 1;
+            }
+            ValidationResult::Invalid(violation) => {
+                if validate_sequence_with_dampener(numbers, violation, bounds).is_valid() {
+                    counts.part2 += 1;
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(counts)
+}
+
+/// Groups identical reports, keeping the first occurrence of each.
+///
+/// # Returns
+/// The unique reports in first-seen order and how many times each occurs.
+pub fn dedupe<T: Level>(sequences: &[Report<T>]) -> (Vec<&Report<T>>, Vec<usize>) {
+    let mut positions = HashMap::with_capacity(sequences.len());
+    let mut unique = Vec::new();
+    let mut multiplicity = Vec::new();
+    for sequence in sequences {
+        match positions.entry(sequence) {
+            Entry::Occupied(entry) => multiplicity[*entry.get()] += 1,
+            Entry::Vacant(entry) => {
+                entry.insert(unique.len());
+                unique.push(sequence);
+                multiplicity.push(1);
+            }
+        }
+    }
+    (unique, multiplicity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn checks_the_step_bounds() {
+        let bounds = StepBounds::default();
+        assert_eq!(bounds.check(), Ok(bounds));
+        let exact = StepBounds {
+            min: 2,
+            max: 2,
+            ..bounds
+        };
+        assert_eq!(exact.check(), Ok(exact));
+
+        let error = StepBounds { min: 0, ..bounds }.check().unwrap_err();
+        assert_eq!(error, BoundsError::MinTooSmall { min: 0 });
+        assert_eq!(
+            error.describe("--min-step", "--max-step"),
+            "--min-step must be at least 1 (got 0)"
+        );
+        let error = StepBounds { min: 4, ..bounds }.check().unwrap_err();
+        assert_eq!(error, BoundsError::MaxBelowMin { min: 4, max: 3 });
+        assert_eq!(
+            error.describe("--min-step", "--max-step"),
+            "--max-step must be at least --min-step (3 < 4)"
+        );
+    }
+
+    #[test]
+    fn notes_the_skipped_lines() {
+        assert_eq!(skipped_lines_note(&[]), None);
+        assert_eq!(skipped_lines_note(&[7]).unwrap(), "1 line skipped: 7");
+        assert_eq!(
+            skipped_lines_note(&[14, 203, 977]).unwrap(),
+            "3 lines skipped: 14, 203, 977"
+        );
+    }
+
+    #[test]
+    fn visits_each_report_and_each_bad_line() {
+        let mut seen = Vec::new();
+        let Ok(()) = for_each_report::<i32, std::convert::Infallible>(
+            "7 6 4\n\n1 x 3\r\n1 3 6\n",
+            |report| {
+                seen.push(report.map(|numbers| numbers.to_vec()).map_err(|e| e.line));
+                Ok(())
+            },
+        );
+        assert_eq!(seen, [Ok(vec![7, 6, 4]), Err(3), Ok(vec![1, 3, 6])]);
+
+        // A visitor's error stops the walk
+        let mut visited = 0;
+        let error = for_each_report::<i32, _>("1 2\n1 x\n3 4\n", |report| {
+            visited += 1;
+            report.map(|_| ())
+        })
+        .unwrap_err();
+        assert_eq!((visited, error.line), (2, 2));
+    }
+
+    /// A fixed linear congruential sequence of values below `range`.
+    struct Values(u64);
+
+    impl Values {
+        fn next(&mut self, range: u64) -> i32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            ((self.0 >> 33) % range) as i32
+        }
+
+        /// A report of `len` levels stepping by up to `spread` either way,
+        /// mostly in one direction, so a fair share of them are safe or
+        /// one or two removals from it.
+        fn walk(&mut self, len: usize, spread: i32) -> Vec<i32> {
+            let direction = if self.next(2) == 0 { 1 } else { -1 };
+            let mut level = self.next(20);
+            (0..len)
+                .map(|_| {
+                    let current = level;
+                    let step = self.next(spread as u64 * 2 + 1) - spread;
+                    // Three steps in four go the report's way
+                    level += if self.next(4) == 0 {
+                        step
+                    } else {
+                        direction * step.abs()
+                    };
+                    current
+                })
+                .collect()
+        }
+    }
+
+    /// The rules as the puzzle states them, checked on the levels directly.
+    fn brute_force_safe(levels: &[i32], bounds: StepBounds) -> bool {
+        let diffs: Vec<i64> = levels
+            .windows(2)
+            .map(|pair| pair[1] as i64 - pair[0] as i64)
+            .collect();
+        let increasing = diffs
+            .iter()
+            .find(|&&diff| diff != 0)
+            .is_none_or(|&diff| diff > 0);
+        diffs.iter().all(|&diff| {
+            (diff == 0 && bounds.allow_plateaus)
+                || ((diff > 0) == increasing
+                    && (bounds.min as i64..=bounds.max as i64).contains(&diff.abs()))
+        })
+    }
+
+    /// Whether removing at most `removals` levels, trying every choice on
+    /// a fresh copy, leaves a safe report.
+    fn brute_force_fixable(levels: &[i32], bounds: StepBounds, removals: usize) -> bool {
+        brute_force_safe(levels, bounds)
+            || (removals > 0
+                && (0..levels.len()).any(|skip| {
+                    let mut rest = levels.to_vec();
+                    rest.remove(skip);
+                    brute_force_fixable(&rest, bounds, removals - 1)
+                }))
+    }
+
+    /// The puzzle's bounds, and the same with plateaus allowed.
+    fn both_rules() -> [StepBounds; 2] {
+        let bounds = StepBounds::default();
+        [
+            bounds,
+            StepBounds {
+                allow_plateaus: true,
+                ..bounds
+            },
+        ]
+    }
+
+    #[test]
+    fn dampener_matches_brute_force() {
+        // Bad first pairs, which set the wrong direction for the rest
+        let mut reports = vec![
+            vec![5, 1, 2, 3, 4],
+            vec![1, 5, 4, 3, 2],
+            vec![2, 1, 2, 3, 4],
+            vec![3, 2, 4, 5, 6],
+            vec![1, 2, 1, 0, -1],
+            vec![8, 9, 7, 6, 5],
+            vec![4, 4, 5, 6],
+            vec![1, 9, 2, 3],
+        ];
+        let mut values = Values(0x2024_1202);
+        reports.extend((0..5000).map(|round| values.walk(round % 12, 4)));
+
+        for bounds in both_rules() {
+            for levels in &reports {
+                let fixable = brute_force_fixable(levels, bounds, 1);
+                let dampened = match validate_sequence(levels, bounds) {
+                    ValidationResult::Valid => {
+                        assert!(brute_force_safe(levels, bounds), "{:?}", levels);
+                        true
+                    }
+                    ValidationResult::Invalid(violation) => {
+                        assert!(!brute_force_safe(levels, bounds), "{:?}", levels);
+                        validate_sequence_with_dampener(levels, violation, bounds).is_valid()
+                    }
+                };
+                assert_eq!(dampened, fixable, "{:?} with {}", levels, bounds);
+            }
+        }
+    }
+
+    /// The first violation of `levels` under the puzzle's rules, if any.
+    fn violation_of(levels: &[i32]) -> Option<Violation> {
+        match validate_sequence(levels, StepBounds::default()) {
+            ValidationResult::Valid => None,
+            ValidationResult::Invalid(violation) => Some(violation),
+        }
+    }
+
+    #[test]
+    fn names_the_first_violation_and_where_it_is() {
+        assert_eq!(
+            violation_of(&[1, 2, 2, 3]),
+            Some(Violation::Plateau { index: 1 })
+        );
+        assert_eq!(
+            violation_of(&[1, 2, 7, 8]),
+            Some(Violation::JumpTooLarge { index: 1, diff: 5 })
+        );
+        assert_eq!(
+            violation_of(&[1, 3, 2, 4]),
+            Some(Violation::DirectionChange { index: 1 })
+        );
+        // Decreasing jumps keep their sign, and only the first break counts
+        assert_eq!(
+            violation_of(&[9, 8, 3, 3]),
+            Some(Violation::JumpTooLarge { index: 1, diff: -5 })
+        );
+        assert_eq!(violation_of(&[1, 2, 4, 7]), None);
+
+        let min_two = StepBounds {
+            min: 2,
+            ..StepBounds::default()
+        };
+        let ValidationResult::Invalid(violation) = validate_sequence(&[1, 3, 4], min_two) else {
+            panic!("a step of 1 is below a minimum of 2");
+        };
+        assert_eq!(
+            violation,
+            Violation::StepTooSmall {
+                index: 1,
+                diff: 1i64
+            }
+        );
+        assert_eq!(violation.index(), 1);
+    }
+
+    #[test]
+    fn a_bad_level_is_an_error_and_extra_spaces_are_not() {
+        let error = count_safe("7 6 4 2 1\n1 2 x 4\n").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError {
+                line: 2,
+                columns: 5..6,
+                kind: ParseErrorKind::InvalidNumber("x".to_string()),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "line 2, columns 5-5: could not parse `x` as a level"
+        );
+        assert_eq!(
+            count_safe("1  2 3\n"),
+            Ok(SafeCounts { part1: 1, part2: 1 })
+        );
+    }
+
+    /// The puzzle's worked example
+    const EXAMPLE: &str = include_str!("../../example_input.txt");
+
+    #[test]
+    fn spacing_and_line_endings_do_not_change_the_counts() {
+        let expected = Ok(SafeCounts { part1: 2, part2: 4 });
+        assert_eq!(count_safe(EXAMPLE), expected);
+        let spaced = EXAMPLE.replace(' ', "  ");
+        let trailing = EXAMPLE.replace('\n', " \t\n");
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        let blank_lines = format!("\n{}", EXAMPLE.replace('\n', "\n\n  \n"));
+        let tabbed = EXAMPLE.replace(' ', "\t");
+        for input in [spaced, trailing, crlf, blank_lines, tabbed] {
+            assert_eq!(count_safe(&input), expected, "{:?}", input);
+        }
+        // Blank lines are not reports, so they aren't counted as safe
+        assert_eq!(
+            count_safe("\n \n\r\n"),
+            Ok(SafeCounts { part1: 0, part2: 0 })
+        );
+    }
+
+    #[test]
+    fn a_larger_max_step_lets_bigger_jumps_through() {
+        let levels = [1, 6, 11];
+        let bounds = StepBounds::default();
+        assert_eq!(
+            validate_sequence(&levels, bounds),
+            ValidationResult::Invalid(Violation::JumpTooLarge {
+                index: 0,
+                diff: 5i64
+            })
+        );
+        let five = StepBounds { max: 5, ..bounds };
+        assert!(validate_sequence(&levels, five).is_valid());
+        assert!(validate_sequence(&[11, 6, 1], five).is_valid());
+        assert!(!validate_sequence(&[1, 6, 12], five).is_valid());
+        assert_eq!(five.to_string(), "1..=5");
+    }
+
+    #[test]
+    fn a_report_two_removals_from_safe_needs_a_budget_of_two() {
+        let bounds = StepBounds::default();
+        let levels = [1, 9, 2, 3, 9, 4];
+        let ValidationResult::Invalid(violation) = validate_sequence(&levels, bounds) else {
+            panic!("{:?} jumps by 8", levels);
+        };
+        for (removals, safe) in [(0, false), (1, false), (2, true), (3, true)] {
+            assert_eq!(
+                validate_with_removals(&levels, violation, bounds, removals).is_valid(),
+                safe,
+                "{} removals",
+                removals
+            );
+            assert_eq!(
+                removal_plan(&levels, violation, bounds, removals),
+                safe.then(|| vec![1, 4]),
+                "{} removals",
+                removals
+            );
+        }
+    }
+
+    #[test]
+    fn larger_budgets_match_brute_force() {
+        let mut values = Values(0x2024_1229);
+        for round in 0..3000 {
+            let levels = values.walk(round % 10, 5);
+            for bounds in both_rules() {
+                let ValidationResult::Invalid(violation) = validate_sequence(&levels, bounds)
+                else {
+                    continue;
+                };
+                for removals in 0..=3 {
+                    let fixable = brute_force_fixable(&levels, bounds, removals);
+                    let arena = Arena::new();
+                    assert_eq!(
+                        validate_with_removals(&levels, violation, bounds, removals).is_valid(),
+                        fixable,
+                        "{:?} with {} removals and {}",
+                        levels,
+                        removals,
+                        bounds
+                    );
+                    assert_eq!(
+                        validate_with_removals_in(&levels, violation, bounds, removals, &arena)
+                            .is_valid(),
+                        fixable
+                    );
+                    // A plan removes as few levels as possible and leaves a safe report
+                    let Some(plan) = removal_plan(&levels, violation, bounds, removals) else {
+                        assert!(!fixable, "{:?} with {} removals", levels, removals);
+                        continue;
+                    };
+                    let fewest = (1..=removals).find(|&r| brute_force_fixable(&levels, bounds, r));
+                    assert_eq!(Some(plan.len()), fewest, "{:?} minus {:?}", levels, plan);
+                    assert!(plan.is_sorted());
+                    let kept: Vec<i32> = (0..levels.len())
+                        .filter(|i| !plan.contains(i))
+                        .map(|i| levels[i])
+                        .collect();
+                    assert!(
+                        brute_force_safe(&kept, bounds),
+                        "{:?} minus {:?}",
+                        levels,
+                        plan
+                    );
+                }
+            }
+        }
+    }
+
+    /// Every sequence of up to `max_len` levels drawn from `values`.
+    fn every_sequence(max_len: usize, values: std::ops::RangeInclusive<i32>) -> Vec<Vec<i32>> {
+        let mut all = vec![vec![]];
+        let mut shorter = vec![vec![]];
+        for _ in 0..max_len {
+            shorter = shorter
+                .iter()
+                .flat_map(|prefix: &Vec<i32>| {
+                    values
+                        .clone()
+                        .map(move |level| [&prefix[..], &[level]].concat())
+                })
+                .collect();
+            all.extend(shorter.iter().cloned());
+        }
+        all
+    }
+
+    /// Checks each removal `fits_without` may be asked about, and the
+    /// fixes `dampener_fixes` finds, against removing levels from a copy.
+    fn assert_skips_match_copies(levels: &[i32], bounds: StepBounds) {
+        let ValidationResult::Invalid(violation) = validate_sequence(levels, bounds) else {
+            return;
+        };
+        let fixed_without = |skip: usize| {
+            let mut rest = levels.to_vec();
+            rest.remove(skip);
+            brute_force_safe(&rest, bounds)
+        };
+        let diffs = adjacent_diffs(levels);
+        // Its callers only skip levels up to just past the first violation
+        for skip in 0..=(violation.index() + 1).min(levels.len() - 1) {
+            assert_eq!(
+                fits_without(levels, &diffs, skip, bounds.into()),
+                fixed_without(skip),
+                "{:?} without levels[{}] and {}",
+                levels,
+                skip,
+                bounds
+            );
+        }
+        let fixes: Vec<usize> = (0..levels.len())
+            .filter(|&skip| fixed_without(skip))
+            .collect();
+        assert_eq!(
+            dampener_fixes(levels, violation, bounds).to_vec(),
+            fixes,
+            "{:?} with {}",
+            levels,
+            bounds
+        );
+    }
+
+    #[test]
+    fn skipping_a_level_matches_removing_it_from_a_copy() {
+        for bounds in both_rules() {
+            for levels in every_sequence(5, 1..=6) {
+                assert_skips_match_copies(&levels, bounds);
+            }
+        }
+        let mut values = Values(0x2024_1232);
+        for round in 0..5000 {
+            let levels = values.walk(6 + round % 20, 4);
+            for bounds in both_rules() {
+                assert_skips_match_copies(&levels, bounds);
+            }
+        }
+    }
+
+    #[test]
+    fn a_long_report_spills_to_the_heap_intact() {
+        let levels: Vec<i32> = (100..150).collect();
+        let line = levels
+            .iter()
+            .map(|level| level.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut report = Report::<i32>::new();
+        LineParser::default()
+            .parse_line(line.as_bytes(), 1, &mut report)
+            .unwrap();
+        assert!(report.spilled());
+        assert_eq!(report.as_slice(), levels);
+        assert!(validate_sequence(&report, StepBounds::default()).is_valid());
+
+        // A spike near the end is still found, and the dampener still fixes it
+        report[45] += 40;
+        let ValidationResult::Invalid(violation) =
+            validate_sequence(&report, StepBounds::default())
+        else {
+            panic!("levels[45] jumps by 41");
+        };
+        assert_eq!(
+            violation,
+            Violation::JumpTooLarge {
+                index: 44,
+                diff: 41
+            }
+        );
+        assert_eq!(
+            dampener_fixes(&report, violation, StepBounds::default()).as_slice(),
+            [45]
+        );
+    }
+
+    /// The first violation found pair by pair on the levels, the way the
+    /// validator worked before it moved to differences.
+    fn pairwise_violation(levels: &[i32], bounds: StepBounds) -> Option<Violation> {
+        let increasing = levels.len() >= 2 && levels[0] < levels[1];
+        levels.windows(2).enumerate().find_map(|(index, pair)| {
+            let diff = pair[1] as i64 - pair[0] as i64;
+            if diff == 0 {
+                Some(Violation::Plateau { index })
+            } else if (diff > 0) != increasing {
+                Some(Violation::DirectionChange { index })
+            } else if diff.abs() < bounds.min as i64 {
+                Some(Violation::StepTooSmall { index, diff })
+            } else if diff.abs() > bounds.max as i64 {
+                Some(Violation::JumpTooLarge { index, diff })
+            } else {
+                None
+            }
+        })
+    }
+
+    #[test]
+    fn difference_validation_matches_checking_pairs() {
+        let bounds = StepBounds::default();
+        let mut counts = SafeCounts::default();
+        for line in EXAMPLE.lines() {
+            let levels: Vec<i32> = line.split(' ').map(|v| v.parse().unwrap()).collect();
+            assert_eq!(violation_of(&levels), pairwise_violation(&levels, bounds));
+            counts.part1 += brute_force_safe(&levels, bounds) as usize;
+            counts.part2 += brute_force_fixable(&levels, bounds, 1) as usize;
+        }
+        assert_eq!(counts, SafeCounts { part1: 2, part2: 4 });
+
+        let mut values = Values(0x2024_1234);
+        for round in 0..5000 {
+            let levels = values.walk(round % 16, 5);
+            let expected = pairwise_violation(&levels, bounds);
+            assert_eq!(violation_of(&levels), expected, "{:?}", levels);
+            let lazily = validate_levels(levels.iter().copied(), bounds);
+            assert_eq!(
+                lazily,
+                expected.map_or(ValidationResult::Valid, ValidationResult::Invalid)
+            );
+            if let Some(violation) = expected {
+                assert_eq!(
+                    validate_sequence_with_dampener(&levels, violation, bounds).is_valid(),
+                    brute_force_fixable(&levels, bounds, 1),
+                    "{:?}",
+                    levels
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dedupe_keeps_first_occurrences_and_counts_them() {
+        let mut reports = Vec::new();
+        for_each_report::<i32, ParseError>(include_str!("../../duplicates_input.txt"), |report| {
+            reports.push(report?.clone());
+            Ok(())
+        })
+        .unwrap();
+        let (unique, multiplicity) = dedupe(&reports);
+        let unique: Vec<&[i32]> = unique.iter().map(|report| report.as_slice()).collect();
+        assert_eq!(
+            unique,
+            [
+                &[7, 6, 4, 2, 1][..],
+                &[1, 3, 2, 4, 5],
+                &[1, 2, 7, 8, 9],
+                &[8, 6, 4, 4, 1],
+            ]
+        );
+        assert_eq!(multiplicity, [3, 2, 2, 1]);
+        assert_eq!(dedupe::<i32>(&[]), (vec![], vec![]));
+    }
+
+    #[test]
+    fn validates_the_edge_cases() {
+        // No pairs, so nothing to break
+        assert_eq!(violation_of(&[]), None);
+        assert_eq!(violation_of(&[7]), None);
+
+        for (diff, expected) in [
+            (1, None),
+            (3, None),
+            (-1, None),
+            (-3, None),
+            (0, Some(Violation::Plateau { index: 0 })),
+            (4, Some(Violation::JumpTooLarge { index: 0, diff: 4 })),
+            (-4, Some(Violation::JumpTooLarge { index: 0, diff: -4 })),
+        ] {
+            assert_eq!(violation_of(&[10, 10 + diff]), expected, "diff {}", diff);
+        }
+
+        assert_eq!(
+            violation_of(&[1, 2, 3, 3, 4, 5]),
+            Some(Violation::Plateau { index: 2 })
+        );
+        // The first pair sets the direction, even when the rest disagree
+        assert_eq!(
+            violation_of(&[5, 4, 5, 6, 7]),
+            Some(Violation::DirectionChange { index: 1 })
+        );
+        assert_eq!(
+            violation_of(&[1, 3, 5, 4]),
+            Some(Violation::DirectionChange { index: 2 })
+        );
+
+        let verdicts: Vec<bool> = EXAMPLE
+            .lines()
+            .map(|line| {
+                let levels: Vec<i32> = line.split(' ').map(|v| v.parse().unwrap()).collect();
+                violation_of(&levels).is_none()
+            })
+            .collect();
+        assert_eq!(verdicts, [true, false, false, false, false, true]);
+    }
+
+    /// Reports of up to 11 levels below 30, half of them sorted either way:
+    /// sorted levels are near-valid, so most of the dampener's choices,
+    /// including the direction of a bad first pair, matter.
+    fn near_valid_reports() -> impl Strategy<Value = Vec<i32>> {
+        let levels = || prop::collection::vec(0..30i32, 0..12);
+        prop_oneof![
+            2 => levels(),
+            1 => levels().prop_map(|mut levels| {
+                levels.sort_unstable();
+                levels
+            }),
+            1 => levels().prop_map(|mut levels| {
+                levels.sort_unstable_by(|a, b| b.cmp(a));
+                levels
+            }),
+        ]
+    }
+
+    proptest! {
+        // Many more cases than the default, unless `PROPTEST_CASES` asks
+        #![proptest_config(ProptestConfig {
+            cases: std::env::var_os("PROPTEST_CASES")
+                .map_or(20_000, |_| ProptestConfig::default().cases),
+            ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn dampener_agrees_with_brute_force(levels in near_valid_reports()) {
+            for bounds in both_rules() {
+                let dampened = match validate_sequence(&levels, bounds) {
+                    ValidationResult::Valid => true,
+                    ValidationResult::Invalid(violation) => {
+                        validate_sequence_with_dampener(&levels, violation, bounds).is_valid()
+                    }
+                };
+                prop_assert_eq!(
+                    dampened,
+                    brute_force_fixable(&levels, bounds, 1),
+                    "with {}",
+                    bounds
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn validates_any_level_type_and_lazy_levels() {
+        let bounds = StepBounds::default();
+        let narrow = [1, 3, 6, 7, 9];
+        let wide: Vec<i64> = narrow
+            .iter()
+            .map(|&level| level as i64 + (1 << 40))
+            .collect();
+        assert!(validate_sequence(&narrow, bounds).is_valid());
+        assert!(validate_sequence(&wide, bounds).is_valid());
+
+        let spiked = [1i64 << 40, (1 << 40) + 2, 1 << 41, (1 << 40) + 4];
+        let ValidationResult::Invalid(violation) = validate_sequence(&spiked, bounds) else {
+            panic!("{:?} jumps by 2^40", spiked);
+        };
+        assert_eq!(
+            violation,
+            Violation::JumpTooLarge {
+                index: 1,
+                diff: (1 << 40) - 2,
+            }
+        );
+        assert!(validate_sequence_with_dampener(&spiked, violation, bounds).is_valid());
+
+        // Levels made on demand, which stop being pulled at the first violation
+        let mut pulled = 0;
+        let levels = (0..).map(|i| {
+            pulled += 1;
+            if i == 3 {
+                100
+            } else {
+                i * 2
+            }
+        });
+        assert_eq!(
+            validate_levels(levels, bounds),
+            ValidationResult::Invalid(Violation::JumpTooLarge {
+                index: 2,
+                diff: 96i64
+            })
+        );
+        assert_eq!(pulled, 4);
+        assert!(validate_levels((0..1000).map(|i| -3 * i), bounds).is_valid());
+        assert!(validate_levels(std::iter::empty::<i32>(), bounds).is_valid());
+    }
+
+    #[test]
+    fn detects_the_delimiter_from_the_first_line_that_shows_one() {
+        assert_eq!(Delimiter::detect(b"7,6"), Some((Delimiter::Comma, 1)));
+        assert_eq!(Delimiter::detect(b" 7 , 6"), Some((Delimiter::Comma, 3)));
+        assert_eq!(Delimiter::detect(b"7,"), Some((Delimiter::Comma, 1)));
+        assert_eq!(Delimiter::detect(b"7 6"), Some((Delimiter::Whitespace, 1)));
+        assert_eq!(
+            Delimiter::detect(b"\t7\t6\r"),
+            Some((Delimiter::Whitespace, 2))
+        );
+        // One level or none reads the same either way
+        for line in [&b""[..], b"7", b"  7 \r", b" \t "] {
+            assert_eq!(Delimiter::detect(line), None, "{:?}", line);
+        }
+
+        let counts = Ok(SafeCounts { part1: 2, part2: 4 });
+        assert_eq!(count_safe(EXAMPLE), counts);
+        assert_eq!(
+            count_safe(include_str!("../../example_comma_input.txt")),
+            counts
+        );
+        // A single level can't set the delimiter, so the next line does
+        assert_eq!(count_safe("5\n7,6,4\n7 6 4\n").unwrap_err().line, 3);
+
+        let error = count_safe(include_str!("../../mixed_delimiters_input.txt")).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError {
+                line: 3,
+                columns: 2..3,
+                kind: ParseErrorKind::MixedDelimiters {
+                    expected: Delimiter::Whitespace,
+                    found: Delimiter::Comma,
+                    first_line: 1,
+                },
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "line 3, columns 2-2: comma-separated levels, but line 1 set the input as \
+             whitespace-separated"
+        );
+    }
+
+    #[test]
+    fn counts_safe_reports_in_a_whole_input() {
+        assert_eq!(count_safe(EXAMPLE), Ok(SafeCounts { part1: 2, part2: 4 }));
+        assert_eq!(count_safe(""), Ok(SafeCounts { part1: 0, part2: 0 }));
+        assert_eq!(
+            count_safe(include_str!("../puzzle_input.txt")),
+            Ok(SafeCounts {
+                part1: 516,
+                part2: 561,
+            })
+        );
+    }
+
+    #[test]
+    fn solves_reports_of_a_hundred_thousand_levels() {
+        let input = include_str!("../../long_reports_input.txt");
+        assert_eq!(count_safe(input), Ok(SafeCounts { part1: 1, part2: 2 }));
+
+        let bounds = StepBounds::default();
+        let mut reports = Vec::new();
+        for_each_report::<i64, ParseError>(input, |report| {
+            reports.push(report?.to_vec());
+            Ok(())
+        })
+        .unwrap();
+        assert!(reports.iter().all(|levels| levels.len() == 100_000));
+        let without = |levels: &[i64], plan: &[usize]| -> Vec<i32> {
+            (0..levels.len())
+                .filter(|i| !plan.contains(i))
+                .map(|i| levels[i] as i32)
+                .collect()
+        };
+        assert!(validate_sequence(&reports[0], bounds).is_valid());
+
+        // A spike in the middle, which only its own removal fixes
+        let ValidationResult::Invalid(spike) = validate_sequence(&reports[1], bounds) else {
+            panic!("the second report has a spike");
+        };
+        assert_eq!(
+            dampener_fixes(&reports[1], spike, bounds).as_slice(),
+            [50_000]
+        );
+        assert!(brute_force_safe(&without(&reports[1], &[50_000]), bounds));
+
+        // A plateau and a spike far apart: no single removal near either
+        // fixes it, and two removals do
+        let ValidationResult::Invalid(plateau) = validate_sequence(&reports[2], bounds) else {
+            panic!("the third report has a plateau");
+        };
+        assert_eq!(plateau, Violation::Plateau { index: 29_999 });
+        for skip in (29_998..=30_001).chain(69_999..=70_001) {
+            assert!(
+                !brute_force_safe(&without(&reports[2], &[skip]), bounds),
+                "{}",
+                skip
+            );
+        }
+        assert!(!validate_sequence_with_dampener(&reports[2], plateau, bounds).is_valid());
+        for removals in [2, 3] {
+            let plan = removal_plan(&reports[2], plateau, bounds, removals).unwrap();
+            assert_eq!(plan.len(), 2);
+            assert!(
+                brute_force_safe(&without(&reports[2], &plan), bounds),
+                "{:?}",
+                plan
+            );
+            let arena = Arena::new();
+            assert!(
+                validate_with_removals_in(&reports[2], plateau, bounds, removals, &arena)
+                    .is_valid()
+            );
+        }
+    }
+
+    #[test]
+    fn allowed_plateaus_take_the_direction_from_the_first_move() {
+        let [strict, plateaus] = both_rules();
+        for (levels, safe_with_plateaus) in [
+            (&[1, 1, 2, 3][..], true),
+            (&[1, 1, 1, 1], true),
+            (&[3, 3, 2, 1], true),
+            (&[3, 3, 4, 2], false),
+            (&[1, 1, 5], false),
+        ] {
+            assert!(
+                !validate_sequence(levels, strict).is_valid(),
+                "{:?}",
+                levels
+            );
+            assert_eq!(
+                validate_sequence(levels, plateaus).is_valid(),
+                safe_with_plateaus,
+                "{:?}",
+                levels
+            );
+        }
+        assert_eq!(
+            validate_sequence(&[3, 3, 4, 2], plateaus),
+            ValidationResult::Invalid(Violation::DirectionChange { index: 2 })
+        );
+
+        // The dampener follows the same rule
+        let levels = [1, 1, 2, 6];
+        for (bounds, fixes) in [(strict, &[][..]), (plateaus, &[3])] {
+            let ValidationResult::Invalid(violation) = validate_sequence(&levels, bounds) else {
+                panic!("{:?} jumps by 4", levels);
+            };
+            assert_eq!(dampener_fixes(&levels, violation, bounds).as_slice(), fixes);
+        }
+    }
+
+    #[test]
+    fn every_short_report_is_answered_like_brute_force() {
+        for bounds in both_rules() {
+            for levels in every_sequence(5, 1..=6) {
+                let (part1, part2) = match validate_sequence(&levels, bounds) {
+                    ValidationResult::Valid => (true, true),
+                    ValidationResult::Invalid(violation) => (
+                        false,
+                        validate_sequence_with_dampener(&levels, violation, bounds).is_valid(),
+                    ),
+                };
+                assert_eq!(part1, brute_force_safe(&levels, bounds), "{:?}", levels);
+                assert_eq!(
+                    part2,
+                    brute_force_fixable(&levels, bounds, 1),
+                    "{:?} with {}",
+                    levels,
+                    bounds
+                );
+            }
+        }
+    }
+
+    /// Parses `line` as the first line of an input.
+    fn parse_report<T: Level>(line: &str) -> Result<Vec<T>, ParseError> {
+        let mut report = Report::new();
+        LineParser::default().parse_line(line.as_bytes(), 1, &mut report)?;
+        Ok(report.to_vec())
+    }
+
+    #[test]
+    fn parses_negative_levels_up_to_the_limits() {
+        assert_eq!(parse_report("-5 -4 -2 -1"), Ok(vec![-5, -4, -2, -1]));
+        assert_eq!(parse_report("-3 0\t2 3"), Ok(vec![-3, 0, 2, 3]));
+        assert_eq!(parse_report("-3, 0 ,2,-1"), Ok(vec![-3, 0, 2, -1]));
+        assert_eq!(
+            parse_report("-2147483648 2147483647"),
+            Ok(vec![i32::MIN, i32::MAX])
+        );
+        assert_eq!(
+            parse_report::<i64>("-2147483649 2147483648"),
+            Ok(vec![-2_147_483_649, 2_147_483_648])
+        );
+
+        for (line, columns, kind) in [
+            (
+                "1 - 3",
+                3..4,
+                ParseErrorKind::InvalidNumber("-".to_string()),
+            ),
+            (
+                "4,-,5",
+                3..4,
+                ParseErrorKind::InvalidNumber("-".to_string()),
+            ),
+            (
+                "1 --2",
+                3..6,
+                ParseErrorKind::InvalidNumber("--2".to_string()),
+            ),
+            (
+                "1 2-3",
+                3..6,
+                ParseErrorKind::InvalidNumber("2-3".to_string()),
+            ),
+            (
+                "1 2147483648",
+                3..13,
+                ParseErrorKind::OutOfRange {
+                    token: "2147483648".to_string(),
+                    width: "i32",
+                },
+            ),
+            (
+                "1 -2147483649",
+                3..14,
+                ParseErrorKind::OutOfRange {
+                    token: "-2147483649".to_string(),
+                    width: "i32",
+                },
+            ),
+        ] {
+            assert_eq!(
+                parse_report::<i32>(line),
+                Err(ParseError {
+                    line: 1,
+                    columns,
+                    kind,
+                }),
+                "{:?}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn judges_steps_across_zero_and_at_the_limits() {
+        assert_eq!(violation_of(&[-1, 1, 3]), None);
+        assert_eq!(violation_of(&[2, 1, -1, -4]), None);
+        assert_eq!(violation_of(&[-3, 0, 2, 3]), None);
+        assert_eq!(
+            violation_of(&[1, -1, -5, -6]),
+            Some(Violation::JumpTooLarge { index: 1, diff: -4 })
+        );
+        // The difference doesn't fit an i32, and is kept exactly
+        assert_eq!(
+            violation_of(&[i32::MAX, i32::MIN, i32::MIN + 1]),
+            Some(Violation::JumpTooLarge {
+                index: 0,
+                diff: -4_294_967_295,
+            })
+        );
+        assert_eq!(violation_of(&[i32::MIN, i32::MIN + 1, i32::MIN + 3]), None);
+        assert_eq!(
+            count_safe(include_str!("../../negative_levels_input.txt")),
+            Ok(SafeCounts { part1: 5, part2: 7 })
+        );
+    }
+
+    #[test]
+    fn explains_steps_at_the_limits_by_their_exact_size() {
+        let explain = |numbers: &[i32]| {
+            let bounds = StepBounds::default();
+            let ValidationResult::Invalid(violation) = validate_sequence(numbers, bounds) else {
+                panic!("{:?} is unsafe", numbers);
+            };
+            Explanation {
+                numbers,
+                violation,
+                bounds,
+            }
+            .to_string()
+        };
+        assert_eq!(
+            explain(&[i32::MIN, i32::MAX]),
+            "levels[0]=-2147483648 → levels[1]=2147483647 increases by 4294967295, more than 3"
+        );
+        assert_eq!(
+            explain(&[i32::MAX, i32::MIN]),
+            "levels[0]=2147483647 → levels[1]=-2147483648 decreases by 4294967295, more than 3"
+        );
+        assert_eq!(
+            explain(&[1, 2, i32::MIN]),
+            "levels[1]=2 → levels[2]=-2147483648 decreases by 2147483650, \
+             but levels[0]=1 → levels[1]=2 increases"
+        );
+        assert_eq!(
+            explain(&[-3, -1, 2, 6]),
+            "levels[2]=2 → levels[3]=6 increases by 4, more than 3"
+        );
+    }
+
+    #[test]
+    fn the_widest_step_bound_still_rejects_jumps_past_i32() {
+        let widest = StepBounds {
+            max: i32::MAX,
+            ..StepBounds::default()
+        };
+        for (levels, diff) in [
+            ([i32::MIN, i32::MAX], 4_294_967_295),
+            ([5, i32::MIN], -2_147_483_653),
+        ] {
+            assert_eq!(
+                validate_sequence(&levels, widest),
+                ValidationResult::Invalid(Violation::JumpTooLarge { index: 0, diff })
+            );
+            // Parsed as i64, the same levels fail the same way
+            assert_eq!(
+                validate_sequence(&levels.map(i64::from), widest),
+                ValidationResult::Invalid(Violation::JumpTooLarge {
+                    index: 0,
+                    diff: diff.into(),
+                })
+            );
+        }
+        // A step of exactly the bound is allowed either way
+        assert!(validate_sequence(&[0, i32::MAX], widest).is_valid());
+        assert!(validate_sequence(&[i32::MAX, 0], widest).is_valid());
+        assert!(validate_sequence(&[-1, i32::MIN], widest).is_valid());
+    }
+
+    #[test]
+    fn finds_every_single_fix_of_the_example() {
+        let bounds = StepBounds::default();
+        let fixes: Vec<Vec<usize>> = EXAMPLE
+            .lines()
+            .map(|line| {
+                let levels: Vec<i32> = line.split(' ').map(|v| v.parse().unwrap()).collect();
+                match validate_sequence(&levels, bounds) {
+                    ValidationResult::Valid => vec![],
+                    ValidationResult::Invalid(violation) => {
+                        dampener_fixes(&levels, violation, bounds).to_vec()
+                    }
+                }
+            })
+            .collect();
+        assert_eq!(
+            fixes,
+            [vec![], vec![], vec![], vec![1, 2], vec![2, 3], vec![]]
+        );
+        // The dampener itself takes the lowest
+        let levels = [8, 6, 4, 4, 1];
+        let ValidationResult::Invalid(violation) = validate_sequence(&levels, bounds) else {
+            panic!("{:?} has a plateau", levels);
+        };
+        assert_eq!(removal_plan(&levels, violation, bounds, 1), Some(vec![2]));
+    }
+
+    #[test]
+    fn totals_the_counts_of_several_inputs() {
+        let inputs = [EXAMPLE, include_str!("../../duplicates_input.txt")];
+        let counts: Vec<SafeCounts> = inputs
+            .iter()
+            .map(|input| count_safe(input).unwrap())
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                SafeCounts { part1: 2, part2: 4 },
+                SafeCounts { part1: 3, part2: 6 },
+            ]
+        );
+        assert_eq!(
+            counts.iter().copied().sum::<SafeCounts>(),
+            SafeCounts {
+                part1: 5,
+                part2: 10
+            }
+        );
+        assert_eq!(
+            std::iter::empty().sum::<SafeCounts>(),
+            SafeCounts::default()
+        );
+    }
+
+    #[test]
+    fn counts_the_first_violations_of_a_known_mix() {
+        let mut kinds = [0; 4];
+        for line in include_str!("../../violations_input.txt").lines() {
+            let levels: Vec<i32> = line.split(' ').map(|l| l.parse().unwrap()).collect();
+            let kind = match violation_of(&levels) {
+                None => continue,
+                Some(Violation::Plateau { .. }) => 0,
+                Some(Violation::StepTooSmall { .. }) => 1,
+                Some(Violation::JumpTooLarge { .. }) => 2,
+                Some(Violation::DirectionChange { .. }) => 3,
+            };
+            kinds[kind] += 1;
+        }
+        assert_eq!(kinds, [2, 0, 3, 1]);
+    }
+
+    #[test]
+    fn the_dampener_removes_the_lowest_level_that_works() {
+        for bounds in both_rules() {
+            for levels in every_sequence(6, 1..=6) {
+                let ValidationResult::Invalid(violation) = validate_sequence(&levels, bounds)
+                else {
+                    continue;
+                };
+                assert_eq!(
+                    removal_plan(&levels, violation, bounds, 1),
+                    dampener_fixes(&levels, violation, bounds)
+                        .first()
+                        .map(|&skip| vec![skip]),
+                    "{:?} under {:?}",
+                    levels,
+                    bounds
+                );
+            }
+        }
+
+        let bounds = StepBounds::default();
+        let mut histogram = Vec::new();
+        for line in include_str!("../../removal_index_input.txt").lines() {
+            let levels: Vec<i32> = line.split(' ').map(|l| l.parse().unwrap()).collect();
+            let ValidationResult::Invalid(violation) = validate_sequence(&levels, bounds) else {
+                continue;
+            };
+            if let Some(plan) = removal_plan(&levels, violation, bounds, 1) {
+                if histogram.len() <= plan[0] {
+                    histogram.resize(plan[0] + 1, 0);
+                }
+                histogram[plan[0]] += 1;
+            }
+        }
+        assert_eq!(histogram, [1, 2, 2, 1, 1]);
+    }
+
+    #[test]
+    fn merges_around_a_removed_level_at_the_i32_limits() {
+        // Neither difference fits an i32, nor does the merged one
+        let levels = [i32::MIN, 0, i32::MIN];
+        for bounds in both_rules() {
+            let ValidationResult::Invalid(violation) = validate_sequence(&levels, bounds) else {
+                panic!("{:?} jumps", levels);
+            };
+            let fixed = validate_with_removals(&levels, violation, bounds, 1).is_valid();
+            assert_eq!(fixed, bounds.allow_plateaus, "{}", bounds);
+        }
+
+        let edges = [i32::MIN, -3, 0, 1, 4, i32::MAX];
+        for bounds in both_rules() {
+            for report in every_sequence(5, 0..=5) {
+                let levels: Vec<i32> = report.iter().map(|&i| edges[i as usize]).collect();
+                assert_skips_match_copies(&levels, bounds);
+            }
+        }
+    }
+
+    /// `validate_sequence` as it was before steps were oriented, kept as an
+    /// oracle: each direction has its own range check, on exact differences.
+    fn two_ranges(levels: &[i32], bounds: StepBounds) -> ValidationResult {
+        let (min, max) = (bounds.min as i64, bounds.max as i64);
+        let mut increasing = None;
+        for (index, pair) in levels.windows(2).enumerate() {
+            let exact = pair[1] as i64 - pair[0] as i64;
+            let diff = pair[0].step_to(pair[1]);
+            if exact == 0 {
+                if bounds.allow_plateaus {
+                    continue;
+                }
+                return ValidationResult::Invalid(Violation::Plateau { index });
+            }
+            let violation = if *increasing.get_or_insert(exact > 0) {
+                if exact > 0 && exact >= min && exact <= max {
+                    continue;
+                }
+                if exact < 0 {
+                    Violation::DirectionChange { index }
+                } else if exact < min {
+                    Violation::StepTooSmall { index, diff }
+                } else {
+                    Violation::JumpTooLarge { index, diff }
+                }
+            } else {
+                if exact < 0 && exact <= -min && exact >= -max {
+                    continue;
+                }
+                if exact > 0 {
+                    Violation::DirectionChange { index }
+                } else if exact > -min {
+                    Violation::StepTooSmall { index, diff }
+                } else {
+                    Violation::JumpTooLarge { index, diff }
+                }
+            };
+            return ValidationResult::Invalid(violation);
+        }
+        ValidationResult::Valid
+    }
+
+    #[test]
+    fn oriented_steps_validate_like_two_directions() {
+        let mut rules = both_rules().to_vec();
+        for (min, max) in [(2, 4), (1, 1)] {
+            for allow_plateaus in [false, true] {
+                rules.push(StepBounds {
+                    min,
+                    max,
+                    allow_plateaus,
+                });
+            }
+        }
+        // Small levels, and the extremes, whose steps don't fit an i32
+        let edges = [i32::MIN, -3, 0, 1, 4, i32::MAX];
+        let mut reports = every_sequence(6, 1..=6);
+        reports.extend(
+            every_sequence(6, 0..=5)
+                .into_iter()
+                .map(|report| report.iter().map(|&i| edges[i as usize]).collect()),
+        );
+        for bounds in rules {
+            for levels in &reports {
+                let expected = two_ranges(levels, bounds);
+                assert_eq!(
+                    validate_sequence(levels, bounds),
+                    expected,
+                    "{:?} under {:?}",
+                    levels,
+                    bounds
+                );
+                let ValidationResult::Invalid(violation) = expected else {
+                    continue;
+                };
+                let fixable = (0..levels.len()).any(|skip| {
+                    let mut rest = levels.clone();
+                    rest.remove(skip);
+                    two_ranges(&rest, bounds).is_valid()
+                });
+                assert_eq!(
+                    validate_sequence_with_dampener(levels, violation, bounds).is_valid(),
+                    fixable,
+                    "{:?} under {:?}",
+                    levels,
+                    bounds
+                );
+            }
+        }
+        for line in EXAMPLE.lines() {
+            let levels = parse_report::<i32>(line).unwrap();
+            assert_eq!(
+                validate_sequence(&levels, StepBounds::default()),
+                two_ranges(&levels, StepBounds::default())
+            );
+        }
+    }
+
+    #[test]
+    fn the_bitmask_path_agrees_with_the_scan() {
+        let edges = [i32::MIN, -3, 0, 1, 4, i32::MAX];
+        let mut reports = every_sequence(5, 1..=6);
+        reports.extend(
+            every_sequence(5, 0..=5)
+                .into_iter()
+                .map(|report| report.iter().map(|&i| edges[i as usize]).collect()),
+        );
+        let wider = StepBounds {
+            min: 2,
+            max: 4,
+            allow_plateaus: false,
+        };
+        for bounds in both_rules().into_iter().chain([wider]) {
+            for levels in &reports {
+                let ValidationResult::Invalid(violation) = validate_sequence(levels, bounds) else {
+                    continue;
+                };
+                assert_eq!(
+                    masked_fix(levels, bounds.into()),
+                    dampener_removal(levels, violation, bounds).is_some(),
+                    "{:?} with {}",
+                    levels,
+                    bounds
+                );
+            }
+        }
+    }
+}
@@ -1,140 +1,496 @@
-//! Day 2: Red-Nosed Reports - Parts 1 & 2
-//!
-//! This solution validates sequences with two different rule sets:
-//! Part 1: Basic validation of strictly increasing/decreasing sequences
-//! Part 2: Allows removal of one number to make an invalid sequence valid
-//!
-//! Performance optimizations:
-//! - Inline validation functions
-//! - Early returns for known cases
-//! - Reuse of vectors to minimize allocations
-//! - Efficient sequence checking with windows()
-//! - For Part 2: Skip rechecking already valid sequences
-//!
-//! Time Complexity (per sequence):
-//! - Part 1: O(k) where k is sequence length
-//! - Part 2: O(k²) due to checking each possible number removal
-//!
-//! Space Complexity:
-//! - O(k) for temporary vector in Part 2 validation
-
+use aoc_common::{
+    day_reporter, fmt_count_rate, fmt_duration, init_parallelism, parallelism, parse_args,
+    scratch_peak, time_block, with_scratch, AocError, Config, DayArgs, MetricsOutput, OutputStyle,
+    Part, Reporter, Solver,
+};
+use clap::Parser;
+use day_02::{
+    dampener_fixes, dedupe, removal_plan, skipped_lines_note, validate_sequence,
+    validate_with_removals, validate_with_removals_in, Day02, Explanation, Level, LineParser,
+    ParseError, Report, StepBounds, ValidationResult, Violation,
+};
+use rayon::prelude::*;
+use serde::{Serialize, Serializer};
+use std::alloc::{GlobalAlloc, Layout, System};
 use std::fs::File;
-use std::io::{self, BufRead, BufReader};
-use std::time::Instant;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// Represents the validation result of a sequence.
-/// Using an enum rather than bool provides more clarity and extensibility.
-#[derive(Debug, PartialEq)]
-enum ValidationResult {
-    /// Sequence follows all safety rules
-    Valid,
-    /// Sequence violates at least one safety rule
-    Invalid,
-}
+/// Heap allocations made by the process so far
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
 
-/// Validates if a sequence of numbers follows the reactor safety rules.
-///
-/// # Rules
-/// 1. Numbers must be strictly increasing or decreasing (no plateaus)
-/// 2. Adjacent differences must be between 1 and 3 inclusive
-///
-/// # Implementation Details
-/// - Uses windows(2) for efficient pairwise comparison
-/// - Determines direction (increasing/decreasing) from first pair
-/// - Early returns for invalid cases to avoid unnecessary computation
-///
-/// # Arguments
-/// * `numbers` - Slice of integers representing the sequence to validate
-///
-/// # Returns
-/// * `ValidationResult::Valid` if sequence follows all rules
-/// * `ValidationResult::Invalid` if any rule is violated
-///
-/// # Time Complexity
-/// O(n) where n is length of sequence
-///
-/// # Space Complexity
-/// O(1) - only uses a few variables regardless of input size
-#[inline]
-fn validate_sequence(numbers: &[i32]) -> ValidationResult {
-    if numbers.len() < 2 {
-        return ValidationResult::Valid;
+/// System allocator wrapper that counts allocations, so the metrics can
+/// show that the dampener validates candidates without allocating.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
     }
 
-    // Check first difference to determine if sequence should increase or decrease
-    let first_diff = numbers[1] - numbers[0];
-    if first_diff == 0 || first_diff.abs() > 3 {
-        return ValidationResult::Invalid;
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
     }
 
-    let should_increase = first_diff > 0;
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
 
-    // Use windows to compare adjacent pairs
-    numbers
-        .windows(2)
-        .skip(1)
-        .all(|pair| {
-            let diff = pair[1] - pair[0];
-            if should_increase {
-                diff > 0 && diff <= 3
-            } else {
-                diff < 0 && diff >= -3
-            }
-        })
-        .then(|| ValidationResult::Valid)
-        .unwrap_or(ValidationResult::Invalid)
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
 }
 
-/// Validates a sequence allowing one number to be removed to make it valid.
-///
-/// # Implementation Details
-/// - First checks if sequence is already valid
-/// - If invalid, tries removing each number once and validates resulting sequence
-/// - Uses a reusable vector to minimize allocations
-/// - Early returns on first valid combination found
-///
-/// # Arguments
-/// * `numbers` - Original sequence to validate
-///
-/// # Returns
-/// * `ValidationResult::Valid` if sequence is valid or can be made valid by removing one number
-/// * `ValidationResult::Invalid` otherwise
-///
-/// # Time Complexity
-/// O(n²) where n is sequence length
-#[inline]
-fn validate_sequence_with_dampener(numbers: &[i32]) -> ValidationResult {
-    // Only called if sequence is already invalid from Part 1, so we can skip that check
-
-    // Try removing each number once
-    let mut temp_sequence = Vec::with_capacity(numbers.len() - 1);
-    for skip_idx in 0..numbers.len() {
-        temp_sequence.clear();
-        temp_sequence.extend(
-            numbers
-                .iter()
-                .enumerate()
-                .filter(|(i, _)| *i != skip_idx)
-                .map(|(_, &n)| n),
-        );
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Day 2: Red-Nosed Reports. Counts the reports whose levels move steadily
+/// in one direction, then those the Problem Dampener can fix.
+#[derive(Debug, Parser)]
+#[command(name = "day-02")]
+struct Cli {
+    #[command(flatten)]
+    common: DayArgs,
+    /// Smallest allowed difference between adjacent levels
+    #[arg(long, value_name = "N", allow_negative_numbers = true,
+          default_value_t = StepBounds::default().min)]
+    min_step: i32,
+    /// Largest allowed difference between adjacent levels
+    #[arg(long, value_name = "N", allow_negative_numbers = true,
+          default_value_t = StepBounds::default().max)]
+    max_step: i32,
+    /// Let equal adjacent levels through
+    #[arg(long)]
+    allow_plateaus: bool,
+    /// Levels the dampener may remove from each report
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_DAMPENER_REMOVALS)]
+    dampener: usize,
+    /// Validate on the calling thread instead of the rayon pool
+    #[arg(long)]
+    serial: bool,
+    /// Validate each report as it is read instead of buffering
+    #[arg(long)]
+    stream: bool,
+    /// Parse on a reader thread while validating on another
+    #[arg(long)]
+    pipeline: bool,
+    /// Print why each unsafe report failed
+    #[arg(long)]
+    explain: bool,
+    /// Print the line numbers of unsafe reports
+    #[arg(long)]
+    list_unsafe: bool,
+    /// Print what the dampener removed from each report it fixed
+    #[arg(long)]
+    show_fixes: bool,
+    /// Count dampener fixes by the index of the level they remove
+    #[arg(long)]
+    removal_stats: bool,
+    /// Validate each distinct report once
+    #[arg(long)]
+    dedupe: bool,
+    /// Parse and validate levels as i64 instead of i32
+    #[arg(long)]
+    wide: bool,
+    /// Skip malformed lines instead of failing on the first one
+    #[arg(long, overrides_with = "strict")]
+    lenient: bool,
+    /// Fail on the first malformed line, the default
+    #[arg(long, overrides_with = "lenient")]
+    strict: bool,
+}
+
+/// Command-line options for a run, checked for conflicts.
+#[derive(Debug, Default)]
+struct Args {
+    /// Input paths, `-` for stdin, or none for the default location; each
+    /// is validated independently
+    inputs: Vec<String>,
+    /// Allowed adjacent difference magnitudes (`--min-step N`, `--max-step N`),
+    /// and whether equal levels pass (`--allow-plateaus`)
+    bounds: StepBounds,
+    /// Levels the dampener may remove from each report (`--dampener N`)
+    dampener: usize,
+    /// Validate on the calling thread instead of the rayon pool (`--serial`,
+    /// or a single thread)
+    serial: bool,
+    /// Validate each report as it is read instead of buffering (`--stream`)
+    stream: bool,
+    /// Parse on a reader thread while validating on another (`--pipeline`)
+    pipeline: bool,
+    /// Print why each unsafe report failed (`--explain`)
+    explain: bool,
+    /// Print the line numbers of unsafe reports (`--list-unsafe`)
+    list_unsafe: bool,
+    /// Print what the dampener removed from each report it fixed
+    /// (`--show-fixes`)
+    show_fixes: bool,
+    /// Count dampener fixes by the index of the level they remove
+    /// (`--removal-stats`)
+    removal_stats: bool,
+    /// Validate each distinct report once (`--dedupe`)
+    dedupe: bool,
+    /// Answers to compute (`--part 1|2|both`)
+    part: Part,
+    /// Parse and validate levels as `i64` instead of `i32` (`--wide`)
+    wide: bool,
+    /// Where to write the metrics as JSON (`--metrics-json [PATH]`)
+    metrics_json: Option<MetricsOutput>,
+    /// Print only the answers (`--quiet`, or `$AOC_OUTPUT`)
+    output: OutputStyle,
+    /// Skip malformed lines instead of failing on the first one
+    /// (`--lenient`; `--strict`, the default, turns it back off)
+    lenient: bool,
+}
+
+/// Removals allowed by the Problem Dampener in the puzzle's Part 2
+const DEFAULT_DAMPENER_REMOVALS: usize = 1;
 
-        if matches!(validate_sequence(&temp_sequence), ValidationResult::Valid) {
-            return ValidationResult::Valid;
+impl Args {
+    /// Checks the parsed options against each other.
+    ///
+    /// # Returns
+    /// The options, with the settings given as flags for `Config::load`.
+    fn from_cli(cli: Cli) -> Result<(Self, Config), AocError> {
+        let flags = cli.common.config_flags();
+        let parsed = Args {
+            output: cli.common.output_style()?,
+            inputs: cli.common.inputs,
+            bounds: StepBounds {
+                min: cli.min_step,
+                max: cli.max_step,
+                allow_plateaus: cli.allow_plateaus,
+            },
+            dampener: cli.dampener,
+            serial: cli.serial,
+            stream: cli.stream,
+            pipeline: cli.pipeline,
+            explain: cli.explain,
+            list_unsafe: cli.list_unsafe,
+            show_fixes: cli.show_fixes,
+            removal_stats: cli.removal_stats,
+            dedupe: cli.dedupe,
+            part: cli.common.part,
+            wide: cli.wide,
+            metrics_json: cli.common.metrics_json,
+            lenient: cli.lenient,
+        };
+
+        parsed
+            .bounds
+            .check()
+            .map_err(|e| AocError::BadArgument(e.describe("--min-step", "--max-step")))?;
+        if parsed.output == OutputStyle::Quiet && parsed.records_unsafe() {
+            return Err(AocError::BadArgument(
+                "--quiet hides the report listings and can't be used with --explain, --list-unsafe, or --show-fixes"
+                    .to_string(),
+            ));
+        }
+        if parsed.inputs.len() > 1 && parsed.records_unsafe() {
+            return Err(AocError::BadArgument(
+                "--explain, --list-unsafe, and --show-fixes take a single input".to_string(),
+            ));
+        }
+        if parsed.show_fixes && !parsed.part.runs_part2() {
+            return Err(AocError::BadArgument(
+                "--show-fixes lists dampener fixes and can't be used with --part 1".to_string(),
+            ));
+        }
+        if parsed.removal_stats && !parsed.part.runs_part2() {
+            return Err(AocError::BadArgument(
+                "--removal-stats counts dampener fixes and can't be used with --part 1".to_string(),
+            ));
+        }
+        if parsed.dedupe && parsed.stream {
+            return Err(AocError::BadArgument(
+                "--dedupe needs every report in memory and can't be used with --stream".to_string(),
+            ));
+        }
+        if parsed.pipeline && (parsed.stream || parsed.dedupe) {
+            return Err(AocError::BadArgument(
+                "--pipeline can't be combined with --stream or --dedupe".to_string(),
+            ));
         }
+        Ok((parsed, flags))
+    }
+
+    /// Whether each unsafe report is looked at again for a listing
+    fn records_unsafe(&self) -> bool {
+        self.explain || self.list_unsafe || self.show_fixes
+    }
+}
+
+/// Names the input a run reads, `-` for standard input.
+///
+/// Reads from standard input when the argument is `-`, or when no argument
+/// is given and stdin is piped. Otherwise uses the given path, or without
+/// one the first input `aoc_common::input::resolve` finds for this day.
+fn input_name(arg: Option<&str>, config: &Config) -> Result<String, AocError> {
+    let explicit = match arg {
+        Some("-") => return Ok("-".to_string()),
+        None if !io::stdin().is_terminal() => return Ok("-".to_string()),
+        explicit => explicit.map(Path::new),
+    };
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let path = aoc_common::input::resolve(Day02::DAY, explicit, crate_dir, config)?;
+    Ok(path.display().to_string())
+}
+
+/// Opens the puzzle input named by `input_name`.
+fn open_input(path: &str) -> io::Result<Box<dyn BufRead + Send>> {
+    if path == "-" {
+        return Ok(Box::new(BufReader::new(io::stdin())));
     }
 
-    ValidationResult::Invalid
+    let file = File::open(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("failed to open {}: {}", path, e)))?;
+    Ok(Box::new(BufReader::new(file)))
 }
 
 /// Performance metrics for each part of the solution
-#[derive(Default, Debug)]
+///
+/// Serialized for `--metrics-json`, with the phase timings flattened in by
+/// `PhaseTimes`.
+#[derive(Default, Debug, Serialize)]
 struct Metrics {
-    file_read_time: std::time::Duration,
-    parsing_time: std::time::Duration,
-    combined_validation_time: std::time::Duration,
+    /// Durations of `read`, `parse`, `part1`, `part2`, and the custom
+    /// `DEDUPE`, `READER_BLOCKED`, `VALIDATOR_WORKING` and
+    /// `VALIDATOR_BLOCKED` phases
+    #[serde(skip)]
+    timings: aoc_common::Metrics,
+    /// Heap allocations made during Part 2
+    part2_allocations: usize,
+    /// Most bytes any thread's scratch arena held for one report's
+    /// dampener run
+    part2_arena_peak: usize,
     total_sequences: usize,
+    /// Sequences that failed Part 1 and went on to the dampener
+    dampened_sequences: usize,
+    validation_threads: usize,
     part1_valid_count: usize,
     part2_valid_count: usize,
+    /// Reports that failed Part 1 and were made safe by the dampener, so
+    /// `part2_valid_count` is always `part1_valid_count` plus this
+    dampener_fixed_count: usize,
+    /// Lines of reports unsafe under Part 1, filled for `--list-unsafe`
+    part1_unsafe_lines: Vec<usize>,
+    /// Lines of reports the dampener couldn't fix, filled for `--list-unsafe`
+    part2_unsafe_lines: Vec<usize>,
+    /// Reports counted by their number of levels, indexed by that number
+    length_histogram: Vec<usize>,
+    /// Part 1 failures counted by the rule their first violation broke
+    violation_counts: ViolationCounts,
+    /// Reports fixed by removing one level, counted by the lowest index
+    /// that works, filled for `--removal-stats`
+    removal_histogram: Vec<usize>,
+    /// Reports only fixed by removing several levels, filled for
+    /// `--removal-stats`
+    multiple_removal_fixes: usize,
+    /// Distinct reports validated with `--dedupe`
+    unique_sequences: usize,
+    /// Dampener runs saved by counting repeated failures once with `--dedupe`
+    skipped_dampener_runs: usize,
+    /// Malformed lines skipped with `--lenient`
+    skipped_line_count: usize,
+    /// Line numbers of the skipped lines, in input order
+    skipped_lines: Vec<usize>,
+}
+
+/// Counts a parsed report of `len` levels in `Metrics::length_histogram`.
+///
+/// Takes the histogram alone so it can be updated while a phase guard
+/// borrows the timings.
+#[inline]
+fn record_length(histogram: &mut Vec<usize>, len: usize) {
+    if histogram.len() <= len {
+        histogram.resize(len + 1, 0);
+    }
+    histogram[len] += 1;
+}
+
+impl Metrics {
+    /// Counts, for `--removal-stats`, what the dampener removes to fix a
+    /// report that failed Part 1 with `violation`, as `weight` reports.
+    ///
+    /// Untimed, since it finds every single removal that works rather
+    /// than stopping at the first.
+    fn record_removal<T: Level>(
+        &mut self,
+        numbers: &[T],
+        violation: Violation<T>,
+        args: &Args,
+        weight: usize,
+    ) {
+        if let Some(&index) = dampener_fixes(numbers, violation, args.bounds).first() {
+            if self.removal_histogram.len() <= index {
+                self.removal_histogram.resize(index + 1, 0);
+            }
+            self.removal_histogram[index] += weight;
+        } else if args.dampener > 1
+            && validate_with_removals(numbers, violation, args.bounds, args.dampener).is_valid()
+        {
+            self.multiple_removal_fixes += weight;
+        }
+    }
+
+    /// Shortest and longest report lengths seen, if any report was parsed
+    fn length_range(&self) -> Option<(usize, usize)> {
+        let mut lengths =
+            (0..self.length_histogram.len()).filter(|&len| self.length_histogram[len] > 0);
+        let min = lengths.next()?;
+        Some((min, lengths.next_back().unwrap_or(min)))
+    }
+
+    /// Mean number of levels per report
+    fn mean_length(&self) -> f64 {
+        let levels: usize = self
+            .length_histogram
+            .iter()
+            .enumerate()
+            .map(|(len, count)| len * count)
+            .sum();
+        levels as f64 / self.total_sequences as f64
+    }
+}
+
+/// Time spent grouping identical reports for `--dedupe`
+const DEDUPE: &str = "Deduplication";
+/// Time the `--pipeline` reader spent waiting for a free batch; its
+/// working time is the `parse` phase
+const READER_BLOCKED: &str = "Reader blocked";
+/// Time the `--pipeline` validator spent validating and counting
+const VALIDATOR_WORKING: &str = "Validator working";
+/// Time the `--pipeline` validator spent waiting for parsed reports
+const VALIDATOR_BLOCKED: &str = "Validator blocked";
+
+/// Reports that failed Part 1, counted by the kind of their first
+/// violation, so every failure is counted exactly once.
+#[derive(Default, Debug, Serialize)]
+struct ViolationCounts {
+    plateau: usize,
+    step_too_small: usize,
+    jump_too_large: usize,
+    direction_change: usize,
+}
+
+impl ViolationCounts {
+    /// Counts `weight` reports that first failed with `violation`.
+    #[inline]
+    fn record<T: Level>(&mut self, violation: Violation<T>, weight: usize) {
+        let count = match violation {
+            Violation::Plateau { .. } => &mut self.plateau,
+            Violation::StepTooSmall { .. } => &mut self.step_too_small,
+            Violation::JumpTooLarge { .. } => &mut self.jump_too_large,
+            Violation::DirectionChange { .. } => &mut self.direction_change,
+        };
+        *count += weight;
+    }
+
+    /// Each kind with its count, in the order they are printed
+    fn by_kind(&self) -> [(&'static str, usize); 4] {
+        [
+            ("plateau", self.plateau),
+            ("step too small", self.step_too_small),
+            ("jump too large", self.jump_too_large),
+            ("direction change", self.direction_change),
+        ]
+    }
+}
+
+/// Serializes a duration as its whole number of nanoseconds.
+fn nanoseconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_nanos())
+}
+
+/// Phase timings as `--metrics-json` fields, in whole nanoseconds.
+#[derive(Serialize)]
+struct PhaseTimes {
+    #[serde(serialize_with = "nanoseconds")]
+    file_read_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    parsing_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    part1_validation_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    part2_validation_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    dedupe_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    reader_blocked_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    validator_working_time: Duration,
+    #[serde(serialize_with = "nanoseconds")]
+    validator_blocked_time: Duration,
+}
+
+impl PhaseTimes {
+    fn of(timings: &aoc_common::Metrics) -> Self {
+        PhaseTimes {
+            file_read_time: timings.elapsed("read"),
+            parsing_time: timings.elapsed("parse"),
+            part1_validation_time: timings.elapsed("part1"),
+            part2_validation_time: timings.elapsed("part2"),
+            dedupe_time: timings.elapsed(DEDUPE),
+            reader_blocked_time: timings.elapsed(READER_BLOCKED),
+            validator_working_time: timings.elapsed(VALIDATOR_WORKING),
+            validator_blocked_time: timings.elapsed(VALIDATOR_BLOCKED),
+        }
+    }
+}
+
+/// The JSON object written by `--metrics-json`.
+#[derive(Serialize)]
+struct MetricsReport<'a> {
+    /// Input path, `-` for stdin
+    input: &'a str,
+    /// Seconds since the Unix epoch when the run finished
+    timestamp: u64,
+    /// Part 1 answer, `null` if `--part` skipped it
+    part1: Option<usize>,
+    /// Part 2 answer, `null` if `--part` skipped it
+    part2: Option<usize>,
+    #[serde(serialize_with = "nanoseconds")]
+    total_time: Duration,
+    #[serde(flatten)]
+    times: PhaseTimes,
+    #[serde(flatten)]
+    metrics: &'a Metrics,
+}
+
+/// Writes the metrics of one input as one JSON object on one line.
+fn write_metrics_json(
+    writer: &mut dyn Write,
+    input: &str,
+    args: &Args,
+    metrics: &Metrics,
+    total_time: Duration,
+) -> Result<(), AocError> {
+    let report = MetricsReport {
+        input,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs()),
+        part1: args
+            .part
+            .reports_part1()
+            .then_some(metrics.part1_valid_count),
+        part2: args.part.runs_part2().then_some(metrics.part2_valid_count),
+        total_time,
+        times: PhaseTimes::of(&metrics.timings),
+        metrics,
+    };
+    serde_json::to_writer(&mut *writer, &report).map_err(io::Error::from)?;
+    writeln!(writer)?;
+    Ok(())
 }
 
 /// Main function that reads and processes the reactor safety report.
@@ -145,100 +501,905 @@ struct Metrics {
 /// - Processes file line by line to minimize memory usage
 ///
 /// # Error Handling
-/// Returns IO errors if file operations fail
+/// Errors are printed to stderr and the process exits nonzero: 2 for bad
+/// usage and 1 for anything else.
 ///
 /// # Time Complexity
 /// O(n) where n is file size
 ///
 /// # Space Complexity
 /// O(m) where m is maximum line length
-fn main() -> io::Result<()> {
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            e.exit_code()
+        }
+    }
+}
+
+/// Reads the input, validates every report, and prints the results.
+///
+/// With several inputs, each is solved on its own with its own metrics,
+/// and `run_many` prints one line per input and the totals instead.
+fn run() -> Result<ExitCode, AocError> {
     let start = Instant::now();
+    let (mut args, flags) = Args::from_cli(parse_args()?)?;
+    let config = Config::load(Path::new(env!("CARGO_MANIFEST_DIR")), flags)?;
+    // Sizes the pool the parallel validation passes run on; `--serial`
+    // is one thread whatever else asks for more
+    init_parallelism(if args.serial {
+        Some(1)
+    } else {
+        config.threads.map(NonZeroUsize::get)
+    });
+    args.serial = parallelism() == 1;
+    let mut json = args
+        .metrics_json
+        .as_ref()
+        .map(MetricsOutput::open)
+        .transpose()?;
+    let mut out = day_reporter(args.output, args.metrics_json.as_ref());
+    if args.inputs.len() > 1 {
+        return run_many(&args, json, start, &mut *out);
+    }
+
+    let input = input_name(args.inputs.first().map(String::as_str), &config)?;
+    let metrics = solve_input(&input, &args, &mut *out)?;
+    if let Some(warning) = skipped_warning(&metrics) {
+        eprintln!("Warning: {}", warning);
+    }
+    report_summary(&args, &metrics, start, &mut *out);
+    if let Some(writer) = json.as_deref_mut() {
+        write_metrics_json(writer, &input, &args, &metrics, start.elapsed())?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Solves each of several inputs independently, reporting one line per
+/// input and then the totals.
+///
+/// An input that fails to open or parse is reported and the rest still
+/// run; the run then exits nonzero. With `--metrics-json`, each input that
+/// succeeds gets its own JSON line. The lines are notes, so a quiet run
+/// prints nothing but the JSON.
+fn run_many(
+    args: &Args,
+    mut json: Option<Box<dyn Write>>,
+    start: Instant,
+    out: &mut dyn Reporter,
+) -> Result<ExitCode, AocError> {
+    let mut totals = Metrics::default();
+    let mut failures = 0;
+    out.section("Files");
+    for input in &args.inputs {
+        let input_start = Instant::now();
+        let metrics = match solve_input(input, args, out) {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                eprintln!("Error: {}: {}", input, e);
+                failures += 1;
+                continue;
+            }
+        };
+        let elapsed = input_start.elapsed();
+        if let Some(warning) = skipped_warning(&metrics) {
+            eprintln!("Warning: {}: {}", input, warning);
+        }
+        out.note(&format!(
+            "{}: {}, {}",
+            input,
+            answers(args, &metrics),
+            fmt_duration(elapsed)
+        ));
+        if let Some(writer) = json.as_deref_mut() {
+            write_metrics_json(writer, input, args, &metrics, elapsed)?;
+        }
+        totals.total_sequences += metrics.total_sequences;
+        totals.part1_valid_count += metrics.part1_valid_count;
+        totals.part2_valid_count += metrics.part2_valid_count;
+    }
+
+    out.section("Totals");
+    out.note(&format!(
+        "Files: {} ({} failed)",
+        args.inputs.len(),
+        failures
+    ));
+    out.note(&format!("Safe reports: {}", answers(args, &totals)));
+    out.note(&format!(
+        "Total sequences processed: {}",
+        totals.total_sequences
+    ));
+    let total = start.elapsed();
+    out.phase("Total", total);
+    out.note(&format!(
+        "Throughput: {}",
+        fmt_count_rate(totals.total_sequences as u64, total, "reports")
+    ));
+    Ok(if failures > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// The answers `--part` asks for, as `Part 1 N, Part 2 M`.
+fn answers(args: &Args, metrics: &Metrics) -> String {
+    let mut parts = Vec::new();
+    if args.part.reports_part1() {
+        parts.push(format!("Part 1 {}", metrics.part1_valid_count));
+    }
+    if args.part.runs_part2() {
+        parts.push(format!("Part 2 {}", metrics.part2_valid_count));
+    }
+    parts.join(", ")
+}
+
+/// Opens and solves one input, returning its metrics and reporting any
+/// listing of its reports to `out` as it goes.
+fn solve_input(input: &str, args: &Args, out: &mut dyn Reporter) -> Result<Metrics, AocError> {
     let mut metrics = Metrics::default();
 
     // File reading
-    let file_start = Instant::now();
-    let file = File::open("./puzzle_input.txt")?;
-    metrics.file_read_time = file_start.elapsed();
+    let reader = time_block!(metrics.timings, "read", { open_input(input)? });
+
+    // With both, each fix follows the explanation of the same report
+    if args.explain {
+        out.section("Explanations");
+    } else if args.show_fixes {
+        out.section("Fixes");
+    }
+    if args.wide {
+        solve::<i64>(reader, args, &mut metrics, out)?;
+    } else {
+        solve::<i32>(reader, args, &mut metrics, out)?;
+    }
+    metrics.skipped_line_count = metrics.skipped_lines.len();
+    if args.part.runs_part2() {
+        debug_assert_eq!(
+            metrics.part2_valid_count,
+            metrics.part1_valid_count + metrics.dampener_fixed_count
+        );
+    }
+    Ok(metrics)
+}
+
+/// Describes the lines `--lenient` skipped, as `skipped_lines_note` does
+/// for the runner, or `None` if there were none.
+fn skipped_warning(metrics: &Metrics) -> Option<String> {
+    skipped_lines_note(&metrics.skipped_lines)
+}
+
+/// Reports the configuration, answers, and performance breakdown of a run
+/// that started at `start`.
+fn report_summary(args: &Args, metrics: &Metrics, start: Instant, out: &mut dyn Reporter) {
+    out.section("Configuration");
+    out.note(&format!("Parts: {}", args.part));
+    out.note(&format!("Step bounds: {}", args.bounds));
+    if args.part.runs_part2() {
+        out.note(&format!("Dampener removals: {}", args.dampener));
+    }
+    let mode = if args.stream {
+        "streaming"
+    } else if args.pipeline {
+        "pipelined"
+    } else {
+        "buffered"
+    };
+    out.note(&format!("Mode: {}", mode));
+    out.note(&format!(
+        "Level type: {}",
+        if args.wide { "i64" } else { "i32" }
+    ));
+    out.note(&format!(
+        "Malformed lines: {}",
+        if args.lenient { "skipped" } else { "fatal" }
+    ));
+
+    out.section("Results");
+    if args.part.reports_part1() {
+        out.answer(
+            1,
+            "Part 1 - Safe reports",
+            &metrics.part1_valid_count.into(),
+        );
+    }
+    if args.part.runs_part2() {
+        out.answer(
+            2,
+            "Part 2 - Safe reports with dampener",
+            &metrics.part2_valid_count.into(),
+        );
+        out.note(&format!(
+            "Reports fixed by dampener: {}",
+            metrics.dampener_fixed_count
+        ));
+    }
+
+    if args.list_unsafe && args.part.reports_part1() {
+        out.section("Unsafe reports (Part 1)");
+        for line_number in &metrics.part1_unsafe_lines {
+            out.note(&line_number.to_string());
+        }
+    }
+    if args.list_unsafe && args.part.runs_part2() {
+        out.section("Unsafe reports with dampener (Part 2)");
+        for line_number in &metrics.part2_unsafe_lines {
+            out.note(&line_number.to_string());
+        }
+    }
+
+    out.section("Performance Breakdown");
+    out.note(&format!(
+        "Total sequences processed: {}",
+        metrics.total_sequences
+    ));
+    if args.dedupe {
+        out.note(&format!(
+            "Unique reports: {} ({} Part 1 and {} Part 2 validations skipped)",
+            metrics.unique_sequences,
+            metrics.total_sequences - metrics.unique_sequences,
+            if args.part.runs_part2() {
+                metrics.skipped_dampener_runs
+            } else {
+                0
+            }
+        ));
+    }
+    out.note(&format!(
+        "Validation threads: {}",
+        metrics.validation_threads
+    ));
+    // With `--pipeline`, parsing is the reader thread's working time
+    metrics.timings.report_to(out);
+    if args.part.runs_part2() {
+        out.note(&format!(
+            "Dampened sequences: {}",
+            metrics.dampened_sequences
+        ));
+        out.note(&format!(
+            "Part 2 heap allocations: {}",
+            metrics.part2_allocations
+        ));
+        out.note(&format!(
+            "Part 2 arena peak: {} bytes",
+            metrics.part2_arena_peak
+        ));
+    }
+    if metrics.part1_valid_count < metrics.total_sequences {
+        out.note("Part 1 failures by first violation:");
+        for (kind, count) in metrics.violation_counts.by_kind() {
+            out.note(&format!("  {}: {}", kind, count));
+        }
+    }
+    if args.removal_stats && args.dampener > 0 {
+        out.note("Dampener fixes by removed index:");
+        for (index, &count) in metrics.removal_histogram.iter().enumerate() {
+            out.note(&format!("  index {}: {}", index, count));
+        }
+        if args.dampener > 1 {
+            out.note(&format!(
+                "  several levels: {}",
+                metrics.multiple_removal_fixes
+            ));
+        }
+    }
+    if let Some((min, max)) = metrics.length_range() {
+        out.note(&format!(
+            "Report lengths: min {}, max {}, mean {:.2}",
+            min,
+            max,
+            metrics.mean_length()
+        ));
+        for (len, &count) in metrics.length_histogram.iter().enumerate() {
+            if count > 0 {
+                out.note(&format!("  {} levels: {}", len, count));
+            }
+        }
+    }
+    let total = start.elapsed();
+    out.phase("Total", total);
+    out.note(&format!(
+        "Throughput: {}",
+        fmt_count_rate(metrics.total_sequences as u64, total, "reports")
+    ));
+
+    out.section("Per-sequence averages");
+    metrics.timings.averages_to(
+        &["parse", "part1", "part2"],
+        metrics.total_sequences,
+        "sequence",
+        out,
+    );
+}
+
+/// Solves with levels of type `T`, `i64` with `--wide` and `i32` otherwise.
+fn solve<T: Level>(
+    reader: Box<dyn BufRead + Send>,
+    args: &Args,
+    metrics: &mut Metrics,
+    out: &mut dyn Reporter,
+) -> Result<(), AocError> {
+    if args.stream {
+        solve_streaming::<T>(reader, args, metrics, out)
+    } else if args.pipeline {
+        solve_pipelined::<T>(reader, args, metrics, out)
+    } else {
+        solve_buffered::<T>(reader, args, metrics, out)
+    }
+}
+
+/// Handles a report that failed Part 1 for `--explain`, `--list-unsafe`,
+/// and `--show-fixes`, outside the timed passes.
+///
+/// `line_number` counts every physical input line, so it matches an editor.
+fn record_unsafe<T: Level>(
+    line_number: usize,
+    numbers: &[T],
+    violation: Violation<T>,
+    args: &Args,
+    metrics: &mut Metrics,
+    out: &mut dyn Reporter,
+) {
+    let plan = if args.part.runs_part2() {
+        removal_plan(numbers, violation, args.bounds, args.dampener)
+    } else {
+        None
+    };
+    if args.explain {
+        report_explanation(line_number, numbers, violation, plan.as_deref(), args, out);
+    }
+    if let (true, Some(plan)) = (args.show_fixes, &plan) {
+        report_fix(line_number, numbers, violation, plan, args, out);
+    }
+    if args.list_unsafe {
+        metrics.part1_unsafe_lines.push(line_number);
+        if args.part.runs_part2() && plan.is_none() {
+            metrics.part2_unsafe_lines.push(line_number);
+        }
+    }
+}
+
+/// Reports why a report failed Part 1 and what, if anything, the dampener
+/// removed to fix it.
+fn report_explanation<T: Level>(
+    line_number: usize,
+    numbers: &[T],
+    violation: Violation<T>,
+    plan: Option<&[usize]>,
+    args: &Args,
+    out: &mut dyn Reporter,
+) {
+    let levels: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+    out.note(&format!("line {}: {}", line_number, levels.join(" ")));
+    let explanation = Explanation {
+        numbers,
+        violation,
+        bounds: args.bounds,
+    };
+    out.note(&format!("  {}", explanation));
+
+    if !args.part.runs_part2() {
+        return;
+    }
+    match plan {
+        Some(removed) => {
+            let removed: Vec<String> = removed
+                .iter()
+                .map(|&i| format!("levels[{}]={}", i, numbers[i]))
+                .collect();
+            out.note(&format!("  safe after removing {}", removed.join(", ")));
+        }
+        None => out.note("  unsafe even with the dampener"),
+    }
+}
+
+/// Reports the levels left after the dampener fixes a report.
+///
+/// A report fixed by removing one level shows the lowest such level, and
+/// the others that would also work, whatever the budget; one that needs
+/// more removals shows the levels in `plan`.
+fn report_fix<T: Level>(
+    line_number: usize,
+    numbers: &[T],
+    violation: Violation<T>,
+    plan: &[usize],
+    args: &Args,
+    out: &mut dyn Reporter,
+) {
+    let single = dampener_fixes(numbers, violation, args.bounds);
+    let removed = if single.is_empty() {
+        plan
+    } else {
+        &single[..1]
+    };
+    let describe = |indices: &[usize]| -> String {
+        let levels: Vec<String> = indices
+            .iter()
+            .map(|&i| format!("levels[{}]={}", i, numbers[i]))
+            .collect();
+        levels.join(", ")
+    };
+    // `removed` is ascending, so it can be searched
+    let kept: Vec<String> = (0..numbers.len())
+        .filter(|i| removed.binary_search(i).is_err())
+        .map(|i| numbers[i].to_string())
+        .collect();
+
+    let mut fix = format!(
+        "line {}: removed {}, leaving {}",
+        line_number,
+        describe(removed),
+        kept.join(" ")
+    );
+    if single.len() > 1 {
+        fix += &format!(" (also fixed by removing {})", describe(&single[1..]));
+    }
+    out.note(&fix);
+}
 
-    let reader = BufReader::new(file);
+/// `line` as read by `read_until`, without its `\n` or `\r\n`, the way
+/// `BufRead::lines` hands lines to the buffered path.
+#[inline]
+fn without_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Records a line that failed to parse with `--lenient`, or fails the run
+/// with its error shown under `line` otherwise.
+fn skip_malformed(
+    error: ParseError,
+    line: &[u8],
+    lenient: bool,
+    skipped: &mut Vec<usize>,
+) -> Result<(), AocError> {
+    if !lenient {
+        return Err(AocError::from(error).on_line(&String::from_utf8_lossy(line)));
+    }
+    skipped.push(error.line);
+    Ok(())
+}
+
+/// Parses every report into memory, then validates them in two timed passes.
+fn solve_buffered<T: Level>(
+    reader: Box<dyn BufRead + Send>,
+    args: &Args,
+    metrics: &mut Metrics,
+    out: &mut dyn Reporter,
+) -> Result<(), AocError> {
     let mut sequences = Vec::with_capacity(1000);
+    // Physical line of each report, counting the blank lines skipped
+    let mut line_numbers = Vec::with_capacity(1000);
 
     // Optimized parsing
-    let parse_start = Instant::now();
-    for line in reader.lines() {
-        let line = line?;
-        // Pre-allocate fixed size array since we know sequences are 5 numbers
-        let mut numbers = Vec::with_capacity(5);
-
-        // Manual parsing is faster than split+parse
-        let mut start = 0;
-        let bytes = line.as_bytes();
-
-        for i in 0..bytes.len() {
-            if bytes[i] == b' ' || i == bytes.len() - 1 {
-                let end = if i == bytes.len() - 1 { i + 1 } else { i };
-                // SAFETY: We know input is valid ASCII numbers with spaces
-                let num = unsafe {
-                    std::str::from_utf8_unchecked(&bytes[start..end])
-                        .parse::<i32>()
-                        .unwrap_unchecked()
-                };
-                numbers.push(num);
-                start = i + 1;
+    time_block!(metrics.timings, "parse", {
+        let mut parser = LineParser::default();
+        let mut previous_len = 0;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            // Sized like the previous report, since neighbouring reports tend
+            // to be similar; up to 8 levels the storage is inline anyway, and a
+            // long report grows it by doubling only once
+            let mut numbers = Report::<T>::with_capacity(previous_len);
+
+            // Manual parsing is faster than split+parse
+            if let Err(error) = parser.parse_line(line.as_bytes(), i + 1, &mut numbers) {
+                skip_malformed(
+                    error,
+                    line.as_bytes(),
+                    args.lenient,
+                    &mut metrics.skipped_lines,
+                )?;
+                continue;
+            }
+
+            // Blank lines aren't reports, so they'd otherwise count as safe
+            if !numbers.is_empty() {
+                previous_len = numbers.len();
+                record_length(&mut metrics.length_histogram, numbers.len());
+                sequences.push(numbers);
+                line_numbers.push(i + 1);
             }
         }
+    });
+    metrics.total_sequences = sequences.len();
 
-        sequences.push(numbers);
+    if args.dedupe {
+        let (unique, multiplicity) = time_block!(metrics.timings, DEDUPE, { dedupe(&sequences) });
+        metrics.unique_sequences = unique.len();
+        validate_all(&unique, Some(&multiplicity), args, metrics);
+    } else {
+        validate_all(&sequences, None, args, metrics);
+    }
+
+    // Untimed, in input order, after the counts are final
+    if args.records_unsafe() {
+        for (sequence, &line_number) in sequences.iter().zip(&line_numbers) {
+            if let ValidationResult::Invalid(violation) = validate_sequence(sequence, args.bounds) {
+                record_unsafe(line_number, sequence, violation, args, metrics, out);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates parsed reports in two timed passes, Part 1 then Part 2.
+///
+/// With `multiplicity`, each report stands for that many identical input
+/// reports: it is validated once and its outcome counted that many times.
+fn validate_all<T: Level, R: AsRef<[T]> + Sync>(
+    reports: &[R],
+    multiplicity: Option<&[usize]>,
+    args: &Args,
+    metrics: &mut Metrics,
+) {
+    let weight = |i: usize| multiplicity.map_or(1, |counts| counts[i]);
+
+    // Part 1, remembering each failure so the dampener doesn't re-validate
+    let check = |(i, sequence): (usize, &R)| match validate_sequence(sequence.as_ref(), args.bounds)
+    {
+        ValidationResult::Valid => None,
+        ValidationResult::Invalid(violation) => Some((i, violation)),
+    };
+    let (invalid, invalid_count) = time_block!(metrics.timings, "part1", {
+        let invalid: Vec<_> = if args.serial {
+            reports.iter().enumerate().filter_map(check).collect()
+        } else {
+            reports.par_iter().enumerate().filter_map(check).collect()
+        };
+        let invalid_count: usize = invalid.iter().map(|&(i, _)| weight(i)).sum();
+        (invalid, invalid_count)
+    });
+    metrics.part1_valid_count = metrics.total_sequences - invalid_count;
+    metrics.dampened_sequences = invalid.len();
+    metrics.skipped_dampener_runs = invalid_count - invalid.len();
+    for &(i, violation) in &invalid {
+        metrics.violation_counts.record(violation, weight(i));
+    }
+    metrics.validation_threads = parallelism();
+    if !args.part.runs_part2() {
+        return;
     }
-    metrics.parsing_time = parse_start.elapsed();
-    metrics.total_sequences = sequences.len();
 
-    // Combined validation
-    let combined_start = Instant::now();
-    for sequence in &sequences {
-        let validation_result = validate_sequence(sequence);
-        if matches!(validation_result, ValidationResult::Valid) {
+    // Part 2, over the Part 1 failures only
+    // Reuse the Part 1 violation to pick the candidate removals
+    let dampen = |&(i, violation): &(usize, Violation<T>)| {
+        // Each thread's arena grows to fit its longest report once, so
+        // long reports stop allocating
+        with_scratch(|arena| {
+            let report = reports[i].as_ref();
+            validate_with_removals_in(report, violation, args.bounds, args.dampener, arena)
+        })
+        .is_valid()
+        .then(|| weight(i))
+    };
+    let (fixed, allocations) = time_block!(metrics.timings, "part2", {
+        let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+        let fixed: usize = if args.serial {
+            invalid.iter().filter_map(dampen).sum()
+        } else {
+            invalid.par_iter().filter_map(dampen).sum()
+        };
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+        metrics.part2_arena_peak = if args.serial {
+            scratch_peak()
+        } else {
+            rayon::broadcast(|_| scratch_peak())
+                .into_iter()
+                .max()
+                .unwrap_or(0)
+        };
+        (fixed, allocations)
+    });
+    // Valid sequences are also valid for part 2
+    metrics.dampener_fixed_count = fixed;
+    metrics.part2_valid_count = metrics.part1_valid_count + fixed;
+    metrics.part2_allocations = allocations;
+
+    if args.removal_stats {
+        for &(i, violation) in &invalid {
+            metrics.record_removal(reports[i].as_ref(), violation, args, weight(i));
+        }
+    }
+}
+
+/// Parses and validates one report at a time, so memory stays at the
+/// longest line regardless of input size.
+///
+/// # Implementation Details
+/// - Reads raw lines into one reused byte buffer and parses into one
+///   reused level buffer, so no per-line allocation happens after warm-up
+/// - Runs the dampener right after a report fails Part 1
+/// - Parse, Part 1, and Part 2 durations are accumulated per line
+fn solve_streaming<T: Level>(
+    mut reader: Box<dyn BufRead + Send>,
+    args: &Args,
+    metrics: &mut Metrics,
+    out: &mut dyn Reporter,
+) -> Result<(), AocError> {
+    let mut line = Vec::new();
+    let mut numbers = Report::<T>::new();
+    let mut parser = LineParser::default();
+    let mut line_number = 0;
+    metrics.validation_threads = 1;
+
+    loop {
+        let parsed = time_block!(metrics.timings, "parse", {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            line_number += 1;
+            numbers.clear();
+            parser.parse_line(without_line_ending(&line), line_number, &mut numbers)
+        });
+        if let Err(error) = parsed {
+            skip_malformed(
+                error,
+                without_line_ending(&line),
+                args.lenient,
+                &mut metrics.skipped_lines,
+            )?;
+            continue;
+        }
+
+        // Blank lines aren't reports, so they'd otherwise count as safe
+        if !numbers.is_empty() {
+            validate_report(line_number, &numbers, args, metrics, out);
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates and counts one parsed, non-empty report, timing Part 1 and
+/// Part 2 separately and running the dampener right after a failure.
+fn validate_report<T: Level>(
+    line_number: usize,
+    numbers: &[T],
+    args: &Args,
+    metrics: &mut Metrics,
+    out: &mut dyn Reporter,
+) {
+    metrics.total_sequences += 1;
+    record_length(&mut metrics.length_histogram, numbers.len());
+
+    let result = time_block!(metrics.timings, "part1", {
+        validate_sequence(numbers, args.bounds)
+    });
+
+    match result {
+        ValidationResult::Valid => {
             metrics.part1_valid_count += 1;
-            metrics.part2_valid_count += 1; // Valid sequences are also valid for part 2
-        } else if matches!(
-            validate_sequence_with_dampener(sequence),
-            ValidationResult::Valid
-        ) {
             metrics.part2_valid_count += 1;
         }
+        ValidationResult::Invalid(violation) => {
+            metrics.dampened_sequences += 1;
+            metrics.violation_counts.record(violation, 1);
+            if args.part.runs_part2() {
+                let (fixed, allocations) = time_block!(metrics.timings, "part2", {
+                    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+                    let fixed = with_scratch(|arena| {
+                        validate_with_removals_in(
+                            numbers,
+                            violation,
+                            args.bounds,
+                            args.dampener,
+                            arena,
+                        )
+                    })
+                    .is_valid();
+                    (
+                        fixed,
+                        ALLOCATIONS.load(Ordering::Relaxed) - allocations_before,
+                    )
+                });
+                metrics.part2_arena_peak = scratch_peak();
+                if fixed {
+                    metrics.dampener_fixed_count += 1;
+                    metrics.part2_valid_count += 1;
+                }
+                metrics.part2_allocations += allocations;
+                if args.removal_stats {
+                    metrics.record_removal(numbers, violation, args, 1);
+                }
+            }
+
+            if args.records_unsafe() {
+                record_unsafe(line_number, numbers, violation, args, metrics, out);
+            }
+        }
     }
-    metrics.combined_validation_time = combined_start.elapsed();
+}
 
-    // Report results
-    println!("\nResults:");
-    println!("Part 1 - Safe reports: {}", metrics.part1_valid_count);
-    println!(
-        "Part 2 - Safe reports with dampener: {}",
-        metrics.part2_valid_count
-    );
+/// Reports handed between the `--pipeline` stages, in input order.
+///
+/// Only the first `len` entries are current; the rest are left over from
+/// earlier use and keep their allocations.
+struct Batch<T> {
+    reports: Vec<Report<T>>,
+    line_numbers: Vec<usize>,
+    len: usize,
+}
 
-    println!("\nPerformance Breakdown:");
-    println!("Total sequences processed: {}", metrics.total_sequences);
-    println!("File read time: {:?}", metrics.file_read_time);
-    println!("Parsing time: {:?}", metrics.parsing_time);
-    println!(
-        "Combined validation time: {:?}",
-        metrics.combined_validation_time
-    );
-    println!("Total time: {:?}", start.elapsed());
+impl<T> Batch<T> {
+    fn new() -> Self {
+        Batch {
+            reports: Vec::with_capacity(PIPELINE_BATCH),
+            line_numbers: Vec::with_capacity(PIPELINE_BATCH),
+            len: 0,
+        }
+    }
+}
 
-    println!("\nPer-sequence averages:");
-    if metrics.total_sequences > 0 {
-        println!(
-            "Parsing: {:?}/sequence",
-            metrics.parsing_time / metrics.total_sequences as u32
-        );
-        println!(
-            "Combined validation: {:?}/sequence",
-            metrics.combined_validation_time / metrics.total_sequences as u32
-        );
+/// Reports per `--pipeline` batch, enough to amortize a channel handoff
+const PIPELINE_BATCH: usize = 1024;
+
+/// Batches in circulation, which bounds how far parsing can run ahead
+const PIPELINE_DEPTH: usize = 4;
+
+/// Parses on a reader thread while validating on the calling thread, so
+/// reading and parsing overlap with validation.
+///
+/// # Implementation Details
+/// - A fixed pool of `PIPELINE_DEPTH` batches cycles between the stages
+///   over two bounded channels: the reader fills a free batch and sends it,
+///   the validator counts it and sends it back. The reader blocks while
+///   every batch is in flight, so memory stays flat whatever the input size
+/// - Reports and line buffers are cleared and refilled in place, so no
+///   per-report allocation happens after warm-up
+/// - A parse error, unless `--lenient` skips the line, ends the reader,
+///   which closes its channel; the validator drains the batches already
+///   sent, and the error, with its line number, is returned once both
+///   stages have stopped
+/// - Counts are the same as `--stream`, since reports are validated one at
+///   a time in input order
+///
+/// # Space Complexity
+/// O(b * m) where b is `PIPELINE_DEPTH * PIPELINE_BATCH` and m is the
+/// longest report
+fn solve_pipelined<T: Level>(
+    reader: Box<dyn BufRead + Send>,
+    args: &Args,
+    metrics: &mut Metrics,
+    out: &mut dyn Reporter,
+) -> Result<(), AocError> {
+    let (full_tx, full_rx) = mpsc::sync_channel(PIPELINE_DEPTH);
+    let (free_tx, free_rx) = mpsc::sync_channel(PIPELINE_DEPTH);
+    for _ in 0..PIPELINE_DEPTH {
+        free_tx
+            .send(Batch::<T>::new())
+            .expect("the pool's receiver is still alive");
     }
+    metrics.validation_threads = 1;
+    // Listed first like the other modes, though only known once the
+    // reader finishes
+    metrics.timings.record("parse", Duration::ZERO);
 
-    Ok(())
+    thread::scope(|scope| {
+        let lenient = args.lenient;
+        let reader = scope.spawn(move || read_batches(reader, lenient, free_rx, full_tx));
+        let validator = validate_batches(full_rx, free_tx, args, metrics, out);
+        let (parser, skipped) = reader
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+        metrics.skipped_lines = skipped;
+        for (stage, name) in [
+            (&parser, "parse"),
+            (&parser, READER_BLOCKED),
+            (&validator, VALIDATOR_WORKING),
+            (&validator, VALIDATOR_BLOCKED),
+        ] {
+            metrics.timings.record(name, stage.elapsed(name));
+        }
+        Ok(())
+    })
+}
+
+/// The `--pipeline` reader: fills free batches with parsed reports and
+/// sends them on, until the input ends or a line fails to parse.
+///
+/// # Returns
+/// The reader's `parse` and `READER_BLOCKED` times, and the lines skipped
+/// as malformed with `lenient`.
+fn read_batches<T: Level>(
+    mut reader: Box<dyn BufRead + Send>,
+    lenient: bool,
+    free: Receiver<Batch<T>>,
+    full: SyncSender<Batch<T>>,
+) -> Result<(aoc_common::Metrics, Vec<usize>), AocError> {
+    let times = aoc_common::Metrics::new();
+    let mut line = Vec::new();
+    let mut parser = LineParser::default();
+    let mut line_number = 0;
+    let mut skipped = Vec::new();
+
+    loop {
+        // Fails only once the validator has stopped
+        let Ok(mut batch) = time_block!(times, READER_BLOCKED, { free.recv() }) else {
+            break;
+        };
+
+        let done = time_block!(times, "parse", {
+            batch.len = 0;
+            let mut done = false;
+            while batch.len < PIPELINE_BATCH {
+                line.clear();
+                if reader.read_until(b'\n', &mut line)? == 0 {
+                    done = true;
+                    break;
+                }
+                line_number += 1;
+                if batch.reports.len() == batch.len {
+                    batch.reports.push(Report::new());
+                    batch.line_numbers.push(0);
+                }
+                let numbers = &mut batch.reports[batch.len];
+                numbers.clear();
+                let text = without_line_ending(&line);
+                if let Err(error) = parser.parse_line(text, line_number, numbers) {
+                    // The slot is cleared before its next use
+                    skip_malformed(error, text, lenient, &mut skipped)?;
+                    continue;
+                }
+                // Blank lines aren't reports, so they'd otherwise count as safe
+                if !numbers.is_empty() {
+                    batch.line_numbers[batch.len] = line_number;
+                    batch.len += 1;
+                }
+            }
+            done
+        });
+
+        if time_block!(times, READER_BLOCKED, {
+            batch.len > 0 && full.send(batch).is_err()
+        }) {
+            break;
+        }
+        if done {
+            break;
+        }
+    }
+
+    Ok((times, skipped))
+}
+
+/// The `--pipeline` validator: validates and counts each batch as it
+/// arrives, then returns it to the reader, until the reader hangs up.
+///
+/// # Returns
+/// The validator's `VALIDATOR_WORKING` and `VALIDATOR_BLOCKED` times; Part 1
+/// and Part 2 are timed into `metrics` as they run.
+fn validate_batches<T: Level>(
+    full: Receiver<Batch<T>>,
+    free: SyncSender<Batch<T>>,
+    args: &Args,
+    metrics: &mut Metrics,
+    out: &mut dyn Reporter,
+) -> aoc_common::Metrics {
+    let times = aoc_common::Metrics::new();
+
+    loop {
+        let Ok(batch) = time_block!(times, VALIDATOR_BLOCKED, { full.recv() }) else {
+            break;
+        };
+
+        time_block!(times, VALIDATOR_WORKING, {
+            for (numbers, &line_number) in
+                batch.reports[..batch.len].iter().zip(&batch.line_numbers)
+            {
+                validate_report(line_number, numbers, args, metrics, out);
+            }
+            // The reader may already be done; the batches it left behind
+            // still get validated
+            let _ = free.send(batch);
+        });
+    }
+
+    times
 }
@@ -0,0 +1,741 @@
+//! The binary's command line, run as a user would run it.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the built `day-02` with `args` from the fixtures directory.
+fn day02(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_day-02"))
+        .args(args)
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+        .output()
+        .expect("day-02 runs")
+}
+
+/// Runs the built `day-02` with `args`, piping `input` to it as stdin.
+fn day02_stdin(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_day-02"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("day-02 runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().expect("day-02 finishes")
+}
+
+#[test]
+fn help_describes_the_day_and_its_own_options() {
+    let output = day02(&["--help"]);
+    assert!(output.status.success());
+    let help = String::from_utf8(output.stdout).unwrap();
+    assert!(help.starts_with("Day 2: Red-Nosed Reports."), "{}", help);
+    assert!(
+        help.contains("      --dampener <N>           Levels the dampener may remove from each report [default: 1]\n"),
+        "{}",
+        help
+    );
+}
+
+#[test]
+fn validates_on_the_requested_threads_with_the_same_answers() {
+    let answers = |args: &[&str]| {
+        let output = day02(&[&["rs/puzzle_input.txt", "--quiet"], args].concat());
+        assert!(output.status.success(), "{:?}", args);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let parallel = answers(&["--threads", "3"]);
+    assert_eq!(parallel, "516\n561\n");
+    assert_eq!(answers(&["--threads", "1"]), parallel);
+    assert_eq!(answers(&["--serial"]), parallel);
+    assert_eq!(answers(&["--serial", "--threads", "3"]), parallel);
+
+    for (args, threads) in [
+        (&["--threads", "3"][..], 3),
+        (&["--threads", "1"], 1),
+        (&["--serial", "--threads", "3"], 1),
+    ] {
+        let output = day02(&[&["rs/puzzle_input.txt"], args].concat());
+        let breakdown = String::from_utf8(output.stdout).unwrap();
+        let line = format!("Validation threads: {}\n", threads);
+        assert!(breakdown.contains(&line), "{:?}: {}", args, breakdown);
+    }
+}
+
+#[test]
+fn every_mode_shows_a_bad_line_without_its_line_ending() {
+    let input = "7 6 4 2 1\r\n1 2 x 4\r\n";
+    let buffered = day02_stdin(&["-"], input);
+    assert_eq!(buffered.status.code(), Some(1));
+    let stderr = String::from_utf8(buffered.stderr).unwrap();
+    assert!(stderr.contains("1 2 x 4\n"), "{:?}", stderr);
+    assert!(!stderr.contains('\r'), "{:?}", stderr);
+    for mode in ["--stream", "--pipeline"] {
+        let output = day02_stdin(&["-", mode], input);
+        assert_eq!(output.status.code(), Some(1), "{}", mode);
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            stderr,
+            "{}",
+            mode
+        );
+    }
+
+    let lenient = day02_stdin(&["-", "--stream", "--lenient", "--quiet"], input);
+    assert_eq!(String::from_utf8(lenient.stdout).unwrap(), "1\n1\n");
+}
+
+#[test]
+fn points_at_a_bad_level_instead_of_miscounting() {
+    let output = day02_stdin(&["-", "--quiet"], "1 2 x 4\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: line 1, columns 5-5: could not parse `x` as a level\n  \
+         |\n\
+         1 | 1 2 x 4\n  \
+         |     ^\n"
+    );
+
+    let output = day02_stdin(&["-", "--quiet"], "1  2 3\n");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n1\n");
+}
+
+#[test]
+fn times_part_2_apart_from_part_1_in_every_mode() {
+    let metrics = |args: &[&str]| {
+        let json = ["--metrics-json", "-", "rs/puzzle_input.txt", "--quiet"];
+        let output = day02(&[&json[..], args].concat());
+        assert!(output.status.success(), "{:?}", args);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    // Half the puzzle's reports fail Part 1 and go through the dampener
+    for args in [&[][..], &["--serial"], &["--stream"], &["--pipeline"]] {
+        let json = metrics(args);
+        assert_eq!(json["dampened_sequences"], 484, "{:?}", args);
+        for phase in ["part1_validation_time", "part2_validation_time"] {
+            let nanos = json[phase].as_u64().unwrap();
+            assert!(nanos > 0, "{:?}: {} is {}", args, phase, nanos);
+        }
+    }
+    let json = metrics(&["--part", "1"]);
+    assert_eq!(json["part2_validation_time"], 0);
+}
+
+#[test]
+fn max_step_widens_the_allowed_jump() {
+    let output = day02_stdin(&["-", "--quiet"], "1 6 11\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0\n0\n");
+
+    let output = day02_stdin(&["-", "--max-step", "5"], "1 6 11\n");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Step bounds: 1..=5\n"), "{}", stdout);
+    assert!(stdout.contains("Part 1 - Safe reports: 1\n"), "{}", stdout);
+}
+
+#[test]
+fn explains_jumps_past_i32_by_their_exact_size() {
+    let input = "-2147483648 2147483647\n";
+    for wide in [&[][..], &["--wide"]] {
+        let output = day02_stdin(&[&["-", "--explain"], wide].concat(), input);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            stdout.starts_with(
+                "\nExplanations:\n\
+                 line 1: -2147483648 2147483647\n  \
+                 levels[0]=-2147483648 → levels[1]=2147483647 increases by 4294967295, more than 3\n"
+            ),
+            "{:?}: {}",
+            wide,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn the_widest_max_step_judges_i32_levels_like_wide_ones() {
+    // Both jumps are more than `i32::MAX`, so neither report is safe
+    let input = "-2147483648 2147483647\n5 -2147483648\n0 2147483647\n";
+    for wide in [&[][..], &["--wide"]] {
+        let args = [&["-", "--max-step", "2147483647", "--quiet"], wide].concat();
+        let output = day02_stdin(&args, input);
+        assert!(output.status.success(), "{:?}", wide);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "1\n3\n",
+            "{:?}",
+            wide
+        );
+    }
+}
+
+/// `count` reports of 1 to 12 levels from a fixed linear congruential
+/// sequence, stepping mostly one way so every outcome turns up often.
+fn random_reports(count: usize, seed: u64) -> String {
+    let mut state = seed;
+    let mut next = |range: u64| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        ((state >> 33) % range) as i64
+    };
+    let mut input = String::new();
+    for _ in 0..count {
+        let direction = if next(2) == 0 { 1 } else { -1 };
+        let mut level = next(100);
+        let levels: Vec<String> = (0..1 + next(12))
+            .map(|_| {
+                let current = level;
+                level += match next(6) {
+                    0 => next(9) - 4,
+                    _ => direction * (1 + next(3)),
+                };
+                current.to_string()
+            })
+            .collect();
+        input.push_str(&levels.join(" "));
+        input.push('\n');
+    }
+    input
+}
+
+#[test]
+fn parallel_validation_counts_like_serial() {
+    let input = random_reports(20_000, 0x2024_1230);
+    let metrics = |args: &[&str]| {
+        let output = day02_stdin(
+            &[&["-", "--quiet", "--metrics-json", "-"], args].concat(),
+            &input,
+        );
+        assert!(output.status.success(), "{:?}", args);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        [
+            "part1",
+            "part2",
+            "dampener_fixed_count",
+            "violation_counts",
+            "length_histogram",
+        ]
+        .map(|field| json[field].clone())
+    };
+    for dampener in ["1", "2"] {
+        let serial = metrics(&["--serial", "--dampener", dampener]);
+        for threads in ["2", "4", "7"] {
+            assert_eq!(
+                metrics(&["--threads", threads, "--dampener", dampener]),
+                serial,
+                "{} threads, dampener {}",
+                threads,
+                dampener
+            );
+        }
+    }
+}
+
+#[test]
+fn streaming_counts_like_buffered() {
+    let answers = |args: &[&str], input: &str| {
+        let output = day02_stdin(&[&["-", "--quiet"], args].concat(), input);
+        assert!(output.status.success(), "{:?}", args);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let example =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../example_input.txt"))
+            .unwrap();
+    assert_eq!(answers(&["--stream"], &example), "2\n4\n");
+
+    // Long enough that the streaming reader refills its buffer many times
+    let input = random_reports(200_000, 0x2024_1231).replace("\n4", "\n\n4");
+    for dampener in ["1", "3"] {
+        let buffered = answers(&["--dampener", dampener], &input);
+        assert_eq!(
+            answers(&["--stream", "--dampener", dampener], &input),
+            buffered,
+            "dampener {}",
+            dampener
+        );
+    }
+}
+
+/// The `--list-unsafe` sections of a run's output.
+fn unsafe_listing(stdout: &str) -> &str {
+    let start = stdout.find("Unsafe reports (Part 1):").expect("a listing");
+    let end = stdout
+        .find("\nPerformance Breakdown:")
+        .expect("a breakdown");
+    &stdout[start..end]
+}
+
+#[test]
+fn lists_the_unsafe_lines_of_the_example() {
+    // Lines 1 and 6 are safe; 4 and 5 are fixed by the dampener
+    let listing = "\
+Unsafe reports (Part 1):
+2
+3
+4
+5
+
+Unsafe reports with dampener (Part 2):
+2
+3
+";
+    for mode in [&[][..], &["--stream"], &["--pipeline"]] {
+        let output = day02(&[&["example_input.txt", "--list-unsafe"], mode].concat());
+        assert!(output.status.success(), "{:?}", mode);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(unsafe_listing(&stdout), listing, "{:?}", mode);
+    }
+
+    // Blank lines still count toward the line numbers
+    let output = day02_stdin(&["-", "--list-unsafe"], "7 6 4 2 1\n\n1 2 7 8 9\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        unsafe_listing(&stdout),
+        "Unsafe reports (Part 1):\n3\n\nUnsafe reports with dampener (Part 2):\n3\n"
+    );
+}
+
+/// Runs `day-02` with `args` and its metrics printed as JSON instead of the
+/// answers.
+fn metrics_json(args: &[&str]) -> serde_json::Value {
+    let output = day02(&[&["--metrics-json", "-", "--quiet"], args].concat());
+    assert!(output.status.success(), "{:?}", args);
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn counts_reports_by_length() {
+    let breakdown = "\
+Report lengths: min 2, max 9, mean 5.33
+  2 levels: 1
+  5 levels: 1
+  9 levels: 1
+";
+    for mode in [&[][..], &["--stream"], &["--pipeline"]] {
+        let output = day02(&[&["mixed_lengths_input.txt"], mode].concat());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains(breakdown), "{:?}: {}", mode, stdout);
+        assert!(stdout.contains("Part 1 - Safe reports: 1\n"), "{}", stdout);
+        assert!(
+            stdout.contains("Part 2 - Safe reports with dampener: 3\n"),
+            "{}",
+            stdout
+        );
+
+        let json = metrics_json(&[&["mixed_lengths_input.txt"], mode].concat());
+        assert_eq!(
+            json["length_histogram"],
+            serde_json::json!([0, 0, 1, 0, 0, 1, 0, 0, 0, 1]),
+            "{:?}",
+            mode
+        );
+    }
+}
+
+#[test]
+fn deduped_counts_match_validating_every_copy() {
+    let counted = |json: &serde_json::Value| {
+        [
+            "part1",
+            "part2",
+            "part1_valid_count",
+            "dampener_fixed_count",
+            "violation_counts",
+        ]
+        .map(|field| json[field].clone())
+    };
+    let every = metrics_json(&["duplicates_input.txt"]);
+    let deduped = metrics_json(&["duplicates_input.txt", "--dedupe"]);
+    assert_eq!(counted(&deduped), counted(&every));
+    assert_eq!((&every["part1"], &every["part2"]), (&3.into(), &6.into()));
+    // 5 copies fail Part 1, but only 3 distinct reports go to the dampener
+    assert_eq!(deduped["unique_sequences"], 4);
+    assert_eq!(deduped["dampened_sequences"], 3);
+    assert_eq!(deduped["skipped_dampener_runs"], 2);
+
+    let input = random_reports(20_000, 0x2024_1238);
+    let answers = |args: &[&str]| {
+        let output = day02_stdin(
+            &[&["-", "--quiet", "--dampener", "2"], args].concat(),
+            &input,
+        );
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(answers(&["--dedupe"]), answers(&[]));
+}
+
+#[test]
+fn prints_only_the_parts_that_ran() {
+    for (part, quiet, present, absent) in [
+        (
+            "1",
+            "2\n",
+            &["Part 1 - Safe reports: 2\n", "Part 1 time: "][..],
+            &[
+                "Part 2 - Safe reports",
+                "Reports fixed by dampener",
+                "Part 2 time: ",
+            ][..],
+        ),
+        (
+            "2",
+            "4\n",
+            &["Part 2 - Safe reports with dampener: 4\n", "Part 2 time: "],
+            &["Part 1 - Safe reports"],
+        ),
+        (
+            "both",
+            "2\n4\n",
+            &[
+                "Part 1 - Safe reports: 2\n",
+                "Part 2 - Safe reports with dampener: 4\n",
+                "Reports fixed by dampener: 2\n",
+                "Part 1 time: ",
+                "Part 2 time: ",
+            ],
+            &[],
+        ),
+    ] {
+        let output = day02(&["example_input.txt", "--part", part, "--quiet"]);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            quiet,
+            "--part {}",
+            part
+        );
+
+        let output = day02(&["example_input.txt", "--part", part]);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains(&format!("Parts: {}\n", part)), "{}", stdout);
+        for line in present {
+            assert!(
+                stdout.contains(line),
+                "--part {}: {:?} in {}",
+                part,
+                line,
+                stdout
+            );
+        }
+        for line in absent {
+            assert!(
+                !stdout.contains(line),
+                "--part {}: {:?} in {}",
+                part,
+                line,
+                stdout
+            );
+        }
+    }
+}
+
+#[test]
+fn solves_very_long_reports_in_every_mode() {
+    for mode in [&[][..], &["--stream"], &["--pipeline"], &["--wide"]] {
+        for (dampener, answers) in [("1", "1\n2\n"), ("2", "1\n3\n")] {
+            let args = ["long_reports_input.txt", "--quiet", "--dampener", dampener];
+            let output = day02(&[&args[..], mode].concat());
+            assert_eq!(
+                String::from_utf8(output.stdout).unwrap(),
+                answers,
+                "{:?} with --dampener {}",
+                mode,
+                dampener
+            );
+        }
+    }
+}
+
+#[test]
+fn metrics_json_matches_the_printed_answers() {
+    let path = std::env::temp_dir().join(format!("day02-metrics-{}.json", std::process::id()));
+    let output = day02(&[
+        "--metrics-json",
+        path.to_str().unwrap(),
+        "rs/puzzle_input.txt",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // The human breakdown is still printed alongside the file
+    let printed = |label: &str| -> u64 {
+        let line = stdout.lines().find(|line| line.starts_with(label)).unwrap();
+        line.rsplit(' ').next().unwrap().parse().unwrap()
+    };
+    assert_eq!(json["part1"], printed("Part 1 - Safe reports:"));
+    assert_eq!(
+        json["part2"],
+        printed("Part 2 - Safe reports with dampener:")
+    );
+    assert_eq!(
+        json["dampener_fixed_count"],
+        printed("Reports fixed by dampener:")
+    );
+    assert_eq!(
+        json["total_sequences"],
+        printed("Total sequences processed:")
+    );
+    assert_eq!(json["part1"], json["part1_valid_count"]);
+    assert_eq!(json["input"], "rs/puzzle_input.txt");
+    assert!(json["timestamp"].as_u64().unwrap() > 0);
+    for field in [
+        "total_time",
+        "file_read_time",
+        "parsing_time",
+        "part1_validation_time",
+        "part2_validation_time",
+        "length_histogram",
+        "violation_counts",
+        "skipped_line_count",
+    ] {
+        assert!(!json[field].is_null(), "{} missing from {}", field, json);
+    }
+
+    let json = metrics_json(&["example_input.txt", "--part", "1"]);
+    assert_eq!(
+        (&json["part1"], &json["part2"]),
+        (&2.into(), &serde_json::Value::Null)
+    );
+}
+
+#[test]
+fn allow_plateaus_lets_equal_levels_through() {
+    let input = "1 1 2 3\n1 1 1 1\n";
+    let output = day02_stdin(&["-", "--quiet"], input);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0\n1\n");
+    let output = day02_stdin(&["-", "--quiet", "--allow-plateaus"], input);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n2\n");
+
+    let output = day02(&["rs/puzzle_input.txt", "--quiet"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "516\n561\n");
+}
+
+#[test]
+fn part_2_is_part_1_plus_the_dampener_fixes() {
+    let output = day02(&["example_input.txt"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Reports fixed by dampener: 2\n"),
+        "{}",
+        stdout
+    );
+
+    for args in [
+        &["example_input.txt"][..],
+        &["rs/puzzle_input.txt"],
+        &["rs/puzzle_input.txt", "--stream"],
+        &["rs/puzzle_input.txt", "--pipeline"],
+        &["duplicates_input.txt", "--dedupe"],
+        &["long_reports_input.txt", "--dampener", "2"],
+    ] {
+        let json = metrics_json(args);
+        let count = |field: &str| json[field].as_u64().unwrap();
+        assert_eq!(
+            count("part2_valid_count"),
+            count("part1_valid_count") + count("dampener_fixed_count"),
+            "{:?}",
+            args
+        );
+    }
+    assert_eq!(
+        metrics_json(&["example_input.txt"])["dampener_fixed_count"],
+        2
+    );
+}
+
+#[test]
+fn shows_the_fixes_of_the_example() {
+    let fixes = "
+Fixes:
+line 4: removed levels[1]=3, leaving 1 2 4 5 (also fixed by removing levels[2]=2)
+line 5: removed levels[2]=4, leaving 8 6 4 1 (also fixed by removing levels[3]=4)
+
+Configuration:
+";
+    for mode in [&[][..], &["--stream"], &["--pipeline"], &["--threads", "4"]] {
+        let output = day02(&[&["example_input.txt", "--show-fixes"], mode].concat());
+        assert!(output.status.success(), "{:?}", mode);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with(fixes), "{:?}: {}", mode, stdout);
+    }
+}
+
+#[test]
+fn lenient_skips_the_garbage_line_that_strict_rejects() {
+    let input = "7 6 4 2 1\n1 3 6 7 9\nfoo bar\n1 2 7 8 9\n";
+    for mode in [&[][..], &["--stream"], &["--pipeline"]] {
+        for strict in [&[][..], &["--strict"]] {
+            let output = day02_stdin(&[&["-", "--quiet"], mode, strict].concat(), input);
+            assert_eq!(output.status.code(), Some(1), "{:?} {:?}", mode, strict);
+            assert!(output.stdout.is_empty());
+            let stderr = String::from_utf8(output.stderr).unwrap();
+            assert!(
+                stderr
+                    .starts_with("Error: line 3, columns 1-3: could not parse `foo` as a level\n"),
+                "{}",
+                stderr
+            );
+        }
+
+        let output = day02_stdin(&[&["-", "--quiet", "--lenient"], mode].concat(), input);
+        assert!(output.status.success(), "{:?}", mode);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "2\n2\n",
+            "{:?}",
+            mode
+        );
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            "Warning: 1 line skipped: 3\n",
+            "{:?}",
+            mode
+        );
+
+        let args = [&["-", "--quiet", "--lenient", "--metrics-json", "-"], mode].concat();
+        let output = day02_stdin(&args, input);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["skipped_line_count"], 1, "{:?}", mode);
+        assert_eq!(json["skipped_lines"], serde_json::json!([3]), "{:?}", mode);
+    }
+}
+
+#[test]
+fn totals_several_inputs_past_a_failed_one() {
+    let output = day02(&["example_input.txt", "missing.txt", "duplicates_input.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let files: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.ends_with("s") && line.contains(": Part 1 "))
+        .map(|line| &line[..line.rfind(", ").unwrap()])
+        .collect();
+    assert_eq!(
+        files,
+        [
+            "example_input.txt: Part 1 2, Part 2 4",
+            "duplicates_input.txt: Part 1 3, Part 2 6",
+        ]
+    );
+    assert!(stdout.contains("Files: 3 (1 failed)\n"), "{}", stdout);
+    assert!(
+        stdout.contains("Safe reports: Part 1 5, Part 2 10\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Total sequences processed: 14\n"),
+        "{}",
+        stdout
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("Error: missing.txt: failed to open missing.txt"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn counts_part_1_failures_by_first_violation() {
+    let breakdown = "\
+Part 1 failures by first violation:
+  plateau: 2
+  step too small: 0
+  jump too large: 3
+  direction change: 1
+";
+    for mode in [&[][..], &["--stream"], &["--pipeline"], &["--dedupe"]] {
+        let output = day02(&[&["violations_input.txt"], mode].concat());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains(breakdown), "{:?}: {}", mode, stdout);
+        assert!(stdout.contains("Part 1 - Safe reports: 1\n"), "{}", stdout);
+        assert!(
+            stdout.contains("Part 2 - Safe reports with dampener: 5\n"),
+            "{}",
+            stdout
+        );
+
+        let json = metrics_json(&[&["violations_input.txt"], mode].concat());
+        assert_eq!(
+            json["violation_counts"],
+            serde_json::json!({
+                "plateau": 2,
+                "step_too_small": 0,
+                "jump_too_large": 3,
+                "direction_change": 1,
+            })
+        );
+    }
+    // Nothing failed, so there is nothing to break down
+    let output = day02_stdin(&["-"], "1 2 3\n9 7 6\n");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Part 1 - Safe reports: 2\n"), "{}", stdout);
+    assert!(!stdout.contains("first violation"), "{}", stdout);
+}
+
+#[test]
+fn removal_stats_counts_fixes_by_removed_index() {
+    let histogram = "\
+Dampener fixes by removed index:
+  index 0: 1
+  index 1: 2
+  index 2: 2
+  index 3: 1
+  index 4: 1
+";
+    for mode in [&[][..], &["--stream"], &["--pipeline"], &["--dedupe"]] {
+        let args = [&["removal_index_input.txt", "--removal-stats"], mode].concat();
+        let stdout = String::from_utf8(day02(&args).stdout).unwrap();
+        assert!(stdout.contains(histogram), "{:?}: {}", mode, stdout);
+        assert!(!stdout.contains("several levels"), "{}", stdout);
+        assert!(
+            stdout.contains("Reports fixed by dampener: 7\n"),
+            "{}",
+            stdout
+        );
+
+        let json = metrics_json(&args);
+        assert_eq!(
+            json["removal_histogram"],
+            serde_json::json!([1, 2, 2, 1, 1])
+        );
+        assert_eq!(json["multiple_removal_fixes"], 0);
+    }
+
+    // `1 2 7 8 9` needs both its low levels gone
+    let args = [
+        "removal_index_input.txt",
+        "--removal-stats",
+        "--dampener",
+        "2",
+    ];
+    let stdout = String::from_utf8(day02(&args).stdout).unwrap();
+    assert!(
+        stdout.contains(&format!("{}  several levels: 1\n", histogram)),
+        "{}",
+        stdout
+    );
+    assert_eq!(metrics_json(&args)["multiple_removal_fixes"], 1);
+
+    // The histogram is only kept when asked for
+    let stdout = String::from_utf8(day02(&["removal_index_input.txt"]).stdout).unwrap();
+    assert!(!stdout.contains("removed index"), "{}", stdout);
+    assert_eq!(
+        metrics_json(&["removal_index_input.txt"])["removal_histogram"],
+        serde_json::json!([])
+    );
+}